@@ -0,0 +1,106 @@
+//! Benchmarks for running the Clac programs in the `benches` directory.
+//!
+//! Run `cargo bench` to benchmark the default backend, or
+//! `cargo bench --features threaded-dispatch` to benchmark threaded dispatch.
+//! A name may be given after `--` to only run programs containing it.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+    time::{Duration, Instant},
+};
+
+// The benchmarks run the Clac binary, so they do not use its library or its
+// dependencies.
+use clac as _;
+use thiserror as _;
+
+/// The number of times each program is run.
+const SAMPLES: usize = 10;
+
+/// Runs the benchmarks.
+fn main() -> ExitCode {
+    let filter = env::args().skip(1).find(|a| !a.starts_with('-'));
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches");
+
+    let mut paths = match program_paths(&directory) {
+        Ok(paths) => paths,
+        Err(error) => {
+            eprintln!("Could not read '{}': {error}", directory.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    paths.retain(|p| {
+        filter
+            .as_ref()
+            .is_none_or(|f| p.to_string_lossy().contains(f))
+    });
+    paths.sort();
+
+    let backend = if cfg!(feature = "threaded-dispatch") {
+        "threaded"
+    } else {
+        "match"
+    };
+
+    println!("backend: {backend}, samples: {SAMPLES}");
+    println!("{:16}{:>12}{:>12}{:>12}", "program", "min", "median", "max");
+
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+
+        match bench_program(&path) {
+            Ok(mut times) => {
+                times.sort();
+                let [min, median, max] =
+                    [0, SAMPLES / 2, SAMPLES - 1].map(|i| format!("{:.2?}", times[i]));
+
+                println!("{name:16}{min:>12}{median:>12}{max:>12}");
+            }
+            Err(error) => {
+                eprintln!("Could not benchmark '{name}': {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Returns the paths to the Clac programs in a directory. This function
+/// returns an error message if the directory could not be read.
+fn program_paths(directory: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(directory).map_err(|e| e.to_string())?;
+    let paths = entries
+        .filter_map(|e| Some(e.ok()?.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "clac"))
+        .collect();
+
+    Ok(paths)
+}
+
+/// Runs a Clac program [`SAMPLES`] times and returns how long each run took.
+/// This function returns an error message if the program could not be run or
+/// reported an error.
+fn bench_program(path: &Path) -> Result<Vec<Duration>, String> {
+    let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut times = Vec::with_capacity(SAMPLES);
+
+    for _ in 0..SAMPLES {
+        let start = Instant::now();
+        let output = Command::new(env!("CARGO_BIN_EXE_clac"))
+            .arg(&source)
+            .output()
+            .map_err(|e| e.to_string())?;
+
+        times.push(start.elapsed());
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+        }
+    }
+
+    Ok(times)
+}
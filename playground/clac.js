@@ -0,0 +1,36 @@
+// A tiny shim around the Clac playground WebAssembly module.
+//
+// Build the module with the `playground` feature and copy it next to this file
+// as `clac.wasm`. Then call `load()` to get an object with `eval(line)`, which
+// returns `{ ok, output }`, and `reset()`, which clears global variables.
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+export async function load(url = new URL("clac.wasm", import.meta.url)) {
+  const { instance } = await WebAssembly.instantiateStreaming(fetch(url));
+  const exports = instance.exports;
+
+  return {
+    eval(line) {
+      const input = encoder.encode(line);
+      const pointer = exports.clac_input(input.length);
+      new Uint8Array(exports.memory.buffer, pointer, input.length).set(input);
+
+      const ok = exports.clac_eval() !== 0;
+      const output = decoder.decode(
+        new Uint8Array(
+          exports.memory.buffer,
+          exports.clac_output(),
+          exports.clac_output_len(),
+        ),
+      );
+
+      return { ok, output };
+    },
+
+    reset() {
+      exports.clac_reset();
+    },
+  };
+}
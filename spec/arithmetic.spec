@@ -0,0 +1,10 @@
+--- source
+1 + 2 * 3
+3 * (1 + 2)
+2 ^ 3 ^ 2
+10 / 4 - 1
+--- stdout
+7
+9
+512
+1.5
@@ -0,0 +1,15 @@
+--- source
+f(x, y) = x + y
+f(1)
+sqrt(1, 2)
+max()
+round(1, 2, 3)
+k(x) = f(x, x, x)
+g = f
+g(1)
+--- errors
+lower: function 'f' expects 2 argument(s), but 1 were given
+lower: function 'sqrt' expects 1 argument(s), but 2 were given
+lower: function 'max' expects 1 or more argument(s), but 0 were given
+lower: function 'round' expects 1 to 2 argument(s), but 3 were given
+lower: function 'f' expects 2 argument(s), but 3 were given
@@ -0,0 +1,18 @@
+--- source
+base(16, `ff`), base(2, `1010`), base(36, `Zz`), base(8, ` -17 `)
+to_base(255, 16), to_base(-10, 2), to_base(0, 36)
+base(16, to_base(123456789, 16)) == 123456789
+base(10, 12)
+base(2, `+1`)
+base(16, `fg`)
+--- stdout
+255
+10
+1295
+-15
+FF
+-1010
+0
+true
+--- errors
+interpret: type error
@@ -0,0 +1,16 @@
+--- source
+f(a, b) = [a == b ? 1 : 0, a != b ? 1 : 0, a < b ? 1 : 0, a <= b ? 1 : 0, a > b ? 1 : 0, a >= b ? 1 : 0]
+f(1, 2)
+f(2, 2)
+f(3, 2)
+f(`a`, `b`)
+f(sqrt(-1), 1)
+f(1, `a`)
+--- stdout
+[0, 1, 1, 1, 0, 0]
+[1, 0, 0, 1, 0, 1]
+[0, 1, 0, 0, 1, 1]
+[0, 1, 1, 1, 0, 0]
+[0, 1, 0, 0, 0, 0]
+--- errors
+interpret: type error
@@ -0,0 +1,12 @@
+--- source
+fib(0) = 0
+fib(1) = 1
+fib(n) = fib(n - 1) + fib(n - 2)
+fib(15)
+sign(x) = -1 when x < 0
+sign(x) = 1 when x > 0
+sign(x) = 0 otherwise
+sign(-4) + sign(3) * 10
+--- stdout
+610
+9
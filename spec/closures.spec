@@ -0,0 +1,14 @@
+--- source
+adder(n) = x -> x + n
+compose(f, g) = x -> g(f(x))
+add_three = compose(adder(1), adder(2))
+add_three(10)
+counter(start) = {
+    step = 2
+    next = () -> start + step
+    next
+}
+counter(5)()
+--- stdout
+13
+7
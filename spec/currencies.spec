@@ -0,0 +1,28 @@
+--- source
+usd = currency(`usd`, 1)
+eur = currency(`eur`, 0.5)
+10 * usd + 5 * usd
+10 * usd + convert(4 * eur, usd)
+10 * usd / (2 * usd), 10 * usd / 4, 3 * usd < 4 * usd
+convert(10 * usd, eur)
+set_rate(eur, 0.25)
+convert(10 * usd, eur)
+dollars = usd
+gbp = currency(`GBP`, 1)
+rates([(eur, 0.5), (gbp, 0.8)])
+convert(10 * dollars, eur), convert(10 * usd, gbp)
+10 * usd + 5 * eur
+--- stdout
+15 usd
+18 usd
+5
+2.5 usd
+true
+5 eur
+1 eur
+2.5 eur
+[(1 eur, 0.5), (1 GBP, 0.8)]
+5 eur
+8 GBP
+--- errors
+interpret: cannot mix amounts in different currencies, use 'convert' first
@@ -0,0 +1,27 @@
+--- source
+(date(2024, 12, 25) - date(2024, 5, 1)) / days(1)
+date(2024, 5, 1) + weeks(2)
+date(2024, 3, 1) - days(1)
+date(2023, 3, 1) - days(1)
+date(1969, 12, 31) + days(1)
+d = date(2024, 2, 29)
+year(d), month(d), day(d), weekday(d)
+days(3) * 2, days(3) - days(1)
+days(1) < weeks(1), date(2024, 1, 1) > date(2023, 12, 31)
+date(2024, 2, 30)
+--- stdout
+238
+2024-05-15
+2024-02-29
+2023-02-28
+1970-01-01
+2024
+2
+29
+4
+6d
+2d
+true
+true
+--- errors
+interpret: invalid date
@@ -0,0 +1,20 @@
+--- source
+as_fraction(0.75)
+as_fraction(-1 / 3)
+as_fraction(3.14159)
+as_fraction(0.0001)
+as_fraction(2)
+as_fraction(true)
+--- stdout
+3/4
+0.75
+-1/3
+-0.3333333333333333
+355/113 (approx)
+3.14159
+1e-4
+0.0001
+2
+2
+--- errors
+interpret: type error
@@ -0,0 +1,21 @@
+--- source
+() -> 3.14
+add(l, r) = l + r
+add
+one() = 1
+one
+x -> y -> x * y
+add_to(l) = r -> add(l, r)
+add_to(5)
+make_counter(n) = {next(k) = k + n, next}, make_counter(1)
+sqrt
+[add, max]
+--- stdout
+() -> …
+add(l, r) = …
+one() = …
+x -> …
+<closure r -> … capturing l>
+<closure next/1 capturing n>
+<native sqrt>
+[add(l, r) = …, <native max>]
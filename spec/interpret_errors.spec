@@ -0,0 +1,9 @@
+--- source
+half(x) = x / 2
+half(8)
+half(true)
+half(2)
+--- stdout
+4
+--- errors
+interpret: type error
@@ -0,0 +1,31 @@
+--- source
+xs = [1, 2, 3]
+xs
+[]
+[`a`, [true, 1 + 1],]
+xs[0] + xs[2]
+len(xs)
+len([])
+xs + [4, 5]
+xs == [1, 2, 3]
+xs != [1, 2]
+2 in xs
+[2] in [[1], [2]]
+first(list) = list[0]
+first([x -> x * 10])(7)
+xs[3]
+--- stdout
+[1, 2, 3]
+[]
+[`a`, [true, 2]]
+4
+3
+0
+[1, 2, 3, 4, 5]
+true
+true
+true
+true
+70
+--- errors
+interpret: index 3 is out of bounds for a list of length 3
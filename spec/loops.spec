@@ -0,0 +1,17 @@
+--- source
+sum(n) = loop(acc = 0, i = 1) { i <= n ? recur(acc + i, i + 1) : acc }
+sum(100)
+sum_to(n) = { i = 0, total = 0, while i < n { i += 1, total += i }, total }
+sum_to(10)
+count = 0
+count += 2
+count
+i = 0, total = 1
+while i < 3 { i += 1, total *= 2 }
+i, total
+--- stdout
+5050
+55
+2
+3
+8
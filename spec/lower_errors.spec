@@ -0,0 +1,13 @@
+--- source
+total = 1
+missing + total
+sqrt += 1, (total, 2) = (1, 2)
+g(0) = 1
+g(1)
+h((a, 1)) = a
+--- errors
+lower: variable 'missing' is undefined
+lower: variable 'sqrt' is already defined
+lower: tuples can only be unpacked into variables
+lower: clauses of function 'g' are not exhaustive, add an 'otherwise' clause
+lower: function parameters must be identifiers or tuples of identifiers
@@ -0,0 +1,12 @@
+--- source
+10
+-2 ^ 2
+10, -2 ^ 2
+double(x) = x * 2
+double
+(4)
+--- stdout
+6
+10
+-4
+8
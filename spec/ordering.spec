@@ -0,0 +1,18 @@
+--- source
+(1, 2) < (1, 3), (2, 0) > (1, 9), (1, 2) <= (1, 2), [1, 2] < [1, 2, 0], [] >= [], [`b`] > [`a`, `z`]
+max((1, `b`), (1, `a`), (0, `z`)), min([3, 1], [2, 9])
+nested = [(1, 2)] < [(1, 1)]
+nested
+mixed = (1, 2) < (1, `a`)
+--- stdout
+true
+true
+true
+true
+true
+true
+(1, `b`)
+[2, 9]
+false
+--- errors
+interpret: cannot compare number with string
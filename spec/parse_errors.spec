@@ -0,0 +1,4 @@
+--- source
+x = 1 +
+--- errors
+parse: expected an expression, got end of file
@@ -0,0 +1,19 @@
+--- source
+squares = pmap(x -> x * x, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16])
+squares[15], len(squares)
+pmap(`n -> (n, n + 1)`, [1, 2])
+pmap(x -> println(x), [1, 2, 3])
+pmap(x -> x, [])
+k = 3
+pmap(`x -> x * k`, [1, 2])
+--- stdout
+256
+16
+[(1, 2), (2, 3)]
+1
+2
+3
+[1, 2, 3]
+[]
+--- errors
+interpret: could not map in parallel: variable 'k' is undefined
@@ -0,0 +1,24 @@
+--- source
+xs = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+y5 = xs + xs + xs + xs + xs
+ys = y5 + y5 + y5 + y5
+ys
+len(ys)
+[[1, 20, 3], [400, 5, 6]]
+[`alpha`, `beta`, `gamma`, `delta`, `epsilon`, `zeta`, `eta`, `theta`, `iota`, `kappa`]
+zs = [xs + xs, [1, 2]]
+zs
+t = (1, [xs, xs])
+t
+ws = [1, [2]]
+ws
+--- stdout
+[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, … 190 more]
+200
+[[  1, 20, 3],
+ [400,  5, 6]]
+[`alpha`, `beta`, `gamma`, `delta`, `epsilon`, `zeta`, `eta`, `theta`, `iota`,
+ `kappa`]
+[[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, … 10 more], [1, 2]]
+(1, [[0, 1, 2, 3, 4, 5, 6, 7, 8, 9], [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]])
+[1, [2]]
@@ -0,0 +1,21 @@
+--- source
+y = println("a", 1, [2, "b"])
+y
+print("no newline")
+print(" ", 3)
+println(1 + 2) * 2
+f(x) = println("x is", x) * 2
+f(5)
+print(1, true), println("")
+--- stdout
+a 1 [2, `b`]
+[2, `b`]
+no newlineno newline
+  33
+3
+6
+x is 5
+10
+1 truetrue
+
+
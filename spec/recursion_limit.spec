@@ -0,0 +1,11 @@
+--- source
+depth(n) = n == 0 ? 0 : 1 + depth(n - 1)
+depth(1000)
+countdown(n) = n == 0 ? `done` : countdown(n - 1)
+countdown(200000)
+depth(200000)
+--- stdout
+1000
+done
+--- errors
+interpret: function calls are nested more than 100000 levels deep, use a loop or a tail call instead
@@ -0,0 +1,12 @@
+--- source
+solve(x ^ 2 - 4 == 0, x)
+solve(max(x, 0) == 3, x)
+f(x) = 2 * x - 1
+solve(f(x) == 5, x)
+solve(sin(x) == 0, x)
+--- stdout
+{-2, 2}
+{3}
+{3}
+--- errors
+interpret: function 'sin' is undefined
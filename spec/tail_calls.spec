@@ -0,0 +1,18 @@
+--- source
+countdown(n) = n > 0 ? countdown(n - 1) : n
+countdown(100000)
+sum(n, acc) = n == 0 ? acc : { next = n - 1, sum(next, acc + n) }
+sum(100000, 0)
+capture_each(n) = { m = n, n > 0 ? capture_each(n - 1) : (() -> m)() }
+capture_each(1000)
+tail_native(x) = sqrt(x)
+tail_native(81)
+apply(f, x) = f(x)
+apply(y -> y * 2, 21)
+--- stdout
+0
+5000050000
+0
+9
+42
+--- errors
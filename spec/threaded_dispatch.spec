@@ -0,0 +1,16 @@
+--- source
+f(a, b) = [a + b, a - b, a * b, a + 1, a < b, a > b]
+f(6, 2)
+f(2, 6)
+h(a, b) = [a + b, a - b, a * 2, a < b, a > b]
+h(days(2), days(1))
+g(x) = { y = x * 2, y + 1 }
+g(20)
+f(1, true)
+--- stdout
+[8, 4, 12, 7, false, true]
+[8, -4, 12, 3, true, false]
+[3d, 1d, 4d, false, true]
+41
+--- errors
+interpret: type error
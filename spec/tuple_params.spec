@@ -0,0 +1,21 @@
+--- source
+f((a, b)) = a + b
+f((1, 2))
+norm((x, y), k) = k * sqrt(x * x + y * y)
+norm((3, 4), 2)
+first(((a, b), c)) = a
+first(((7, 8), 9))
+swap = ((a, b)) -> (b, a)
+swap((1, `x`))
+dist(0, p) = 0
+dist(n, (x, y)) = n * (x + y)
+dist(2, (1, 1)) + dist(0, (5, 5))
+f((1, 2, 3))
+--- stdout
+3
+10
+7
+(`x`, 1)
+4
+--- errors
+interpret: cannot unpack a tuple of length 3 into 2 variables
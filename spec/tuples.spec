@@ -0,0 +1,24 @@
+--- source
+(1,), [(2,)]
+point = (3, `a`, [true])
+point
+point[0] + 1, point[1], (1, 2) == (1, 2), (1, 2) != (2, 1)
+swap(pair) = (pair[1], pair[0]), (x, y) = swap((1, 2))
+x - y
+divmod(a, b) = { q = round(a / b - 0.5); (q, a - q * b) }
+let (q, r) = divmod(17, 5) in q * 10 + r
+fib(n) = { a = 0; b = 1; while n > 0 { (a, b) = (b, a + b); n -= 1 }; a }
+fib(10), (lo, hi) = (1, 2, 3)
+--- stdout
+(1,)
+[(2,)]
+(3, `a`, [true])
+4
+a
+true
+true
+1
+32
+55
+--- errors
+interpret: cannot unpack a tuple of length 3 into 2 variables
@@ -0,0 +1,4 @@
+--- source
+1 + 2 $ 3
+--- errors
+parse: unexpected character '$'
@@ -0,0 +1,32 @@
+--- source
+outer(a) = {
+    b = a * 2
+    middle = c -> {
+        inner = d -> a + b + c + d
+        inner(1000)
+    }
+    middle(100)
+}
+outer(1)
+shadow(x) = {
+    get_outer = () -> x
+    {
+        x = x + 1
+        get_inner = () -> x
+        get_outer() * 10 + get_inner()
+    }
+}
+shadow(1)
+loop_closures(n) = loop(i = 0, acc = 0) {
+    i < n ? recur(i + 1, acc + (() -> i)()) : acc
+}
+loop_closures(4)
+get_captured(x) = { get = () -> x, get() }
+call_in_closure(a) = { add = () -> a + get_captured(5), add() }
+call_in_closure(1)
+--- stdout
+1103
+12
+6
+6
+--- errors
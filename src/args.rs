@@ -0,0 +1,615 @@
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::{
+    Mode,
+    completions::Shell,
+    format::{self, Notation, NumberFormat, RoundingMode},
+    integer::IntegerMode,
+    limits::Limits,
+    output::{Echo, ResultFormat},
+};
+
+/// Metadata for every command line option.
+pub const OPTIONS: &[OptionInfo] = &[
+    OptionInfo {
+        name: "--check",
+        short: None,
+        value: None,
+        description: "Check code for errors without executing it",
+    },
+    OptionInfo {
+        name: "--dump-tokens",
+        short: None,
+        value: None,
+        description: "Print the tokens of code without executing it",
+    },
+    OptionInfo {
+        name: "--dump-ast",
+        short: None,
+        value: None,
+        description: "Print the syntax tree of code without executing it",
+    },
+    OptionInfo {
+        name: "--dump-hir",
+        short: None,
+        value: None,
+        description: "Print the lowered form of code without executing it",
+    },
+    OptionInfo {
+        name: "--dump-cfg",
+        short: None,
+        value: None,
+        description: "Print the compiled form of code without executing it",
+    },
+    OptionInfo {
+        name: "--file",
+        short: Some("-f"),
+        value: Some(ValueKind::File),
+        description: "Run a script file",
+    },
+    OptionInfo {
+        name: "--jobs",
+        short: Some("-j"),
+        value: Some(ValueKind::Number),
+        description: "Run multiple script files on a number of threads",
+    },
+    OptionInfo {
+        name: "--watch",
+        short: None,
+        value: Some(ValueKind::File),
+        description: "Run a script file whenever it changes",
+    },
+    OptionInfo {
+        name: "--keep-globals",
+        short: None,
+        value: None,
+        description: "Keep global variables between runs in watch mode",
+    },
+    OptionInfo {
+        name: "--echo",
+        short: None,
+        value: Some(ValueKind::Choice(&["on", "off"])),
+        description: "Print the results of top-level expressions",
+    },
+    OptionInfo {
+        name: "--format",
+        short: None,
+        value: Some(ValueKind::Choice(&["pretty", "csv", "tsv"])),
+        description: "Print tabular results as pretty lists or rows of values",
+    },
+    OptionInfo {
+        name: "--output",
+        short: Some("-o"),
+        value: Some(ValueKind::File),
+        description: "Write output to a file",
+    },
+    OptionInfo {
+        name: "--preload",
+        short: None,
+        value: Some(ValueKind::File),
+        description: "Run a script file before the REPL or code",
+    },
+    OptionInfo {
+        name: "--log",
+        short: None,
+        value: Some(ValueKind::File),
+        description: "Log input and output to a transcript file",
+    },
+    OptionInfo {
+        name: "--decimal-comma",
+        short: None,
+        value: None,
+        description: "Display a decimal comma",
+    },
+    OptionInfo {
+        name: "--group-digits",
+        short: None,
+        value: None,
+        description: "Separate the digits of large numbers into groups",
+    },
+    OptionInfo {
+        name: "--notation",
+        short: None,
+        value: Some(ValueKind::Choice(&[
+            "decimal",
+            "engineering",
+            "si",
+            "fraction",
+        ])),
+        description: "Choose how numbers are displayed",
+    },
+    OptionInfo {
+        name: "--si-suffixes",
+        short: None,
+        value: None,
+        description: "Allow SI prefixes after number literals",
+    },
+    OptionInfo {
+        name: "--allow-native-overrides",
+        short: None,
+        value: None,
+        description: "Allow native functions to be redefined in the REPL",
+    },
+    OptionInfo {
+        name: "--mode",
+        short: None,
+        value: Some(ValueKind::Choice(&[
+            "float", "u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64",
+        ])),
+        description: "Switch to an integer mode",
+    },
+    OptionInfo {
+        name: "--decimals",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Display results with a number of decimal places",
+    },
+    OptionInfo {
+        name: "--max-tokens",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Reject code with more than a number of tokens",
+    },
+    OptionInfo {
+        name: "--max-depth",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Reject code nested deeper than a number of levels",
+    },
+    OptionInfo {
+        name: "--max-instructions",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Reject code which compiles to more than a number of instructions",
+    },
+    OptionInfo {
+        name: "--max-recursion",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Stop code which nests more than a number of function calls",
+    },
+    OptionInfo {
+        name: "--max-steps",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Stop code which runs for more than a number of steps",
+    },
+    OptionInfo {
+        name: "--max-length",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Stop code which builds a list or string longer than a number",
+    },
+    OptionInfo {
+        name: "--max-errors",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Report at most a number of errors at once",
+    },
+    OptionInfo {
+        name: "--rounding",
+        short: None,
+        value: Some(ValueKind::Choice(&["half-up", "half-even", "toward-zero"])),
+        description: "Choose how numbers are rounded",
+    },
+    OptionInfo {
+        name: "--port",
+        short: None,
+        value: Some(ValueKind::Number),
+        description: "Listen on a port in serve mode",
+    },
+];
+
+/// Metadata for every subcommand.
+pub const COMMANDS: &[CommandInfo] = &[
+    CommandInfo {
+        name: "completions",
+        value: Some(ValueKind::Choice(&["bash", "zsh", "fish"])),
+        description: "Print a shell completion script",
+    },
+    CommandInfo {
+        name: "fmt",
+        value: Some(ValueKind::File),
+        description: "Print a script file in a consistent format",
+    },
+    CommandInfo {
+        name: "repl",
+        value: None,
+        description: "Start the REPL",
+    },
+    CommandInfo {
+        name: "serve",
+        value: None,
+        description: "Start an HTTP server which evaluates code",
+    },
+];
+
+/// Metadata for a command line option.
+pub struct OptionInfo {
+    /// The option's name, including its leading dashes.
+    pub name: &'static str,
+
+    /// The option's short name, if any.
+    pub short: Option<&'static str>,
+
+    /// The [`ValueKind`] of the option's value, if it takes a value.
+    pub value: Option<ValueKind>,
+
+    /// A short description of the option.
+    pub description: &'static str,
+}
+
+/// Metadata for a subcommand.
+pub struct CommandInfo {
+    /// The subcommand's name.
+    pub name: &'static str,
+
+    /// The [`ValueKind`] of the subcommand's value, if it takes a value.
+    pub value: Option<ValueKind>,
+
+    /// A short description of the subcommand.
+    pub description: &'static str,
+}
+
+/// A kind of value taken by an option or subcommand.
+pub enum ValueKind {
+    /// A file path.
+    File,
+
+    /// A non-negative integer.
+    Number,
+
+    /// One of a list of names.
+    Choice(&'static [&'static str]),
+}
+
+/// An error caught while parsing command line arguments.
+#[derive(Debug, Error)]
+pub enum ArgsError {
+    /// An unknown option was given.
+    #[error("unknown option '{0}'")]
+    UnknownOption(String),
+
+    /// An option or subcommand was given without its value.
+    #[error("'{0}' requires a value")]
+    MissingValue(&'static str),
+
+    /// An option or subcommand was given with an invalid value.
+    #[error("invalid value '{1}' for '{0}'")]
+    InvalidValue(&'static str, String),
+
+    /// Code was given with an option or subcommand that does not accept code.
+    #[error("'{0}' cannot be used with code arguments")]
+    UnexpectedCode(&'static str),
+
+    /// Two options or subcommands which cannot be used together were given.
+    #[error("'{0}' cannot be used with '{1}'")]
+    Conflict(&'static str, &'static str),
+
+    /// An option was given without the subcommand that it requires.
+    #[error("'{0}' can only be used with '{1}'")]
+    RequiresCommand(&'static str, &'static str),
+}
+
+/// A subcommand.
+pub enum Command {
+    /// Prints a completion script for a [`Shell`].
+    Completions(Shell),
+
+    /// Prints a script file, or source code from standard input if no path is
+    /// given, in a consistent format.
+    Fmt(Option<PathBuf>),
+
+    /// Prints the grammar in EBNF. This command is hidden, so it is not listed
+    /// in [`COMMANDS`].
+    Grammar,
+
+    /// Starts the REPL.
+    Repl,
+
+    /// Starts an HTTP server which evaluates source code.
+    Serve,
+}
+
+/// Command line options.
+#[derive(Default)]
+pub struct Options {
+    /// The [`Mode`] for running source code.
+    pub mode: Mode,
+
+    /// The paths to script files to run.
+    pub files: Vec<PathBuf>,
+
+    /// The number of threads to run multiple script files on, if given.
+    pub jobs: Option<NonZeroUsize>,
+
+    /// The path to a script file to watch for changes, if any.
+    pub watch: Option<PathBuf>,
+
+    /// Whether global variables should be kept between runs in watch mode.
+    pub keep_globals: bool,
+
+    /// The [`Echo`] mode outside of the REPL.
+    pub echo: Echo,
+
+    /// The [`ResultFormat`] for printing top-level results.
+    pub result_format: ResultFormat,
+
+    /// The path to a file to write output to instead of standard output, if
+    /// any.
+    pub output: Option<PathBuf>,
+
+    /// The paths to script files to run before the REPL or code.
+    pub preload: Vec<PathBuf>,
+
+    /// The path to a transcript file to log to, if any.
+    pub log: Option<PathBuf>,
+
+    /// The [`NumberFormat`] for displaying numbers.
+    pub number_format: NumberFormat,
+
+    /// The [`IntegerMode`] for wrapping arithmetic, if any.
+    pub integer_mode: Option<IntegerMode>,
+
+    /// Whether SI prefix suffixes are recognized on number literals.
+    pub si_suffixes: bool,
+
+    /// Whether global variables bound to native functions can be redefined.
+    pub native_overrides: bool,
+
+    /// The [`Limits`] on code size.
+    pub limits: Limits,
+
+    /// The port to listen on in serve mode, if given.
+    pub port: Option<u16>,
+
+    /// The [`Command`], if any.
+    pub command: Option<Command>,
+
+    /// The code arguments.
+    pub code: Vec<String>,
+}
+
+/// Parses [`Options`] from command line arguments, excluding the program name.
+/// This function returns an [`ArgsError`] if the arguments are invalid.
+pub fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Options, ArgsError> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+
+    if args.next_if(|a| a == "completions").is_some() {
+        let shell = args.next().ok_or(ArgsError::MissingValue("completions"))?;
+        let shell =
+            Shell::from_name(&shell).ok_or(ArgsError::InvalidValue("completions", shell))?;
+        options.command = Some(Command::Completions(shell));
+
+        return match args.next() {
+            None => Ok(options),
+            Some(_) => Err(ArgsError::UnexpectedCode("completions")),
+        };
+    }
+
+    if args.next_if(|a| a == "fmt").is_some() {
+        options.command = Some(Command::Fmt(args.next().map(PathBuf::from)));
+
+        return match args.next() {
+            None => Ok(options),
+            Some(_) => Err(ArgsError::UnexpectedCode("fmt")),
+        };
+    }
+
+    if args.next_if(|a| a == "grammar").is_some() {
+        options.command = Some(Command::Grammar);
+
+        return match args.next() {
+            None => Ok(options),
+            Some(_) => Err(ArgsError::UnexpectedCode("grammar")),
+        };
+    }
+
+    if args.next_if(|a| a == "repl").is_some() {
+        options.command = Some(Command::Repl);
+    } else if args.next_if(|a| a == "serve").is_some() {
+        options.command = Some(Command::Serve);
+    }
+
+    // Options may only appear before code, and code may begin with '--' (e.g.
+    // `--1`), so only arguments which begin like a long option are options.
+    while let Some(arg) = args.next_if(|a| is_option(a)) {
+        let Some(info) = OPTIONS
+            .iter()
+            .find(|o| o.name == arg || o.short == Some(arg.as_str()))
+        else {
+            return Err(ArgsError::UnknownOption(arg));
+        };
+
+        let name = info.name;
+        let value = match info.value {
+            None => String::new(),
+            Some(_) => args.next().ok_or(ArgsError::MissingValue(name))?,
+        };
+
+        apply_option(&mut options, name, value)?;
+    }
+
+    options.code.extend(args);
+
+    // Code arguments which all name script files are run as files, as `.clac`
+    // is never valid code.
+    if options.files.is_empty()
+        && !options.code.is_empty()
+        && options.code.iter().all(|a| {
+            Path::new(a)
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("clac"))
+        })
+    {
+        options.files = options.code.drain(..).map(PathBuf::from).collect();
+    }
+
+    if !options.files.is_empty() {
+        if !options.code.is_empty() {
+            return Err(ArgsError::UnexpectedCode("--file"));
+        }
+
+        if options.watch.is_some() {
+            return Err(ArgsError::Conflict("--file", "--watch"));
+        }
+    }
+
+    if options.watch.is_some() {
+        if !options.code.is_empty() {
+            return Err(ArgsError::UnexpectedCode("--watch"));
+        }
+
+        if !options.preload.is_empty() {
+            return Err(ArgsError::Conflict("--preload", "--watch"));
+        }
+    }
+
+    check_command(&options)?;
+    Ok(options)
+}
+
+/// Checks that the options which were given with a subcommand are compatible
+/// with it. This function returns an [`ArgsError`] if they are not.
+fn check_command(options: &Options) -> Result<(), ArgsError> {
+    let command = match options.command {
+        Some(Command::Repl) => Some("repl"),
+        Some(Command::Serve) => Some("serve"),
+        _ => None,
+    };
+
+    if let Some(command) = command {
+        if !options.code.is_empty() {
+            return Err(ArgsError::UnexpectedCode(command));
+        }
+
+        match options.mode {
+            Mode::Execute => {}
+            Mode::Check => return Err(ArgsError::Conflict(command, "--check")),
+            Mode::DumpTokens => return Err(ArgsError::Conflict(command, "--dump-tokens")),
+            Mode::DumpAst => return Err(ArgsError::Conflict(command, "--dump-ast")),
+            Mode::DumpHir => return Err(ArgsError::Conflict(command, "--dump-hir")),
+            Mode::DumpCfg => return Err(ArgsError::Conflict(command, "--dump-cfg")),
+        }
+
+        if options.watch.is_some() {
+            return Err(ArgsError::Conflict(command, "--watch"));
+        }
+
+        if !options.files.is_empty() {
+            return Err(ArgsError::Conflict(command, "--file"));
+        }
+    }
+
+    // Each session starts with only natives, so preloaded files cannot be
+    // run before it.
+    if command == Some("serve") && !options.preload.is_empty() {
+        return Err(ArgsError::Conflict("serve", "--preload"));
+    }
+
+    if options.port.is_some() && command != Some("serve") {
+        return Err(ArgsError::RequiresCommand("--port", "serve"));
+    }
+
+    Ok(())
+}
+
+/// Applies an option from [`OPTIONS`] with its value, or an empty string if it
+/// does not take a value, to [`Options`]. This function returns an
+/// [`ArgsError`] if the value is invalid.
+fn apply_option(options: &mut Options, name: &'static str, value: String) -> Result<(), ArgsError> {
+    match name {
+        "--check" => options.mode = Mode::Check,
+        "--dump-tokens" => options.mode = Mode::DumpTokens,
+        "--dump-ast" => options.mode = Mode::DumpAst,
+        "--dump-hir" => options.mode = Mode::DumpHir,
+        "--dump-cfg" => options.mode = Mode::DumpCfg,
+        "--file" => options.files.push(value.into()),
+        "--jobs" => {
+            let Ok(jobs) = value.parse() else {
+                return Err(ArgsError::InvalidValue(name, value));
+            };
+
+            options.jobs = Some(jobs);
+        }
+        "--watch" => options.watch = Some(value.into()),
+        "--keep-globals" => options.keep_globals = true,
+        "--echo" => {
+            options.echo = Echo::from_name(&value).ok_or(ArgsError::InvalidValue(name, value))?;
+        }
+        "--format" => {
+            options.result_format =
+                ResultFormat::from_name(&value).ok_or(ArgsError::InvalidValue(name, value))?;
+        }
+        "--output" => options.output = Some(value.into()),
+        "--preload" => options.preload.push(value.into()),
+        "--log" => options.log = Some(value.into()),
+        "--decimal-comma" => options.number_format.decimal_comma = true,
+        "--group-digits" => options.number_format.group_digits = true,
+        "--notation" => {
+            options.number_format.notation =
+                Notation::from_name(&value).ok_or(ArgsError::InvalidValue(name, value))?;
+        }
+        "--si-suffixes" => options.si_suffixes = true,
+        "--allow-native-overrides" => options.native_overrides = true,
+        "--mode" => {
+            options.integer_mode = if value == "float" {
+                None
+            } else {
+                let integer_mode = IntegerMode::from_name(&value);
+                Some(integer_mode.ok_or(ArgsError::InvalidValue(name, value))?)
+            };
+        }
+        "--decimals" => match value.parse() {
+            Ok(places) if places <= format::MAX_DECIMAL_PLACES => {
+                options.number_format.decimals = Some(places);
+            }
+            _ => return Err(ArgsError::InvalidValue(name, value)),
+        },
+        "--max-tokens" | "--max-depth" | "--max-instructions" | "--max-recursion"
+        | "--max-steps" | "--max-length" | "--max-errors" => {
+            let Ok(max) = value.parse() else {
+                return Err(ArgsError::InvalidValue(name, value));
+            };
+
+            match name {
+                "--max-tokens" => options.limits.tokens = Some(max),
+                "--max-depth" => options.limits.depth = Some(max),
+                "--max-instructions" => options.limits.instructions = Some(max),
+                "--max-recursion" => options.limits.recursion = Some(max),
+                "--max-steps" => options.limits.steps = Some(max),
+                "--max-length" => options.limits.length = Some(max),
+                _ => options.limits.errors = Some(max),
+            }
+        }
+        "--port" => {
+            let Ok(port) = value.parse() else {
+                return Err(ArgsError::InvalidValue(name, value));
+            };
+
+            options.port = Some(port);
+        }
+        "--rounding" => {
+            options.number_format.rounding =
+                RoundingMode::from_name(&value).ok_or(ArgsError::InvalidValue(name, value))?;
+        }
+        _ => unreachable!("option '{name}' should be handled"),
+    }
+
+    Ok(())
+}
+
+/// Returns [`true`] if a command line argument is an option. Short options are
+/// only recognized if they appear in [`OPTIONS`].
+fn is_option(arg: &str) -> bool {
+    OPTIONS.iter().any(|o| o.short == Some(arg))
+        || arg
+            .strip_prefix("--")
+            .is_some_and(|a| a.starts_with(|c: char| c.is_ascii_alphabetic()))
+}
@@ -1,6 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
-use super::{Ast, BinOp, Expr, Literal, LogicOp, UnOp};
+use super::{Ast, BinOp, Expr, ExprKind, Literal, LogicOp, UnOp};
 
 impl Display for Ast {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -9,22 +9,45 @@ impl Display for Ast {
 }
 
 impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.kind, f)
+    }
+}
+
+impl Display for ExprKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Literal(literal) => write!(f, "{literal}"),
             Self::Variable(symbol) => write!(f, "{symbol}"),
             Self::Paren(expr) => fmt_s_expr(f, "p:", &[expr]),
             Self::Tuple(exprs) => fmt_s_expr(f, "t:", exprs),
+            Self::List(exprs) => fmt_s_expr(f, "l:", exprs),
             Self::Block(stmts) => fmt_s_expr(f, "b:", stmts),
             Self::Assign(target, source) => fmt_s_expr(f, "=", &[target, source]),
+            Self::CompoundAssign(op, target, source) => {
+                fmt_s_expr(f, format_args!("{op}="), &[target, source])
+            }
             Self::Function(list, body) => fmt_s_expr(f, "->", &[list, body]),
             Self::Call(callee, list) => fmt_s_expr(f, callee, &[list]),
+            Self::Index(list, index) => fmt_s_expr(f, "[]", &[list, index]),
             Self::Unary(op, rhs) => fmt_s_expr(f, op, &[rhs]),
             Self::Binary(op, lhs, rhs) => fmt_s_expr(f, op, &[lhs, rhs]),
             Self::Logic(op, lhs, rhs) => fmt_s_expr(f, op, &[lhs, rhs]),
+            Self::Let(target, value, body) => fmt_s_expr(f, "let", &[target, value, body]),
+            Self::Lazy(expr) => fmt_s_expr(f, "lazy", &[expr]),
+            Self::Loop(list, body) => fmt_s_expr(f, "loop", &[list, body]),
+            Self::Recur(list) => fmt_s_expr(f, "recur", &[list]),
+            Self::While(cond, body) => fmt_s_expr(f, "while", &[cond, body]),
             Self::Cond(cond, then_expr, else_expr) => {
                 fmt_s_expr(f, "?", &[cond, then_expr, else_expr])
             }
+            Self::Silent(stmt) => fmt_s_expr(f, ";", &[stmt]),
+            Self::Operator(symbol, lhs, rhs) => fmt_s_expr(f, symbol, &[lhs, rhs]),
+            Self::Guarded(value, Some(guard)) => fmt_s_expr(f, "when", &[value, guard]),
+            Self::Guarded(value, None) => fmt_s_expr(f, "otherwise", &[value]),
+            Self::DefineOperator(symbol, _, list, body) => {
+                write!(f, "(operator {symbol} {list} {body})")
+            }
         }
     }
 }
@@ -33,7 +56,12 @@ impl Display for Literal {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Number(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
             Self::Bool(value) => write!(f, "{value}"),
+            // Raw strings cannot contain backticks, so strings which contain
+            // them are written as quoted strings.
+            Self::Str(value) if value.contains('`') => fmt_quoted_string(f, value),
+            Self::Str(value) => write!(f, "`{value}`"),
         }
     }
 }
@@ -63,6 +91,7 @@ impl Display for BinOp {
             Self::LessEqual => "<=",
             Self::Greater => ">",
             Self::GreaterEqual => ">=",
+            Self::In => "in",
         };
 
         write!(f, "{op}")
@@ -91,3 +120,22 @@ fn fmt_s_expr<O: Display, A: Display>(f: &mut Formatter<'_>, op: O, args: &[A])
 
     write!(f, ")")
 }
+
+/// Formats a string as a quoted string literal with escape sequences with a
+/// [`Formatter`]. This function returns a [`fmt::Error`] if an error occurred.
+fn fmt_quoted_string(f: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+
+    for char in value.chars() {
+        match char {
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\0' => f.write_str("\\0")?,
+            '\\' | '"' => write!(f, "\\{char}")?,
+            _ => write!(f, "{char}")?,
+        }
+    }
+
+    f.write_str("\"")
+}
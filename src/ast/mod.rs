@@ -1,14 +1,137 @@
 mod display;
+mod pretty;
 
-use crate::symbols::Symbol;
+use std::rc::Rc;
+
+use crate::{spans::Span, symbols::Symbol};
+
+pub use self::pretty::pretty_print;
 
 /// An abstract syntax tree.
 #[derive(Debug)]
 pub struct Ast(pub Box<[Expr]>);
 
-/// An expression.
+impl Ast {
+    /// Returns the top-level statement [`Expr`] with a [`Span`]. Silent
+    /// statements are unwrapped. This function returns [`None`] if no
+    /// top-level statement has the [`Span`].
+    pub fn stmt(&self, span: Span) -> Option<&Expr> {
+        self.0
+            .iter()
+            .map(|s| match &s.kind {
+                ExprKind::Silent(stmt) => stmt,
+                _ => s,
+            })
+            .find(|s| s.span == span)
+    }
+
+    /// Returns the `Ast`'s nesting depth, which is the greatest nesting depth
+    /// of its top-level statement [`Expr`]s.
+    pub fn depth(&self) -> usize {
+        self.0.iter().map(Expr::depth).max().unwrap_or_default()
+    }
+}
+
+/// An expression with a [`Span`].
+#[derive(Debug)]
+pub struct Expr {
+    /// The [`ExprKind`].
+    pub kind: ExprKind,
+
+    /// The [`Span`].
+    pub span: Span,
+}
+
+impl Expr {
+    /// Creates a new `Expr` from an [`ExprKind`] and a [`Span`].
+    pub const fn new(kind: ExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    /// Returns the `Expr`'s nesting depth, which is the greatest number of
+    /// brackets (`()`, `[]`, or `{}`) around any part of it. Chained
+    /// operations such as `1 + 2 + 3` are not nested, so they do not increase
+    /// the nesting depth.
+    pub fn depth(&self) -> usize {
+        /// Returns the nesting depth of an [`Expr`] inside brackets.
+        fn bracketed(expr: &Expr) -> usize {
+            expr.depth() + 1
+        }
+
+        /// Returns the greatest nesting depth of a slice of [`Expr`]s.
+        fn max_depth(exprs: &[Expr]) -> usize {
+            exprs.iter().map(Expr::depth).max().unwrap_or_default()
+        }
+
+        match &self.kind {
+            ExprKind::Literal(_) | ExprKind::Variable(_) => 0,
+            ExprKind::Paren(expr) => bracketed(expr),
+            ExprKind::Tuple(exprs) | ExprKind::List(exprs) | ExprKind::Block(exprs) => {
+                max_depth(exprs) + 1
+            }
+            ExprKind::Index(lhs, rhs) => lhs.depth().max(bracketed(rhs)),
+            ExprKind::Unary(_, expr)
+            | ExprKind::Lazy(expr)
+            | ExprKind::Recur(expr)
+            | ExprKind::Silent(expr)
+            | ExprKind::Guarded(expr, None) => expr.depth(),
+            ExprKind::Assign(lhs, rhs)
+            | ExprKind::CompoundAssign(_, lhs, rhs)
+            | ExprKind::Function(lhs, rhs)
+            | ExprKind::Call(lhs, rhs)
+            | ExprKind::Binary(_, lhs, rhs)
+            | ExprKind::Logic(_, lhs, rhs)
+            | ExprKind::Loop(lhs, rhs)
+            | ExprKind::While(lhs, rhs)
+            | ExprKind::Operator(_, lhs, rhs)
+            | ExprKind::Guarded(lhs, Some(rhs))
+            | ExprKind::DefineOperator(_, _, lhs, rhs) => lhs.depth().max(rhs.depth()),
+            ExprKind::Let(first, second, third) | ExprKind::Cond(first, second, third) => {
+                first.depth().max(second.depth()).max(third.depth())
+            }
+        }
+    }
+
+    /// Calls a function on each of the `Expr`'s direct subexpressions.
+    pub fn for_each_child<F: FnMut(&Self)>(&self, mut f: F) {
+        match &self.kind {
+            ExprKind::Literal(_) | ExprKind::Variable(_) => {}
+            ExprKind::Tuple(exprs) | ExprKind::List(exprs) | ExprKind::Block(exprs) => {
+                exprs.iter().for_each(f);
+            }
+            ExprKind::Paren(expr)
+            | ExprKind::Unary(_, expr)
+            | ExprKind::Lazy(expr)
+            | ExprKind::Recur(expr)
+            | ExprKind::Silent(expr)
+            | ExprKind::Guarded(expr, None) => f(expr),
+            ExprKind::Assign(lhs, rhs)
+            | ExprKind::CompoundAssign(_, lhs, rhs)
+            | ExprKind::Function(lhs, rhs)
+            | ExprKind::Call(lhs, rhs)
+            | ExprKind::Index(lhs, rhs)
+            | ExprKind::Binary(_, lhs, rhs)
+            | ExprKind::Logic(_, lhs, rhs)
+            | ExprKind::Loop(lhs, rhs)
+            | ExprKind::While(lhs, rhs)
+            | ExprKind::Operator(_, lhs, rhs)
+            | ExprKind::Guarded(lhs, Some(rhs))
+            | ExprKind::DefineOperator(_, _, lhs, rhs) => {
+                f(lhs);
+                f(rhs);
+            }
+            ExprKind::Let(first, second, third) | ExprKind::Cond(first, second, third) => {
+                f(first);
+                f(second);
+                f(third);
+            }
+        }
+    }
+}
+
+/// An [`Expr`]'s kind.
 #[derive(Debug)]
-pub enum Expr {
+pub enum ExprKind {
     /// A [`Literal`].
     Literal(Literal),
 
@@ -16,45 +139,89 @@ pub enum Expr {
     Variable(Symbol),
 
     /// A parenthesized expression.
-    Paren(Box<Self>),
+    Paren(Box<Expr>),
 
     /// A tuple.
-    Tuple(Box<[Self]>),
+    Tuple(Box<[Expr]>),
+
+    /// A list.
+    List(Box<[Expr]>),
 
     /// A block.
-    Block(Box<[Self]>),
+    Block(Box<[Expr]>),
 
     /// An assignment.
-    Assign(Box<Self>, Box<Self>),
+    Assign(Box<Expr>, Box<Expr>),
+
+    /// A compound assignment which updates a target with a [`BinOp`] and a
+    /// value.
+    CompoundAssign(BinOp, Box<Expr>, Box<Expr>),
 
     /// An anonymous function.
-    Function(Box<Self>, Box<Self>),
+    Function(Box<Expr>, Box<Expr>),
 
     /// A function call.
-    Call(Box<Self>, Box<Self>),
+    Call(Box<Expr>, Box<Expr>),
+
+    /// An index into a list.
+    Index(Box<Expr>, Box<Expr>),
 
     /// A unary operation.
-    Unary(UnOp, Box<Self>),
+    Unary(UnOp, Box<Expr>),
 
     /// A binary operation.
-    Binary(BinOp, Box<Self>, Box<Self>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
 
     /// A short-circuiting logical operation.
-    Logic(LogicOp, Box<Self>, Box<Self>),
+    Logic(LogicOp, Box<Expr>, Box<Expr>),
+
+    /// A `let` expression binding a target to a value inside a body.
+    Let(Box<Expr>, Box<Expr>, Box<Expr>),
+
+    /// A `lazy` expression whose value is evaluated when it is first used.
+    Lazy(Box<Expr>),
+
+    /// A `loop` expression with a list of variable definitions and a body.
+    Loop(Box<Expr>, Box<Expr>),
+
+    /// A `recur` expression with a list of new values for a loop's variables.
+    Recur(Box<Expr>),
+
+    /// A `while` expression with a condition and a body.
+    While(Box<Expr>, Box<Expr>),
 
     /// A ternary conditional.
-    Cond(Box<Self>, Box<Self>, Box<Self>),
+    Cond(Box<Expr>, Box<Expr>, Box<Expr>),
+
+    /// A statement whose value is not printed.
+    Silent(Box<Expr>),
+
+    /// A custom binary operation.
+    Operator(Symbol, Box<Expr>, Box<Expr>),
+
+    /// A function clause's value with a guard condition, or with no guard
+    /// condition if it is an `otherwise` clause.
+    Guarded(Box<Expr>, Option<Box<Expr>>),
+
+    /// A custom operator definition with modifiers.
+    DefineOperator(Symbol, Box<[Symbol]>, Box<Expr>, Box<Expr>),
 }
 
 /// A value which can be represented with a single
 /// [`Token`][crate::tokens::Token].
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Literal {
     /// A number.
     Number(f64),
 
+    /// An integer which is too large to be represented exactly as a number.
+    Integer(i128),
+
     /// A Boolean value.
     Bool(bool),
+
+    /// A string.
+    Str(Rc<str>),
 }
 
 /// A unary operator.
@@ -102,6 +269,9 @@ pub enum BinOp {
 
     /// A greater than or equal to comparison.
     GreaterEqual,
+
+    /// A membership test.
+    In,
 }
 
 /// A short-circuiting logical operator.
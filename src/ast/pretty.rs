@@ -0,0 +1,278 @@
+use super::{Ast, BinOp, Expr, ExprKind};
+
+/// The [`str`] used to indent one level of a block.
+const INDENT: &str = "    ";
+
+/// Formats an [`Ast`] parsed from source code as canonical source code.
+/// Literals are written as they appear in the source code, and blank lines
+/// between top-level statements are kept.
+pub fn pretty_print(ast: &Ast, source: &str) -> String {
+    let mut printer = Printer::new(source, Layout::Lines);
+    printer.print_sequence(&ast.0);
+    printer.output.push('\n');
+    printer.output
+}
+
+impl Expr {
+    /// Returns the `Expr` as source code on a single line. Literals are
+    /// written from their values, so the source code which the `Expr` was
+    /// parsed from is not needed.
+    pub fn to_source(&self) -> String {
+        let mut printer = Printer::new("", Layout::Inline);
+        printer.print_expr(self);
+        printer.output
+    }
+}
+
+/// A way of laying out statements.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// Statements are printed on separate lines and blocks are indented.
+    Lines,
+
+    /// Statements are printed on a single line.
+    Inline,
+}
+
+/// A structure which prints [`Expr`]s as source code.
+struct Printer<'src> {
+    /// The source code which the [`Expr`]s were parsed from, or an empty
+    /// string slice if it is not known.
+    source: &'src str,
+
+    /// The [`Layout`].
+    layout: Layout,
+
+    /// The printed source code.
+    output: String,
+
+    /// The number of blocks which the next line is indented by.
+    depth: usize,
+}
+
+impl<'src> Printer<'src> {
+    /// Creates a new `Printer` from source code and a [`Layout`].
+    const fn new(source: &'src str, layout: Layout) -> Self {
+        Self {
+            source,
+            layout,
+            output: String::new(),
+            depth: 0,
+        }
+    }
+
+    /// Prints a sequence of statement [`Expr`]s. Every statement except the
+    /// last is followed by a comma, unless it is silent.
+    fn print_sequence(&mut self, stmts: &[Expr]) {
+        let mut previous_end = None;
+
+        for (index, stmt) in stmts.iter().enumerate() {
+            match previous_end {
+                None => {}
+                Some(_) if self.layout == Layout::Inline => self.output.push(' '),
+                Some(previous_end) => {
+                    self.output.push('\n');
+
+                    let gap = self.source.get(previous_end..stmt.span.start);
+
+                    if self.depth == 0 && gap.is_some_and(|g| g.matches('\n').count() > 1) {
+                        self.output.push('\n');
+                    }
+
+                    self.print_indent();
+                }
+            }
+
+            self.print_expr(stmt);
+
+            if !matches!(stmt.kind, ExprKind::Silent(_)) && index + 1 < stmts.len() {
+                self.output.push(',');
+            }
+
+            previous_end = Some(stmt.span.end);
+        }
+    }
+
+    /// Prints an [`Expr`].
+    fn print_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Literal(literal) => {
+                let text = expr.span.slice(self.source);
+
+                if text.is_empty() {
+                    self.output.push_str(&literal.to_string());
+                } else {
+                    self.output.push_str(text);
+                }
+            }
+            ExprKind::Variable(symbol) => self.output.push_str(&symbol.to_string()),
+            ExprKind::Paren(expr) => {
+                self.output.push('(');
+                self.print_expr(expr);
+                self.output.push(')');
+            }
+            ExprKind::Tuple(exprs) => self.print_tuple(exprs),
+            ExprKind::List(exprs) => self.print_list(exprs),
+            ExprKind::Block(stmts) => self.print_block(stmts),
+            ExprKind::Assign(target, source) => self.print_infix(target, "=", source),
+            ExprKind::CompoundAssign(op, target, source) => {
+                self.print_infix(target, &format!("{op}="), source);
+            }
+            ExprKind::Function(list, body) => self.print_infix(list, "->", body),
+            ExprKind::Call(callee, list) => {
+                self.print_expr(callee);
+                self.print_expr(list);
+            }
+            ExprKind::Index(list, index) => {
+                self.print_expr(list);
+                self.output.push('[');
+                self.print_expr(index);
+                self.output.push(']');
+            }
+            ExprKind::Unary(op, rhs) => {
+                self.output.push_str(&op.to_string());
+                self.print_expr(rhs);
+            }
+            ExprKind::Binary(BinOp::Power, lhs, rhs) if self.is_superscript(rhs) => {
+                self.print_expr(lhs);
+                self.output.push_str(rhs.span.slice(self.source));
+            }
+            ExprKind::Binary(op, lhs, rhs) => self.print_infix(lhs, &op.to_string(), rhs),
+            ExprKind::Logic(op, lhs, rhs) => self.print_infix(lhs, &op.to_string(), rhs),
+            ExprKind::Let(target, value, body) => {
+                self.output.push_str("let ");
+                self.print_infix(target, "=", value);
+                self.output.push_str(" in ");
+                self.print_expr(body);
+            }
+            ExprKind::Lazy(expr) => {
+                self.output.push_str("lazy ");
+                self.print_expr(expr);
+            }
+            ExprKind::Loop(list, body) => {
+                self.output.push_str("loop");
+                self.print_expr(list);
+                self.output.push(' ');
+                self.print_expr(body);
+            }
+            ExprKind::Recur(list) => {
+                self.output.push_str("recur");
+                self.print_expr(list);
+            }
+            ExprKind::While(cond, body) => {
+                self.output.push_str("while ");
+                self.print_expr(cond);
+                self.output.push(' ');
+                self.print_expr(body);
+            }
+            ExprKind::Cond(cond, then_expr, else_expr) => {
+                self.print_infix(cond, "?", then_expr);
+                self.output.push_str(" : ");
+                self.print_expr(else_expr);
+            }
+            ExprKind::Silent(stmt) => {
+                self.print_expr(stmt);
+                self.output.push(';');
+            }
+            ExprKind::Operator(symbol, lhs, rhs) => {
+                self.print_infix(lhs, &symbol.to_string(), rhs);
+            }
+            ExprKind::Guarded(value, Some(guard)) => self.print_infix(value, "when", guard),
+            ExprKind::Guarded(value, None) => {
+                self.print_expr(value);
+                self.output.push_str(" otherwise");
+            }
+            ExprKind::DefineOperator(symbol, modifiers, list, body) => {
+                self.output.push_str("operator ");
+                self.output.push_str(&symbol.to_string());
+
+                for modifier in modifiers {
+                    self.output.push(' ');
+                    self.output.push_str(&modifier.to_string());
+                }
+
+                self.output.push(' ');
+                self.print_infix(list, "=", body);
+            }
+        }
+    }
+
+    /// Prints a tuple of [`Expr`]s. A tuple with one [`Expr`] is given a
+    /// trailing comma so that it is not parsed as a parenthesized [`Expr`].
+    fn print_tuple(&mut self, exprs: &[Expr]) {
+        self.output.push('(');
+
+        for (index, expr) in exprs.iter().enumerate() {
+            if index > 0 {
+                self.output.push_str(", ");
+            }
+
+            self.print_expr(expr);
+        }
+
+        if exprs.len() == 1 {
+            self.output.push(',');
+        }
+
+        self.output.push(')');
+    }
+
+    /// Prints a list of [`Expr`]s.
+    fn print_list(&mut self, exprs: &[Expr]) {
+        self.output.push('[');
+
+        for (index, expr) in exprs.iter().enumerate() {
+            if index > 0 {
+                self.output.push_str(", ");
+            }
+
+            self.print_expr(expr);
+        }
+
+        self.output.push(']');
+    }
+
+    /// Prints a block of statement [`Expr`]s. Blocks with more than one
+    /// statement are printed with one indented statement per line unless the
+    /// [`Layout`] is inline.
+    fn print_block(&mut self, stmts: &[Expr]) {
+        if stmts.is_empty() {
+            self.output.push_str("{}");
+        } else if stmts.len() == 1 || self.layout == Layout::Inline {
+            self.output.push_str("{ ");
+            self.print_sequence(stmts);
+            self.output.push_str(" }");
+        } else {
+            self.output.push('{');
+            self.depth += 1;
+            self.output.push('\n');
+            self.print_indent();
+            self.print_sequence(stmts);
+            self.depth -= 1;
+            self.output.push('\n');
+            self.print_indent();
+            self.output.push('}');
+        }
+    }
+
+    /// Prints a binary operator between two [`Expr`]s.
+    fn print_infix(&mut self, lhs: &Expr, op: &str, rhs: &Expr) {
+        self.print_expr(lhs);
+        self.output.push(' ');
+        self.output.push_str(op);
+        self.output.push(' ');
+        self.print_expr(rhs);
+    }
+
+    /// Prints the indentation for the current depth.
+    fn print_indent(&mut self) {
+        for _ in 0..self.depth {
+            self.output.push_str(INDENT);
+        }
+    }
+
+    /// Returns [`true`] if an [`Expr`] was parsed from a superscript exponent.
+    fn is_superscript(&self, expr: &Expr) -> bool {
+        matches!(expr.span.slice(self.source), "²" | "³")
+    }
+}
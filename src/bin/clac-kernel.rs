@@ -0,0 +1,10 @@
+use std::process::ExitCode;
+
+// The binary only runs the library's Jupyter kernel, so it does not use the
+// library's dependencies.
+use thiserror as _;
+
+/// Runs Clac's Jupyter kernel.
+fn main() -> ExitCode {
+    clac::run_kernel()
+}
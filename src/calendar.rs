@@ -0,0 +1,35 @@
+/// Converts a number of days since the Unix epoch to a `(year, month, day)`
+/// date in the proleptic Gregorian calendar.
+pub const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // See: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "month and day are always small and positive"
+    )]
+    (year, month as u32, day as u32)
+}
+
+/// Converts a `(year, month, day)` date in the proleptic Gregorian calendar to
+/// a number of days since the Unix epoch. The month must be from 1 to 12.
+pub const fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // See: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let (month, day) = (month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
@@ -1,6 +1,6 @@
 use std::fmt::{self, Display, Formatter, Write as _};
 
-use super::{BasicBlock, Cfg, Instruction, Label, Terminator};
+use super::{BasicBlock, Cfg, Comparison, Function, Instruction, Label, Terminator};
 
 impl Display for Cfg {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -23,6 +23,31 @@ impl Display for Cfg {
     }
 }
 
+impl Display for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = self
+            .name
+            .map_or_else(|| "<anonymous>".to_owned(), |s| s.to_string());
+        let params = self.params.iter().map(ToString::to_string);
+        write!(f, "{name}({})", params.collect::<Vec<_>>().join(", "))?;
+
+        if !self.captures.is_empty() {
+            let captures = self.captures.iter().map(ToString::to_string);
+            write!(f, " captures {}", captures.collect::<Vec<_>>().join(", "))?;
+        }
+
+        f.write_str(":")?;
+
+        // The function's body is indented so that it is nested under the
+        // instruction which pushes it.
+        for line in self.cfg.to_string().lines() {
+            write!(f, "\n{:8}{line}", "")?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Display for Label {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self.0 {
@@ -48,12 +73,14 @@ impl Display for BasicBlock {
 impl Display for Instruction {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let name = match self {
-            Self::PushLiteral(literal) => return write!(f, "{:16}{literal}", "push_literal"),
-            Self::PushFunction(_) => return write!(f, "{:16}...", "push_function"),
-            Self::PushGlobal(symbol) => return write!(f, "{:16}{symbol}", "push_global"),
-            Self::PushLocal(offset) => return write!(f, "{:16}[{offset}]", "push_local"),
-            Self::PushUpvar(offset) => return write!(f, "{:16}[{offset}]", "push_upvar"),
-            Self::Pop(count) => return write!(f, "{:16}({count})", "pop"),
+            Self::PushLiteral(literal) => return write!(f, "{:15} {literal}", "push_literal"),
+            Self::PushFunction(function) => {
+                return write!(f, "{:15} {function}", "push_function");
+            }
+            Self::PushGlobal(symbol) => return write!(f, "{:15} {symbol}", "push_global"),
+            Self::PushLocal(offset) => return write!(f, "{:15} [{offset}]", "push_local"),
+            Self::PushUpvar(offset) => return write!(f, "{:15} [{offset}]", "push_upvar"),
+            Self::Pop(count) => return write!(f, "{:15} ({count})", "pop"),
             Self::Print => "print",
             Self::Negate => "negate",
             Self::Not => "not",
@@ -68,11 +95,23 @@ impl Display for Instruction {
             Self::LessEqual => "less_equal",
             Self::Greater => "greater",
             Self::GreaterEqual => "greater_equal",
-            Self::StoreGlobal(symbol) => return write!(f, "{:16}{symbol}", "store_global"),
-            Self::StoreLocal(offset) => return write!(f, "{:16}[{offset}]", "store_local"),
+            Self::In => "in",
+            Self::MakeList(count) => return write!(f, "{:15} ({count})", "make_list"),
+            Self::MakeTuple(count) => return write!(f, "{:15} ({count})", "make_tuple"),
+            Self::Index => "index",
+            Self::Unpack(count) => return write!(f, "{:15} ({count})", "unpack"),
+            Self::AddLocalNumber(offset, value) => {
+                return write!(f, "{:15} [{offset}] {value}", "add_local_number");
+            }
+            Self::SubtractLocalNumber(offset, value) => {
+                return write!(f, "{:15} [{offset}] {value}", "subtract_local_number");
+            }
+            Self::StoreGlobal(symbol) => return write!(f, "{:15} {symbol}", "store_global"),
+            Self::StoreLocal(offset) => return write!(f, "{:15} [{offset}]", "store_local"),
             Self::DefineUpvar => "define_upvar",
-            Self::PopUpvars(count) => return write!(f, "{:16}({count})", "pop_upvars"),
+            Self::PopUpvars(count) => return write!(f, "{:15} ({count})", "pop_upvars"),
             Self::IntoClosure => "into_closure",
+            Self::IntoThunk => "into_thunk",
         };
 
         f.write_str(name)
@@ -83,12 +122,35 @@ impl Display for Terminator {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Halt => f.write_str("halt"),
-            Self::Jump(label) => write!(f, "{:16}{label}", "jump"),
+            Self::Jump(label) => write!(f, "{:15} {label}", "jump"),
             Self::Branch(then_label, else_label) => {
-                write!(f, "{:16}{then_label} else {else_label}", "branch")
+                write!(f, "{:15} {then_label} else {else_label}", "branch")
             }
-            Self::Call(arity, label) => write!(f, "{:16}({arity}) return {label}", "call"),
+            Self::BranchCompare(comparison, then_label, else_label) => {
+                write!(
+                    f,
+                    "{:15} {comparison} {then_label} else {else_label}",
+                    "branch_compare"
+                )
+            }
+            Self::Call(arity, label) => write!(f, "{:15} ({arity}) return {label}", "call"),
+            Self::TailCall(arity) => write!(f, "{:15} ({arity})", "tail_call"),
             Self::Return => f.write_str("return"),
         }
     }
 }
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterEqual => ">=",
+        };
+
+        f.write_str(symbol)
+    }
+}
@@ -1,23 +1,41 @@
 mod display;
+mod simplify;
 
-use std::rc::Rc;
+use std::{collections::HashMap, mem, rc::Rc};
 
-use crate::{ast::Literal, symbols::Symbol};
+#[cfg(feature = "threaded-dispatch")]
+use std::cell::OnceCell;
+
+#[cfg(feature = "threaded-dispatch")]
+use crate::interpret::ThreadedCode;
+use crate::{
+    ast::Literal,
+    spans::{SourceSpan, Span},
+    symbols::Symbol,
+};
 
 /// A control flow graph.
 #[derive(Debug)]
 pub struct Cfg {
-    // NOTE: This should be changed to a hash map or a similar structure if the
-    // basic blocks need to be rearranged (e.g. if CFG optimizations are added),
-    // but a vector has a faster lookup time.
+    // Basic blocks are indexed by their labels for a fast lookup time, so they
+    // must only be rearranged with `rearrange_basic_blocks`, which also remaps
+    // the labels and spans which refer to them.
     basic_blocks: Vec<BasicBlock>,
+
+    /// The source code that the `Cfg` was compiled from.
+    source: Rc<str>,
+
+    /// The map of code locations to source code [`Span`]s.
+    spans: HashMap<(Label, usize), Span>,
 }
 
 impl Cfg {
-    /// Creates a new `Cfg`.
-    pub fn new() -> Self {
+    /// Creates a new `Cfg` from the source code that it is compiled from.
+    pub fn new(source: Rc<str>) -> Self {
         let mut cfg = Self {
             basic_blocks: Vec::new(),
+            source,
+            spans: HashMap::new(),
         };
 
         let main_label = cfg.insert_basic_block();
@@ -30,11 +48,26 @@ impl Cfg {
         cfg
     }
 
+    /// Returns the number of [`Instruction`]s in the `Cfg`, including the
+    /// [`Instruction`]s of [`Function`]s which it pushes.
+    pub fn instruction_count(&self) -> usize {
+        self.basic_blocks
+            .iter()
+            .flat_map(|b| &b.instructions)
+            .map(|i| match i {
+                Instruction::PushFunction(function) => 1 + function.cfg.instruction_count(),
+                _ => 1,
+            })
+            .sum()
+    }
+
     /// Inserts a new [`BasicBlock`] into the `Cfg` and returns its [`Label`].
     pub fn insert_basic_block(&mut self) -> Label {
         self.basic_blocks.push(BasicBlock {
             instructions: Vec::new(),
             terminator: Terminator::Halt,
+            #[cfg(feature = "threaded-dispatch")]
+            threaded: OnceCell::new(),
         });
 
         Label(self.basic_blocks.len() - 1)
@@ -49,6 +82,75 @@ impl Cfg {
     pub fn basic_block_mut(&mut self, label: Label) -> &mut BasicBlock {
         &mut self.basic_blocks[label.0]
     }
+
+    /// Replaces every [`Label`] which a [`Terminator`] in the `Cfg` jumps to
+    /// with the [`Label`] returned by a function.
+    pub fn redirect_labels<F: FnMut(Label) -> Label>(&mut self, mut redirect: F) {
+        for basic_block in &mut self.basic_blocks {
+            for label in basic_block.terminator.labels_mut() {
+                *label = redirect(*label);
+            }
+        }
+    }
+
+    /// Rearranges the `Cfg`'s [`BasicBlock`]s into an order of [`Label`]s and
+    /// removes any [`BasicBlock`]s which are not in the order. The [`Label`]s
+    /// which [`Terminator`]s jump to and the [`Label`]s of source code
+    /// [`Span`]s are remapped to the new positions of their [`BasicBlock`]s.
+    /// The main [`BasicBlock`] must stay first, and the kept [`BasicBlock`]s
+    /// must not jump to removed [`BasicBlock`]s.
+    pub fn rearrange_basic_blocks(&mut self, order: &[Label]) {
+        debug_assert_eq!(
+            order.first(),
+            Some(&Label::default()),
+            "main basic block should stay first"
+        );
+
+        let mut new_labels = vec![None; self.basic_blocks.len()];
+
+        for (index, label) in order.iter().enumerate() {
+            new_labels[label.0] = Some(Label(index));
+        }
+
+        let mut old_basic_blocks: Vec<_> = mem::take(&mut self.basic_blocks)
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        self.basic_blocks = order
+            .iter()
+            .map(|l| {
+                old_basic_blocks[l.0]
+                    .take()
+                    .expect("labels should not be repeated")
+            })
+            .collect();
+
+        self.redirect_labels(|l| {
+            new_labels[l.0].expect("kept basic blocks should only jump to kept basic blocks")
+        });
+
+        self.spans = mem::take(&mut self.spans)
+            .into_iter()
+            .filter_map(|((label, index), span)| Some(((new_labels[label.0]?, index), span)))
+            .collect();
+    }
+
+    /// Sets the source code [`Span`] of the code at an index in a
+    /// [`BasicBlock`]. The index after the final [`Instruction`] refers to the
+    /// [`BasicBlock`]'s [`Terminator`].
+    pub fn set_span(&mut self, label: Label, index: usize, span: Span) {
+        self.spans.insert((label, index), span);
+    }
+
+    /// Returns the [`SourceSpan`] of the code at an index in a [`BasicBlock`].
+    /// This function returns [`None`] if the code has no [`Span`].
+    pub fn span(&self, label: Label, index: usize) -> Option<SourceSpan> {
+        self.spans.get(&(label, index)).map(|s| SourceSpan {
+            source: Rc::clone(&self.source),
+            span: *s,
+        })
+    }
 }
 
 /// A function.
@@ -59,6 +161,15 @@ pub struct Function {
 
     /// The number of parameters.
     pub arity: usize,
+
+    /// The name, if any.
+    pub name: Option<Symbol>,
+
+    /// The parameter names.
+    pub params: Box<[Symbol]>,
+
+    /// The names of the variables captured from outer functions.
+    pub captures: Box<[Symbol]>,
 }
 
 /// A label for a [`BasicBlock`].
@@ -74,6 +185,11 @@ pub struct BasicBlock {
 
     /// The [`Terminator`].
     pub terminator: Terminator,
+
+    /// The [`ThreadedCode`], which is created when the `BasicBlock` is first
+    /// interpreted.
+    #[cfg(feature = "threaded-dispatch")]
+    pub threaded: OnceCell<ThreadedCode>,
 }
 
 /// An instruction which can appear in the middle of a [`BasicBlock`].
@@ -85,7 +201,9 @@ pub enum Instruction {
     /// Pushes a [`Function`] value to the stack.
     PushFunction(Rc<Function>),
 
-    /// Loads a value from a global variable and pushes it to the stack.
+    /// Loads a value from a global variable's slot and pushes it to the stack.
+    /// The slot is indexed by the [`Symbol`], so it is resolved at compile
+    /// time.
     PushGlobal(Symbol),
 
     /// Loads a value from a stack frame offset and pushes it to the stack.
@@ -159,7 +277,40 @@ pub enum Instruction {
     /// the right-hand and the result is pushed to the stack.
     GreaterEqual,
 
-    /// Pops a value from the stack and stores it in a local variable.
+    /// Pops a collection value from the stack, then an element value. The
+    /// collection is tested for containing the element and the result is
+    /// pushed to the stack.
+    In,
+
+    /// Pops a number of element values from the stack, collects them into a
+    /// list in the order they were pushed, and pushes the list to the stack.
+    MakeList(usize),
+
+    /// Pops a number of element values from the stack, collects them into a
+    /// tuple in the order they were pushed, and pushes the tuple to the stack.
+    MakeTuple(usize),
+
+    /// Pops an index number value from the stack, then a list or tuple value.
+    /// The element at the index is pushed to the stack.
+    Index,
+
+    /// Checks that the value at the top of the stack is a tuple with a number
+    /// of elements without popping it.
+    Unpack(usize),
+
+    /// Loads a value from a stack frame offset, adds a number to it, and pushes
+    /// the result to the stack. This is a superinstruction for
+    /// [`Instruction::PushLocal`], a number [`Instruction::PushLiteral`], and
+    /// [`Instruction::Add`].
+    AddLocalNumber(usize, f64),
+
+    /// Loads a value from a stack frame offset, subtracts a number from it,
+    /// and pushes the result to the stack. This is a superinstruction for
+    /// [`Instruction::PushLocal`], a number [`Instruction::PushLiteral`], and
+    /// [`Instruction::Subtract`].
+    SubtractLocalNumber(usize, f64),
+
+    /// Pops a value from the stack and stores it in a global variable's slot.
     StoreGlobal(Symbol),
 
     /// Pops a value from the stack and stores it at a stack frame offset.
@@ -174,6 +325,10 @@ pub enum Instruction {
     /// Pops a [`Function`] value from the stack, converts it to a closure, and
     /// pushes the result to the stack.
     IntoClosure,
+
+    /// Pops a function value with no parameters from the stack, converts it to
+    /// a lazy value, and pushes the result to the stack.
+    IntoThunk,
 }
 
 /// A [`BasicBlock`]'s terminator.
@@ -189,9 +344,71 @@ pub enum Terminator {
     /// [`true`], or jumps to another [`Label`] if it is [`false`].
     Branch(Label, Label),
 
+    /// Pops two values from the stack and jumps to a [`Label`] if comparing
+    /// the first value to the second value with a [`Comparison`] is [`true`],
+    /// or jumps to another [`Label`] if it is [`false`].
+    BranchCompare(Comparison, Label, Label),
+
     /// Performs a call with an arity and returns to a [`Label`].
     Call(usize, Label),
 
+    /// Performs a call with an arity in place of the current function, so that
+    /// the callee returns to the current function's caller.
+    TailCall(usize),
+
     /// Pops a value from the top of the stack and returns it.
     Return,
 }
+
+impl Terminator {
+    /// Returns the [`Label`]s which the `Terminator` may jump to.
+    pub fn labels(&self) -> impl Iterator<Item = Label> {
+        let (first, second) = match *self {
+            Self::Jump(label) | Self::Call(_, label) => (Some(label), None),
+            Self::Branch(then_label, else_label)
+            | Self::BranchCompare(_, then_label, else_label) => {
+                (Some(then_label), Some(else_label))
+            }
+            Self::Halt | Self::TailCall(..) | Self::Return => (None, None),
+        };
+
+        first.into_iter().chain(second)
+    }
+
+    /// Returns mutable references to the [`Label`]s which the `Terminator` may
+    /// jump to.
+    fn labels_mut(&mut self) -> impl Iterator<Item = &mut Label> {
+        let (first, second) = match self {
+            Self::Jump(label) | Self::Call(_, label) => (Some(label), None),
+            Self::Branch(then_label, else_label)
+            | Self::BranchCompare(_, then_label, else_label) => {
+                (Some(then_label), Some(else_label))
+            }
+            Self::Halt | Self::TailCall(..) | Self::Return => (None, None),
+        };
+
+        first.into_iter().chain(second)
+    }
+}
+
+/// A comparison which can be fused into a [`Terminator::BranchCompare`].
+#[derive(Clone, Copy, Debug)]
+pub enum Comparison {
+    /// An equality comparison.
+    Equal,
+
+    /// An inequality comparison.
+    NotEqual,
+
+    /// A less than comparison.
+    Less,
+
+    /// A less than or equal to comparison.
+    LessEqual,
+
+    /// A greater than comparison.
+    Greater,
+
+    /// A greater than or equal to comparison.
+    GreaterEqual,
+}
@@ -0,0 +1,116 @@
+use std::mem;
+
+use super::{Cfg, Label, Terminator};
+
+impl Cfg {
+    /// Simplifies the `Cfg` by skipping empty basic blocks which only jump to
+    /// another basic block, merging straight-line basic blocks, and removing
+    /// unreachable basic blocks. The main basic block keeps the default
+    /// [`Label`].
+    pub fn simplify(&mut self) {
+        self.thread_jumps();
+        self.merge_basic_blocks();
+
+        let reachable = self.reachable_labels();
+        let order: Vec<Label> = (0..self.basic_blocks.len())
+            .map(Label)
+            .filter(|l| reachable[l.0])
+            .collect();
+
+        self.rearrange_basic_blocks(&order);
+    }
+
+    /// Redirects jumps to empty basic blocks which only jump to another basic
+    /// block so that they jump to the final basic block directly.
+    fn thread_jumps(&mut self) {
+        let block_count = self.basic_blocks.len();
+
+        let targets: Vec<Label> = (0..block_count)
+            .map(|index| {
+                let mut label = Label(index);
+
+                // Chains are followed for a limited number of steps so that a
+                // cycle of empty basic blocks does not loop forever.
+                for _ in 0..block_count {
+                    let basic_block = &self.basic_blocks[label.0];
+
+                    match basic_block.terminator {
+                        Terminator::Jump(target) if basic_block.instructions.is_empty() => {
+                            label = target;
+                        }
+                        _ => break,
+                    }
+                }
+
+                label
+            })
+            .collect();
+
+        self.redirect_labels(|l| targets[l.0]);
+    }
+
+    /// Merges each basic block which unconditionally jumps to a basic block
+    /// with no other reachable predecessors with that basic block. Merged
+    /// basic blocks are left empty and unreachable.
+    fn merge_basic_blocks(&mut self) {
+        let reachable = self.reachable_labels();
+        let mut predecessor_counts = vec![0_usize; self.basic_blocks.len()];
+
+        for (basic_block, _) in self
+            .basic_blocks
+            .iter()
+            .zip(&reachable)
+            .filter(|(_, r)| **r)
+        {
+            for label in basic_block.terminator.labels() {
+                predecessor_counts[label.0] += 1;
+            }
+        }
+
+        for index in (0..self.basic_blocks.len()).filter(|i| reachable[*i]) {
+            let label = Label(index);
+
+            while let Terminator::Jump(target) = self.basic_blocks[index].terminator
+                && target != label
+                && target != Label::default()
+                && predecessor_counts[target.0] == 1
+            {
+                let target_block = &mut self.basic_blocks[target.0];
+                let instructions = mem::take(&mut target_block.instructions);
+                let terminator = mem::replace(&mut target_block.terminator, Terminator::Halt);
+
+                // The jump's span is replaced by the target's spans, which are
+                // offset by the instructions that they are appended to.
+                let offset = self.basic_blocks[index].instructions.len();
+                self.spans.remove(&(label, offset));
+
+                let moved_spans: Vec<_> = self
+                    .spans
+                    .extract_if(|(l, _), _| *l == target)
+                    .map(|((_, i), span)| ((label, offset + i), span))
+                    .collect();
+
+                self.spans.extend(moved_spans);
+
+                let basic_block = &mut self.basic_blocks[index];
+                basic_block.instructions.extend(instructions);
+                basic_block.terminator = terminator;
+            }
+        }
+    }
+
+    /// Returns whether each basic block is reachable from the main basic
+    /// block, indexed by [`Label`].
+    fn reachable_labels(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.basic_blocks.len()];
+        let mut stack = vec![Label::default()];
+
+        while let Some(label) = stack.pop() {
+            if !mem::replace(&mut reachable[label.0], true) {
+                stack.extend(self.basic_blocks[label.0].terminator.labels());
+            }
+        }
+
+        reachable
+    }
+}
@@ -1,30 +1,46 @@
+#[cfg(test)]
+mod tests;
+
 mod stack;
 mod upvars;
 
-use std::mem;
+use std::{mem, rc::Rc};
 
 use crate::{
-    ast::{BinOp, UnOp},
-    cfg::{BasicBlock, Cfg, Function, Instruction, Label, Terminator},
+    ast::{BinOp, Literal, UnOp},
+    cfg::{BasicBlock, Cfg, Comparison, Function, Instruction, Label, Terminator},
     hir::{Expr, Hir, Stmt},
     locals::{Local, LocalTable},
+    spans::Span,
     symbols::Symbol,
 };
 
 use self::{stack::StackFrame, upvars::UpvarStack};
 
-/// Compiles [`Hir`] to a [`Cfg`] with a [`LocalTable`].
-pub fn compile_hir(hir: &Hir, locals: &LocalTable) -> Cfg {
-    let mut compiler = Compiler::new(locals);
+/// Compiles [`Hir`] to a [`Cfg`] with a [`LocalTable`] and the source code that
+/// the [`Hir`] was lowered from.
+pub fn compile_hir(hir: &Hir, locals: &LocalTable, source: &Rc<str>) -> Cfg {
+    let mut compiler = Compiler::new(locals, Rc::clone(source));
     compiler.compile_hir(hir);
     compiler.into_cfg()
 }
 
+/// Compiles a single [`Stmt`] to a [`Cfg`] with a [`LocalTable`] and the source
+/// code that the [`Stmt`] was lowered from.
+pub fn compile_stmt(stmt: &Stmt, locals: &LocalTable, source: &Rc<str>) -> Cfg {
+    let mut compiler = Compiler::new(locals, Rc::clone(source));
+    compiler.compile_stmt(stmt);
+    compiler.into_cfg()
+}
+
 /// A structure which compiles [`Hir`] to a [`Cfg`].
 struct Compiler<'loc> {
     /// The [`LocalTable`].
     locals: &'loc LocalTable,
 
+    /// The source code.
+    source: Rc<str>,
+
     /// The [`UpvarStack`].
     upvars: UpvarStack,
 
@@ -36,19 +52,29 @@ struct Compiler<'loc> {
 }
 
 impl<'loc> Compiler<'loc> {
-    /// Creates a new `Compiler` from a [`LocalTable`].
-    fn new(locals: &'loc LocalTable) -> Self {
+    /// Creates a new `Compiler` from a [`LocalTable`] and source code.
+    fn new(locals: &'loc LocalTable, source: Rc<str>) -> Self {
+        let function = FunctionContext::new(0, Rc::clone(&source));
+
         Self {
             locals,
+            source,
             upvars: UpvarStack::new(),
-            function: FunctionContext::new(0),
+            function,
             function_depth: 0,
         }
     }
 
     /// Consumes the `Compiler` and converts it to a [`Cfg`].
     fn into_cfg(self) -> Cfg {
-        self.function.cfg
+        debug_assert!(
+            self.upvars.is_balanced(),
+            "every upvar scope should be popped after compiling"
+        );
+
+        let mut cfg = self.function.cfg;
+        cfg.simplify();
+        cfg
     }
 
     /// Compiles [`Hir`].
@@ -67,7 +93,8 @@ impl<'loc> Compiler<'loc> {
     fn compile_stmt(&mut self, stmt: &Stmt) {
         match stmt {
             Stmt::Block(stmts) => self.compile_stmt_block(stmts),
-            Stmt::AssignGlobal(symbol, value) => self.compile_stmt_assign_global(*symbol, value),
+            Stmt::Sequence(stmts) => self.compile_stmts(stmts),
+            Stmt::AssignGlobal(symbol, value, _) => self.compile_stmt_assign_global(*symbol, value),
             Stmt::DefineLocal(id, value) => self.compile_stmt_define_local(*id, value),
             Stmt::Print(value) => self.compile_stmt_print(value),
             Stmt::Expr(expr) => self.compile_stmt_expr(expr),
@@ -98,8 +125,7 @@ impl<'loc> Compiler<'loc> {
         self.compile_expr(value);
 
         if self.locals.data(local).is_upvar {
-            self.append_instruction(Instruction::DefineUpvar);
-            self.upvars.push_upvar(local);
+            self.define_upvar(local);
         } else {
             self.function.stack_frame.push_local(local);
         }
@@ -120,15 +146,63 @@ impl<'loc> Compiler<'loc> {
     /// Compiles an [`Expr`].
     fn compile_expr(&mut self, expr: &Expr) {
         match expr {
-            Expr::Literal(literal) => self.append_instruction(Instruction::PushLiteral(*literal)),
-            Expr::Global(symbol) => self.append_instruction(Instruction::PushGlobal(*symbol)),
+            Expr::Literal(literal) => {
+                self.append_instruction(Instruction::PushLiteral(literal.clone()));
+            }
+            Expr::Global(symbol, span) => {
+                self.mark_span(*span);
+                self.append_instruction(Instruction::PushGlobal(*symbol));
+            }
             Expr::Local(local) => self.compile_expr_local(*local),
-            Expr::Block(stmts, expr) => self.compile_expr_block(stmts, expr),
+            Expr::Block(stmts, expr) => self.compile_expr_block(stmts, expr, false),
             Expr::Function(name, params, body) => self.compile_expr_function(*name, params, body),
-            Expr::Call(callee, args) => self.compile_expr_call(callee, args),
-            Expr::Unary(op, rhs) => self.compile_expr_unary(*op, rhs),
-            Expr::Binary(op, lhs, rhs) => self.compile_expr_binary(*op, lhs, rhs),
-            Expr::Cond(cond, then, or) => self.compile_expr_cond(cond, then, or),
+            Expr::Call(callee, args, span) => self.compile_expr_call(callee, args, *span, false),
+            Expr::List(elems) => self.compile_expr_list(elems),
+            Expr::Tuple(elems) => self.compile_expr_tuple(elems),
+            Expr::Index(list, index, span) => self.compile_expr_index(list, index, *span),
+            Expr::Unpack(tuple, count, span) => {
+                self.compile_expr(tuple);
+                self.mark_span(*span);
+                self.append_instruction(Instruction::Unpack(*count));
+            }
+            Expr::Unary(op, rhs, span) => self.compile_expr_unary(*op, rhs, *span),
+            Expr::Binary(op, lhs, rhs, span) => self.compile_expr_binary(*op, lhs, rhs, *span),
+            Expr::Lazy(function) => {
+                self.compile_expr(function);
+                self.append_instruction(Instruction::IntoThunk);
+            }
+            Expr::Loop(locals, values, body) => {
+                self.compile_expr_loop(locals, values, body, false);
+            }
+            Expr::Recur(args) => self.compile_expr_recur(args),
+            Expr::While(locals, values, cond, body, exit, span) => {
+                self.compile_expr_while(locals, values, cond, body, exit, *span);
+            }
+            Expr::Cond(cond, then, or, span) => {
+                self.compile_expr_cond(cond, then, or, *span, false);
+            }
+        }
+    }
+
+    /// Compiles an [`Expr`] in a tail position, where its value is returned
+    /// from the current function. Calls in tail positions are compiled to tail
+    /// calls so that they do not grow the stack.
+    fn compile_tail_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Block(stmts, expr) => self.compile_expr_block(stmts, expr, true),
+            Expr::Call(callee, args, span) => self.compile_expr_call(callee, args, *span, true),
+            Expr::Loop(locals, values, body) => self.compile_expr_loop(locals, values, body, true),
+            Expr::Cond(cond, then, or, span) => self.compile_expr_cond(cond, then, or, *span, true),
+            _ => self.compile_expr(expr),
+        }
+    }
+
+    /// Compiles an [`Expr`] in a tail position if a condition is [`true`].
+    fn compile_expr_in_tail(&mut self, expr: &Expr, is_tail: bool) {
+        if is_tail {
+            self.compile_tail_expr(expr);
+        } else {
+            self.compile_expr(expr);
         }
     }
 
@@ -140,19 +214,23 @@ impl<'loc> Compiler<'loc> {
             let offset = self.upvars.upvar_offset(local);
             self.append_instruction(Instruction::PushUpvar(offset));
             self.function.access_upvar(local_data.function_depth);
+
+            if local_data.function_depth < self.function_depth {
+                self.function.capture(local);
+            }
         } else {
             let offset = self.function.stack_frame.local_offset(local);
             self.append_instruction(Instruction::PushLocal(offset));
         }
     }
 
-    /// Compiles a block [`Expr`].
-    fn compile_expr_block(&mut self, stmts: &[Stmt], expr: &Expr) {
+    /// Compiles a block [`Expr`], which may be in a tail position.
+    fn compile_expr_block(&mut self, stmts: &[Stmt], expr: &Expr, is_tail: bool) {
         self.upvars.push_scope();
 
         self.function.stack_frame.push_scope();
         self.compile_stmts(stmts);
-        self.compile_expr(expr);
+        self.compile_expr_in_tail(expr, is_tail);
         let local_count = self.function.stack_frame.pop_scope();
 
         if local_count > 0 {
@@ -174,7 +252,7 @@ impl<'loc> Compiler<'loc> {
         self.function_depth += 1;
         let mut other_function = mem::replace(
             &mut self.function,
-            FunctionContext::new(self.function_depth),
+            FunctionContext::new(self.function_depth, Rc::clone(&self.source)),
         );
 
         // At this point during runtime, the caller has already placed the
@@ -189,8 +267,7 @@ impl<'loc> Compiler<'loc> {
             if self.locals.data(local).is_upvar {
                 self.function.stack_frame.push_temp();
                 self.append_instruction(Instruction::PushLocal(0));
-                self.append_instruction(Instruction::DefineUpvar);
-                self.upvars.push_upvar(local);
+                self.define_upvar(local);
             } else {
                 self.function.stack_frame.push_callee(local);
             }
@@ -203,14 +280,13 @@ impl<'loc> Compiler<'loc> {
                 let offset = self.function.stack_frame.len();
                 self.function.stack_frame.push_temp();
                 self.append_instruction(Instruction::PushLocal(offset));
-                self.append_instruction(Instruction::DefineUpvar);
-                self.upvars.push_upvar(local);
+                self.define_upvar(local);
             } else {
                 self.function.stack_frame.push_param(local);
             }
         }
 
-        self.compile_expr(body);
+        self.compile_tail_expr(body);
         let upvar_count = self.upvars.pop_scope();
         self.append_pop_upvars_instruction(upvar_count);
         self.basic_block_mut().terminator = Terminator::Return;
@@ -219,10 +295,27 @@ impl<'loc> Compiler<'loc> {
         self.function_depth -= 1;
         let upvar_function_depth = other_function.min_upvar_function_depth;
 
+        // Variables captured from outside the outer function are also captured
+        // by the outer function.
+        for local in other_function.captures.iter().copied() {
+            if self.locals.data(local).function_depth < self.function_depth {
+                self.function.capture(local);
+            }
+        }
+
+        let symbol = |local| self.locals.data(local).symbol;
+        let defines_upvars = other_function.defines_upvars;
+        self.function.defines_upvars |= defines_upvars;
+        let mut cfg = other_function.cfg;
+        cfg.simplify();
+
         self.append_instruction(Instruction::PushFunction(
             Function {
-                cfg: other_function.cfg,
+                cfg,
                 arity: params.len(),
+                name: name.map(symbol),
+                params: params.iter().copied().map(symbol).collect(),
+                captures: other_function.captures.into_iter().map(symbol).collect(),
             }
             .into(),
         ));
@@ -235,11 +328,16 @@ impl<'loc> Compiler<'loc> {
             // If the accessed upvar is declared outside of the outer function,
             // then the outer function may also need to be a closure.
             self.function.access_upvar(upvar_function_depth);
+        } else if defines_upvars && self.upvars.len() > 0 {
+            // Functions start with an empty upvar stack, but the inner
+            // function's upvars are offset by the upvars declared outside of
+            // it, so it needs a closure to start with those upvars.
+            self.append_instruction(Instruction::IntoClosure);
         }
     }
 
-    /// Compiles a function call [`Expr`].
-    fn compile_expr_call(&mut self, callee: &Expr, args: &[Expr]) {
+    /// Compiles a function call [`Expr`], which may be in a tail position.
+    fn compile_expr_call(&mut self, callee: &Expr, args: &[Expr], span: Span, is_tail: bool) {
         self.compile_expr(callee);
         self.function.stack_frame.push_temp();
 
@@ -248,20 +346,26 @@ impl<'loc> Compiler<'loc> {
             self.function.stack_frame.push_temp();
         }
 
+        // Code after a tail call is unreachable, but is still compiled into the
+        // return basic block as if the call had returned.
         let arity = args.len();
         let return_label = self.cfg_mut().insert_basic_block();
-        let terminator = mem::replace(
-            &mut self.basic_block_mut().terminator,
-            Terminator::Call(arity, return_label),
-        );
+        let call = if is_tail {
+            Terminator::TailCall(arity)
+        } else {
+            Terminator::Call(arity, return_label)
+        };
 
+        let terminator = mem::replace(&mut self.basic_block_mut().terminator, call);
+
+        self.mark_span(span);
         self.set_label(return_label);
         self.function.stack_frame.pop_temps(arity + 1);
         self.basic_block_mut().terminator = terminator;
     }
 
     /// Compiles a unary [`Expr`].
-    fn compile_expr_unary(&mut self, op: UnOp, rhs: &Expr) {
+    fn compile_expr_unary(&mut self, op: UnOp, rhs: &Expr, span: Span) {
         self.compile_expr(rhs);
 
         let instruction = match op {
@@ -269,16 +373,78 @@ impl<'loc> Compiler<'loc> {
             UnOp::Not => Instruction::Not,
         };
 
+        self.mark_span(span);
         self.append_instruction(instruction);
     }
 
+    /// Compiles a list [`Expr`].
+    fn compile_expr_list(&mut self, elems: &[Expr]) {
+        for elem in elems {
+            self.compile_expr(elem);
+            self.function.stack_frame.push_temp();
+        }
+
+        self.append_instruction(Instruction::MakeList(elems.len()));
+        self.function.stack_frame.pop_temps(elems.len());
+    }
+
+    /// Compiles a tuple [`Expr`].
+    fn compile_expr_tuple(&mut self, elems: &[Expr]) {
+        for elem in elems {
+            self.compile_expr(elem);
+            self.function.stack_frame.push_temp();
+        }
+
+        self.append_instruction(Instruction::MakeTuple(elems.len()));
+        self.function.stack_frame.pop_temps(elems.len());
+    }
+
+    /// Compiles an index [`Expr`].
+    fn compile_expr_index(&mut self, list: &Expr, index: &Expr, span: Span) {
+        self.compile_expr(list);
+        self.function.stack_frame.push_temp();
+        self.compile_expr(index);
+        self.mark_span(span);
+        self.append_instruction(Instruction::Index);
+        self.function.stack_frame.pop_temps(1);
+    }
+
     /// Compiles a binary [`Expr`].
-    fn compile_expr_binary(&mut self, op: BinOp, lhs: &Expr, rhs: &Expr) {
-        self.compile_expr(lhs);
+    fn compile_expr_binary(&mut self, op: BinOp, lhs: &Expr, rhs: &Expr, span: Span) {
+        // Chains of operations nest to the left and can be thousands of
+        // operations long, so they are compiled in a loop instead of recursing
+        // into each left operand.
+        let mut operations = vec![(op, rhs, span)];
+        let mut first = lhs;
+
+        while let Expr::Binary(inner_op, inner_lhs, inner_rhs, inner_span) = first {
+            operations.push((*inner_op, inner_rhs, *inner_span));
+            first = inner_lhs;
+        }
+
+        self.compile_expr(first);
+
+        for (link_op, link_rhs, link_span) in operations.into_iter().rev() {
+            self.compile_operation(link_op, link_rhs, link_span);
+        }
+    }
+
+    /// Compiles a binary operation on the value on top of the stack and a
+    /// right-hand side [`Expr`].
+    fn compile_operation(&mut self, op: BinOp, rhs: &Expr, span: Span) {
         self.function.stack_frame.push_temp();
         self.compile_expr(rhs);
 
         let instruction = match op {
+            BinOp::Add | BinOp::Subtract
+                if let Some((offset, value)) = self.take_local_number_operands() =>
+            {
+                if matches!(op, BinOp::Add) {
+                    Instruction::AddLocalNumber(offset, value)
+                } else {
+                    Instruction::SubtractLocalNumber(offset, value)
+                }
+            }
             BinOp::Add => Instruction::Add,
             BinOp::Subtract => Instruction::Subtract,
             BinOp::Multiply => Instruction::Multiply,
@@ -290,35 +456,240 @@ impl<'loc> Compiler<'loc> {
             BinOp::LessEqual => Instruction::LessEqual,
             BinOp::Greater => Instruction::Greater,
             BinOp::GreaterEqual => Instruction::GreaterEqual,
+            BinOp::In => Instruction::In,
         };
 
+        self.mark_span(span);
         self.append_instruction(instruction);
         self.function.stack_frame.pop_temps(1);
     }
 
-    /// Compiles a ternary conditional [`Expr`].
-    fn compile_expr_cond(&mut self, cond: &Expr, then_expr: &Expr, else_expr: &Expr) {
-        self.compile_expr(cond);
+    /// Removes a [`Instruction::PushLocal`] followed by a number
+    /// [`Instruction::PushLiteral`] from the end of the current [`BasicBlock`]
+    /// so that they can be fused into a superinstruction, then returns their
+    /// stack frame offset and number. This function returns [`None`] if the
+    /// current [`BasicBlock`] does not end with these [`Instruction`]s.
+    fn take_local_number_operands(&mut self) -> Option<(usize, f64)> {
+        let instructions = &mut self.basic_block_mut().instructions;
+
+        let [
+            ..,
+            Instruction::PushLocal(offset),
+            Instruction::PushLiteral(Literal::Number(value)),
+        ] = instructions.as_slice()
+        else {
+            return None;
+        };
+
+        let operands = (*offset, *value);
+        instructions.truncate(instructions.len() - 2);
+        Some(operands)
+    }
+
+    /// Compiles a ternary conditional [`Expr`], which may be in a tail
+    /// position.
+    fn compile_expr_cond(
+        &mut self,
+        cond: &Expr,
+        then_expr: &Expr,
+        else_expr: &Expr,
+        span: Span,
+        is_tail: bool,
+    ) {
         let then_label = self.cfg_mut().insert_basic_block();
         let else_label = self.cfg_mut().insert_basic_block();
         let join_label = self.cfg_mut().insert_basic_block();
-        let terminator = mem::replace(
-            &mut self.basic_block_mut().terminator,
-            Terminator::Branch(then_label, else_label),
-        );
-
+        let terminator = self.compile_branch(cond, span, then_label, else_label);
         self.set_label(then_label);
-        self.compile_expr(then_expr);
+        self.compile_expr_in_tail(then_expr, is_tail);
         self.basic_block_mut().terminator = Terminator::Jump(join_label);
 
         self.set_label(else_label);
-        self.compile_expr(else_expr);
+        self.compile_expr_in_tail(else_expr, is_tail);
         self.basic_block_mut().terminator = Terminator::Jump(join_label);
 
         self.set_label(join_label);
         self.basic_block_mut().terminator = terminator;
     }
 
+    /// Compiles a condition [`Expr`] with its [`Span`] and terminates the
+    /// current [`BasicBlock`] with a branch to a then [`Label`] or an else
+    /// [`Label`]. The current [`BasicBlock`]'s previous [`Terminator`] is
+    /// returned.
+    fn compile_branch(
+        &mut self,
+        cond: &Expr,
+        span: Span,
+        then_label: Label,
+        else_label: Label,
+    ) -> Terminator {
+        // Comparisons are fused into the branch so that their Boolean result
+        // is never pushed to the stack.
+        let comparison = if let Expr::Binary(op, lhs, rhs, cond_span) = cond
+            && let Some(comparison) = comparison(*op)
+        {
+            self.compile_expr(lhs);
+            self.function.stack_frame.push_temp();
+            self.compile_expr(rhs);
+            self.function.stack_frame.pop_temps(1);
+            Some((comparison, *cond_span))
+        } else {
+            self.compile_expr(cond);
+            None
+        };
+
+        let (branch, span) = match comparison {
+            Some((comparison, cond_span)) => (
+                Terminator::BranchCompare(comparison, then_label, else_label),
+                cond_span,
+            ),
+            None => (Terminator::Branch(then_label, else_label), span),
+        };
+
+        let terminator = mem::replace(&mut self.basic_block_mut().terminator, branch);
+        self.mark_span(span);
+        terminator
+    }
+
+    /// Compiles a loop [`Expr`], which may be in a tail position.
+    fn compile_expr_loop(&mut self, locals: &[Local], values: &[Expr], body: &Expr, is_tail: bool) {
+        let (context, terminator) = self.begin_loop(locals, values);
+        self.function.loops.push(context);
+        self.compile_expr_in_tail(body, is_tail);
+        self.function.loops.pop();
+        self.end_loop(context, terminator);
+    }
+
+    /// Compiles a while loop [`Expr`]. The exit [`Expr`] is compiled outside of
+    /// the loop's [`LoopContext`] so that it can recur to an outer loop.
+    fn compile_expr_while(
+        &mut self,
+        locals: &[Local],
+        values: &[Expr],
+        cond: &Expr,
+        body: &Expr,
+        exit: &Expr,
+        span: Span,
+    ) {
+        let (context, terminator) = self.begin_loop(locals, values);
+        let body_label = self.cfg_mut().insert_basic_block();
+        let exit_label = self.cfg_mut().insert_basic_block();
+        self.compile_branch(cond, span, body_label, exit_label);
+
+        // The body ends with a recur expression, so the basic block after it
+        // is unreachable.
+        self.set_label(body_label);
+        self.function.loops.push(context);
+        self.compile_expr(body);
+        self.function.loops.pop();
+
+        self.set_label(exit_label);
+        self.compile_expr(exit);
+        self.end_loop(context, terminator);
+    }
+
+    /// Compiles the initial values of a loop's variable [`Local`]s and starts
+    /// the loop's header [`BasicBlock`]. The loop's [`LoopContext`] and the
+    /// previous [`Terminator`] are returned.
+    fn begin_loop(&mut self, locals: &[Local], values: &[Expr]) -> (LoopContext, Terminator) {
+        // Loop variables are kept in stack slots so that recur expressions can
+        // overwrite them. Loop variables which are upvars are also defined as
+        // upvars from their stack slots at the start of each iteration.
+        self.function.stack_frame.push_scope();
+        let slot_offset = self.function.stack_frame.len();
+
+        for (local, value) in locals.iter().copied().zip(values) {
+            self.compile_expr(value);
+            self.function.stack_frame.push_local(local);
+        }
+
+        let header_label = self.cfg_mut().insert_basic_block();
+        let terminator = mem::replace(
+            &mut self.basic_block_mut().terminator,
+            Terminator::Jump(header_label),
+        );
+
+        self.set_label(header_label);
+        self.upvars.push_scope();
+        let upvar_offset = self.upvars.len();
+
+        for (index, local) in locals.iter().copied().enumerate() {
+            if self.locals.data(local).is_upvar {
+                self.append_instruction(Instruction::PushLocal(slot_offset + index));
+                self.define_upvar(local);
+            }
+        }
+
+        let context = LoopContext {
+            header_label,
+            slot_offset,
+            upvar_offset,
+        };
+
+        (context, terminator)
+    }
+
+    /// Ends a loop with its [`LoopContext`] by replacing its variables with
+    /// its value and restoring the previous [`Terminator`].
+    fn end_loop(&mut self, context: LoopContext, terminator: Terminator) {
+        let slot_offset = context.slot_offset;
+        let upvar_count = self.upvars.pop_scope();
+        self.append_pop_upvars_instruction(upvar_count);
+        let local_count = self.function.stack_frame.pop_scope();
+
+        if local_count > 0 {
+            self.append_instruction(Instruction::StoreLocal(slot_offset));
+            self.append_pop_instruction(local_count - 1);
+        }
+
+        self.basic_block_mut().terminator = terminator;
+    }
+
+    /// Compiles a recur [`Expr`].
+    fn compile_expr_recur(&mut self, args: &[Expr]) {
+        let context = *self
+            .function
+            .loops
+            .last()
+            .expect("recur expressions should be inside a loop");
+
+        for arg in args {
+            self.compile_expr(arg);
+            self.function.stack_frame.push_temp();
+        }
+
+        for index in (0..args.len()).rev() {
+            self.append_instruction(Instruction::StoreLocal(context.slot_offset + index));
+        }
+
+        self.function.stack_frame.pop_temps(args.len());
+
+        // Recur expressions are in tail positions, so only the local
+        // variables of blocks inside the loop remain above the loop variables.
+        let local_count = self.function.stack_frame.len() - context.slot_offset - args.len();
+        self.append_pop_instruction(local_count);
+        let upvar_count = self.upvars.len() - context.upvar_offset;
+        self.append_pop_upvars_instruction(upvar_count);
+
+        // Code after the jump is unreachable, but is still compiled into a new
+        // basic block as if the recur expression had a value.
+        let unreachable_label = self.cfg_mut().insert_basic_block();
+        let terminator = mem::replace(
+            &mut self.basic_block_mut().terminator,
+            Terminator::Jump(context.header_label),
+        );
+
+        self.set_label(unreachable_label);
+        self.basic_block_mut().terminator = terminator;
+    }
+
+    /// Defines an upvar [`Local`] from the value on top of the stack.
+    fn define_upvar(&mut self, local: Local) {
+        self.append_instruction(Instruction::DefineUpvar);
+        self.upvars.push_upvar(local);
+        self.function.defines_upvars = true;
+    }
+
     /// Returns a mutable reference to the current [`Cfg`].
     const fn cfg_mut(&mut self) -> &mut Cfg {
         &mut self.function.cfg
@@ -355,6 +726,31 @@ impl<'loc> Compiler<'loc> {
     fn append_instruction(&mut self, instruction: Instruction) {
         self.basic_block_mut().instructions.push(instruction);
     }
+
+    /// Marks the next [`Instruction`] appended to the current [`BasicBlock`]
+    /// with a source code [`Span`]. If no more [`Instruction`]s are appended,
+    /// then the [`BasicBlock`]'s [`Terminator`] is marked instead.
+    fn mark_span(&mut self, span: Span) {
+        let label = self.function.label;
+        let index = self.basic_block_mut().instructions.len();
+        self.cfg_mut().set_span(label, index, span);
+    }
+}
+
+/// Returns the [`Comparison`] for a [`BinOp`]. This function returns [`None`]
+/// if the [`BinOp`] is not a comparison.
+const fn comparison(op: BinOp) -> Option<Comparison> {
+    let comparison = match op {
+        BinOp::Equal => Comparison::Equal,
+        BinOp::NotEqual => Comparison::NotEqual,
+        BinOp::Less => Comparison::Less,
+        BinOp::LessEqual => Comparison::LessEqual,
+        BinOp::Greater => Comparison::Greater,
+        BinOp::GreaterEqual => Comparison::GreaterEqual,
+        _ => return None,
+    };
+
+    Some(comparison)
 }
 
 /// Context for compiling a [`Function`].
@@ -370,16 +766,30 @@ struct FunctionContext {
 
     /// The minimum function depth where an accessed upvar was declared.
     min_upvar_function_depth: usize,
+
+    /// Whether any upvars are defined in the function or the functions nested
+    /// inside it, which makes their upvar stack offsets depend on the upvars
+    /// declared outside of the function.
+    defines_upvars: bool,
+
+    /// The [`Local`]s captured from outer functions.
+    captures: Vec<Local>,
+
+    /// The stack of [`LoopContext`]s for the loops being compiled.
+    loops: Vec<LoopContext>,
 }
 
 impl FunctionContext {
-    /// Creates a new `FunctionContext` at a function depth.
-    fn new(function_depth: usize) -> Self {
+    /// Creates a new `FunctionContext` at a function depth from source code.
+    fn new(function_depth: usize, source: Rc<str>) -> Self {
         Self {
-            cfg: Cfg::new(),
+            cfg: Cfg::new(source),
             label: Label::default(),
             stack_frame: StackFrame::new(),
             min_upvar_function_depth: function_depth,
+            defines_upvars: false,
+            captures: Vec::new(),
+            loops: Vec::new(),
         }
     }
 
@@ -387,4 +797,24 @@ impl FunctionContext {
     fn access_upvar(&mut self, function_depth: usize) {
         self.min_upvar_function_depth = self.min_upvar_function_depth.min(function_depth);
     }
+
+    /// Marks a [`Local`] being captured from an outer function.
+    fn capture(&mut self, local: Local) {
+        if !self.captures.contains(&local) {
+            self.captures.push(local);
+        }
+    }
+}
+
+/// Context for compiling recur expressions inside a loop.
+#[derive(Clone, Copy)]
+struct LoopContext {
+    /// The [`Label`] at the start of each iteration.
+    header_label: Label,
+
+    /// The stack frame offset to the first loop variable.
+    slot_offset: usize,
+
+    /// The upvar stack offset before the loop's upvars are defined.
+    upvar_offset: usize,
 }
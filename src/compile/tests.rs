@@ -0,0 +1,124 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use super::*;
+use crate::{
+    interpret::Globals,
+    lower::{self, GlobalMode},
+    parse::{self, Operators},
+    settings::Settings,
+};
+
+/// The name of the environment variable which causes golden files to be
+/// rewritten instead of compared.
+const BLESS_VAR: &str = "CLAC_BLESS";
+
+/// Compiles source code and returns its disassembled [`Cfg`].
+fn disassemble(source: &str) -> String {
+    let ast = parse::parse_source(source, &Settings::new(), &mut Operators::new())
+        .expect("source code should parse");
+    let mut locals = LocalTable::new();
+    let mut warnings = Vec::new();
+
+    let hir = lower::lower_ast(
+        &ast,
+        &mut Globals::new(),
+        &mut locals,
+        GlobalMode::Sealed,
+        &mut warnings,
+    )
+    .expect("source code should lower");
+
+    let cfg = compile_hir(&hir, &locals, &Rc::from(source));
+    format!("{cfg}\n")
+}
+
+/// Returns the paths to the source code snippets in the `golden` directory,
+/// sorted by name.
+fn golden_snippet_paths() -> Vec<PathBuf> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/compile/golden");
+    let entries = fs::read_dir(&directory).expect("golden directory should be readable");
+
+    let mut paths: Vec<PathBuf> = entries
+        .map(|e| e.expect("golden directory entry should be readable").path())
+        .filter(|p| p.extension().is_some_and(|e| e == "clac"))
+        .collect();
+
+    paths.sort();
+    paths
+}
+
+/// Tests that the source code snippets in the `golden` directory compile to
+/// the disassembled [`Cfg`]s in their matching `.cfg` files. Set the
+/// `CLAC_BLESS` environment variable to rewrite the `.cfg` files after an
+/// intended change to code generation.
+#[test]
+fn cfgs_match_golden_files() {
+    let is_blessing = env::var_os(BLESS_VAR).is_some();
+    let paths = golden_snippet_paths();
+    let mut mismatches = Vec::new();
+    assert!(!paths.is_empty(), "there should be golden snippets");
+
+    for path in paths {
+        let source = fs::read_to_string(&path).expect("snippet should be readable");
+        let actual = disassemble(&source);
+        let golden_path = path.with_extension("cfg");
+
+        if is_blessing {
+            fs::write(&golden_path, actual).expect("golden file should be writable");
+            continue;
+        }
+
+        let expected = fs::read_to_string(&golden_path).unwrap_or_default();
+
+        // Golden files may be checked out with Windows line endings.
+        if expected.replace("\r\n", "\n") != actual {
+            mismatches.push(format!(
+                "{}:\n{actual}",
+                golden_path.file_name().unwrap_or_default().display()
+            ));
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "disassembled CFGs do not match their golden files, rerun with \
+        '{BLESS_VAR}=1' if this is intended:\n\n{}",
+        mismatches.join("\n")
+    );
+}
+
+/// Tests that simplifying a [`Cfg`] merges straight-line basic blocks, removes
+/// unreachable basic blocks, and keeps the source code [`Span`]s of moved
+/// code.
+#[test]
+fn simplified_cfgs_keep_spans() {
+    let mut cfg = Cfg::new(Rc::from("-1"));
+    let unreachable_label = cfg.insert_basic_block();
+    let empty_label = cfg.insert_basic_block();
+    let negate_label = cfg.insert_basic_block();
+
+    let main = cfg.basic_block_mut(Label::default());
+    main.instructions
+        .push(Instruction::PushLiteral(Literal::Number(1.0)));
+    main.terminator = Terminator::Jump(empty_label);
+    cfg.basic_block_mut(unreachable_label).terminator = Terminator::Jump(negate_label);
+    cfg.basic_block_mut(empty_label).terminator = Terminator::Jump(negate_label);
+    cfg.basic_block_mut(negate_label)
+        .instructions
+        .push(Instruction::Negate);
+    cfg.set_span(negate_label, 0, Span::new(0, 1));
+
+    cfg.simplify();
+
+    assert_eq!(
+        cfg.to_string(),
+        "main:\n        push_literal    1\n        negate\n        halt"
+    );
+    assert_eq!(
+        cfg.span(Label::default(), 1).map(|s| s.span),
+        Some(Span::new(0, 1))
+    );
+}
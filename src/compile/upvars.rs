@@ -16,6 +16,17 @@ impl UpvarStack {
         Self::default()
     }
 
+    /// Returns the number of upvars in the `UpvarStack`.
+    pub const fn len(&self) -> usize {
+        self.upvars.len()
+    }
+
+    /// Returns [`true`] if every scope pushed to the `UpvarStack` has been
+    /// popped.
+    pub const fn is_balanced(&self) -> bool {
+        self.upvars.is_empty() && self.scope_offsets.is_empty()
+    }
+
     /// Returns an upvar's upvar stack offset from its [`Local`].
     pub fn upvar_offset(&self, local: Local) -> usize {
         self.upvars
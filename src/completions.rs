@@ -0,0 +1,219 @@
+use crate::args::{COMMANDS, OPTIONS, OptionInfo, ValueKind};
+
+/// A shell which completion scripts can be generated for.
+#[derive(Clone, Copy)]
+pub enum Shell {
+    /// The Bourne Again Shell.
+    Bash,
+
+    /// The Z shell.
+    Zsh,
+
+    /// The friendly interactive shell.
+    Fish,
+}
+
+impl Shell {
+    /// Creates a new `Shell` from its name. This function returns [`None`] if
+    /// the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// Generates a completion script for a [`Shell`] from the command line option
+/// and subcommand metadata.
+pub fn generate(shell: Shell) -> String {
+    let lines = match shell {
+        Shell::Bash => generate_bash(),
+        Shell::Zsh => generate_zsh(),
+        Shell::Fish => generate_fish(),
+    };
+
+    let mut script = lines.join("\n");
+    script.push('\n');
+    script
+}
+
+/// Generates the lines of a completion script for [`Shell::Bash`].
+fn generate_bash() -> Vec<String> {
+    let mut lines = vec![
+        "_clac() {".to_owned(),
+        "    local cur=\"${COMP_WORDS[COMP_CWORD]}\"".to_owned(),
+        "    local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"".to_owned(),
+        String::new(),
+        "    case \"$prev\" in".to_owned(),
+    ];
+
+    let values = OPTIONS
+        .iter()
+        .filter_map(|o| Some((option_names(o).join("|"), o.value.as_ref()?)))
+        .chain(
+            COMMANDS
+                .iter()
+                .filter_map(|c| Some((c.name.to_owned(), c.value.as_ref()?))),
+        );
+
+    for (pattern, value) in values {
+        let reply = match value {
+            ValueKind::File => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_owned(),
+            ValueKind::Number => "COMPREPLY=()".to_owned(),
+            ValueKind::Choice(names) => {
+                format!(
+                    "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                    names.join(" ")
+                )
+            }
+        };
+
+        lines.push(format!("        {pattern}) {reply}; return ;;"));
+    }
+
+    let options: Vec<&str> = OPTIONS.iter().flat_map(option_names).collect();
+    let commands: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+
+    lines.extend([
+        "    esac".to_owned(),
+        String::new(),
+        format!("    local words=\"{}\"", options.join(" ")),
+        String::new(),
+        "    if [[ $COMP_CWORD -eq 1 ]]; then".to_owned(),
+        format!("        words=\"{} $words\"", commands.join(" ")),
+        "    fi".to_owned(),
+        String::new(),
+        "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))".to_owned(),
+        "}".to_owned(),
+        String::new(),
+        "complete -F _clac clac".to_owned(),
+    ]);
+
+    lines
+}
+
+/// Generates the lines of a completion script for [`Shell::Zsh`].
+fn generate_zsh() -> Vec<String> {
+    let mut lines = vec![
+        "#compdef clac".to_owned(),
+        String::new(),
+        "_clac() {".to_owned(),
+    ];
+
+    for (command, value) in COMMANDS.iter().filter_map(|c| Some((c, c.value.as_ref()?))) {
+        lines.extend([
+            format!(
+                "    if (( CURRENT == 3 )) && [[ $words[2] == {} ]]; then",
+                command.name
+            ),
+            format!(
+                "        _arguments '1: :({})' '2:value:{}'",
+                command.name,
+                zsh_action(value)
+            ),
+            "        return".to_owned(),
+            "    fi".to_owned(),
+            String::new(),
+        ]);
+    }
+
+    lines.push("    _arguments -s \\".to_owned());
+
+    for option in OPTIONS {
+        let names = option_names(option);
+        let description = option.description;
+
+        let action = option
+            .value
+            .as_ref()
+            .map_or_else(String::new, |v| format!(":value:{}", zsh_action(v)));
+
+        let spec = if let [name] = names.as_slice() {
+            format!("'{name}[{description}]{action}'")
+        } else {
+            let exclusions = names.join(" ");
+            let alternatives = names.join(",");
+            format!("'({exclusions})'{{{alternatives}}}'[{description}]{action}'")
+        };
+
+        lines.push(format!("        {spec} \\"));
+    }
+
+    let commands: Vec<String> = COMMANDS
+        .iter()
+        .map(|c| format!("{}\\:\"{}\"", c.name, c.description))
+        .collect();
+
+    lines.extend([
+        format!("        '1:: :(({}))' \\", commands.join(" ")),
+        "        '*:: :'".to_owned(),
+        "}".to_owned(),
+        String::new(),
+        "_clac \"$@\"".to_owned(),
+    ]);
+
+    lines
+}
+
+/// Returns a [`Shell::Zsh`] completion action for a [`ValueKind`].
+fn zsh_action(value: &ValueKind) -> String {
+    match value {
+        ValueKind::File => "_files".to_owned(),
+        ValueKind::Number => " ".to_owned(),
+        ValueKind::Choice(names) => format!("({})", names.join(" ")),
+    }
+}
+
+/// Generates the lines of a completion script for [`Shell::Fish`].
+fn generate_fish() -> Vec<String> {
+    let mut lines = vec!["complete -c clac -f".to_owned()];
+
+    for option in OPTIONS {
+        let short = option
+            .short
+            .and_then(|s| s.strip_prefix('-'))
+            .map_or_else(String::new, |s| format!(" -s {s}"));
+
+        let long = option.name.strip_prefix("--").unwrap_or(option.name);
+        let flags = option.value.as_ref().map_or_else(String::new, fish_flags);
+
+        lines.push(format!(
+            "complete -c clac{short} -l {long}{flags} -d '{}'",
+            option.description
+        ));
+    }
+
+    for command in COMMANDS {
+        lines.push(format!(
+            "complete -c clac -n __fish_use_subcommand -a {} -d '{}'",
+            command.name, command.description
+        ));
+
+        if let Some(value) = &command.value {
+            lines.push(format!(
+                "complete -c clac -n '__fish_seen_subcommand_from {}'{}",
+                command.name,
+                fish_flags(value)
+            ));
+        }
+    }
+
+    lines
+}
+
+/// Returns [`Shell::Fish`] completion flags for a [`ValueKind`].
+fn fish_flags(value: &ValueKind) -> String {
+    match value {
+        ValueKind::File => " -r -F".to_owned(),
+        ValueKind::Number => " -x".to_owned(),
+        ValueKind::Choice(names) => format!(" -x -a '{}'", names.join(" ")),
+    }
+}
+
+/// Returns an [`OptionInfo`]'s names, including its short name if it has one.
+fn option_names(option: &OptionInfo) -> Vec<&'static str> {
+    option.short.into_iter().chain([option.name]).collect()
+}
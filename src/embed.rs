@@ -0,0 +1,773 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    cmp::Ordering,
+    fmt::{self, Debug, Display, Formatter},
+    rc::Rc,
+};
+
+use thiserror::Error;
+
+use crate::{
+    Collected, Mode,
+    ast::Literal,
+    errors::ClacError,
+    format::{self, Notation, RoundingMode},
+    integer::IntegerMode,
+    interpret::{
+        self, ArithOp, CallHook, ErrorKind, Globals, InterpretError, Object, Provider, Side,
+        StatementHook,
+    },
+    lower::GlobalMode,
+    settings::Settings,
+    source::Origin,
+    symbols::Symbol,
+    warnings::Warning,
+};
+
+/// An embeddable Clac engine which evaluates source code with persistent
+/// global variables.
+///
+/// Each `Engine` has its own settings, which start with the same defaults as
+/// the command line interface. An `Engine` shares values between its global
+/// variables, so it cannot be sent to other threads.
+pub struct Engine {
+    /// The [`Globals`], which are borrowed while a [`FunctionHandle`] is
+    /// called.
+    globals: RefCell<Globals>,
+
+    /// The [`Observer`], if any.
+    observer: Option<Rc<RefCell<dyn Observer>>>,
+}
+
+impl Engine {
+    /// Creates a new `Engine` with the standard library's native functions.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_settings(Settings::new())
+    }
+
+    /// Creates a new `Engine` with the standard library's native functions
+    /// and [`Settings`].
+    pub(crate) fn with_settings(settings: Settings) -> Self {
+        Self {
+            globals: RefCell::new(crate::new_globals(settings)),
+            observer: None,
+        }
+    }
+
+    /// Evaluates source code and returns an [`Evaluation`] with the results
+    /// of its top-level expressions in order, its warnings, and any errors.
+    /// Global variables defined by the source code are kept for later
+    /// evaluations, and can be redefined like in the REPL. Global variables
+    /// which were defined before a runtime error are also kept.
+    pub fn eval(&mut self, source: &str) -> Evaluation {
+        let mut collected = Collected::default();
+        let globals = self.globals.get_mut();
+
+        let result = crate::try_run_source(
+            source,
+            Origin::Repl,
+            globals,
+            Mode::Execute,
+            GlobalMode::Redefinable,
+            Some(&mut collected),
+        );
+
+        let errors: Vec<EvalError> = result
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(EvalError)
+            .collect();
+
+        for error in &errors {
+            notify(self.observer.as_ref(), |o| o.error(error));
+        }
+
+        Evaluation {
+            values: collected.results.into_iter().map(Value::new).collect(),
+            warnings: collected.warnings.into_iter().map(EvalWarning).collect(),
+            errors,
+        }
+    }
+
+    /// Formats a [`Value`] as it would be printed with the `Engine`'s
+    /// settings.
+    ///
+    /// # Panics
+    /// This function panics if it is called while the `Engine` is calling a
+    /// function.
+    #[must_use]
+    pub fn format(&self, value: &Value) -> String {
+        let globals = self.globals.borrow();
+
+        value.to_interpret().map_or_else(
+            |_| value.to_string(),
+            |v| v.display(globals.settings()).to_string(),
+        )
+    }
+
+    /// Sets the maximum number of tokens in evaluated source code, or removes
+    /// the maximum if [`None`] is given.
+    pub fn set_max_tokens(&mut self, max: Option<usize>) {
+        self.settings_mut().limits.tokens = max;
+    }
+
+    /// Sets the maximum nesting depth of evaluated source code, or removes the
+    /// maximum if [`None`] is given. The nesting depth is the greatest number
+    /// of brackets around any part of the source code.
+    pub fn set_max_depth(&mut self, max: Option<usize>) {
+        self.settings_mut().limits.depth = max;
+    }
+
+    /// Sets the maximum number of compiled instructions in evaluated source
+    /// code, or removes the maximum if [`None`] is given.
+    pub fn set_max_instructions(&mut self, max: Option<usize>) {
+        self.settings_mut().limits.instructions = max;
+    }
+
+    /// Sets the maximum number of nested function calls, or restores the
+    /// default maximum if [`None`] is given.
+    pub fn set_max_recursion(&mut self, max: Option<usize>) {
+        self.settings_mut().limits.recursion = max;
+    }
+
+    /// Sets the maximum number of steps run by one evaluation or function
+    /// call, or removes the maximum if [`None`] is given. Every loop iteration
+    /// and function call takes at least one step, so this stops source code
+    /// which never finishes.
+    pub fn set_max_steps(&mut self, max: Option<usize>) {
+        self.settings_mut().limits.steps = max;
+    }
+
+    /// Sets the maximum length of a list or string built by evaluated source
+    /// code, or restores the default maximum if [`None`] is given. Lists are
+    /// measured in elements and strings are measured in bytes.
+    pub fn set_max_length(&mut self, max: Option<usize>) {
+        self.settings_mut().limits.length = max;
+    }
+
+    /// Sets the arithmetic mode from its name, which is `float` or an integer
+    /// mode such as `u8` or `i32`.
+    ///
+    /// # Errors
+    /// This function returns a [`SettingError`] if the mode is unknown.
+    pub fn set_mode(&mut self, name: &str) -> Result<(), SettingError> {
+        let mode = if name == "float" {
+            None
+        } else {
+            let mode = IntegerMode::from_name(name);
+            Some(mode.ok_or_else(|| SettingError::new("mode", name))?)
+        };
+
+        self.settings_mut().integer_mode = mode;
+        Ok(())
+    }
+
+    /// Sets the rounding mode used by `round` and fixed decimal places from
+    /// its name, which is `half-up`, `half-even`, or `toward-zero`.
+    ///
+    /// # Errors
+    /// This function returns a [`SettingError`] if the rounding mode is
+    /// unknown.
+    pub fn set_rounding(&mut self, name: &str) -> Result<(), SettingError> {
+        let rounding = RoundingMode::from_name(name)
+            .ok_or_else(|| SettingError::new("rounding mode", name))?;
+
+        self.settings_mut().number_format.rounding = rounding;
+        Ok(())
+    }
+
+    /// Sets the notation used to format numbers from its name, which is
+    /// `decimal`, `engineering`, `si`, or `fraction`.
+    ///
+    /// # Errors
+    /// This function returns a [`SettingError`] if the notation is unknown.
+    pub fn set_notation(&mut self, name: &str) -> Result<(), SettingError> {
+        let notation =
+            Notation::from_name(name).ok_or_else(|| SettingError::new("notation", name))?;
+        self.settings_mut().number_format.notation = notation;
+        Ok(())
+    }
+
+    /// Sets the fixed number of decimal places used to format numbers, or
+    /// removes it if [`None`] is given.
+    ///
+    /// # Errors
+    /// This function returns a [`SettingError`] if there are too many decimal
+    /// places.
+    pub fn set_decimals(&mut self, decimals: Option<u32>) -> Result<(), SettingError> {
+        if let Some(places) = decimals
+            && places > format::MAX_DECIMAL_PLACES
+        {
+            return Err(SettingError::new(
+                "number of decimal places",
+                &places.to_string(),
+            ));
+        }
+
+        self.settings_mut().number_format.decimals = decimals;
+        Ok(())
+    }
+
+    /// Sets whether numbers are formatted with a decimal comma (`,`) instead
+    /// of a decimal point (`.`).
+    pub fn set_decimal_comma(&mut self, enabled: bool) {
+        self.settings_mut().number_format.decimal_comma = enabled;
+    }
+
+    /// Sets whether the digits of the integer parts of numbers are formatted
+    /// in groups of three.
+    pub fn set_group_digits(&mut self, enabled: bool) {
+        self.settings_mut().number_format.group_digits = enabled;
+    }
+
+    /// Returns a mutable reference to the `Engine`'s [`Settings`].
+    fn settings_mut(&mut self) -> &mut Settings {
+        self.globals.get_mut().settings_mut()
+    }
+
+    /// Sets the [`VariableProvider`] which is asked for undefined global
+    /// variables, replacing any previous [`VariableProvider`]. Provided
+    /// [`Other`][Value::Other] values are ignored because they have no
+    /// equivalent in Clac.
+    pub fn set_provider<P: VariableProvider + 'static>(&mut self, provider: P) {
+        let provider = RefCell::new(provider);
+        let provider: Provider = Rc::new(move |symbol: Symbol| {
+            let value = provider.borrow_mut().provide(&symbol.to_string())?;
+            value.to_interpret().ok()
+        });
+
+        self.globals.get_mut().set_provider(Some(provider));
+    }
+
+    /// Sets the [`Observer`] which is notified of evaluation events, replacing
+    /// any previous [`Observer`]. While an [`Observer`] is set, printed output
+    /// is passed to it instead of standard output.
+    pub fn set_observer<O: Observer + 'static>(&mut self, observer: O) {
+        let observer: Rc<RefCell<dyn Observer>> = Rc::new(RefCell::new(observer));
+
+        let call_observer = Rc::clone(&observer);
+        let call_hook: CallHook = Rc::new(move |callee, args| {
+            let function = FunctionHandle(callee.clone());
+            let args: Vec<Value> = args.iter().cloned().map(Value::new).collect();
+            notify(Some(&call_observer), |o| {
+                o.function_called(&function, &args);
+            });
+        });
+
+        let statement_observer = Rc::clone(&observer);
+        let statement_hook: StatementHook = Rc::new(move |result| {
+            let result = result.cloned().map(Value::new);
+            notify(Some(&statement_observer), |o| {
+                o.statement_executed(result.as_ref());
+            });
+        });
+
+        let print_observer = Rc::clone(&observer);
+        let print_hook = Box::new(move |text: &str| {
+            notify(Some(&print_observer), |o| o.printed(text));
+        });
+
+        let globals = self.globals.get_mut();
+        globals.set_hooks(Some(call_hook), Some(statement_hook));
+        globals.output().observe(print_hook);
+        self.observer = Some(observer);
+    }
+}
+
+/// Notifies an [`Observer`] of an event if there is one. Events which occur
+/// while the [`Observer`] is already being notified are ignored.
+fn notify<F: FnOnce(&mut dyn Observer)>(observer: Option<&Rc<RefCell<dyn Observer>>>, event: F) {
+    if let Some(mut observer) = observer.and_then(|o| o.try_borrow_mut().ok()) {
+        event(&mut *observer);
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A host-defined source of global variables for an [`Engine`].
+///
+/// When source code reads a global variable which is undefined, the
+/// `VariableProvider` is asked for its value before an error is reported, so
+/// values can be fetched lazily when they are first read. Provided values are
+/// kept as global variables, so each variable is only provided once.
+pub trait VariableProvider {
+    /// Returns the [`Value`] of an undefined global variable from its name.
+    /// This function returns [`None`] if the host has no variable with the
+    /// name.
+    fn provide(&mut self, name: &str) -> Option<Value>;
+}
+
+impl<F: FnMut(&str) -> Option<Value>> VariableProvider for F {
+    fn provide(&mut self, name: &str) -> Option<Value> {
+        self(name)
+    }
+}
+
+/// A host-defined observer of evaluation events in an [`Engine`].
+///
+/// Each method does nothing by default, so an `Observer` only needs to
+/// implement the events it is interested in. Events which occur while the
+/// `Observer` is being notified of another event are not reported.
+pub trait Observer {
+    /// Called after a top-level statement is executed, with its result
+    /// [`Value`] if it is an expression whose result would be printed.
+    fn statement_executed(&mut self, _result: Option<&Value>) {}
+
+    /// Called with text which is printed by source code, such as by `print`.
+    /// The text is passed to the `Observer` instead of being printed.
+    fn printed(&mut self, _text: &str) {}
+
+    /// Called with each [`EvalError`] which is caught while evaluating source
+    /// code or calling a [`FunctionHandle`].
+    fn error(&mut self, _error: &EvalError) {}
+
+    /// Called before a function is called, with the function as a
+    /// [`FunctionHandle`] and its argument [`Value`]s.
+    fn function_called(&mut self, _function: &FunctionHandle, _args: &[Value]) {}
+}
+
+/// A value returned to the host by an [`Engine`]. More kinds of values may be
+/// added in later versions, so matches on a `Value` should have a wildcard
+/// arm.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Value {
+    /// A number.
+    Number(f64),
+
+    /// A Boolean value.
+    Bool(bool),
+
+    /// A string.
+    Str(String),
+
+    /// A list of `Value`s.
+    List(Vec<Self>),
+
+    /// A tuple of `Value`s.
+    Tuple(Vec<Self>),
+
+    /// A date as the number of seconds since the Unix epoch in UTC.
+    Date(f64),
+
+    /// A duration as a number of seconds.
+    Duration(f64),
+
+    /// A [`FunctionHandle`].
+    Function(FunctionHandle),
+
+    /// An [`ObjectHandle`] to a [`HostObject`].
+    Object(ObjectHandle),
+
+    /// Any other value, such as an amount of money or a symbolic expression,
+    /// as it would be printed with the default settings.
+    Other(String),
+}
+
+impl Value {
+    /// Creates a new `Value` from an interpreter [`Value`][interpret::Value].
+    fn new(value: interpret::Value) -> Self {
+        match value {
+            interpret::Value::Number(value) => Self::Number(value),
+            interpret::Value::Integer(_) => Self::Number(value.as_float().unwrap_or_default()),
+            interpret::Value::Bool(value) => Self::Bool(value),
+            interpret::Value::Str(value) => Self::Str(value.as_ref().to_owned()),
+            interpret::Value::List(elems) => {
+                Self::List(elems.iter().cloned().map(Self::new).collect())
+            }
+            interpret::Value::Tuple(elems) => {
+                Self::Tuple(elems.iter().cloned().map(Self::new).collect())
+            }
+            interpret::Value::Date(date) => Self::Date(date),
+            interpret::Value::Duration(duration) => Self::Duration(duration),
+            interpret::Value::Function(_)
+            | interpret::Value::Closure(_)
+            | interpret::Value::Native(_) => Self::Function(FunctionHandle(value)),
+            interpret::Value::Object(_) | interpret::Value::Thunk(_) => {
+                value.downcast_object::<Hosted>().map_or_else(
+                    || Self::Other(value.display(&Settings::new()).to_string()),
+                    |hosted| Self::Object(hosted.0.clone()),
+                )
+            }
+        }
+    }
+
+    /// Converts the `Value` to an interpreter [`Value`][interpret::Value].
+    /// This function returns an [`EvalError`] if the `Value` is or contains an
+    /// [`Other`][Self::Other] value, because it has no equivalent.
+    fn to_interpret(&self) -> Result<interpret::Value, EvalError> {
+        let value = match self {
+            Self::Number(value) => interpret::Value::Number(*value),
+            Self::Bool(value) => interpret::Value::Bool(*value),
+            Self::Str(value) => interpret::Value::Str(value.as_str().into()),
+            Self::List(elems) => {
+                let elems = elems.iter().map(Self::to_interpret);
+                interpret::Value::List(elems.collect::<Result<_, _>>()?)
+            }
+            Self::Tuple(elems) => {
+                let elems = elems.iter().map(Self::to_interpret);
+                interpret::Value::Tuple(elems.collect::<Result<_, _>>()?)
+            }
+            Self::Date(date) => interpret::Value::Date(*date),
+            Self::Duration(duration) => interpret::Value::Duration(*duration),
+            Self::Function(function) => function.0.clone(),
+            Self::Object(object) => interpret::Value::Object(Rc::new(Hosted(object.clone()))),
+            Self::Other(_) => {
+                let error = InterpretError::from(ErrorKind::UnsupportedHostValue);
+                return Err(EvalError(error.into()));
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let settings = Settings::new();
+
+        match self {
+            Self::Number(value) => {
+                Display::fmt(&interpret::Value::Number(*value).display(&settings), f)
+            }
+            Self::Bool(value) => Display::fmt(value, f),
+            Self::Str(value) | Self::Other(value) => f.write_str(value),
+            Self::List(elems) => fmt_elems(f, "[", elems, "]"),
+            Self::Tuple(elems) if elems.len() == 1 => fmt_elems(f, "(", elems, ",)"),
+            Self::Tuple(elems) => fmt_elems(f, "(", elems, ")"),
+            Self::Date(date) => Display::fmt(&interpret::Value::Date(*date).display(&settings), f),
+            Self::Duration(duration) => {
+                let duration = interpret::Value::Duration(*duration);
+                Display::fmt(&duration.display(&settings), f)
+            }
+            Self::Function(function) => Display::fmt(function, f),
+            Self::Object(object) => Display::fmt(object, f),
+        }
+    }
+}
+
+/// Formats a list's or tuple's element [`Value`]s between opening and closing
+/// brackets with a [`Formatter`]. String elements are quoted. This function
+/// returns a [`fmt::Error`] if an error occurred.
+fn fmt_elems(f: &mut Formatter<'_>, open: &str, elems: &[Value], close: &str) -> fmt::Result {
+    f.write_str(open)?;
+
+    for (index, elem) in elems.iter().enumerate() {
+        if index > 0 {
+            f.write_str(", ")?;
+        }
+
+        match elem {
+            Value::Str(value) => Display::fmt(&Literal::Str(value.as_str().into()), f)?,
+            _ => Display::fmt(elem, f)?,
+        }
+    }
+
+    f.write_str(close)
+}
+
+/// A handle to a Clac function which was returned to the host.
+#[derive(Clone, PartialEq)]
+pub struct FunctionHandle(interpret::Value);
+
+impl FunctionHandle {
+    /// Returns the name of the function. This function returns [`None`] if
+    /// the function is anonymous.
+    #[must_use]
+    pub fn name(&self) -> Option<String> {
+        match &self.0 {
+            interpret::Value::Function(function) => function.name.map(|s| s.to_string()),
+            interpret::Value::Closure(closure) => closure.function.name.map(|s| s.to_string()),
+            interpret::Value::Native(native) => Some(native.name().to_owned()),
+            _ => None,
+        }
+    }
+
+    /// Calls the function with arguments and the global variables of an
+    /// [`Engine`], then returns its return [`Value`]. A `FunctionHandle` may be
+    /// called with any [`Engine`], but global variables are read from the
+    /// [`Engine`] it was called with.
+    ///
+    /// # Errors
+    /// This function returns an [`EvalError`] if an argument is an
+    /// [`Other`][Value::Other] value, or if an error occurred while calling
+    /// the function.
+    ///
+    /// # Panics
+    /// This function panics if it is called while the [`Engine`] is already
+    /// calling a function.
+    pub fn call(&self, engine: &Engine, args: &[Value]) -> Result<Value, EvalError> {
+        let args = args
+            .iter()
+            .map(Value::to_interpret)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut globals = engine.globals.borrow_mut();
+        let observer = engine.observer.as_ref();
+        let value = interpret::call_value(&self.0, &args, &mut globals)
+            .map_err(|e| EvalError(e.into()))
+            .inspect_err(|error| notify(observer, |o| o.error(error)))?;
+
+        Ok(Value::new(value))
+    }
+}
+
+impl Debug for FunctionHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FunctionHandle")
+            .field(&self.0.display(&Settings::new()).to_string())
+            .finish()
+    }
+}
+
+impl Display for FunctionHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0.display(&Settings::new()), f)
+    }
+}
+
+/// A host-defined value type which can overload operators in an [`Engine`].
+///
+/// Every operation is unsupported by default, so a `HostObject` only needs to
+/// implement the operations which make sense for its type. `HostObject`s are
+/// passed to an [`Engine`] as [`ObjectHandle`]s.
+pub trait HostObject: Any + Display {
+    /// Returns the name of the `HostObject`'s type. `HostObject`s can only be
+    /// compared with `HostObject`s with the same type name.
+    fn type_name(&self) -> &'static str;
+
+    /// Returns the result of an [`ArithOp`] between the `HostObject` and
+    /// another [`Value`], with the `HostObject` on a [`Side`]. The result may
+    /// be an error message. This function returns [`None`] if the operation is
+    /// not supported.
+    fn arith(&self, _op: ArithOp, _other: &Value, _side: Side) -> Option<Result<Value, String>> {
+        None
+    }
+
+    /// Returns the negation of the `HostObject`. This function returns
+    /// [`None`] if negation is not supported.
+    fn negate(&self) -> Option<Value> {
+        None
+    }
+
+    /// Returns [`true`] if the `HostObject` is equal to another `HostObject`
+    /// with the same type name. By default, `HostObject`s are only equal to
+    /// themselves.
+    fn equals(&self, _other: &dyn HostObject) -> bool {
+        false
+    }
+
+    /// Compares the `HostObject` to another `HostObject` with the same type
+    /// name. This function returns [`None`] if the `HostObject`s cannot be
+    /// compared.
+    fn compare(&self, _other: &dyn HostObject) -> Option<Ordering> {
+        None
+    }
+}
+
+/// A handle to a [`HostObject`] which can be passed to and returned from an
+/// [`Engine`].
+#[derive(Clone)]
+pub struct ObjectHandle(Rc<dyn HostObject>);
+
+impl ObjectHandle {
+    /// Creates a new `ObjectHandle` from a [`HostObject`].
+    pub fn new<T: HostObject>(object: T) -> Self {
+        Self(Rc::new(object))
+    }
+
+    /// Returns a reference to the [`HostObject`] as a concrete type. This
+    /// function returns [`None`] if the [`HostObject`] is not of the type.
+    #[must_use]
+    pub fn downcast_ref<T: HostObject>(&self) -> Option<&T> {
+        let object: &dyn Any = &*self.0;
+        object.downcast_ref()
+    }
+}
+
+impl PartialEq for ObjectHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+            || self.0.type_name() == other.0.type_name() && self.0.equals(&*other.0)
+    }
+}
+
+impl Debug for ObjectHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ObjectHandle")
+            .field(&self.0.to_string())
+            .finish()
+    }
+}
+
+impl Display for ObjectHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// An [`Object`] which wraps a [`HostObject`] and converts the [`Value`]s
+/// passed between them.
+struct Hosted(ObjectHandle);
+
+impl Object for Hosted {
+    fn type_name(&self) -> &'static str {
+        self.0.0.type_name()
+    }
+
+    fn arith(
+        &self,
+        op: ArithOp,
+        other: &interpret::Value,
+        side: Side,
+    ) -> Option<Result<interpret::Value, InterpretError>> {
+        let result = self.0.0.arith(op, &Value::new(other.clone()), side)?;
+        let result = result.map_err(|m| InterpretError::from(ErrorKind::HostObject(m)));
+        Some(result.and_then(|v| host_value(&v)))
+    }
+
+    fn negate(&self) -> Option<interpret::Value> {
+        host_value(&self.0.0.negate()?).ok()
+    }
+
+    fn equals(&self, other: &dyn Object) -> bool {
+        downcast_hosted(other).is_some_and(|other| self.0.0.equals(&*other.0.0))
+    }
+
+    fn compare(&self, other: &dyn Object) -> Result<Option<Ordering>, InterpretError> {
+        let other = downcast_hosted(other).ok_or(ErrorKind::InvalidType)?;
+        Ok(self.0.0.compare(&*other.0.0))
+    }
+}
+
+impl Display for Hosted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Returns a reference to an [`Object`] as a [`Hosted`] object. This function
+/// returns [`None`] if the [`Object`] is not a [`Hosted`] object.
+fn downcast_hosted(object: &dyn Object) -> Option<&Hosted> {
+    let object: &dyn Any = object;
+    object.downcast_ref()
+}
+
+/// Converts a [`Value`] returned by a [`HostObject`] to an interpreter
+/// [`Value`][interpret::Value]. This function returns an [`InterpretError`]
+/// if the [`Value`] has no equivalent.
+fn host_value(value: &Value) -> Result<interpret::Value, InterpretError> {
+    value
+        .to_interpret()
+        .map_err(|_error| ErrorKind::UnsupportedHostValue.into())
+}
+
+/// The outcome of evaluating source code with an [`Engine`].
+#[derive(Debug)]
+#[must_use]
+pub struct Evaluation {
+    /// The results of the top-level expressions which were evaluated, in
+    /// order. If a runtime error occurred, then only the results from before
+    /// the error are included.
+    pub values: Vec<Value>,
+
+    /// The [`EvalWarning`]s about suspicious source code.
+    pub warnings: Vec<EvalWarning>,
+
+    /// The [`EvalError`]s which were caught, if the source code could not be
+    /// evaluated.
+    pub errors: Vec<EvalError>,
+}
+
+impl Evaluation {
+    /// Returns [`true`] if no [`EvalError`]s were caught.
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Converts the `Evaluation` to a [`Result`] with its result [`Value`]s,
+    /// discarding its [`EvalWarning`]s.
+    ///
+    /// # Errors
+    /// This function returns the [`EvalError`]s if any were caught.
+    pub fn into_result(self) -> Result<Vec<Value>, Vec<EvalError>> {
+        if self.is_ok() {
+            Ok(self.values)
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// A warning about suspicious source code evaluated with an [`Engine`].
+#[derive(Debug, Error)]
+#[error("{}", .0.kind())]
+pub struct EvalWarning(Warning);
+
+/// An error caused by an invalid setting for an [`Engine`].
+#[derive(Debug, Error)]
+#[error("invalid {setting} '{value}'")]
+pub struct SettingError {
+    /// The description of the setting.
+    setting: &'static str,
+
+    /// The invalid value.
+    value: String,
+}
+
+impl SettingError {
+    /// Creates a new `SettingError` from the description of a setting and an
+    /// invalid value.
+    fn new(setting: &'static str, value: &str) -> Self {
+        Self {
+            setting,
+            value: value.to_owned(),
+        }
+    }
+}
+
+/// An error caught while evaluating source code with an [`Engine`].
+#[derive(Debug, Error)]
+#[error("{}", .0.message())]
+pub struct EvalError(ClacError);
+
+impl EvalError {
+    /// Returns the name of the stage which caught the `EvalError`. This is one
+    /// of `parse`, `lower`, `limit`, or `interpret`.
+    #[must_use]
+    pub fn stage(&self) -> &'static str {
+        self.0.stage()
+    }
+}
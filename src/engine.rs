@@ -0,0 +1,344 @@
+use std::{
+    fs, iter,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    Collected, Mode,
+    errors::ClacError,
+    interpret::{self, ErrorKind, Globals, InterpretError, Value},
+    lower::GlobalMode,
+    output::{Capture, Output, errln},
+    settings::Settings,
+    source::Origin,
+};
+
+// NOTE: Clac's runtime shares values and output with `Rc` and stores symbols
+// in thread-local storage, so an engine (a set of `Globals`) only works on the
+// thread which created it. `Globals` is not `Send`, so this is enforced at
+// compile time. Running code on multiple threads means giving each thread its
+// own engine, which `EnginePool` and `map_parallel` do.
+
+/// A script file to run on an [`EnginePool`].
+struct Job {
+    /// The path to the script file.
+    path: PathBuf,
+
+    /// The [`Mode`] to run the script file in.
+    mode: Mode,
+
+    /// The [`Sender`] for the [`Capture`] of the script file's output and
+    /// whether it ran without errors.
+    reply: Sender<(Capture, bool)>,
+}
+
+/// A pool of worker threads which each own an engine for running script files
+/// in parallel. Each script file is run with new [`Globals`] after the preloaded
+/// source code, so script files cannot affect each other.
+pub struct EnginePool {
+    /// The [`Sender`] for queueing [`Job`]s, which is dropped to stop the
+    /// workers.
+    sender: Option<Sender<Job>>,
+
+    /// The worker threads.
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl EnginePool {
+    /// Creates a new `EnginePool` with a number of worker threads which apply
+    /// [`Settings`] and run preloaded source code from paths before each
+    /// script file.
+    pub fn new(size: NonZeroUsize, settings: Settings, preload: Vec<(PathBuf, String)>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let preload = Arc::<[(PathBuf, String)]>::from(preload);
+
+        let workers = iter::repeat_with(|| {
+            let receiver = Arc::clone(&receiver);
+            let preload = Arc::clone(&preload);
+            thread::spawn(move || run_worker(&receiver, settings, &preload))
+        })
+        .take(size.get())
+        .collect();
+
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Queues a script file from its path to run in a [`Mode`]. This function
+    /// returns a [`Receiver`] for the [`Capture`] of the script file's output
+    /// and whether it ran without errors.
+    pub fn run_file(&self, path: PathBuf, mode: Mode) -> Receiver<(Capture, bool)> {
+        let (reply, receiver) = mpsc::channel();
+        let job = Job { path, mode, reply };
+
+        if let Some(sender) = &self.sender {
+            // If every worker has stopped, then the job is dropped and the
+            // receiver reports that the sender disconnected.
+            let _: Result<_, _> = sender.send(job);
+        }
+
+        receiver
+    }
+}
+
+impl Drop for EnginePool {
+    fn drop(&mut self) {
+        self.sender = None;
+
+        for worker in self.workers.drain(..) {
+            let _: Result<_, _> = worker.join();
+        }
+    }
+}
+
+/// Runs [`Job`]s from a shared [`Receiver`] with [`Settings`] and preloaded
+/// source code until the [`Receiver`] is disconnected.
+fn run_worker(receiver: &Mutex<Receiver<Job>>, settings: Settings, preload: &[(PathBuf, String)]) {
+    loop {
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+
+        let Ok(job) = job else {
+            return;
+        };
+
+        let output = Output::default();
+        output.start_capture();
+        let is_ok = run_job(&job, settings, preload, &output);
+
+        // The reply is dropped if the pool's owner stopped waiting for it.
+        let _: Result<_, _> = job.reply.send((output.take_capture(), is_ok));
+    }
+}
+
+/// Runs a [`Job`] with new [`Globals`], [`Settings`], and an [`Output`] after
+/// preloaded source code. Panics are caught so that a bug in Clac does not stop
+/// the worker. This function returns [`true`] if no errors occurred.
+fn run_job(job: &Job, settings: Settings, preload: &[(PathBuf, String)], output: &Output) -> bool {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut globals = crate::new_globals(settings);
+        globals.set_output(output.clone());
+
+        for (path, source) in preload {
+            run_source(source, path, &mut globals, Mode::Execute);
+        }
+
+        match fs::read_to_string(&job.path) {
+            Ok(source) => run_source(&source, &job.path, &mut globals, job.mode),
+            Err(error) => {
+                errln!(output, "Could not read '{}': {error}", job.path.display());
+                false
+            }
+        }
+    }));
+
+    result.unwrap_or_else(|_| {
+        errln!(output, "Error: internal error, please report this as a bug");
+        false
+    })
+}
+
+/// Runs source code from a script file's path with [`Globals`] in a [`Mode`].
+/// This function returns [`true`] if no errors occurred.
+fn run_source(source: &str, path: &Path, globals: &mut Globals, mode: Mode) -> bool {
+    crate::run_source(
+        source,
+        Origin::File(path),
+        globals,
+        mode,
+        GlobalMode::Sealed,
+    )
+}
+
+/// A data [`Value`] which can be sent to another thread.
+#[derive(Clone)]
+enum Portable {
+    /// A number.
+    Number(f64),
+
+    /// An integer number in an integer mode.
+    Integer(i128),
+
+    /// A Boolean value.
+    Bool(bool),
+
+    /// A string.
+    Str(String),
+
+    /// A list.
+    List(Vec<Self>),
+
+    /// A tuple.
+    Tuple(Vec<Self>),
+
+    /// A date as the number of seconds since the Unix epoch in UTC.
+    Date(f64),
+
+    /// A duration as a number of seconds.
+    Duration(f64),
+}
+
+impl Portable {
+    /// Creates a new `Portable` value from a [`Value`]. This function returns
+    /// [`None`] if the [`Value`] is or contains a value which is not data,
+    /// such as a function.
+    fn new(value: &Value) -> Option<Self> {
+        let portable = match value {
+            Value::Number(value) => Self::Number(*value),
+            Value::Integer(value) => Self::Integer(*value),
+            Value::Bool(value) => Self::Bool(*value),
+            Value::Str(value) => Self::Str(value.as_ref().to_owned()),
+            Value::List(elems) => Self::List(elems.iter().map(Self::new).collect::<Option<_>>()?),
+            Value::Tuple(elems) => Self::Tuple(elems.iter().map(Self::new).collect::<Option<_>>()?),
+            Value::Date(date) => Self::Date(*date),
+            Value::Duration(duration) => Self::Duration(*duration),
+            _ => return None,
+        };
+
+        Some(portable)
+    }
+
+    /// Converts the `Portable` value to a [`Value`].
+    fn into_value(self) -> Value {
+        match self {
+            Self::Number(value) => Value::Number(value),
+            Self::Integer(value) => Value::Integer(value),
+            Self::Bool(value) => Value::Bool(value),
+            Self::Str(value) => Value::Str(value.into()),
+            Self::List(elems) => Value::List(elems.into_iter().map(Self::into_value).collect()),
+            Self::Tuple(elems) => Value::Tuple(elems.into_iter().map(Self::into_value).collect()),
+            Self::Date(date) => Value::Date(date),
+            Self::Duration(duration) => Value::Duration(duration),
+        }
+    }
+}
+
+/// Calls a function from its source code on each element of a list and
+/// returns the results. The list is split between worker threads which each
+/// run the function's source code with new [`Globals`] and [`Settings`], so
+/// the function can only use its parameters and native functions. Quoted
+/// functions which use other variables are rejected when they are lowered,
+/// but functions given as strings are only checked by the workers. Output from
+/// each worker thread is printed to an [`Output`] in order. This function
+/// returns an [`InterpretError`] if an element or result is not data, or if
+/// the function could not be run.
+pub fn map_parallel(
+    source: &str,
+    elems: &[Value],
+    settings: Settings,
+    output: &Output,
+) -> Result<Vec<Value>, InterpretError> {
+    let elems = elems
+        .iter()
+        .map(Portable::new)
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ErrorKind::UnsupportedParallelValue)?;
+
+    // WebAssembly has no threads, so the elements are mapped on the current
+    // thread with a separate engine.
+    let threads = if cfg!(target_arch = "wasm32") {
+        1
+    } else {
+        thread::available_parallelism().map_or(1, NonZeroUsize::get)
+    };
+
+    let chunk_size = elems.len().div_ceil(threads).max(1);
+    let mut chunks = elems.chunks(chunk_size);
+
+    let replies = if chunks.len() <= 1 {
+        vec![map_chunk(
+            source,
+            chunks.next().unwrap_or_default(),
+            settings,
+        )]
+    } else {
+        thread::scope(|scope| {
+            #[expect(
+                clippy::needless_collect,
+                reason = "every worker must be spawned before any are joined"
+            )]
+            let workers = chunks
+                .map(|chunk| scope.spawn(move || map_chunk(source, chunk, settings)))
+                .collect::<Vec<_>>();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap_or_else(|p| panic::resume_unwind(p)))
+                .collect()
+        })
+    };
+
+    let mut results = Vec::with_capacity(elems.len());
+
+    for (capture, reply) in replies {
+        output.print_capture(capture);
+        let reply = reply.map_err(ErrorKind::ParallelMap)?;
+        results.extend(reply.into_iter().map(Portable::into_value));
+    }
+
+    Ok(results)
+}
+
+/// Calls a function from its source code on each element of a chunk of a list
+/// with new [`Globals`] and [`Settings`]. This function returns the
+/// [`Capture`] of the output and the results, or an error message if the
+/// function could not be run.
+fn map_chunk(
+    source: &str,
+    chunk: &[Portable],
+    settings: Settings,
+) -> (Capture, Result<Vec<Portable>, String>) {
+    let output = Output::default();
+    output.start_capture();
+
+    let mut globals = crate::new_globals(settings);
+    globals.set_output(output.clone());
+    let mut collected = Collected::default();
+
+    let function = crate::try_run_source(
+        source,
+        Origin::Args,
+        &mut globals,
+        Mode::Execute,
+        GlobalMode::Sealed,
+        Some(&mut collected),
+    );
+
+    let results = match (function, collected.results.as_slice()) {
+        (Err(errors), _) => Err(errors.first().map(ClacError::message).unwrap_or_default()),
+        (Ok(()), [function]) => {
+            // Panics are caught so that a bug in Clac is reported as an error
+            // instead of stopping the calling thread.
+            let results = panic::catch_unwind(AssertUnwindSafe(|| {
+                chunk
+                    .iter()
+                    .map(|elem| {
+                        let arg = elem.clone().into_value();
+                        let result = interpret::call_value(function, &[arg], &mut globals);
+                        let result = result.map_err(|e| e.to_string())?;
+                        let result = Portable::new(&result);
+                        result.ok_or_else(|| ErrorKind::UnsupportedParallelValue.to_string())
+                    })
+                    .collect()
+            }));
+
+            results
+                .unwrap_or_else(|_| Err("internal error, please report this as a bug".to_owned()))
+        }
+        (Ok(()), _) => Err("the function must be a single expression".to_owned()),
+    };
+
+    (output.take_capture(), results)
+}
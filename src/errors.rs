@@ -1,17 +1,62 @@
 use thiserror::Error;
 
-use crate::{interpret::InterpretError, lower::LowerError, parse::ParseError};
+use crate::{
+    interpret::InterpretError, lex::LexError, limits::LimitError, lower::LowerError,
+    parse::ParseError, spans::SourceSpan,
+};
 
 /// An error caught while running Clac.
 #[derive(Debug, Error)]
-#[repr(transparent)]
-#[error(transparent)]
-pub struct ClacError(Box<Kind>);
+#[error("{0}")]
+pub struct ClacError(Box<Kind>, Option<SourceSpan>);
+
+impl ClacError {
+    /// Returns the `ClacError` at a [`SourceSpan`] in the source code that
+    /// was run.
+    pub fn at(mut self, span: SourceSpan) -> Self {
+        self.1 = Some(span);
+        self
+    }
+
+    /// Returns the [`SourceSpan`] where the `ClacError` occurred. Interpreter
+    /// errors may occur in functions from other source code, so they have
+    /// their own [`SourceSpan`]s. This function returns [`None`] if the
+    /// `ClacError` has no [`SourceSpan`].
+    pub fn span(&self) -> Option<&SourceSpan> {
+        match self.0.as_ref() {
+            Kind::Interpret(error) => error.span(),
+            Kind::Lex(_) | Kind::Parse(_) | Kind::Lower(_) | Kind::Limit(_) => self.1.as_ref(),
+        }
+    }
+
+    /// Returns the name of the stage which caught the `ClacError`.
+    pub fn stage(&self) -> &'static str {
+        match self.0.as_ref() {
+            Kind::Lex(_) => "lex",
+            Kind::Parse(_) => "parse",
+            Kind::Lower(_) => "lower",
+            Kind::Limit(_) => "limit",
+            Kind::Interpret(_) => "interpret",
+        }
+    }
+
+    /// Returns the `ClacError`'s message without a location or an `Error:`
+    /// prefix.
+    pub fn message(&self) -> String {
+        match self.0.as_ref() {
+            Kind::Lex(error) => error.to_string(),
+            Kind::Parse(error) => error.to_string(),
+            Kind::Lower(error) => error.to_string(),
+            Kind::Limit(error) => error.to_string(),
+            Kind::Interpret(error) => error.to_string(),
+        }
+    }
+}
 
 impl<E: Into<Kind>> From<E> for ClacError {
     #[cold]
     fn from(value: E) -> Self {
-        Self(Box::new(value.into()))
+        Self(Box::new(value.into()), None)
     }
 }
 
@@ -19,12 +64,18 @@ impl<E: Into<Kind>> From<E> for ClacError {
 #[derive(Debug, Error)]
 #[error("Error: {0}")]
 enum Kind {
+    /// A [`LexError`].
+    Lex(#[from] LexError),
+
     /// A [`ParseError`].
     Parse(#[from] ParseError),
 
     /// A [`LowerError`].
     Lower(#[from] LowerError),
 
+    /// A [`LimitError`].
+    Limit(#[from] LimitError),
+
     /// An [`InterpretError`].
     Interpret(#[from] InterpretError),
 }
@@ -0,0 +1,705 @@
+use std::iter;
+
+use crate::settings::Settings;
+
+/// Options for displaying numbers. Source code is not affected by the
+/// `NumberFormat`.
+#[derive(Clone, Copy, Default)]
+pub struct NumberFormat {
+    /// Whether a decimal comma (`,`) is displayed instead of a decimal point
+    /// (`.`).
+    pub decimal_comma: bool,
+
+    /// Whether the digits of the integer part are displayed in groups of
+    /// three.
+    pub group_digits: bool,
+
+    /// The [`Notation`].
+    pub notation: Notation,
+
+    /// The fixed number of decimal places displayed in decimal notation, if
+    /// any.
+    pub decimals: Option<u32>,
+
+    /// The [`RoundingMode`] used for fixed decimal places.
+    pub rounding: RoundingMode,
+}
+
+impl NumberFormat {
+    /// Creates a new `NumberFormat` with a decimal point and no digit
+    /// grouping.
+    pub const fn new() -> Self {
+        Self {
+            decimal_comma: false,
+            group_digits: false,
+            notation: Notation::Decimal,
+            decimals: None,
+            rounding: RoundingMode::HalfUp,
+        }
+    }
+
+    /// Returns the [`char`] which separates the integer and fractional parts of
+    /// a number.
+    const fn decimal_separator(self) -> char {
+        if self.decimal_comma { ',' } else { '.' }
+    }
+
+    /// Returns the [`char`] which separates groups of digits. The opposite of
+    /// the decimal separator is used so that the two cannot be confused.
+    const fn group_separator(self) -> char {
+        if self.decimal_comma { '.' } else { ',' }
+    }
+
+    /// Returns the [`char`] which separates the elements of lists and tuples.
+    /// A semicolon is used with a decimal comma so that elements cannot be
+    /// confused with fractional parts.
+    pub const fn element_separator(self) -> char {
+        if self.decimal_comma { ';' } else { ',' }
+    }
+}
+
+/// A notation for displaying numbers.
+#[derive(Clone, Copy, Default)]
+pub enum Notation {
+    /// Decimal notation (e.g. `12300`).
+    #[default]
+    Decimal,
+
+    /// Engineering notation with exponents in multiples of three (e.g.
+    /// `12.3e3`).
+    Engineering,
+
+    /// Engineering notation with SI prefixes instead of exponents (e.g.
+    /// `12.3k`).
+    Si,
+
+    /// The nearest simple fraction (e.g. `1/3`).
+    Fraction,
+}
+
+impl Notation {
+    /// Returns a `Notation` from its name. This function returns [`None`] if
+    /// the name does not correspond to a `Notation`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let notation = match name {
+            "decimal" => Self::Decimal,
+            "engineering" => Self::Engineering,
+            "si" => Self::Si,
+            "fraction" => Self::Fraction,
+            _ => return None,
+        };
+
+        Some(notation)
+    }
+}
+
+/// A mode for rounding numbers to a number of decimal places.
+#[derive(Clone, Copy, Default)]
+pub enum RoundingMode {
+    /// Round to the nearest value, with ties rounded away from zero.
+    #[default]
+    HalfUp,
+
+    /// Round to the nearest value, with ties rounded to an even digit.
+    HalfEven,
+
+    /// Round toward zero, discarding extra digits.
+    TowardZero,
+}
+
+impl RoundingMode {
+    /// Returns a `RoundingMode` from its name. This function returns [`None`]
+    /// if the name does not correspond to a `RoundingMode`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mode = match name {
+            "half-up" => Self::HalfUp,
+            "half-even" => Self::HalfEven,
+            "toward-zero" => Self::TowardZero,
+            _ => return None,
+        };
+
+        Some(mode)
+    }
+
+    /// Returns the `RoundingMode`'s name.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::HalfUp => "half-up",
+            Self::HalfEven => "half-even",
+            Self::TowardZero => "toward-zero",
+        }
+    }
+}
+
+/// A specification for formatting a single value in a format string, written
+/// as `[[fill]align][0][width][.precision][style]` (e.g. `*^8.3e`).
+#[derive(Clone, Copy)]
+pub struct FormatSpec {
+    /// The [`char`] used to pad the value to its width.
+    fill: char,
+
+    /// The [`Alignment`], if any.
+    alignment: Option<Alignment>,
+
+    /// Whether numbers are padded with zeros after their sign.
+    zero_pad: bool,
+
+    /// The minimum width in [`char`]s.
+    width: usize,
+
+    /// The fixed number of decimal places, if any.
+    precision: Option<u32>,
+
+    /// The [`NumberStyle`].
+    style: NumberStyle,
+}
+
+impl FormatSpec {
+    /// Creates a new `FormatSpec` which formats values as they are displayed.
+    pub const fn new() -> Self {
+        Self {
+            fill: ' ',
+            alignment: None,
+            zero_pad: false,
+            width: 0,
+            precision: None,
+            style: NumberStyle::General,
+        }
+    }
+
+    /// Parses a `FormatSpec` from its text. This function returns [`None`] if
+    /// the text is not a valid `FormatSpec`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut spec = Self::new();
+        let mut rest = text;
+        let mut chars = rest.chars();
+        let first = chars.next();
+
+        if let Some(alignment) = chars.next().and_then(Alignment::from_char) {
+            spec.fill = first.expect("fill should precede alignment");
+            spec.alignment = Some(alignment);
+            rest = chars.as_str();
+        } else if let Some(alignment) = first.and_then(Alignment::from_char) {
+            spec.alignment = Some(alignment);
+            rest = rest.split_at(1).1;
+        }
+
+        if let Some(digits) = rest.strip_prefix('0') {
+            spec.zero_pad = true;
+            rest = digits;
+        }
+
+        let (width, after_width) = split_digits(rest);
+        rest = after_width;
+
+        if !width.is_empty() {
+            spec.width = width.parse().ok()?;
+        }
+
+        if let Some(after_point) = rest.strip_prefix('.') {
+            let (precision, after_precision) = split_digits(after_point);
+            let precision = precision.parse().ok()?;
+
+            if precision > MAX_DECIMAL_PLACES {
+                return None;
+            }
+
+            spec.precision = Some(precision);
+            rest = after_precision;
+        }
+
+        spec.style = match rest {
+            "" => NumberStyle::General,
+            "e" => NumberStyle::Scientific,
+            "x" => NumberStyle::LowerHex,
+            "X" => NumberStyle::UpperHex,
+            _ => return None,
+        };
+
+        if spec.precision.is_some() && spec.style.is_hex() {
+            return None;
+        }
+
+        Some(spec)
+    }
+
+    /// Formats a number with the `FormatSpec` and a [`NumberFormat`]. This
+    /// function returns [`None`] if a hexadecimal number is not an integer with
+    /// a magnitude less than 2^64.
+    pub fn format_number(self, value: f64, format: NumberFormat) -> Option<String> {
+        // Infinities and NaN have no digits to format.
+        let text = if value.is_finite() {
+            match self.style {
+                NumberStyle::General => self.precision.map_or_else(
+                    || format_with(value, format),
+                    |places| {
+                        let decimals = Some(places);
+                        let notation = Notation::Decimal;
+                        format_decimal(
+                            value,
+                            NumberFormat {
+                                notation,
+                                decimals,
+                                ..format
+                            },
+                        )
+                    },
+                ),
+                NumberStyle::Scientific => format_scientific(value, self.precision, format),
+                NumberStyle::LowerHex => format_hex(value, false)?,
+                NumberStyle::UpperHex => format_hex(value, true)?,
+            }
+        } else {
+            value.to_string()
+        };
+
+        Some(self.pad(&text, Alignment::Right, true))
+    }
+
+    /// Formats text with the `FormatSpec`. This function returns [`None`] if
+    /// the `FormatSpec` has a precision or style which only applies to
+    /// numbers.
+    pub fn format_text(self, text: &str) -> Option<String> {
+        if self.precision.is_some() || !matches!(self.style, NumberStyle::General) {
+            return None;
+        }
+
+        Some(self.pad(text, Alignment::Left, false))
+    }
+
+    /// Pads text to the `FormatSpec`'s width with a default [`Alignment`].
+    /// Numeric text is padded with zeros after its sign if zero padding is
+    /// enabled.
+    fn pad(self, text: &str, default_alignment: Alignment, is_numeric: bool) -> String {
+        let padding = self.width.saturating_sub(text.chars().count());
+
+        if padding == 0 {
+            return text.to_owned();
+        }
+
+        if is_numeric && self.zero_pad && self.alignment.is_none() {
+            let (sign, digits) = text.strip_prefix('-').map_or(("", text), |d| ("-", d));
+            return format!("{sign}{}{digits}", "0".repeat(padding));
+        }
+
+        let (before, after) = match self.alignment.unwrap_or(default_alignment) {
+            Alignment::Left => (0, padding),
+            Alignment::Center => (padding / 2, padding - padding / 2),
+            Alignment::Right => (padding, 0),
+        };
+
+        let fill = |count| iter::repeat_n(self.fill, count);
+        fill(before)
+            .chain(text.chars())
+            .chain(fill(after))
+            .collect()
+    }
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An alignment for padding a value to a [`FormatSpec`]'s width.
+#[derive(Clone, Copy)]
+enum Alignment {
+    /// Left alignment (`<`).
+    Left,
+
+    /// Center alignment (`^`).
+    Center,
+
+    /// Right alignment (`>`).
+    Right,
+}
+
+impl Alignment {
+    /// Returns an `Alignment` from its [`char`]. This function returns [`None`]
+    /// if the [`char`] does not correspond to an `Alignment`.
+    const fn from_char(value: char) -> Option<Self> {
+        match value {
+            '<' => Some(Self::Left),
+            '^' => Some(Self::Center),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
+/// A style for formatting numbers with a [`FormatSpec`].
+#[derive(Clone, Copy)]
+enum NumberStyle {
+    /// The current [`Notation`], or decimal notation with a precision.
+    General,
+
+    /// Scientific notation (`e`).
+    Scientific,
+
+    /// Lowercase hexadecimal (`x`).
+    LowerHex,
+
+    /// Uppercase hexadecimal (`X`).
+    UpperHex,
+}
+
+impl NumberStyle {
+    /// Returns [`true`] if the `NumberStyle` is hexadecimal.
+    const fn is_hex(self) -> bool {
+        matches!(self, Self::LowerHex | Self::UpperHex)
+    }
+}
+
+/// The greatest number of decimal places which can be displayed. This is more
+/// than the number of decimal places in any number's shortest representation.
+pub const MAX_DECIMAL_PLACES: u32 = 400;
+
+/// The largest denominator of a fraction displayed in fraction notation.
+const MAX_DENOMINATOR: f64 = 1000.0;
+
+/// The SI prefixes and their exponents.
+pub const SI_PREFIXES: [(char, i32); 20] = [
+    ('q', -30),
+    ('r', -27),
+    ('y', -24),
+    ('z', -21),
+    ('a', -18),
+    ('f', -15),
+    ('p', -12),
+    ('n', -9),
+    ('µ', -6),
+    ('m', -3),
+    ('k', 3),
+    ('M', 6),
+    ('G', 9),
+    ('T', 12),
+    ('P', 15),
+    ('E', 18),
+    ('Z', 21),
+    ('Y', 24),
+    ('R', 27),
+    ('Q', 30),
+];
+
+/// Rounds a number to a number of decimal places with a [`RoundingMode`].
+pub fn round(value: f64, places: u32, rounding: RoundingMode) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+
+    let places = places.min(MAX_DECIMAL_PLACES);
+
+    round_to_string(value, places, rounding)
+        .parse()
+        .expect("rounded number should be a valid float")
+}
+
+/// Formats a number with a session's [`Settings`].
+pub fn format_number(value: f64, settings: &Settings) -> String {
+    let format = settings.number_format;
+
+    // Infinities and NaN have no digits to format.
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    format_with(value, format)
+}
+
+/// Formats an integer with a session's [`Settings`]. Integers are also
+/// displayed in hexadecimal and binary in an integer mode.
+pub fn format_integer(value: i128, settings: &Settings) -> String {
+    let formatted = localize(&value.to_string(), settings.number_format);
+
+    match settings.integer_mode {
+        None => formatted,
+        Some(mode) => {
+            let pattern = mode.bit_pattern(value);
+            format!("{formatted} (0x{pattern:X}, 0b{pattern:b})")
+        }
+    }
+}
+
+/// Formats a finite number with a [`NumberFormat`]'s [`Notation`].
+fn format_with(value: f64, format: NumberFormat) -> String {
+    match format.notation {
+        Notation::Decimal => format_decimal(value, format),
+        Notation::Engineering => format_engineering(value, format, false),
+        Notation::Si => format_engineering(value, format, true),
+        Notation::Fraction => format_fraction(value, format),
+    }
+}
+
+/// Formats a finite number in decimal notation with a [`NumberFormat`].
+fn format_decimal(value: f64, format: NumberFormat) -> String {
+    let text = format.decimals.map_or_else(
+        || value.to_string(),
+        |places| round_to_string(value, places, format.rounding),
+    );
+
+    localize(&text, format)
+}
+
+/// Rounds a finite number to a number of decimal places with a
+/// [`RoundingMode`] and returns it as a string with exactly that many decimal
+/// places. The shortest decimal representation of the number is rounded, so
+/// `2.675` is treated as exactly halfway between `2.67` and `2.68`.
+fn round_to_string(value: f64, places: u32, mode: RoundingMode) -> String {
+    let text = value.abs().to_string();
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let places = usize::try_from(places).unwrap_or(usize::MAX);
+    let (kept, discarded) = fraction.split_at(places.min(fraction.len()));
+
+    let mut digits: Vec<u8> = integer.bytes().chain(kept.bytes()).collect();
+    let mut discarded = discarded.bytes();
+
+    let is_rounded_up = match (mode, discarded.next()) {
+        (RoundingMode::TowardZero, _) | (_, None) => false,
+        (RoundingMode::HalfUp, Some(first)) => first >= b'5',
+        (RoundingMode::HalfEven, Some(first)) => {
+            let is_last_odd = digits.last().is_some_and(|d| d % 2 == 1);
+            first > b'5' || first == b'5' && (discarded.any(|d| d != b'0') || is_last_odd)
+        }
+    };
+
+    if is_rounded_up {
+        let carry = digits.iter_mut().rev().all(|digit| {
+            let is_carried = *digit == b'9';
+            *digit = if is_carried { b'0' } else { *digit + 1 };
+            is_carried
+        });
+
+        if carry {
+            digits.insert(0, b'1');
+        }
+    }
+
+    digits.resize(digits.len() + places - kept.len(), b'0');
+    let (rounded_integer, rounded_fraction) = digits.split_at(digits.len() - places);
+    let mut rounded = String::new();
+
+    // Numbers which round to zero are not displayed with a negative sign.
+    if value.is_sign_negative() && digits.iter().any(|d| *d != b'0') {
+        rounded.push('-');
+    }
+
+    rounded.extend(rounded_integer.iter().map(|d| char::from(*d)));
+
+    if !rounded_fraction.is_empty() {
+        rounded.push('.');
+        rounded.extend(rounded_fraction.iter().map(|d| char::from(*d)));
+    }
+
+    rounded
+}
+
+/// Formats a decimal number string with a [`NumberFormat`]'s separators.
+fn localize(text: &str, format: NumberFormat) -> String {
+    let (sign, digits) = text.strip_prefix('-').map_or(("", text), |d| ("-", d));
+
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let mut formatted = String::from(sign);
+
+    for (index, digit) in integer.char_indices() {
+        if format.group_digits && index > 0 && (integer.len() - index).is_multiple_of(3) {
+            formatted.push(format.group_separator());
+        }
+
+        formatted.push(digit);
+    }
+
+    if !fraction.is_empty() {
+        formatted.push(format.decimal_separator());
+        formatted.push_str(fraction);
+    }
+
+    formatted
+}
+
+/// Formats a finite number in engineering notation with a [`NumberFormat`].
+/// SI prefixes are used instead of exponents where possible if SI prefixes are
+/// enabled.
+fn format_engineering(value: f64, format: NumberFormat, use_si_prefixes: bool) -> String {
+    // Scientific notation is used as a source of exact shortest digits so that
+    // the decimal point can be moved without any rounding errors.
+    let scientific = format!("{:e}", value.abs());
+
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation should contain an exponent");
+
+    let exponent: i32 = exponent.parse().expect("exponent should be an integer");
+    let engineering_exponent = exponent.div_euclid(3_i32) * 3_i32;
+    let integer_len = usize::try_from(exponent - engineering_exponent)
+        .expect("exponent offset should be non-negative")
+        + 1;
+
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let digits = format!("{digits:0<integer_len$}");
+    let (integer, fraction) = digits.split_at(integer_len);
+
+    let mut formatted = String::new();
+
+    if value.is_sign_negative() {
+        formatted.push('-');
+    }
+
+    formatted.push_str(integer);
+
+    if !fraction.is_empty() {
+        formatted.push(format.decimal_separator());
+        formatted.push_str(fraction);
+    }
+
+    let prefix = SI_PREFIXES
+        .iter()
+        .find(|(_, e)| *e == engineering_exponent)
+        .filter(|_| use_si_prefixes);
+
+    if let Some((prefix, _)) = prefix {
+        formatted.push(*prefix);
+    } else if engineering_exponent != 0_i32 {
+        formatted.push('e');
+        formatted.push_str(&engineering_exponent.to_string());
+    }
+
+    formatted
+}
+
+/// Formats a finite number in scientific notation with a [`NumberFormat`] and
+/// an optional fixed number of decimal places in the mantissa.
+fn format_scientific(value: f64, places: Option<u32>, format: NumberFormat) -> String {
+    let scientific = format!("{value:e}");
+
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation should contain an exponent");
+
+    let mut exponent: i32 = exponent.parse().expect("exponent should be an integer");
+
+    let mantissa = places.map_or_else(
+        || mantissa.to_owned(),
+        |places| {
+            let mantissa: f64 = mantissa.parse().expect("mantissa should be a valid float");
+            let rounded = round_to_string(mantissa, places, format.rounding);
+
+            // Rounding may carry into another digit (e.g. `9.99` to `10.0`).
+            if rounded.trim_start_matches('-').starts_with("10") {
+                exponent += 1_i32;
+                round_to_string(mantissa / 10.0_f64, places, format.rounding)
+            } else {
+                rounded
+            }
+        },
+    );
+
+    format!("{}e{exponent}", localize(&mantissa, format))
+}
+
+/// Formats a finite number in hexadecimal. This function returns [`None`] if
+/// the number is not an integer with a magnitude less than 2^64.
+fn format_hex(value: f64, is_uppercase: bool) -> Option<String> {
+    if value.fract() != 0.0_f64 || value.abs() >= 2.0_f64.powi(64) {
+        return None;
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "the magnitude is an integer less than 2^64"
+    )]
+    let magnitude = value.abs() as u64;
+
+    let sign = if value < 0.0_f64 { "-" } else { "" };
+
+    Some(if is_uppercase {
+        format!("{sign}{magnitude:X}")
+    } else {
+        format!("{sign}{magnitude:x}")
+    })
+}
+
+/// Splits a string into its leading ASCII digits and the remaining text.
+fn split_digits(text: &str) -> (&str, &str) {
+    let len = text.bytes().take_while(u8::is_ascii_digit).count();
+    text.split_at(len)
+}
+
+/// Formats a number as the nearest simple fraction with a [`NumberFormat`].
+/// Fractions which are not exactly equal to the number are marked as
+/// approximate. Integers, infinities, and NaN are formatted in decimal notation,
+/// and numbers nearer to zero than to any other fraction are formatted in
+/// scientific notation.
+pub fn format_fraction(value: f64, format: NumberFormat) -> String {
+    if !value.is_finite() || value.fract() == 0.0_f64 {
+        return localize(&value.to_string(), format);
+    }
+
+    let target = value.abs();
+    let (numerator, denominator) = nearest_fraction(target);
+
+    if numerator == 0.0_f64 {
+        return format_scientific(value, None, format);
+    }
+
+    let mut formatted = String::new();
+
+    if value.is_sign_negative() {
+        formatted.push('-');
+    }
+
+    formatted.push_str(&localize(&numerator.to_string(), format));
+
+    if denominator > 1.0_f64 {
+        formatted.push('/');
+        formatted.push_str(&localize(&denominator.to_string(), format));
+    }
+
+    #[expect(
+        clippy::float_cmp,
+        reason = "only exactly equal fractions are not approximations"
+    )]
+    if numerator / denominator != target {
+        formatted.push_str(" (approx)");
+    }
+
+    formatted
+}
+
+/// Returns the `(numerator, denominator)` of the nearest fraction to a
+/// non-negative number with a denominator no greater than
+/// [`MAX_DENOMINATOR`]. The fraction is found from the convergents of the
+/// number's continued fraction expansion.
+fn nearest_fraction(target: f64) -> (f64, f64) {
+    // The two most recent convergents, starting with the conventional seeds of
+    // `0/1` and `1/0`.
+    let (mut previous_numerator, mut numerator) = (0.0_f64, 1.0_f64);
+    let (mut previous_denominator, mut denominator) = (1.0_f64, 0.0_f64);
+    let mut remainder = target;
+
+    loop {
+        let term = remainder.floor();
+        let next_denominator = term.mul_add(denominator, previous_denominator);
+
+        if next_denominator > MAX_DENOMINATOR {
+            break;
+        }
+
+        let next_numerator = term.mul_add(numerator, previous_numerator);
+        (previous_numerator, numerator) = (numerator, next_numerator);
+        (previous_denominator, denominator) = (denominator, next_denominator);
+
+        #[expect(
+            clippy::float_cmp,
+            reason = "the expansion ends when the fraction is exact"
+        )]
+        if remainder == term || numerator / denominator == target {
+            break;
+        }
+
+        remainder = (remainder - term).recip();
+    }
+
+    (numerator, denominator)
+}
@@ -0,0 +1,180 @@
+use crate::{
+    interpret::{Category, NATIVES, Native, NativeInfo},
+    output::{Output, errln, outln},
+    parse::grammar_ebnf,
+};
+
+/// The usage and description of every REPL command.
+const COMMANDS: &[(&str, &str)] = &[
+    (
+        ":check CODE",
+        "Checks code for errors without executing it.",
+    ),
+    (
+        ":edit [NAME]",
+        "Opens a scratch buffer in $EDITOR, then executes it.",
+    ),
+    (
+        ":source NAME",
+        "Prints the code which defined a global variable.",
+    ),
+    (
+        ":save FILE",
+        "Writes the global variables to FILE for :load.",
+    ),
+    (
+        ":load FILE",
+        "Runs FILE, such as one written by :save, as Clac code.",
+    ),
+    (
+        ":undef NAME...",
+        "Removes global variables so they can be defined again.",
+    ),
+    (
+        ":snapshot [NAME]",
+        "Saves the global variables as NAME, or lists the snapshots.",
+    ),
+    (
+        ":restore NAME",
+        "Replaces the global variables with the snapshot NAME.",
+    ),
+    (":undo", "Reverts the last change to the global variables."),
+    (":log [SETTING]", "Shows or changes transcript logging."),
+    (":rounding [MODE]", "Shows or changes the rounding mode."),
+    (":mode [MODE]", "Shows or changes the integer mode."),
+    (
+        ":reactive [SETTING]",
+        "Shows or changes whether dependents are recalculated.",
+    ),
+    (
+        ":paste",
+        "Reads lines until :end, then executes them together.",
+    ),
+    (
+        ":help [NAME]",
+        "Shows help for a native function, or lists the commands.",
+    ),
+    (":help topic TOPIC", "Shows help for a topic."),
+];
+
+/// A help topic.
+#[derive(Clone, Copy)]
+enum Topic {
+    /// The operators and their precedence.
+    Operators,
+
+    /// A summary of the grammar.
+    Grammar,
+
+    /// The signatures of every native function by category.
+    Natives,
+}
+
+impl Topic {
+    /// Every `Topic`.
+    const ALL: [Self; 3] = [Self::Operators, Self::Grammar, Self::Natives];
+
+    /// Returns the `Topic`'s name.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Operators => "operators",
+            Self::Grammar => "grammar",
+            Self::Natives => "natives",
+        }
+    }
+
+    /// Prints the `Topic` to an [`Output`].
+    fn print(self, output: &Output) {
+        match self {
+            Self::Operators => outln!(output, "{OPERATORS_TOPIC}"),
+            Self::Grammar => outln!(output, "{}", grammar_ebnf().trim_end()),
+            Self::Natives => {
+                for category in Category::ALL {
+                    outln!(output, "{}:", category.name());
+
+                    for info in NATIVES.iter().filter(|i| i.category == category) {
+                        outln!(output, "  {}", info.native.signature());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The text of the `operators` help topic.
+const OPERATORS_TOPIC: &str = "\
+Operators from lowest to highest precedence:
+  x = y                  Assignment
+  (x) -> y, c ? x : y    Function and condition (right-associative)
+  x || y                 Logical or
+  x && y                 Logical and
+  == != < <= > >= in     Comparison (non-associative)
+  x + y, x - y           Sum
+  x * y, x / y           Term
+  -x, !x                 Prefix
+  x ^ y, x², x³          Power (right-associative)
+  f(x), xs[i]            Call and index
+Custom operators are defined at the sum or term precedence level.";
+
+/// Runs the `:help` command. With no arguments, the REPL commands are listed.
+/// A native function name shows its signature and description, and any other
+/// name searches the native functions. `topic TOPIC` shows a help topic. Help
+/// is printed to an [`Output`].
+pub fn run_help(output: &Output, args: &str) {
+    let args = args.trim();
+
+    if args.is_empty() {
+        print_commands(output);
+    } else if let Some(topic) = args
+        .strip_prefix("topic")
+        .filter(|t| t.is_empty() || t.starts_with(char::is_whitespace))
+    {
+        print_topic(output, topic.trim());
+    } else if let Some(native) = Native::from_name(args) {
+        let info = native.info();
+        outln!(output, "{}\n{}", native.signature(), info.description);
+        outln!(output, "Category: {}", info.category.name());
+    } else {
+        search_natives(output, args);
+    }
+}
+
+/// Prints the usage and description of every REPL command to an [`Output`].
+fn print_commands(output: &Output) {
+    let width = COMMANDS.iter().map(|(u, _)| u.len()).max().unwrap_or(0);
+    outln!(output, "Commands:");
+
+    for (usage, description) in COMMANDS {
+        outln!(output, "  {usage:width$}  {description}");
+    }
+
+    let topics: Vec<&str> = Topic::ALL.iter().map(|t| t.name()).collect();
+    outln!(output, "Topics: {}", topics.join(", "));
+}
+
+/// Prints a help [`Topic`] from its name to an [`Output`].
+fn print_topic(output: &Output, name: &str) {
+    match Topic::ALL.into_iter().find(|t| t.name() == name) {
+        Some(topic) => topic.print(output),
+        None if name.is_empty() => errln!(output, "Error: a topic name is required"),
+        None => errln!(output, "Error: unknown topic '{name}'"),
+    }
+}
+
+/// Prints the signature of every native function whose name or description
+/// contains a search query to an [`Output`].
+fn search_natives(output: &Output, query: &str) {
+    let query = query.to_lowercase();
+    let natives: Vec<&NativeInfo> = NATIVES
+        .iter()
+        .filter(|i| i.name.contains(&query) || i.description.to_lowercase().contains(&query))
+        .collect();
+
+    if natives.is_empty() {
+        errln!(output, "Error: no help found for '{query}'");
+    }
+
+    for info in natives {
+        outln!(output, "{}", info.native.signature());
+    }
+}
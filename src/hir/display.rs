@@ -0,0 +1,187 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::locals::{Local, LocalTable};
+
+use super::{Expr, Hir, Stmt};
+
+impl Hir {
+    /// Returns a displayable S-expression representation of the `Hir` which
+    /// names its [`Local`]s with a [`LocalTable`].
+    pub const fn display<'hir>(&'hir self, locals: &'hir LocalTable) -> HirDisplay<'hir, Self> {
+        HirDisplay { node: self, locals }
+    }
+}
+
+/// A node of a [`Hir`] which is displayed with a [`LocalTable`].
+pub struct HirDisplay<'hir, T> {
+    /// The node.
+    node: &'hir T,
+
+    /// The [`LocalTable`].
+    locals: &'hir LocalTable,
+}
+
+impl<'hir, T> HirDisplay<'hir, T> {
+    /// Returns a `HirDisplay` for another node with the same [`LocalTable`].
+    const fn with<U>(&self, node: &'hir U) -> HirDisplay<'hir, U> {
+        HirDisplay {
+            node,
+            locals: self.locals,
+        }
+    }
+
+    /// Returns an [`Iterator`] over `HirDisplay`s for a slice of nodes.
+    fn each<U>(&self, nodes: &'hir [U]) -> impl Iterator<Item = HirDisplay<'hir, U>> {
+        let locals = self.locals;
+        nodes.iter().map(move |node| HirDisplay { node, locals })
+    }
+}
+
+impl Display for HirDisplay<'_, Hir> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt_s_expr(f, "h:", self.each(&self.node.0))
+    }
+}
+
+impl Display for HirDisplay<'_, Stmt> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.node {
+            Stmt::Block(stmts) => fmt_s_expr(f, "b:", self.each(stmts)),
+            Stmt::Sequence(stmts) => fmt_s_expr(f, "s:", self.each(stmts)),
+            Stmt::AssignGlobal(symbol, expr, _) => {
+                write!(f, "(global {symbol} {})", self.with(&**expr))
+            }
+            Stmt::DefineLocal(local, expr) => {
+                write!(f, "(local {} {})", self.with(local), self.with(&**expr))
+            }
+            Stmt::Print(expr) => write!(f, "(print {})", self.with(&**expr)),
+            Stmt::Expr(expr) => write!(f, "{}", self.with(&**expr)),
+        }
+    }
+}
+
+impl Display for HirDisplay<'_, Expr> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.node {
+            Expr::Literal(literal) => write!(f, "{literal}"),
+            Expr::Global(symbol, _) => write!(f, "{symbol}"),
+            Expr::Local(local) => write!(f, "{}", self.with(local)),
+            Expr::Block(stmts, expr) => {
+                write!(f, "(b:")?;
+
+                for stmt in self.each(stmts) {
+                    write!(f, " {stmt}")?;
+                }
+
+                write!(f, " {})", self.with(&**expr))
+            }
+            Expr::Function(name, params, body) => {
+                f.write_str("(fn ")?;
+
+                if let Some(name) = name {
+                    write!(f, "{} ", self.with(name))?;
+                }
+
+                fmt_s_expr(f, "", self.each(params))?;
+                write!(f, " {})", self.with(&**body))
+            }
+            Expr::Call(callee, args, _) => {
+                write!(f, "(call {}", self.with(&**callee))?;
+
+                for arg in self.each(args) {
+                    write!(f, " {arg}")?;
+                }
+
+                f.write_str(")")
+            }
+            Expr::List(elems) => fmt_s_expr(f, "list", self.each(elems)),
+            Expr::Tuple(elems) => fmt_s_expr(f, "tuple", self.each(elems)),
+            Expr::Index(list, index, _) => {
+                fmt_s_expr(f, "index", [self.with(&**list), self.with(&**index)])
+            }
+            Expr::Unpack(tuple, count, _) => {
+                write!(f, "(unpack {} {count})", self.with(&**tuple))
+            }
+            Expr::Unary(op, rhs, _) => fmt_s_expr(f, op, [self.with(&**rhs)]),
+            Expr::Binary(op, lhs, rhs, _) => {
+                fmt_s_expr(f, op, [self.with(&**lhs), self.with(&**rhs)])
+            }
+            Expr::Lazy(expr) => fmt_s_expr(f, "lazy", [self.with(&**expr)]),
+            Expr::Loop(locals, values, body) => {
+                f.write_str("(loop ")?;
+                self.fmt_bindings(f, locals, values)?;
+                write!(f, " {})", self.with(&**body))
+            }
+            Expr::Recur(values) => fmt_s_expr(f, "recur", self.each(values)),
+            Expr::While(locals, values, cond, body, exit, _) => {
+                f.write_str("(while ")?;
+                self.fmt_bindings(f, locals, values)?;
+                let exprs = [cond, body, exit].map(|e| self.with(&**e));
+                write!(f, " {} {} {})", exprs[0], exprs[1], exprs[2])
+            }
+            Expr::Cond(cond, then_expr, else_expr, _) => {
+                let exprs = [cond, then_expr, else_expr].map(|e| self.with(&**e));
+                fmt_s_expr(f, "?", exprs)
+            }
+        }
+    }
+}
+
+impl HirDisplay<'_, Expr> {
+    /// Formats loop variable [`Local`]s with their initial values with a
+    /// [`Formatter`]. This function returns a [`fmt::Error`] if an error
+    /// occurred.
+    fn fmt_bindings(
+        &self,
+        f: &mut Formatter<'_>,
+        locals: &[Local],
+        values: &[Expr],
+    ) -> fmt::Result {
+        f.write_str("(")?;
+
+        for (index, (local, value)) in locals.iter().zip(values).enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+
+            write!(f, "({} {})", self.with(local), self.with(value))?;
+        }
+
+        f.write_str(")")
+    }
+}
+
+impl Display for HirDisplay<'_, Local> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let data = self.locals.data(*self.node);
+        write!(f, "{}{}", data.symbol, self.node)?;
+
+        if data.is_upvar {
+            f.write_str("^")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats an operator and arguments as an S-expression with a [`Formatter`].
+/// The operator is omitted if it is empty. This function returns a
+/// [`fmt::Error`] if an error occurred.
+fn fmt_s_expr<O: Display, A: Display>(
+    f: &mut Formatter<'_>,
+    op: O,
+    args: impl IntoIterator<Item = A>,
+) -> fmt::Result {
+    let op = op.to_string();
+    write!(f, "({op}")?;
+
+    for (index, arg) in args.into_iter().enumerate() {
+        if index > 0 || !op.is_empty() {
+            f.write_str(" ")?;
+        }
+
+        write!(f, "{arg}")?;
+    }
+
+    f.write_str(")")
+}
@@ -0,0 +1,212 @@
+mod display;
+
+use crate::{
+    ast::{BinOp, Literal, UnOp},
+    locals::Local,
+    spans::Span,
+    symbols::Symbol,
+};
+
+/// A high-level intermediate representation of a program.
+#[derive(Debug)]
+pub struct Hir(pub Box<[Stmt]>);
+
+impl Hir {
+    /// Returns an [`Iterator`] over the [`Symbol`]s and [`Span`]s of global
+    /// variable assignments in the `Hir`.
+    pub fn global_assignments(&self) -> impl Iterator<Item = (Symbol, Span)> {
+        self.0.iter().flat_map(Stmt::global_assignments)
+    }
+
+    /// Returns an [`Iterator`] over the [`Symbol`]s of global variable
+    /// assignments in the `Hir` with the top-level [`Stmt`]s which contain
+    /// them.
+    pub fn global_assignment_stmts(&self) -> impl Iterator<Item = (Symbol, &Stmt)> {
+        self.0
+            .iter()
+            .flat_map(|s| s.global_assignments().into_iter().map(move |(g, _)| (g, s)))
+    }
+}
+
+/// A statement.
+#[derive(Debug)]
+pub enum Stmt {
+    /// A block `Stmt`.
+    Block(Box<[Self]>),
+
+    /// A sequence of `Stmt`s which are executed in the current scope.
+    Sequence(Box<[Self]>),
+
+    /// A global variable assignment with the assignment's [`Span`].
+    AssignGlobal(Symbol, Box<Expr>, Span),
+
+    /// A local variable definition.
+    DefineLocal(Local, Box<Expr>),
+
+    /// An implicit print.
+    Print(Box<Expr>),
+
+    /// An `Expr`.
+    Expr(Box<Expr>),
+}
+
+impl Stmt {
+    /// Returns the [`Symbol`]s and [`Span`]s of the global variables assigned
+    /// by the `Stmt`. Global variables can only be assigned at the top level,
+    /// or in a top-level block which unpacks a tuple.
+    fn global_assignments(&self) -> Vec<(Symbol, Span)> {
+        match self {
+            Self::AssignGlobal(symbol, _, span) => vec![(*symbol, *span)],
+            Self::Block(stmts) => stmts.iter().flat_map(Self::global_assignments).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Appends the [`Symbol`]s of the global variables read by the `Stmt` to
+    /// a [`Vec`] without duplicates, including those read by nested functions.
+    pub fn collect_global_reads(&self, reads: &mut Vec<Symbol>) {
+        match self {
+            Self::Block(stmts) | Self::Sequence(stmts) => {
+                for stmt in stmts {
+                    stmt.collect_global_reads(reads);
+                }
+            }
+            Self::AssignGlobal(_, expr, _)
+            | Self::DefineLocal(_, expr)
+            | Self::Print(expr)
+            | Self::Expr(expr) => expr.collect_global_reads(reads),
+        }
+    }
+}
+
+/// An expression.
+#[derive(Debug)]
+pub enum Expr {
+    /// A [`Literal`].
+    Literal(Literal),
+
+    /// A global variable with the variable's [`Span`].
+    Global(Symbol, Span),
+
+    /// A local variable.
+    Local(Local),
+
+    /// A block `Expr`.
+    Block(Box<[Stmt]>, Box<Self>),
+
+    /// A function.
+    Function(Option<Local>, Box<[Local]>, Box<Self>),
+
+    /// A function call with the call's [`Span`].
+    Call(Box<Self>, Box<[Self]>, Span),
+
+    /// A list of element `Expr`s.
+    List(Box<[Self]>),
+
+    /// A tuple of element `Expr`s.
+    Tuple(Box<[Self]>),
+
+    /// An index into a list or tuple with the index's [`Span`].
+    Index(Box<Self>, Box<Self>, Span),
+
+    /// A tuple which is checked to have a number of elements before it is
+    /// unpacked, with the unpacking assignment's [`Span`].
+    Unpack(Box<Self>, usize, Span),
+
+    /// A unary operation with the operation's [`Span`].
+    Unary(UnOp, Box<Self>, Span),
+
+    /// A binary operation with the operation's [`Span`].
+    Binary(BinOp, Box<Self>, Box<Self>, Span),
+
+    /// A lazy value of a function `Expr` with no parameters.
+    Lazy(Box<Self>),
+
+    /// A loop with variable [`Local`]s, their initial values, and a body.
+    Loop(Box<[Local]>, Box<[Self]>, Box<Self>),
+
+    /// A jump to the start of the innermost loop with new values for its
+    /// variables.
+    Recur(Box<[Self]>),
+
+    /// A while loop with variable [`Local`]s, their initial values, a
+    /// condition, a body which recurs to the next iteration, an exit `Expr`
+    /// which is evaluated when the condition is false, and the condition's
+    /// [`Span`].
+    While(
+        Box<[Local]>,
+        Box<[Self]>,
+        Box<Self>,
+        Box<Self>,
+        Box<Self>,
+        Span,
+    ),
+
+    /// A ternary conditional with the condition's [`Span`].
+    Cond(Box<Self>, Box<Self>, Box<Self>, Span),
+}
+
+impl Expr {
+    /// Appends the [`Symbol`]s of the global variables read by the `Expr` to
+    /// a [`Vec`] without duplicates, including those read by nested functions.
+    pub fn collect_global_reads(&self, reads: &mut Vec<Symbol>) {
+        match self {
+            Self::Literal(_) | Self::Local(_) => {}
+            Self::Global(symbol, _) => {
+                if !reads.contains(symbol) {
+                    reads.push(*symbol);
+                }
+            }
+            Self::Block(stmts, expr) => {
+                for stmt in stmts {
+                    stmt.collect_global_reads(reads);
+                }
+
+                expr.collect_global_reads(reads);
+            }
+            Self::Function(_, _, expr)
+            | Self::Unpack(expr, ..)
+            | Self::Unary(_, expr, _)
+            | Self::Lazy(expr) => {
+                expr.collect_global_reads(reads);
+            }
+            Self::Call(callee, args, _) => {
+                callee.collect_global_reads(reads);
+
+                for arg in args {
+                    arg.collect_global_reads(reads);
+                }
+            }
+            Self::Loop(_, args, body) => {
+                for arg in args {
+                    arg.collect_global_reads(reads);
+                }
+
+                body.collect_global_reads(reads);
+            }
+            Self::Binary(_, lhs, rhs, _) | Self::Index(lhs, rhs, _) => {
+                lhs.collect_global_reads(reads);
+                rhs.collect_global_reads(reads);
+            }
+            Self::List(exprs) | Self::Tuple(exprs) | Self::Recur(exprs) => {
+                for expr in exprs {
+                    expr.collect_global_reads(reads);
+                }
+            }
+            Self::While(_, values, cond, body, exit, _) => {
+                for value in values {
+                    value.collect_global_reads(reads);
+                }
+
+                cond.collect_global_reads(reads);
+                body.collect_global_reads(reads);
+                exit.collect_global_reads(reads);
+            }
+            Self::Cond(cond, then_expr, else_expr, _) => {
+                cond.collect_global_reads(reads);
+                then_expr.collect_global_reads(reads);
+                else_expr.collect_global_reads(reads);
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+/// A fixed-width integer mode where numbers are integers and arithmetic wraps
+/// around.
+#[derive(Clone, Copy)]
+pub struct IntegerMode {
+    /// The number of bits.
+    bits: u32,
+
+    /// Whether negative numbers are represented with two's complement.
+    is_signed: bool,
+}
+
+impl IntegerMode {
+    /// Returns an `IntegerMode` from its name (e.g. `u32` or `i64`). This
+    /// function returns [`None`] if the name does not correspond to an
+    /// `IntegerMode`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (is_signed, bits) = match name.split_at_checked(1)? {
+            ("u", bits) => (false, bits),
+            ("i", bits) => (true, bits),
+            _ => return None,
+        };
+
+        let bits = match bits {
+            "8" => 8,
+            "16" => 16,
+            "32" => 32,
+            "64" => 64,
+            _ => return None,
+        };
+
+        Some(Self { bits, is_signed })
+    }
+
+    /// Returns the `IntegerMode`'s name.
+    pub fn name(self) -> String {
+        let prefix = if self.is_signed { 'i' } else { 'u' };
+        format!("{prefix}{}", self.bits)
+    }
+
+    /// Wraps an integer into the `IntegerMode`'s range.
+    pub const fn wrap(self, value: i128) -> i128 {
+        let shift = 128 - self.bits;
+
+        // Shifting the low bits to the top and back discards the high bits,
+        // and an arithmetic right shift extends the sign of signed integers.
+        if self.is_signed {
+            (value << shift) >> shift
+        } else {
+            ((value.cast_unsigned() << shift) >> shift).cast_signed()
+        }
+    }
+
+    /// Truncates a number to an integer and wraps it into the `IntegerMode`'s
+    /// range. Infinities and NaN are wrapped to zero.
+    pub fn truncate(self, value: f64) -> i128 {
+        if !value.is_finite() {
+            return 0;
+        }
+
+        // The remainder of a float is exact, so large numbers are wrapped
+        // without losing their low bits.
+        let modulus = 2.0_f64.powi(self.bits.cast_signed());
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the remainder is an integer with a magnitude less than 2^64"
+        )]
+        let value = (value.trunc() % modulus) as i128;
+
+        self.wrap(value)
+    }
+
+    /// Returns the wrapped quotient of two integers, rounded toward zero. This
+    /// function returns [`None`] if the divisor is zero.
+    pub const fn divide(self, lhs: i128, rhs: i128) -> Option<i128> {
+        if rhs == 0 {
+            return None;
+        }
+
+        Some(self.wrap(lhs.wrapping_div(rhs)))
+    }
+
+    /// Returns an integer raised to an integer power and wrapped. Negative
+    /// powers are rounded toward zero. This function returns [`None`] if zero
+    /// is raised to a negative power.
+    pub const fn power(self, base: i128, exponent: i128) -> Option<i128> {
+        if exponent < 0 {
+            return match base {
+                0 => None,
+                1 => Some(1),
+                -1 if exponent % 2 == 0 => Some(1),
+                -1 => Some(self.wrap(-1)),
+                _ => Some(0),
+            };
+        }
+
+        // Exponentiation by squaring wraps at every step, which keeps the low
+        // bits of the result exact.
+        let (mut result, mut base, mut exponent) = (1_i128, base, exponent.cast_unsigned());
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.wrapping_mul(base);
+            }
+
+            base = base.wrapping_mul(base);
+            exponent >>= 1_u32;
+        }
+
+        Some(self.wrap(result))
+    }
+
+    /// Returns the two's complement bit pattern of an integer.
+    pub const fn bit_pattern(self, value: i128) -> u64 {
+        let mask = u128::MAX >> (128 - self.bits);
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the pattern is masked to at most 64 bits"
+        )]
+        let pattern = (value.cast_unsigned() & mask) as u64;
+
+        pattern
+    }
+}
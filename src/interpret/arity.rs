@@ -0,0 +1,39 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A range of numbers of arguments accepted by a function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Arity {
+    /// The least number of arguments.
+    min: usize,
+
+    /// The greatest number of arguments, or [`None`] if there is no limit.
+    max: Option<usize>,
+}
+
+impl Arity {
+    /// Creates a new `Arity` from a least number of arguments and an optional
+    /// greatest number of arguments.
+    pub const fn new(min: usize, max: Option<usize>) -> Self {
+        Self { min, max }
+    }
+
+    /// Creates a new `Arity` which accepts an exact number of arguments.
+    pub const fn exact(count: usize) -> Self {
+        Self::new(count, Some(count))
+    }
+
+    /// Returns [`true`] if the `Arity` accepts a number of arguments.
+    pub fn accepts(self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|m| count <= m)
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{max}"),
+            Some(max) => write!(f, "{} to {max}", self.min),
+            None => write!(f, "{} or more", self.min),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::symbols::Symbol;
+
 use super::InterpretError;
 
 /// A kind of [`InterpretError`].
@@ -9,6 +11,10 @@ pub enum ErrorKind {
     #[error("type error")]
     InvalidType,
 
+    /// Values with different types were ordered.
+    #[error("cannot compare {0} with {1}")]
+    MixedComparison(&'static str, &'static str),
+
     /// A division by zero was attempted.
     #[error("cannot divide by zero")]
     DivideByZero,
@@ -20,11 +26,156 @@ pub enum ErrorKind {
     /// A function was called with the incorrect number of arguments.
     #[error("incorrect number of arguments for function call")]
     IncorrectCallArity,
+
+    /// A number of decimal places was not a non-negative integer.
+    #[error("decimal places must be a non-negative integer")]
+    InvalidDecimalPlaces,
+
+    /// A base was not an integer from 2 to 36.
+    #[error("base must be an integer from 2 to 36")]
+    InvalidBase,
+
+    /// A number could not be converted between bases.
+    #[error("only integers with a magnitude less than 2^64 can be converted between bases")]
+    InvalidBaseNumber,
+
+    /// A string contained digits which are not valid in a base.
+    #[error("string contains digits which are not valid in base {0}")]
+    InvalidDigits(u32),
+
+    /// A list or tuple index was not a non-negative integer.
+    #[error("indices must be non-negative integers")]
+    InvalidIndex,
+
+    /// A list or tuple index was greater than or equal to its length.
+    #[error("index {0} is out of bounds for a {1} of length {2}")]
+    IndexOutOfBounds(f64, &'static str, usize),
+
+    /// A value which is not a tuple was unpacked.
+    #[error("cannot unpack a {0} value, only tuples can be unpacked")]
+    UnpackedNonTuple(&'static str),
+
+    /// A tuple was unpacked into a different number of variables.
+    #[error("cannot unpack a tuple of length {0} into {1} variables")]
+    UnpackLength(usize, usize),
+
+    /// A format string was invalid.
+    #[error("invalid format string")]
+    InvalidFormatString,
+
+    /// A plot range was not finite and increasing.
+    #[error("plot range must be finite with a minimum less than its maximum")]
+    InvalidPlotRange,
+
+    /// A table range was invalid or had too many rows.
+    #[error("table step must be positive and give from 1 to 10000 rows")]
+    InvalidTableRange,
+
+    /// A histogram had an invalid number of bins.
+    #[error("histogram bins must be a whole number from 1 to 1000")]
+    InvalidBinCount,
+
+    /// A histogram was drawn without any finite numbers.
+    #[error("histograms need at least one finite number")]
+    EmptyHistogram,
+
+    /// A date was invalid or out of range.
+    #[error("invalid date")]
+    InvalidDate,
+
+    /// Amounts of money in different currencies were mixed.
+    #[error("cannot mix amounts in different currencies, use 'convert' first")]
+    MixedCurrencies,
+
+    /// A currency was created with an empty name.
+    #[error("currency names must not be empty")]
+    EmptyCurrencyName,
+
+    /// A currency exchange rate was not positive.
+    #[error("currency rates must be positive")]
+    InvalidRate,
+
+    /// A lazy value depended on itself while it was being forced.
+    #[error("lazy value depends on itself")]
+    CyclicLazyValue,
+
+    /// Code could not be used as a symbolic expression.
+    #[error("only numbers, variables, arithmetic, and function calls can be used in expressions")]
+    InvalidExpression,
+
+    /// An equation was solved for something other than a variable.
+    #[error("equations can only be solved for a variable")]
+    InvalidUnknown,
+
+    /// An equation was true for every value of its variable.
+    #[error("equation is true for every value")]
+    IndeterminateEquation,
+
+    /// An equation had an unknown variable other than the one it was solved
+    /// for.
+    #[error("cannot solve for more than one variable, '{0}' is unknown")]
+    ExtraUnknown(Symbol),
+
+    /// An equation called a function which is not a global variable or a
+    /// native function.
+    #[error("function '{0}' is undefined")]
+    UndefinedFunction(Symbol),
+
+    /// Function calls were nested deeper than the maximum recursion depth.
+    #[error(
+        "function calls are nested more than {0} levels deep, use a loop or a tail call instead"
+    )]
+    RecursionLimit(usize),
+
+    /// Code ran for more than a maximum number of steps.
+    #[error("code ran for more than {0} steps")]
+    StepLimit(usize),
+
+    /// Code built a list or string longer than a maximum length.
+    #[error("list or string is longer than the maximum length of {0}")]
+    LengthLimit(usize),
+
+    /// A host value which has no equivalent [`Value`][super::Value] was passed
+    /// to a function.
+    #[error(
+        "only numbers, Booleans, strings, lists, tuples, dates, durations, and functions can be passed from the host"
+    )]
+    UnsupportedHostValue,
+
+    /// A value which cannot be sent to another thread was mapped in parallel.
+    #[error(
+        "only numbers, Booleans, strings, lists, tuples, dates, and durations can be mapped in parallel"
+    )]
+    UnsupportedParallelValue,
+
+    /// A host object reported an error from an operation.
+    #[error("{0}")]
+    HostObject(String),
+
+    /// A function which was mapped in parallel could not be run on a worker
+    /// thread.
+    #[error("could not map in parallel: {0}")]
+    ParallelMap(String),
+
+    /// The current date was read on a platform without a clock.
+    #[error("the current date is not available on this platform")]
+    NoClock,
+
+    /// An internal invariant was broken by a bug in Clac.
+    #[error("internal error: {0}")]
+    Internal(&'static str),
+
+    /// A global variable was used after it was removed.
+    #[error("variable '{0}' is no longer defined")]
+    UndefinedGlobal(Symbol),
 }
 
 impl From<ErrorKind> for InterpretError {
     #[cold]
     fn from(value: ErrorKind) -> Self {
-        Self(value)
+        Self {
+            kind: value,
+            span: None,
+        }
     }
 }
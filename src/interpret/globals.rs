@@ -1,14 +1,65 @@
-use std::collections::HashMap;
+use std::{mem, rc::Rc};
 
-use crate::symbols::Symbol;
+use crate::{
+    output::Output, parse::Operators, reactive::Formula, settings::Settings, symbols::Symbol,
+};
 
-use super::value::Value;
+use super::{arity::Arity, native::Native, value::Value};
 
-/// A map of global variables.
-#[derive(Default)]
+/// A function which provides the [`Value`] of an undefined global variable
+/// from its [`Symbol`], or [`None`] if it has no [`Value`] for it.
+pub type Provider = Rc<dyn Fn(Symbol) -> Option<Value>>;
+
+/// A function which observes a call to a function [`Value`] with its argument
+/// [`Value`]s before the call is made.
+pub type CallHook = Rc<dyn Fn(&Value, &[Value])>;
+
+/// A function which observes a top-level statement after it is executed, with
+/// its result [`Value`] if it is an expression.
+pub type StatementHook = Rc<dyn Fn(Option<&Value>)>;
+
+/// A map of global variables in definition order. Each global variable is
+/// stored in a slot indexed by its [`Symbol`], so [`Symbol`]s in compiled code
+/// are already resolved to slots and no hashing is needed to access them.
+#[derive(Clone, Default)]
 pub struct Globals {
-    /// The map of [`Symbol`]s to [`Value`]s.
-    values: HashMap<Symbol, Value>,
+    /// The slots of [`Value`]s indexed by [`Symbol`], or [`None`] for
+    /// undefined global variables.
+    slots: Vec<Option<Value>>,
+
+    /// The defined [`Symbol`]s in definition order.
+    order: Vec<Symbol>,
+
+    /// The source code of global variable definitions in definition order.
+    sources: Vec<(Symbol, Box<str>)>,
+
+    /// The [`Formula`]s of global variables recorded in reactive mode.
+    formulas: Vec<(Symbol, Rc<Formula>)>,
+
+    /// The [`Symbol`]s assigned since they were last taken.
+    assigned: Vec<Symbol>,
+
+    /// The number of changes to the global variables, which is used to detect
+    /// whether they were changed.
+    changes: usize,
+
+    /// The [`Provider`] for undefined global variables, if any.
+    provider: Option<Provider>,
+
+    /// The [`CallHook`], if any.
+    call_hook: Option<CallHook>,
+
+    /// The [`StatementHook`], if any.
+    statement_hook: Option<StatementHook>,
+
+    /// The session's [`Settings`].
+    settings: Settings,
+
+    /// The session's custom [`Operators`].
+    operators: Operators,
+
+    /// The session's [`Output`].
+    output: Output,
 }
 
 impl Globals {
@@ -17,18 +68,240 @@ impl Globals {
         Self::default()
     }
 
-    /// Returns an [`Iterator`] over the defined global variable [`Symbol`]s.
+    /// Captures the global variables and their source code in a [`Snapshot`].
+    pub fn snapshot(&self) -> Snapshot {
+        let mut globals = self.clone();
+        globals.assigned.clear();
+        Snapshot(globals)
+    }
+
+    /// Replaces the global variables and their source code with those in a
+    /// [`Snapshot`]. The [`Snapshot`] is unchanged, so it can be restored
+    /// again later. The current [`Settings`], [`Operators`], and [`Output`]
+    /// are kept.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let changes = self.changes + 1;
+        let settings = self.settings;
+        let operators = mem::take(&mut self.operators);
+        let output = mem::take(&mut self.output);
+        *self = snapshot.fork();
+        self.changes = changes;
+        self.settings = settings;
+        self.operators = operators;
+        self.output = output;
+    }
+
+    /// Returns the session's [`Settings`].
+    pub const fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Returns a mutable reference to the session's [`Settings`].
+    pub const fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Returns the session's [`Output`].
+    pub const fn output(&self) -> &Output {
+        &self.output
+    }
+
+    /// Replaces the session's [`Output`].
+    pub fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+
+    /// Returns a mutable reference to the session's custom [`Operators`].
+    pub const fn operators_mut(&mut self) -> &mut Operators {
+        &mut self.operators
+    }
+
+    /// Returns the number of changes to the global variables. This can be
+    /// compared with an earlier number to detect whether the global variables
+    /// were assigned, removed, or restored.
+    pub const fn change_count(&self) -> usize {
+        self.changes
+    }
+
+    /// Returns an [`Iterator`] over the defined global variable [`Symbol`]s in
+    /// definition order.
     pub fn symbols(&self) -> impl Iterator<Item = Symbol> {
-        self.values.keys().copied()
+        self.order.iter().copied()
     }
 
-    /// Assigns a [`Value`] to a [`Symbol`].
+    /// Assigns a [`Value`] to a [`Symbol`]. A redefined global variable keeps
+    /// its original position in definition order.
     pub fn assign(&mut self, symbol: Symbol, value: Value) {
-        self.values.insert(symbol, value);
+        let index = symbol.index();
+
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+
+        if self.slots[index].replace(value).is_none() {
+            self.order.push(symbol);
+        }
+
+        self.assigned.push(symbol);
+        self.changes += 1;
+    }
+
+    /// Sets the [`Provider`] which is consulted when an undefined global
+    /// variable is read, or removes it if [`None`] is given.
+    pub fn set_provider(&mut self, provider: Option<Provider>) {
+        self.provider = provider;
+    }
+
+    /// Returns the [`Value`] of an undefined global variable from the
+    /// [`Provider`] from its [`Symbol`]. This function returns [`None`] if
+    /// there is no [`Provider`] or it has no [`Value`] for the global variable.
+    pub fn provided(&self, symbol: Symbol) -> Option<Value> {
+        let provider = self.provider.as_ref()?;
+        provider(symbol)
+    }
+
+    /// Sets the [`CallHook`] and the [`StatementHook`], or removes them if
+    /// [`None`] is given.
+    pub fn set_hooks(
+        &mut self,
+        call_hook: Option<CallHook>,
+        statement_hook: Option<StatementHook>,
+    ) {
+        self.call_hook = call_hook;
+        self.statement_hook = statement_hook;
+    }
+
+    /// Returns the [`CallHook`]. This function returns [`None`] if there is no
+    /// [`CallHook`].
+    pub fn call_hook(&self) -> Option<CallHook> {
+        self.call_hook.clone()
+    }
+
+    /// Returns the [`StatementHook`]. This function returns [`None`] if there
+    /// is no [`StatementHook`].
+    pub fn statement_hook(&self) -> Option<StatementHook> {
+        self.statement_hook.clone()
+    }
+
+    /// Takes the [`Symbol`]s which were assigned since they were last taken.
+    pub fn take_assigned(&mut self) -> Vec<Symbol> {
+        mem::take(&mut self.assigned)
+    }
+
+    /// Returns a reference to a [`Value`] from its [`Symbol`]. This function
+    /// returns [`None`] if the global variable is undefined.
+    pub fn read(&self, symbol: Symbol) -> Option<&Value> {
+        self.slots.get(symbol.index())?.as_ref()
+    }
+
+    /// Returns the [`Arity`] of a global function from its [`Symbol`]. This
+    /// function returns [`None`] if the global variable is undefined or is not
+    /// a function.
+    pub fn arity(&self, symbol: Symbol) -> Option<Arity> {
+        self.read(symbol).and_then(Value::arity)
+    }
+
+    /// Returns [`true`] if a global variable is still bound to a native function
+    /// from its [`Symbol`].
+    pub fn is_native(&self, symbol: Symbol) -> bool {
+        self.native(symbol).is_some()
+    }
+
+    /// Returns the [`Native`] which a global variable is bound to from its
+    /// [`Symbol`]. This function returns [`None`] if the global variable is
+    /// undefined or is not bound to a native function.
+    pub fn native(&self, symbol: Symbol) -> Option<Native> {
+        match self.read(symbol) {
+            Some(Value::Native(native)) => Some(*native),
+            _ => None,
+        }
+    }
+
+    /// Removes a global variable, its source code, and its [`Formula`] from its
+    /// [`Symbol`]. This function returns [`true`] if the global variable was
+    /// defined.
+    pub fn remove(&mut self, symbol: Symbol) -> bool {
+        self.sources.retain(|(s, _)| *s != symbol);
+        self.remove_formula(symbol);
+
+        let Some(slot) = self.slots.get_mut(symbol.index()) else {
+            return false;
+        };
+
+        if slot.take().is_none() {
+            return false;
+        }
+
+        self.order.retain(|s| *s != symbol);
+        self.changes += 1;
+        true
+    }
+
+    /// Returns [`true`] if a global variable is defined from its [`Symbol`].
+    pub fn contains(&self, symbol: Symbol) -> bool {
+        self.read(symbol).is_some()
+    }
+
+    /// Sets the source code which defined a global variable from its
+    /// [`Symbol`].
+    pub fn set_source(&mut self, symbol: Symbol, source: &str) {
+        self.sources.retain(|(s, _)| *s != symbol);
+        self.sources.push((symbol, source.into()));
+    }
+
+    /// Returns the source code which defined a global variable from its
+    /// [`Symbol`]. This function returns [`None`] if the global variable has
+    /// no source code.
+    pub fn source(&self, symbol: Symbol) -> Option<&str> {
+        self.sources
+            .iter()
+            .find_map(|(s, source)| (*s == symbol).then_some(source.as_ref()))
+    }
+
+    /// Sets the [`Formula`] which recalculates a global variable from its
+    /// [`Symbol`].
+    pub fn set_formula(&mut self, symbol: Symbol, formula: Rc<Formula>) {
+        self.remove_formula(symbol);
+        self.formulas.push((symbol, formula));
+    }
+
+    /// Removes the [`Formula`] which recalculates a global variable from its
+    /// [`Symbol`].
+    pub fn remove_formula(&mut self, symbol: Symbol) {
+        self.formulas.retain(|(s, _)| *s != symbol);
+    }
+
+    /// Returns the [`Formula`] which recalculates a global variable from its
+    /// [`Symbol`]. This function returns [`None`] if the global variable has
+    /// no [`Formula`].
+    pub fn formula(&self, symbol: Symbol) -> Option<Rc<Formula>> {
+        self.formulas
+            .iter()
+            .find_map(|(s, formula)| (*s == symbol).then(|| Rc::clone(formula)))
+    }
+
+    /// Returns an [`Iterator`] over the [`Symbol`]s and [`Formula`]s of global
+    /// variables in the order the [`Formula`]s were recorded.
+    pub fn formulas(&self) -> impl Iterator<Item = (Symbol, &Formula)> {
+        self.formulas
+            .iter()
+            .map(|(s, formula)| (*s, formula.as_ref()))
+    }
+}
+
+/// An immutable copy of [`Globals`]. [`Value`]s cannot be mutated, so they are
+/// shared with the [`Globals`] instead of being deeply copied.
+pub struct Snapshot(Globals);
+
+impl Snapshot {
+    /// Creates new [`Globals`] from the `Snapshot` which can be changed
+    /// independently of the `Snapshot` and any other [`Globals`].
+    pub fn fork(&self) -> Globals {
+        self.0.clone()
     }
 
-    /// Returns a reference to a [`Value`] from its [`Symbol`].
-    pub fn read(&self, symbol: Symbol) -> &Value {
-        &self.values[&symbol]
+    /// Returns the number of global variables in the `Snapshot`.
+    pub const fn variable_count(&self) -> usize {
+        self.0.order.len()
     }
 }
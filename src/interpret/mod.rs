@@ -1,56 +1,106 @@
+#[cfg(test)]
+mod tests;
+
+mod arity;
 mod errors;
 mod globals;
+mod money;
 mod native;
+mod native_info;
+mod object;
+mod pretty;
+mod range;
+mod symbolic;
+#[cfg(feature = "threaded-dispatch")]
+mod threaded;
+mod thunk;
+mod time;
 mod value;
 
 use thiserror::Error;
 
-pub use self::{globals::Globals, native::install_natives};
+pub use self::{
+    arity::Arity,
+    errors::ErrorKind,
+    globals::{CallHook, Globals, Provider, Snapshot, StatementHook},
+    native::{Native, install_natives},
+    native_info::{Category, NATIVES, NativeInfo},
+    object::{ArithOp, Object, Side},
+    value::Value,
+};
 
-use std::{mem, rc::Rc};
+#[cfg(feature = "threaded-dispatch")]
+pub use self::threaded::ThreadedCode;
 
-use crate::cfg::{BasicBlock, Cfg, Function, Instruction, Label, Terminator};
+use std::{cmp::Ordering, mem, rc::Rc};
 
-use self::{
-    errors::ErrorKind,
-    value::{Closure, Value},
+use crate::{
+    ast::Literal,
+    cfg::{Cfg, Comparison, Function, Instruction, Label, Terminator},
+    integer::IntegerMode,
+    output::Output,
+    settings::Settings,
+    spans::SourceSpan,
+    symbols::Symbol,
 };
 
+use self::{native::Caller, thunk::Thunk, value::Closure};
+
+/// An error caught while interpreting a [`Cfg`].
 #[derive(Debug, Error)]
-#[repr(transparent)]
-#[error(transparent)]
-pub struct InterpretError(ErrorKind);
+#[error("{kind}")]
+pub struct InterpretError {
+    /// The [`ErrorKind`].
+    kind: ErrorKind,
+
+    /// The [`SourceSpan`] where the `InterpretError` occurred, if any.
+    span: Option<SourceSpan>,
+}
+
+impl InterpretError {
+    /// Returns the [`SourceSpan`] where the `InterpretError` occurred. This
+    /// function returns [`None`] if the `InterpretError` has no
+    /// [`SourceSpan`].
+    pub const fn span(&self) -> Option<&SourceSpan> {
+        self.span.as_ref()
+    }
+
+    /// Sets the `InterpretError`'s [`SourceSpan`] if it does not already have
+    /// one.
+    fn with_span(mut self, span: Option<SourceSpan>) -> Self {
+        if self.span.is_none() {
+            self.span = span;
+        }
+
+        self
+    }
+}
 
 /// Interprets a [`Cfg`] with [`Globals`]. This function returns an
 /// [`InterpretError`] if an error occurred.
 pub fn interpret_cfg(cfg: &Cfg, globals: &mut Globals) -> Result<(), InterpretError> {
+    Interpreter::new(globals).run(cfg)
+}
+
+/// Interprets a [`Cfg`] with [`Globals`] and returns the results of its
+/// top-level expressions instead of printing them. This function returns an
+/// [`InterpretError`] if an error occurred.
+pub fn evaluate_cfg(cfg: &Cfg, globals: &mut Globals) -> Result<Vec<Value>, InterpretError> {
     let mut interpreter = Interpreter::new(globals);
-    let mut called_functions: Vec<Rc<Function>> = Vec::new();
-    let mut label = Label::default();
-
-    loop {
-        let basic_block = called_functions
-            .last()
-            .map_or(cfg, |f| &f.cfg)
-            .basic_block(label);
-
-        let flow = interpreter.interpret_basic_block(basic_block)?;
-
-        match flow {
-            Flow::Halt => break,
-            Flow::Jump(target_label) => label = target_label,
-            Flow::Call(function) => {
-                called_functions.push(function);
-                label = Label::default();
-            }
-            Flow::Return(return_label) => {
-                called_functions.truncate(called_functions.len() - 1);
-                label = return_label;
-            }
-        }
-    }
+    interpreter.results = Some(Vec::new());
+    interpreter.run(cfg)?;
+    Ok(interpreter.results.unwrap_or_default())
+}
 
-    Ok(())
+/// Calls a function [`Value`] with arguments and [`Globals`], then returns its
+/// return [`Value`]. This function returns an [`InterpretError`] if an error
+/// occurred.
+pub fn call_value(
+    callee: &Value,
+    args: &[Value],
+    globals: &mut Globals,
+) -> Result<Value, InterpretError> {
+    Interpreter::new(globals).call(callee, args)
 }
 
 /// A structure which interprets a [`Cfg`].
@@ -69,28 +119,125 @@ struct Interpreter<'glb> {
 
     /// The stack of [`Return`]s.
     returns: Vec<Return>,
+
+    /// The [`IntegerMode`] for wrapping numbers, if any.
+    integer_mode: Option<IntegerMode>,
+
+    /// The maximum number of nested function calls.
+    max_recursion: usize,
+
+    /// The maximum number of steps, if any.
+    max_steps: Option<usize>,
+
+    /// The maximum length of a list or string.
+    max_length: usize,
+
+    /// The number of steps which have been run.
+    steps: usize,
+
+    /// The [`CallHook`], if any.
+    call_hook: Option<CallHook>,
+
+    /// The results of top-level expressions if they are collected instead of
+    /// printed.
+    results: Option<Vec<Value>>,
 }
 
 impl<'glb> Interpreter<'glb> {
     /// Creates a new `Interpreter` from [`Globals`].
-    const fn new(globals: &'glb mut Globals) -> Self {
+    fn new(globals: &'glb mut Globals) -> Self {
         Self {
             stack: Vec::new(),
             frame: 0,
+            call_hook: globals.call_hook(),
+            integer_mode: globals.settings().integer_mode,
+            max_recursion: globals.settings().limits.max_recursion(),
+            max_steps: globals.settings().limits.steps,
+            max_length: globals.settings().limits.max_length(),
+            steps: 0,
             globals,
             upvars: Vec::new(),
             returns: Vec::new(),
+            results: None,
         }
     }
 
-    /// Interprets a [`BasicBlock`] and returns a [`Flow`]. This function
-    /// returns an [`InterpretError`] if an error occurred.
-    fn interpret_basic_block(&mut self, basic_block: &BasicBlock) -> Result<Flow, InterpretError> {
-        for instruction in &basic_block.instructions {
-            self.interpret_instruction(instruction)?;
+    /// Interprets a [`Cfg`] until it halts or returns. This function returns
+    /// an [`InterpretError`] if an error occurred.
+    fn run(&mut self, cfg: &Cfg) -> Result<(), InterpretError> {
+        let mut called_functions: Vec<Rc<Function>> = Vec::new();
+        let mut tail_called_function: Option<Rc<Function>> = None;
+        let mut label = Label::default();
+
+        loop {
+            // A function which was tail called by the outermost function
+            // replaces its CFG.
+            let current_cfg = called_functions
+                .last()
+                .or(tail_called_function.as_ref())
+                .map_or(cfg, |f| &f.cfg);
+
+            // Each basic block is one step, so loops which never end are
+            // stopped when there is a step limit.
+            self.steps += 1;
+
+            if let Some(max_steps) = self.max_steps
+                && self.steps > max_steps
+            {
+                return Err(ErrorKind::StepLimit(max_steps).into());
+            }
+
+            let flow = self.interpret_basic_block(current_cfg, label)?;
+
+            match flow {
+                Flow::Halt => break,
+                Flow::Jump(target_label) => label = target_label,
+                Flow::Call(function) => {
+                    called_functions.push(function);
+                    label = Label::default();
+                }
+                Flow::TailCall(function) => {
+                    match called_functions.last_mut() {
+                        Some(called_function) => *called_function = function,
+                        None => tail_called_function = Some(function),
+                    }
+
+                    label = Label::default();
+                }
+                Flow::Return(return_label) => {
+                    // Returning from the outermost function ends a call which
+                    // was made by a native.
+                    if called_functions.pop().is_none() {
+                        break;
+                    }
+
+                    label = return_label;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Interprets a [`BasicBlock`][crate::cfg::BasicBlock] from a [`Cfg`] and
+    /// its [`Label`], then returns a [`Flow`]. This function returns an
+    /// [`InterpretError`] if an error occurred.
+    fn interpret_basic_block(&mut self, cfg: &Cfg, label: Label) -> Result<Flow, InterpretError> {
+        let basic_block = cfg.basic_block(label);
+
+        #[cfg(feature = "threaded-dispatch")]
+        self.interpret_threaded(basic_block)
+            .map_err(|(index, e)| e.with_span(cfg.span(label, index)))?;
+
+        #[cfg(not(feature = "threaded-dispatch"))]
+        for (index, instruction) in basic_block.instructions.iter().enumerate() {
+            self.interpret_instruction(instruction)
+                .map_err(|e| e.with_span(cfg.span(label, index)))?;
         }
 
+        let index = basic_block.instructions.len();
         self.interpret_terminator(&basic_block.terminator)
+            .map_err(|e| e.with_span(cfg.span(label, index)))
     }
 
     /// Interprets an [`Instruction`]. This function returns an
@@ -101,104 +248,162 @@ impl<'glb> Interpreter<'glb> {
     )]
     fn interpret_instruction(&mut self, instruction: &Instruction) -> Result<(), InterpretError> {
         match instruction {
-            Instruction::PushLiteral(literal) => self.push((*literal).into()),
+            Instruction::PushLiteral(Literal::Number(value)) => self.push_number(*value),
+            Instruction::PushLiteral(Literal::Integer(value)) => self.push_integer(*value),
+            Instruction::PushLiteral(literal) => self.push(literal.clone().into()),
             Instruction::PushFunction(function) => self.push(Value::Function(Rc::clone(function))),
-            Instruction::PushGlobal(symbol) => self.push(self.globals.read(*symbol).clone()),
-            Instruction::PushLocal(offset) => self.push(self.stack[self.frame + *offset].clone()),
-            Instruction::PushUpvar(offset) => self.push((*self.upvars[*offset]).clone()),
-            Instruction::Pop(count) => self.stack.truncate(self.stack.len() - count),
-            Instruction::Print => println!("{}", self.pop()),
-            Instruction::Negate => {
-                let rhs = self.pop_number()?;
-                self.push(Value::Number(-rhs));
-            }
-            Instruction::Not => {
-                let rhs = self.pop_bool()?;
-                self.push(Value::Bool(!rhs));
+            Instruction::PushGlobal(symbol) => {
+                let value = if let Some(value) = self.globals.read(*symbol) {
+                    value.clone()
+                } else {
+                    // Global variables which were removed after compiling may
+                    // still be provided by the host.
+                    let value = self.globals.provided(*symbol);
+                    let value = value.ok_or(ErrorKind::UndefinedGlobal(*symbol))?;
+                    self.globals.assign(*symbol, value.clone());
+                    value
+                };
+                self.push_forced(value)?;
             }
-            Instruction::Add => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Number(lhs + rhs));
+            Instruction::PushLocal(offset) => {
+                let value = self.local(*offset)?;
+                self.push_forced(value)?;
             }
-            Instruction::Subtract => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Number(lhs - rhs));
+            Instruction::PushUpvar(offset) => {
+                let value = self.upvar(*offset)?;
+                self.push_forced(value)?;
             }
-            Instruction::Multiply => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Number(lhs * rhs));
+            Instruction::Pop(count) => {
+                let len = self.stack.len().checked_sub(*count);
+                let len = len.ok_or(ErrorKind::Internal("popped past the bottom of the stack"))?;
+                self.stack.truncate(len);
             }
-            Instruction::Divide => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-
-                if !rhs.is_normal() {
-                    return Err(ErrorKind::DivideByZero.into());
+            Instruction::Print => {
+                let value = self.pop()?;
+                let value = thunk::force(value, self)?;
+
+                match &mut self.results {
+                    None => {
+                        let settings = self.globals.settings();
+                        let output = self.globals.output();
+                        let result = settings.result_format.separator().map_or_else(
+                            || pretty::pretty(&value, settings),
+                            |separator| pretty::table(&value, separator, settings),
+                        );
+
+                        output.echo(&result, settings.echo);
+                    }
+                    Some(results) => results.push(value),
                 }
-
-                self.push(Value::Number(lhs / rhs));
             }
-            Instruction::Power => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Number(lhs.powf(rhs)));
+            Instruction::Negate => match self.pop()? {
+                Value::Number(rhs) => self.push_number(-rhs),
+                Value::Integer(rhs) => self.push_integer(rhs.wrapping_neg()),
+                Value::Duration(rhs) => self.push(Value::Duration(-rhs)),
+                Value::Object(rhs) => self.push(rhs.negate().ok_or(ErrorKind::InvalidType)?),
+                _ => return Err(ErrorKind::InvalidType.into()),
+            },
+            Instruction::Not => {
+                let rhs = self.pop_bool()?;
+                self.push(Value::Bool(!rhs));
             }
-            Instruction::Equal => {
-                let rhs = self.pop();
-                let lhs = self.pop();
+            Instruction::Add => match (self.pop()?, self.pop()?) {
+                (Value::Number(rhs), Value::Number(lhs)) => self.push_number(lhs + rhs),
+                (rhs, lhs) => self.push_arith(ArithOp::Add, &lhs, &rhs)?,
+            },
+            Instruction::Subtract => match (self.pop()?, self.pop()?) {
+                (Value::Number(rhs), Value::Number(lhs)) => self.push_number(lhs - rhs),
+                (rhs, lhs) => self.push_arith(ArithOp::Subtract, &lhs, &rhs)?,
+            },
+            Instruction::Multiply => match (self.pop()?, self.pop()?) {
+                (Value::Number(rhs), Value::Number(lhs)) => self.push_number(lhs * rhs),
+                (rhs, lhs) => self.push_arith(ArithOp::Multiply, &lhs, &rhs)?,
+            },
+            Instruction::Divide => match (self.pop()?, self.pop()?) {
+                (Value::Number(rhs), Value::Number(lhs)) => {
+                    if !rhs.is_normal() {
+                        return Err(ErrorKind::DivideByZero.into());
+                    }
 
-                if !lhs.matches_value_type(&rhs) {
-                    return Err(ErrorKind::InvalidType.into());
+                    self.push_number(lhs / rhs);
                 }
-
-                self.push(Value::Bool(lhs == rhs));
+                (rhs, lhs) => self.push_arith(ArithOp::Divide, &lhs, &rhs)?,
+            },
+            Instruction::Power => {
+                let rhs = self.pop()?;
+                let lhs = self.pop()?;
+                self.push_power(&lhs, &rhs)?;
             }
-            Instruction::NotEqual => {
-                let rhs = self.pop();
-                let lhs = self.pop();
-
-                if !lhs.matches_value_type(&rhs) {
-                    return Err(ErrorKind::InvalidType.into());
-                }
-
-                self.push(Value::Bool(lhs != rhs));
+            Instruction::Equal => self.push_comparison(Comparison::Equal)?,
+            Instruction::NotEqual => self.push_comparison(Comparison::NotEqual)?,
+            Instruction::Less => self.push_comparison(Comparison::Less)?,
+            Instruction::LessEqual => self.push_comparison(Comparison::LessEqual)?,
+            Instruction::Greater => self.push_comparison(Comparison::Greater)?,
+            Instruction::GreaterEqual => self.push_comparison(Comparison::GreaterEqual)?,
+            Instruction::In => {
+                let collection = self.pop()?;
+                let element = self.pop()?;
+                self.push(Value::Bool(collection.contains(&element)?));
+            }
+            Instruction::MakeList(count) => {
+                let start = self.stack.len().checked_sub(*count);
+                let start =
+                    start.ok_or(ErrorKind::Internal("popped past the bottom of the stack"))?;
+                let elems = self.stack.drain(start..).collect();
+                self.push(Value::List(elems));
             }
-            Instruction::Less => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Bool(lhs < rhs));
+            Instruction::MakeTuple(count) => {
+                let start = self.stack.len().checked_sub(*count);
+                let start =
+                    start.ok_or(ErrorKind::Internal("popped past the bottom of the stack"))?;
+                let elems = self.stack.drain(start..).collect();
+                self.push(Value::Tuple(elems));
             }
-            Instruction::LessEqual => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Bool(lhs <= rhs));
+            Instruction::Index => {
+                let index = self.pop()?;
+                let list = self.pop()?;
+                let elem = list.index(&index)?;
+                let elem = thunk::force(elem, self)?;
+                self.push(elem);
             }
-            Instruction::Greater => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Bool(lhs > rhs));
+            Instruction::Unpack(count) => {
+                let tuple = thunk::force(self.pop()?, self)?;
+                tuple.check_unpack(*count)?;
+                self.push(tuple);
             }
-            Instruction::GreaterEqual => {
-                let rhs = self.pop_number()?;
-                let lhs = self.pop_number()?;
-                self.push(Value::Bool(lhs >= rhs));
+            Instruction::AddLocalNumber(offset, rhs) => {
+                match thunk::force(self.local(*offset)?, self)? {
+                    Value::Number(lhs) => self.push_number(lhs + rhs),
+                    lhs => self.push_arith(ArithOp::Add, &lhs, &Value::Number(*rhs))?,
+                }
+            }
+            Instruction::SubtractLocalNumber(offset, rhs) => {
+                match thunk::force(self.local(*offset)?, self)? {
+                    Value::Number(lhs) => self.push_number(lhs - rhs),
+                    lhs => self.push_arith(ArithOp::Subtract, &lhs, &Value::Number(*rhs))?,
+                }
             }
             Instruction::StoreGlobal(symbol) => {
-                let value = self.pop();
+                let value = self.pop()?;
                 self.globals.assign(*symbol, value);
             }
-            Instruction::StoreLocal(offset) => self.stack[self.frame + *offset] = self.pop(),
+            Instruction::StoreLocal(offset) => {
+                let value = self.pop()?;
+                let local = self.stack.get_mut(self.frame + *offset);
+                *local.ok_or(ErrorKind::Internal("local variable is out of bounds"))? = value;
+            }
             Instruction::DefineUpvar => {
-                let value = self.pop();
+                let value = self.pop()?;
                 self.upvars.push(value.into());
             }
-            Instruction::PopUpvars(count) => self.upvars.truncate(self.upvars.len() - count),
+            Instruction::PopUpvars(count) => {
+                let len = self.upvars.len().checked_sub(*count);
+                let len = len.ok_or(ErrorKind::Internal("popped past the bottom of the upvars"))?;
+                self.upvars.truncate(len);
+            }
             Instruction::IntoClosure => {
-                let Value::Function(function) = self.pop() else {
-                    unreachable!("value should be a function");
+                let Value::Function(function) = self.pop()? else {
+                    return Err(ErrorKind::Internal("closures must be made from functions").into());
                 };
 
                 let closure = Closure {
@@ -208,6 +413,10 @@ impl<'glb> Interpreter<'glb> {
 
                 self.push(Value::Closure(closure.into()));
             }
+            Instruction::IntoThunk => {
+                let function = self.pop()?;
+                self.push(Value::Thunk(Thunk::new(function).into()));
+            }
         }
 
         Ok(())
@@ -217,7 +426,14 @@ impl<'glb> Interpreter<'glb> {
     /// returns an [`InterpretError`] if an error occurred.
     fn interpret_terminator(&mut self, terminator: &Terminator) -> Result<Flow, InterpretError> {
         let branch = match terminator {
-            Terminator::Halt => Flow::Halt,
+            Terminator::Halt => {
+                debug_assert!(
+                    self.upvars.is_empty(),
+                    "upvars defined at the top level should be popped before halting"
+                );
+
+                Flow::Halt
+            }
             Terminator::Jump(label) => Flow::Jump(*label),
             Terminator::Branch(then_label, else_label) => {
                 let label = if self.pop_bool()? {
@@ -228,55 +444,100 @@ impl<'glb> Interpreter<'glb> {
 
                 Flow::Jump(label)
             }
-            Terminator::Call(arity, return_label) => {
-                let mut return_data = Return {
-                    label: *return_label,
-                    frame: self.frame,
-                    upvars: None,
+            Terminator::BranchCompare(comparison, then_label, else_label) => {
+                let label = if self.pop_comparison(*comparison)? {
+                    *then_label
+                } else {
+                    *else_label
                 };
 
+                Flow::Jump(label)
+            }
+            Terminator::Call(arity, return_label) => {
                 let arity = *arity;
-                self.frame = self.stack.len() - arity - 1;
-
-                let function = match &self.stack[self.frame] {
-                    Value::Function(function) => Rc::clone(function),
-                    Value::Closure(closure) => {
-                        let outer_upvars = mem::replace(&mut self.upvars, closure.upvars.clone());
-                        return_data.upvars = Some(outer_upvars);
-                        Rc::clone(&closure.function)
+                let caller_frame = self.frame;
+                let frame = self.stack.len().checked_sub(arity + 1);
+                self.frame = frame.ok_or(ErrorKind::Internal("callee is not on the stack"))?;
+                self.run_call_hook();
+
+                match self.callee(arity)? {
+                    Callee::Function(function, upvars) => {
+                        if self.returns.len() >= self.max_recursion {
+                            return Err(ErrorKind::RecursionLimit(self.max_recursion).into());
+                        }
+
+                        let outer_upvars = mem::replace(&mut self.upvars, upvars);
+
+                        self.returns.push(Return {
+                            label: *return_label,
+                            frame: caller_frame,
+                            upvars: outer_upvars,
+                            upvar_count: self.upvars.len(),
+                        });
+
+                        Flow::Call(function)
                     }
-                    Value::Native(native) => {
-                        let return_value = native.call(&self.stack[self.frame + 1..])?;
-                        self.stack.truncate(self.frame);
+                    Callee::Native(native) => {
+                        let return_value = self.call_native(native, caller_frame)?;
                         self.push(return_value);
-                        self.frame = return_data.frame;
-                        return Ok(Flow::Jump(*return_label));
+                        Flow::Jump(*return_label)
                     }
-                    _ => return Err(ErrorKind::CalledNonFunction.into()),
-                };
-
-                if arity != function.arity {
-                    return Err(ErrorKind::IncorrectCallArity.into());
                 }
+            }
+            Terminator::TailCall(arity) => {
+                let arity = *arity;
+                let callee_offset = self.stack.len().checked_sub(arity + 1);
+                let callee_offset =
+                    callee_offset.ok_or(ErrorKind::Internal("callee is not on the stack"))?;
+
+                // The callee and its arguments replace the current stack frame,
+                // and the current function's upvars are popped as if it had
+                // returned.
+                self.stack.drain(self.frame..callee_offset);
+                let upvar_count = self
+                    .returns
+                    .last()
+                    .ok_or(ErrorKind::Internal("tail called without a caller"))?
+                    .upvar_count;
+
+                self.upvars.truncate(upvar_count);
+                self.run_call_hook();
+
+                match self.callee(arity)? {
+                    Callee::Function(function, upvars) => {
+                        self.upvars = upvars;
 
-                self.returns.push(return_data);
-                Flow::Call(function)
+                        if let Some(return_data) = self.returns.last_mut() {
+                            return_data.upvar_count = self.upvars.len();
+                        }
+
+                        Flow::TailCall(function)
+                    }
+                    Callee::Native(native) => {
+                        let return_value = self.call_native(native, self.frame)?;
+                        self.push(return_value);
+                        return self.interpret_terminator(&Terminator::Return);
+                    }
+                }
             }
             Terminator::Return => {
-                let return_value = self.pop();
+                let return_value = self.pop()?;
                 self.stack.truncate(self.frame);
                 self.push(return_value);
                 let return_data = self
                     .returns
                     .pop()
-                    .expect("return stack should not be empty");
+                    .ok_or(ErrorKind::Internal("returned without a caller"))?;
 
                 self.frame = return_data.frame;
 
-                if let Some(upvars) = return_data.upvars {
-                    self.upvars = upvars;
-                }
+                debug_assert_eq!(
+                    self.upvars.len(),
+                    return_data.upvar_count,
+                    "upvars defined in a function should be popped before it returns"
+                );
 
+                self.upvars = return_data.upvars;
                 Flow::Return(return_data.label)
             }
         };
@@ -284,37 +545,274 @@ impl<'glb> Interpreter<'glb> {
         Ok(branch)
     }
 
+    /// Runs the [`CallHook`] with the callee at the current stack frame and
+    /// the arguments above it if there is a [`CallHook`].
+    fn run_call_hook(&self) {
+        if let Some(call_hook) = &self.call_hook
+            && let Some((callee, args)) = self.stack[self.frame..].split_first()
+        {
+            call_hook(callee, args);
+        }
+    }
+
+    /// Returns the [`Callee`] at the current stack frame for a call with an
+    /// arity. This function returns an [`InterpretError`] if the callee is not
+    /// a function or the arity is incorrect.
+    fn callee(&self, arity: usize) -> Result<Callee, InterpretError> {
+        // Functions which are not closures do not access any upvars from
+        // outside of them, so they start with an empty upvar stack.
+        let (function, upvars) = match &self.stack[self.frame] {
+            Value::Function(function) => (Rc::clone(function), Vec::new()),
+            Value::Closure(closure) => (Rc::clone(&closure.function), closure.upvars.clone()),
+            Value::Native(native) => return Ok(Callee::Native(*native)),
+            _ => return Err(ErrorKind::CalledNonFunction.into()),
+        };
+
+        if arity != function.arity {
+            return Err(ErrorKind::IncorrectCallArity.into());
+        }
+
+        Ok(Callee::Function(function, upvars))
+    }
+
+    /// Calls a [`Native`] at the current stack frame with the arguments above
+    /// it, then restores a caller's stack frame offset and returns the
+    /// [`Native`]'s return [`Value`]. This function returns an
+    /// [`InterpretError`] if an error occurred.
+    fn call_native(
+        &mut self,
+        native: Native,
+        caller_frame: usize,
+    ) -> Result<Value, InterpretError> {
+        // Natives operate on floats, so integers are converted to floats and
+        // back again.
+        let args: Vec<Value> = self
+            .stack
+            .drain(self.frame + 1..)
+            .map(|a| a.to_float())
+            .collect();
+        self.stack.truncate(self.frame);
+        self.frame = caller_frame;
+        let value = native.call(&args, self)?;
+        Ok(self
+            .integer_mode
+            .map_or_else(|| value.clone(), |m| value.to_integer(m)))
+    }
+
     /// Pushes a [`Value`] to the stack.
     fn push(&mut self, value: Value) {
         self.stack.push(value);
     }
 
-    /// Pops a [`Value`] from the stack.
-    fn pop(&mut self) -> Value {
-        self.stack.pop().expect("stack should not be empty")
+    /// Pushes a [`Value`] to the stack after forcing it if it is a lazy
+    /// [`Value`]. This function returns an [`InterpretError`] if an error
+    /// occurred.
+    fn push_forced(&mut self, value: Value) -> Result<(), InterpretError> {
+        let value = thunk::force(value, self)?;
+        self.push(value);
+        Ok(())
+    }
+
+    /// Pushes a number [`Value`] to the stack. The number is truncated and
+    /// wrapped to an integer if there is an [`IntegerMode`].
+    fn push_number(&mut self, value: f64) {
+        let value = self
+            .integer_mode
+            .map_or(Value::Number(value), |m| Value::Integer(m.truncate(value)));
+
+        self.push(value);
     }
 
-    /// Pops a number [`Value`] from the stack and returns its underlying
-    /// [`f64`]. This function returns an [`InterpretError`] if the [`Value`] is
-    /// not a number.
-    fn pop_number(&mut self) -> Result<f64, InterpretError> {
-        match self.pop() {
-            Value::Number(value) => Ok(value),
-            _ => Err(ErrorKind::InvalidType.into()),
+    /// Pushes an integer [`Value`] to the stack. The integer is wrapped if there
+    /// is an [`IntegerMode`], or converted to a float otherwise.
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "large integers are rounded like any other number"
+    )]
+    fn push_integer(&mut self, value: i128) {
+        let value = self.integer_mode.map_or(Value::Number(value as f64), |m| {
+            Value::Integer(m.wrap(value))
+        });
+
+        self.push(value);
+    }
+
+    /// Pushes the result of an arithmetic operation on two [`Value`]s to the
+    /// stack. Numbers use wrapping integer arithmetic if there is an
+    /// [`IntegerMode`]. This function returns an [`InterpretError`] if the
+    /// operation is invalid.
+    fn push_arith(&mut self, op: ArithOp, lhs: &Value, rhs: &Value) -> Result<(), InterpretError> {
+        let (Some(mode), Some(lhs_int), Some(rhs_int)) = (
+            self.integer_mode,
+            self.integer_mode.and_then(|m| lhs.as_integer(m)),
+            self.integer_mode.and_then(|m| rhs.as_integer(m)),
+        ) else {
+            self.check_length(op, lhs, rhs)?;
+            self.push(lhs.arith(op, rhs)?);
+            return Ok(());
+        };
+
+        let value = match op {
+            ArithOp::Add => lhs_int.wrapping_add(rhs_int),
+            ArithOp::Subtract => lhs_int.wrapping_sub(rhs_int),
+            ArithOp::Multiply => lhs_int.wrapping_mul(rhs_int),
+            ArithOp::Divide => mode
+                .divide(lhs_int, rhs_int)
+                .ok_or(ErrorKind::DivideByZero)?,
+        };
+
+        self.push_integer(value);
+        Ok(())
+    }
+
+    /// Checks that an arithmetic operation on two [`Value`]s would not build a
+    /// list or string longer than the maximum length. This function returns an
+    /// [`InterpretError`] if the result would be too long.
+    fn check_length(&self, op: ArithOp, lhs: &Value, rhs: &Value) -> Result<(), InterpretError> {
+        let length = match (op, lhs, rhs) {
+            (ArithOp::Add, Value::Str(lhs), Value::Str(rhs)) => lhs.len().saturating_add(rhs.len()),
+            (ArithOp::Add, Value::List(lhs), Value::List(rhs)) => {
+                lhs.len().saturating_add(rhs.len())
+            }
+            _ => return Ok(()),
+        };
+
+        if length > self.max_length {
+            return Err(ErrorKind::LengthLimit(self.max_length).into());
+        }
+
+        Ok(())
+    }
+
+    /// Pushes a number [`Value`] raised to the power of another number
+    /// [`Value`] to the stack. Numbers use wrapping integer arithmetic if there
+    /// is an [`IntegerMode`]. This function returns an [`InterpretError`] if
+    /// either [`Value`] is not a number.
+    fn push_power(&mut self, lhs: &Value, rhs: &Value) -> Result<(), InterpretError> {
+        let (Some(lhs), Some(rhs)) = (lhs.as_float(), rhs.as_float()) else {
+            return Err(ErrorKind::InvalidType.into());
+        };
+
+        match self.integer_mode {
+            None => self.push_number(lhs.powf(rhs)),
+            Some(mode) => {
+                let value = mode.power(mode.truncate(lhs), mode.truncate(rhs));
+                self.push_integer(value.ok_or(ErrorKind::DivideByZero)?);
+            }
         }
+
+        Ok(())
+    }
+
+    /// Pops a [`Value`] from the stack. This function returns an
+    /// [`InterpretError`] if the stack is empty.
+    fn pop(&mut self) -> Result<Value, InterpretError> {
+        self.stack
+            .pop()
+            .ok_or_else(|| ErrorKind::Internal("stack is empty").into())
+    }
+
+    /// Returns the [`Value`] of a local variable from its stack frame offset.
+    /// This function returns an [`InterpretError`] if the offset is out of
+    /// bounds.
+    fn local(&self, offset: usize) -> Result<Value, InterpretError> {
+        self.stack
+            .get(self.frame + offset)
+            .cloned()
+            .ok_or_else(|| ErrorKind::Internal("local variable is out of bounds").into())
+    }
+
+    /// Returns the [`Value`] of an upvar from its upvar stack offset. This
+    /// function returns an [`InterpretError`] if the offset is out of bounds.
+    fn upvar(&self, offset: usize) -> Result<Value, InterpretError> {
+        self.upvars
+            .get(offset)
+            .map(|u| (**u).clone())
+            .ok_or_else(|| ErrorKind::Internal("upvar is out of bounds").into())
+    }
+
+    /// Pops two [`Value`]s from the stack, compares the first [`Value`] to the
+    /// second [`Value`] with a [`Comparison`], and pushes the Boolean result to
+    /// the stack. This function returns an [`InterpretError`] if the
+    /// [`Value`]s cannot be compared.
+    fn push_comparison(&mut self, comparison: Comparison) -> Result<(), InterpretError> {
+        let result = self.pop_comparison(comparison)?;
+        self.push(Value::Bool(result));
+        Ok(())
+    }
+
+    /// Pops two [`Value`]s from the stack and returns whether the first
+    /// [`Value`] compared to the second [`Value`] with a [`Comparison`] is
+    /// [`true`]. Unordered [`Value`]s (e.g. NaN) are only unequal. This
+    /// function returns an [`InterpretError`] if the [`Value`]s cannot be
+    /// compared.
+    fn pop_comparison(&mut self, comparison: Comparison) -> Result<bool, InterpretError> {
+        let rhs = self.pop()?;
+        let lhs = self.pop()?;
+
+        let ordering = match comparison {
+            Comparison::Equal => return lhs.equals(&rhs),
+            Comparison::NotEqual => return lhs.equals(&rhs).map(|e| !e),
+            _ => lhs.compare(&rhs)?,
+        };
+
+        let result = match comparison {
+            Comparison::Less => ordering == Some(Ordering::Less),
+            Comparison::LessEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Comparison::Greater => ordering == Some(Ordering::Greater),
+            Comparison::GreaterEqual => {
+                matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+            }
+            Comparison::Equal | Comparison::NotEqual => unreachable!("equality is handled above"),
+        };
+
+        Ok(result)
     }
 
     /// Pops a boolean [`Value`] from the stack and returns its underlying
     /// [`bool`]. This function returns an [`InterpretError`] if the [`Value`]
     /// is not a Boolean value.
     fn pop_bool(&mut self) -> Result<bool, InterpretError> {
-        match self.pop() {
+        match self.pop()? {
             Value::Bool(value) => Ok(value),
             _ => Err(ErrorKind::InvalidType.into()),
         }
     }
 }
 
+impl Caller for Interpreter<'_> {
+    fn call(&mut self, callee: &Value, args: &[Value]) -> Result<Value, InterpretError> {
+        self.push(callee.clone());
+
+        match self.integer_mode {
+            None => self.stack.extend_from_slice(args),
+            Some(mode) => self.stack.extend(args.iter().map(|a| a.to_integer(mode))),
+        }
+
+        // The return label is never used because the call ends when its
+        // function returns.
+        let call = Terminator::Call(args.len(), Label::default());
+
+        if let Flow::Call(function) = self.interpret_terminator(&call)? {
+            self.run(&function.cfg)?;
+        }
+
+        Ok(self.pop()?.to_float())
+    }
+
+    fn global(&self, symbol: Symbol) -> Option<Value> {
+        self.globals.read(symbol).cloned()
+    }
+
+    fn settings(&self) -> &Settings {
+        self.globals.settings()
+    }
+
+    fn output(&self) -> &Output {
+        self.globals.output()
+    }
+}
+
 /// Control flow after interpreting a [`Terminator`].
 enum Flow {
     /// Halts execution.
@@ -326,10 +824,23 @@ enum Flow {
     /// Calls a [`Function`].
     Call(Rc<Function>),
 
+    /// Replaces the current [`Function`] with a [`Function`] which returns to
+    /// its caller.
+    TailCall(Rc<Function>),
+
     /// Returns to a [`Label`] from a [`Function`].
     Return(Label),
 }
 
+/// A callee of a call.
+enum Callee {
+    /// A [`Function`] with the upvars that it starts with.
+    Function(Rc<Function>, Vec<Rc<Value>>),
+
+    /// A [`Native`].
+    Native(Native),
+}
+
 /// Data for returning from a function.
 struct Return {
     /// The [`Label`] to return to.
@@ -338,6 +849,10 @@ struct Return {
     /// The stack offset of the return stack frame.
     frame: usize,
 
-    /// The optional stack of upvars to restore.
-    upvars: Option<Vec<Rc<Value>>>,
+    /// The stack of upvars to restore.
+    upvars: Vec<Rc<Value>>,
+
+    /// The number of upvars when the function was called, which should be
+    /// restored by the time it returns.
+    upvar_count: usize,
 }
@@ -0,0 +1,238 @@
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    rc::Rc,
+};
+
+use crate::{format, settings::Settings};
+
+use super::{
+    InterpretError,
+    errors::ErrorKind,
+    object::{self, ArithOp, Object, Side},
+    value::Value,
+};
+
+/// An amount of money in a [`Currency`].
+#[derive(Clone)]
+pub struct Money {
+    /// The amount.
+    amount: f64,
+
+    /// The [`Currency`].
+    currency: Rc<Currency>,
+}
+
+impl Money {
+    /// Creates a new `Money` for one unit of a new [`Currency`] with a name and
+    /// an exchange rate. This function returns an [`InterpretError`] if the
+    /// name is empty or the exchange rate is not positive.
+    pub fn new_unit(name: &str, rate: f64) -> Result<Self, InterpretError> {
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(ErrorKind::EmptyCurrencyName.into());
+        }
+
+        let currency = Currency {
+            name: name.into(),
+            rate: Cell::new(check_rate(rate)?),
+        };
+
+        Ok(Self {
+            amount: 1.0,
+            currency: Rc::new(currency),
+        })
+    }
+
+    /// Sets the exchange rate of the `Money`'s [`Currency`]. This function
+    /// returns an [`InterpretError`] if the exchange rate is not positive.
+    pub fn set_rate(&self, rate: f64) -> Result<(), InterpretError> {
+        self.currency.rate.set(check_rate(rate)?);
+        Ok(())
+    }
+
+    /// Sets the exchange rates of the [`Currency`]s of `Money`s. No exchange
+    /// rate is set if any of them is not positive, in which case this function
+    /// returns an [`InterpretError`].
+    pub fn set_rates(rates: &[(&Self, f64)]) -> Result<(), InterpretError> {
+        for (_, rate) in rates {
+            check_rate(*rate)?;
+        }
+
+        for (money, rate) in rates {
+            money.currency.rate.set(*rate);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `Money` converted to another `Money`'s [`Currency`].
+    pub fn convert(&self, unit: &Self) -> Self {
+        let amount = self.amount / self.currency.rate.get() * unit.currency.rate.get();
+        unit.with_amount(amount)
+    }
+
+    /// Returns the sum of the `Money` and another `Money`. This function
+    /// returns an [`InterpretError`] if the [`Currency`]s do not match.
+    fn add(&self, other: &Self) -> Result<Self, InterpretError> {
+        self.check_currency(other)?;
+        Ok(self.with_amount(self.amount + other.amount))
+    }
+
+    /// Returns the difference between the `Money` and another `Money`. This
+    /// function returns an [`InterpretError`] if the [`Currency`]s do not
+    /// match.
+    fn subtract(&self, other: &Self) -> Result<Self, InterpretError> {
+        self.check_currency(other)?;
+        Ok(self.with_amount(self.amount - other.amount))
+    }
+
+    /// Returns the `Money` multiplied by a factor.
+    fn scale(&self, factor: f64) -> Self {
+        self.with_amount(self.amount * factor)
+    }
+
+    /// Returns the ratio between the `Money` and another `Money`. This
+    /// function returns an [`InterpretError`] if the [`Currency`]s do not
+    /// match or the other `Money` is zero.
+    fn ratio(&self, other: &Self) -> Result<f64, InterpretError> {
+        self.check_currency(other)?;
+
+        if !other.amount.is_normal() {
+            return Err(ErrorKind::DivideByZero.into());
+        }
+
+        Ok(self.amount / other.amount)
+    }
+
+    /// Returns the amounts of the `Money` and another `Money`. This function
+    /// returns [`None`] if the [`Currency`]s do not match.
+    fn amounts(&self, other: &Self) -> Option<(f64, f64)> {
+        Rc::ptr_eq(&self.currency, &other.currency).then_some((self.amount, other.amount))
+    }
+
+    /// Returns a new `Money` with the same [`Currency`] and a different
+    /// amount.
+    fn with_amount(&self, amount: f64) -> Self {
+        Self {
+            amount,
+            currency: Rc::clone(&self.currency),
+        }
+    }
+
+    /// Returns an [`InterpretError`] if the `Money`'s [`Currency`] does not
+    /// match another `Money`'s [`Currency`].
+    fn check_currency(&self, other: &Self) -> Result<(), InterpretError> {
+        if Rc::ptr_eq(&self.currency, &other.currency) {
+            Ok(())
+        } else {
+            Err(ErrorKind::MixedCurrencies.into())
+        }
+    }
+}
+
+impl Object for Money {
+    fn type_name(&self) -> &'static str {
+        "money"
+    }
+
+    fn fmt_with(&self, f: &mut Formatter<'_>, settings: &Settings) -> fmt::Result {
+        let amount = format::format_number(self.amount, settings);
+        write!(f, "{amount} {}", self.currency.name)
+    }
+
+    fn arith(
+        &self,
+        op: ArithOp,
+        other: &Value,
+        side: Side,
+    ) -> Option<Result<Value, InterpretError>> {
+        if let Some(other) = other.downcast_object::<Self>() {
+            let (lhs, rhs) = match side {
+                Side::Lhs => (self, other),
+                Side::Rhs => (other, self),
+            };
+
+            let result = match op {
+                ArithOp::Add => lhs.add(rhs).map(Value::from),
+                ArithOp::Subtract => lhs.subtract(rhs).map(Value::from),
+                ArithOp::Multiply => return None,
+                ArithOp::Divide => lhs.ratio(rhs).map(Value::Number),
+            };
+
+            return Some(result);
+        }
+
+        let Value::Number(number) = other else {
+            return None;
+        };
+
+        match (op, side) {
+            (ArithOp::Multiply, _) => Some(Ok(self.scale(*number).into())),
+            (ArithOp::Divide, Side::Lhs) => {
+                if !number.is_normal() {
+                    return Some(Err(ErrorKind::DivideByZero.into()));
+                }
+
+                Some(Ok(self.scale(number.recip()).into()))
+            }
+            _ => None,
+        }
+    }
+
+    fn negate(&self) -> Option<Value> {
+        Some(self.scale(-1.0).into())
+    }
+
+    #[expect(
+        clippy::float_cmp,
+        reason = "amounts are compared exactly like numbers"
+    )]
+    fn equals(&self, other: &dyn Object) -> bool {
+        object::downcast_ref::<Self>(other)
+            .and_then(|other| self.amounts(other))
+            .is_some_and(|(lhs, rhs)| lhs == rhs)
+    }
+
+    fn compare(&self, other: &dyn Object) -> Result<Option<Ordering>, InterpretError> {
+        let (lhs, rhs) = object::downcast_ref::<Self>(other)
+            .and_then(|other| self.amounts(other))
+            .ok_or(ErrorKind::MixedCurrencies)?;
+
+        Ok(lhs.partial_cmp(&rhs))
+    }
+}
+
+impl From<Money> for Value {
+    fn from(value: Money) -> Self {
+        Self::Object(Rc::new(value))
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &Settings::new())
+    }
+}
+
+/// A currency with an exchange rate.
+struct Currency {
+    /// The name.
+    name: Rc<str>,
+
+    /// The number of units of the `Currency` which are worth one unit of the
+    /// base currency.
+    rate: Cell<f64>,
+}
+
+/// Returns an exchange rate if it is positive. This function returns an
+/// [`InterpretError`] if the exchange rate is not positive.
+fn check_rate(rate: f64) -> Result<f64, InterpretError> {
+    if rate.is_normal() && rate > 0.0_f64 {
+        Ok(rate)
+    } else {
+        Err(ErrorKind::InvalidRate.into())
+    }
+}
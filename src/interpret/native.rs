@@ -1,6 +1,27 @@
-use crate::symbols::Symbol;
+use std::{
+    cmp::Ordering,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use super::{Globals, InterpretError, errors::ErrorKind, value::Value};
+use crate::{
+    engine,
+    format::{self, FormatSpec},
+    output::{Output, out, outln},
+    plot,
+    settings::Settings,
+    symbols::Symbol,
+};
+
+use super::{
+    Globals, InterpretError,
+    errors::ErrorKind,
+    money::Money,
+    native_info::NATIVES,
+    range::Range,
+    symbolic::{self, Expression},
+    thunk, time,
+    value::Value,
+};
 
 /// A native function.
 #[expect(
@@ -14,71 +35,351 @@ pub enum Native {
     /// Signature: `__dump(f: function) -> function`
     Dump,
 
+    /// Prints `x` as the nearest simple fraction and returns `x`.
+    ///
+    /// Signature: `as_fraction(x: number) -> number`
+    AsFraction,
+
+    /// Returns the number written in base `n` by the string `digits`.
+    ///
+    /// Signature: `base(n: number, digits: string) -> number`
+    Base,
+
+    /// Returns integer `x` written in base `n` as a string.
+    ///
+    /// Signature: `to_base(x: number, n: number) -> string`
+    ToBase,
+
+    /// Returns the date for a year, month, and day.
+    ///
+    /// Signature: `date(year: number, month: number, day: number) -> date`
+    Date,
+
+    /// Returns the current date in UTC.
+    ///
+    /// Signature: `today() -> date`
+    Today,
+
+    /// Returns the year of `d`.
+    ///
+    /// Signature: `year(d: date) -> number`
+    Year,
+
+    /// Returns the month of `d`, from 1 to 12.
+    ///
+    /// Signature: `month(d: date) -> number`
+    Month,
+
+    /// Returns the day of the month of `d`, from 1 to 31.
+    ///
+    /// Signature: `day(d: date) -> number`
+    Day,
+
+    /// Returns the day of the week of `d`, from 1 for Monday to 7 for Sunday.
+    ///
+    /// Signature: `weekday(d: date) -> number`
+    Weekday,
+
+    /// Returns a duration of `n` weeks.
+    ///
+    /// Signature: `weeks(n: number) -> duration`
+    Weeks,
+
+    /// Returns a duration of `n` days.
+    ///
+    /// Signature: `days(n: number) -> duration`
+    Days,
+
+    /// Returns a duration of `n` hours.
+    ///
+    /// Signature: `hours(n: number) -> duration`
+    Hours,
+
+    /// Returns a duration of `n` minutes.
+    ///
+    /// Signature: `minutes(n: number) -> duration`
+    Minutes,
+
+    /// Returns a duration of `n` seconds.
+    ///
+    /// Signature: `seconds(n: number) -> duration`
+    Seconds,
+
+    /// Returns one unit of a new currency named `name` which is worth
+    /// `1 / rate` units of the base currency.
+    ///
+    /// Signature: `currency(name: string, rate: number) -> money`
+    Currency,
+
+    /// Sets the exchange rate of the currency of `unit` and returns `unit`.
+    ///
+    /// Signature: `set_rate(unit: money, rate: number) -> money`
+    SetRate,
+
+    /// Sets the rate of the currency of each `(unit, rate)` tuple in `rates`
+    /// and returns `rates`.
+    ///
+    /// Signature: `rates(rates: list) -> list`
+    Rates,
+
+    /// Returns `amount` converted to the currency of `unit`.
+    ///
+    /// Signature: `convert(amount: money, unit: money) -> money`
+    Convert,
+
+    /// Returns `x` rounded to `places` decimal places, or to an integer if
+    /// `places` is not given, using the current rounding mode.
+    ///
+    /// Signature: `round(x: number, places: number = 0) -> number`
+    Round,
+
+    /// Returns the least of one or more values which can be ordered.
+    ///
+    /// Signature: `min(x: any, ...) -> any`
+    Min,
+
+    /// Returns the greatest of one or more values which can be ordered.
+    ///
+    /// Signature: `max(x: any, ...) -> any`
+    Max,
+
+    /// Returns an inclusive range from `start` to `end` for use with the `in`
+    /// operator.
+    ///
+    /// Signature: `range(start: any, end: any) -> range`
+    Range,
+
+    /// Prints a plot of `f(x)` for `x` from `x_min` to `x_max` which fits the
+    /// terminal and returns `f`.
+    ///
+    /// Signature: `plot(f: function, x_min: number, x_max: number) -> function`
+    Plot,
+
+    /// Prints a table of `x` and `f(x)` for `x` from `a` to `b` in increments
+    /// of `step` and returns `f`.
+    ///
+    /// Signature: `table(f: function, a: number, b: number, step: number) -> function`
+    Table,
+
+    /// Prints a histogram of the numbers in `xs` split into `bins` bins of
+    /// equal width and returns the count in each bin.
+    ///
+    /// Signature: `hist(xs: list, bins: number) -> list`
+    Hist,
+
+    /// Prints one or more values separated by spaces without a newline and
+    /// returns the last value.
+    ///
+    /// Signature: `print(x: any, ...) -> any`
+    Print,
+
+    /// Prints one or more values separated by spaces followed by a newline and
+    /// returns the last value.
+    ///
+    /// Signature: `println(x: any, ...) -> any`
+    Println,
+
     /// Returns the square root of `n`.
     ///
     /// Signature: `sqrt(n: number) -> number`
     Sqrt,
+
+    /// Returns `template` with each `{}` or `{:spec}` placeholder replaced by
+    /// the next argument formatted with the spec.
+    ///
+    /// Signature: `format(template: string, args: any, ...) -> string`
+    Format,
+
+    /// Returns the number of elements in `xs`.
+    ///
+    /// Signature: `len(xs: list) -> number`
+    Len,
+
+    /// Returns a list of `f(x)` for each element `x` of `xs`, calling `f` on
+    /// multiple threads. A call to `pmap` with code for `f` instead of a
+    /// string quotes the code, which can only use native functions.
+    ///
+    /// Signature: `pmap(f: string, xs: list) -> list`
+    Pmap,
+
+    /// Returns the value of `x`, evaluating it first if it is a lazy value.
+    ///
+    /// Signature: `force(x: any) -> any`
+    Force,
+
+    /// Returns `source` as an unevaluated symbolic expression. A call to
+    /// `expr` with code instead of a string quotes the code.
+    ///
+    /// Signature: `expr(source: string) -> expression`
+    Expr,
+
+    /// Returns `e` with like terms combined and constants folded, or a number
+    /// if it is constant.
+    ///
+    /// Signature: `simplify(e: expression) -> any`
+    Simplify,
+
+    /// Returns `e` with products and small powers of sums multiplied out, or a
+    /// number if it is constant.
+    ///
+    /// Signature: `expand(e: expression) -> any`
+    Expand,
+
+    /// Returns `e` with the variable `name` replaced by `value` and
+    /// simplified, or a number if it is constant.
+    ///
+    /// Signature: `subst(e: expression, name: string, value: any) -> any`
+    Subst,
+
+    /// Returns the real roots of `equation` when it is solved for the variable
+    /// `name`. A call to `solve` with code instead of strings quotes the code,
+    /// which cannot use local variables.
+    ///
+    /// Signature: `solve(equation: string, name: string) -> roots`
+    Solve,
+}
+
+/// An interface for calling function [`Value`]s from [`Native`]s.
+pub trait Caller {
+    /// Calls a function [`Value`] with arguments and returns its return
+    /// [`Value`]. This function returns an [`InterpretError`] if an error
+    /// occurred.
+    fn call(&mut self, callee: &Value, args: &[Value]) -> Result<Value, InterpretError>;
+
+    /// Returns the [`Value`] of a global variable from its [`Symbol`]. This
+    /// function returns [`None`] if the global variable is undefined.
+    fn global(&self, symbol: Symbol) -> Option<Value>;
+
+    /// Returns the session's [`Settings`].
+    fn settings(&self) -> &Settings;
+
+    /// Returns the session's [`Output`].
+    fn output(&self) -> &Output;
 }
 
 impl Native {
-    /// Calls the `Native` and returns its return [`Value`]. This function
-    /// returns an [`InterpretError`] if an error occurred.
-    pub fn call(self, args: &[Value]) -> Result<Value, InterpretError> {
-        self.fn_ptr()(args)
+    /// Calls the `Native` with a [`Caller`] and returns its return [`Value`].
+    /// This function returns an [`InterpretError`] if an error occurred.
+    pub fn call(self, args: &[Value], caller: &mut dyn Caller) -> Result<Value, InterpretError> {
+        if !self.accepts_arity(args.len()) {
+            return Err(ErrorKind::IncorrectCallArity.into());
+        }
+
+        match self {
+            Self::Dump => native_dump(args, caller),
+            Self::AsFraction => native_as_fraction(args, caller),
+            Self::Round => native_round(args, caller.settings()),
+            Self::Plot => native_plot(args, caller),
+            Self::Table => native_table(args, caller),
+            Self::Hist => native_hist(args, caller),
+            Self::Print => native_print(args, false, caller),
+            Self::Println => native_print(args, true, caller),
+            Self::Format => native_format(args, caller.settings()),
+            Self::Force => thunk::force(args[0].clone(), caller),
+            Self::Solve => native_solve(args, caller),
+            Self::Pmap => native_pmap(args, caller),
+            _ => self.fn_ptr()(args),
+        }
     }
 
-    /// Returns the `Native`'s name.
-    const fn name(self) -> &'static str {
+    /// Returns the number of leading arguments whose source code is passed as
+    /// strings by calls to the `Native` instead of evaluating them.
+    pub const fn quoted_arg_count(self) -> usize {
         match self {
-            Self::Dump => "__dump",
-            Self::Sqrt => "sqrt",
+            Self::Expr | Self::Pmap => 1,
+            Self::Solve => 2,
+            _ => 0,
         }
     }
 
+    /// Returns [`true`] if code quoted by the `Native` is run without access to
+    /// the caller's local variables.
+    pub const fn isolates_quoted_code(self) -> bool {
+        matches!(self, Self::Pmap | Self::Solve)
+    }
+
     /// Returns the `Native`'s function pointer.
     fn fn_ptr(self) -> fn(&[Value]) -> Result<Value, InterpretError> {
         match self {
-            Self::Dump => native_dump,
+            Self::Dump => unreachable!("__dump should be called with a caller"),
+            Self::AsFraction => unreachable!("as_fraction should be called with a caller"),
+            Self::Base => native_base,
+            Self::ToBase => native_to_base,
+            Self::Date => native_date,
+            Self::Today => native_today,
+            Self::Year => |args| native_date_part(args, |(year, _, _)| year),
+            Self::Month => |args| native_date_part(args, |(_, month, _)| month.into()),
+            Self::Day => |args| native_date_part(args, |(_, _, day)| day.into()),
+            Self::Weekday => native_weekday,
+            Self::Weeks => |args| native_duration(args, time::SECS_PER_WEEK),
+            Self::Days => |args| native_duration(args, time::SECS_PER_DAY),
+            Self::Hours => |args| native_duration(args, time::SECS_PER_HOUR),
+            Self::Minutes => |args| native_duration(args, time::SECS_PER_MINUTE),
+            Self::Seconds => |args| native_duration(args, 1.0),
+            Self::Currency => native_currency,
+            Self::SetRate => native_set_rate,
+            Self::Rates => native_rates,
+            Self::Convert => native_convert,
+            Self::Round => unreachable!("round should be called with a caller"),
+            Self::Min => |args| native_extremum(args, Ordering::Less),
+            Self::Max => |args| native_extremum(args, Ordering::Greater),
+            Self::Range => native_range,
+            Self::Plot => unreachable!("plot should be called with a caller"),
+            Self::Table => unreachable!("table should be called with a caller"),
+            Self::Hist => unreachable!("hist should be called with a caller"),
+            Self::Print => unreachable!("print should be called with a caller"),
+            Self::Println => unreachable!("println should be called with a caller"),
             Self::Sqrt => native_sqrt,
+            Self::Format => unreachable!("format should be called with a caller"),
+            Self::Len => native_len,
+            Self::Pmap => unreachable!("pmap should be called with a caller"),
+            Self::Force => unreachable!("force should be called with a caller"),
+            Self::Expr => native_expr,
+            Self::Simplify => |args| native_rewrite(args, Expression::simplify),
+            Self::Expand => |args| native_rewrite(args, Expression::expand),
+            Self::Subst => native_subst,
+            Self::Solve => unreachable!("solve should be called with a caller"),
         }
     }
 }
 
 /// Installs [`Native`] variables into [`Globals`].
 pub fn install_natives(globals: &mut Globals) {
-    install_native(Native::Dump, globals);
-    install_native(Native::Sqrt, globals);
-}
-
-/// Installs a [`Native`] variable into [`Globals`].
-fn install_native(native: Native, globals: &mut Globals) {
-    globals.assign(Symbol::intern(native.name()), Value::Native(native));
+    for info in NATIVES {
+        globals.assign(Symbol::intern(info.name), Value::Native(info.native));
+    }
 }
 
 /// The native `__dump` function.
-fn native_dump(args: &[Value]) -> Result<Value, InterpretError> {
+fn native_dump(args: &[Value], caller: &dyn Caller) -> Result<Value, InterpretError> {
+    let output = caller.output();
+
     match args {
         [Value::Function(function)] => {
-            println!(
+            outln!(
+                output,
                 "[function with {} parameter(s)]\n{}",
-                function.arity, function.cfg,
+                function.arity,
+                function.cfg,
             );
         }
         [Value::Closure(closure)] => {
-            println!(
+            outln!(
+                output,
                 "[closure with {} parameter(s) and {} upvar(s)]",
                 closure.function.arity,
                 closure.upvars.len()
             );
 
             for (offset, upvar) in closure.upvars.iter().enumerate() {
-                println!("{:8}[{offset}] = {upvar}", "");
+                let upvar = upvar.display(caller.settings());
+                outln!(output, "{:8}[{offset}] = {upvar}", "");
             }
 
-            println!("{}", closure.function.cfg);
+            outln!(output, "{}", closure.function.cfg);
         }
         [Value::Native(native)] => {
-            println!("[native '{}' function]", native.name());
+            outln!(output, "[native '{}' function]", native.name());
         }
         [_] => return Err(ErrorKind::InvalidType.into()),
         _ => return Err(ErrorKind::IncorrectCallArity.into()),
@@ -87,6 +388,522 @@ fn native_dump(args: &[Value]) -> Result<Value, InterpretError> {
     Ok(args[0].clone())
 }
 
+/// The native `as_fraction` function.
+fn native_as_fraction(args: &[Value], caller: &dyn Caller) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Number(value)] => {
+            let format = caller.settings().number_format;
+            outln!(
+                caller.output(),
+                "{}",
+                format::format_fraction(*value, format)
+            );
+            Ok(Value::Number(*value))
+        }
+        [_] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `base` function.
+fn native_base(args: &[Value]) -> Result<Value, InterpretError> {
+    let (radix, digits) = match args {
+        [Value::Number(radix), Value::Str(digits)] => (*radix, digits.trim()),
+        [_, _] => return Err(ErrorKind::InvalidType.into()),
+        _ => return Err(ErrorKind::IncorrectCallArity.into()),
+    };
+
+    let radix = radix_from_number(radix)?;
+    let (is_negative, digits) = digits
+        .strip_prefix('-')
+        .map_or((false, digits), |d| (true, d));
+
+    // Signs are handled above so that only digits are accepted here.
+    if digits.starts_with('+') {
+        return Err(ErrorKind::InvalidDigits(radix).into());
+    }
+
+    let magnitude = u64::from_str_radix(digits, radix)
+        .ok()
+        .ok_or(ErrorKind::InvalidDigits(radix))?;
+
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "large integers are rounded like any other number"
+    )]
+    let magnitude = magnitude as f64;
+
+    Ok(Value::Number(if is_negative {
+        -magnitude
+    } else {
+        magnitude
+    }))
+}
+
+/// The native `to_base` function.
+fn native_to_base(args: &[Value]) -> Result<Value, InterpretError> {
+    let (value, radix) = match args {
+        [Value::Number(value), Value::Number(radix)] => (*value, *radix),
+        [_, _] => return Err(ErrorKind::InvalidType.into()),
+        _ => return Err(ErrorKind::IncorrectCallArity.into()),
+    };
+
+    let radix = radix_from_number(radix)?;
+    let (is_negative, mut magnitude) = magnitude_from_number(value)?;
+    let mut digits = Vec::new();
+
+    loop {
+        let digit = u32::try_from(magnitude % u64::from(radix)).expect("digit should fit in u32");
+        digits.push(char::from_digit(digit, radix).expect("digit should be valid in radix"));
+        magnitude /= u64::from(radix);
+
+        if magnitude == 0 {
+            break;
+        }
+    }
+
+    if is_negative {
+        digits.push('-');
+    }
+
+    let digits: String = digits.iter().rev().collect();
+    Ok(Value::Str(digits.to_uppercase().into()))
+}
+
+/// Converts a number to a radix from 2 to 36. This function returns an
+/// [`InterpretError`] if the number is not a valid radix.
+fn radix_from_number(radix: f64) -> Result<u32, InterpretError> {
+    if !(2.0_f64..=36.0_f64).contains(&radix) || radix.fract() != 0.0_f64 {
+        return Err(ErrorKind::InvalidBase.into());
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "radix is an integer from 2 to 36"
+    )]
+    Ok(radix as u32)
+}
+
+/// Converts an integer number to its sign and magnitude. This function returns
+/// an [`InterpretError`] if the number is not an integer or its magnitude is
+/// not less than 2^64.
+fn magnitude_from_number(value: f64) -> Result<(bool, u64), InterpretError> {
+    let magnitude = value.abs();
+
+    if magnitude >= 2.0_f64.powi(64) || magnitude.fract() != 0.0_f64 {
+        return Err(ErrorKind::InvalidBaseNumber.into());
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "magnitude is a non-negative integer less than 2^64"
+    )]
+    Ok((value < 0.0_f64, magnitude as u64))
+}
+
+/// The native `date` function.
+fn native_date(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [
+            Value::Number(year),
+            Value::Number(month),
+            Value::Number(day),
+        ] => Ok(Value::Date(time::date(*year, *month, *day)?)),
+        [_, _, _] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `today` function.
+fn native_today(args: &[Value]) -> Result<Value, InterpretError> {
+    if !args.is_empty() {
+        return Err(ErrorKind::IncorrectCallArity.into());
+    }
+
+    // WebAssembly hosts do not give the standard library a clock, so reading
+    // the time would panic.
+    if cfg!(target_arch = "wasm32") {
+        return Err(ErrorKind::NoClock.into());
+    }
+
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0_f64, |d| d.as_secs_f64());
+
+    let days = (secs / time::SECS_PER_DAY).floor();
+    Ok(Value::Date(days * time::SECS_PER_DAY))
+}
+
+/// Calls a native function which returns a number from the `(year, month,
+/// day)` of a date.
+fn native_date_part(
+    args: &[Value],
+    part: fn((i64, u32, u32)) -> i64,
+) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Date(date)] => {
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "date parts are within the range of exactly representable integers"
+            )]
+            let value = part(time::date_parts(*date)?) as f64;
+
+            Ok(Value::Number(value))
+        }
+        [_] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `weekday` function.
+fn native_weekday(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Date(date)] => {
+            #[expect(clippy::cast_precision_loss, reason = "weekdays are from 1 to 7")]
+            let weekday = time::weekday(*date)? as f64;
+
+            Ok(Value::Number(weekday))
+        }
+        [_] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// Calls a native function which returns a duration from a number of units
+/// with a length in seconds.
+fn native_duration(args: &[Value], unit_secs: f64) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Number(count)] => Ok(Value::Duration(count * unit_secs)),
+        [_] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `currency` function.
+fn native_currency(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Str(name), Value::Number(rate)] => Ok(Money::new_unit(name, *rate)?.into()),
+        [_, _] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `set_rate` function.
+fn native_set_rate(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [unit, Value::Number(rate)] => {
+            let money = unit
+                .downcast_object::<Money>()
+                .ok_or(ErrorKind::InvalidType)?;
+
+            money.set_rate(*rate)?;
+            Ok(unit.clone())
+        }
+        [_, _] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `rates` function.
+fn native_rates(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [list @ Value::List(elems)] => {
+            let rates = elems
+                .iter()
+                .map(unit_rate)
+                .collect::<Option<Vec<_>>>()
+                .ok_or(ErrorKind::InvalidType)?;
+
+            Money::set_rates(&rates)?;
+            Ok(list.clone())
+        }
+        [_] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// Returns the [`Money`] and exchange rate of a `(unit, rate)` tuple
+/// [`Value`]. This function returns [`None`] if the [`Value`] is not a
+/// `(unit, rate)` tuple.
+fn unit_rate(value: &Value) -> Option<(&Money, f64)> {
+    match value {
+        Value::Tuple(elems) => match &**elems {
+            [unit, Value::Number(rate)] => Some((unit.downcast_object::<Money>()?, *rate)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The native `convert` function.
+fn native_convert(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [amount, unit] => match (
+            amount.downcast_object::<Money>(),
+            unit.downcast_object::<Money>(),
+        ) {
+            (Some(amount), Some(unit)) => Ok(amount.convert(unit).into()),
+            _ => Err(ErrorKind::InvalidType.into()),
+        },
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `round` function.
+fn native_round(args: &[Value], settings: &Settings) -> Result<Value, InterpretError> {
+    let (value, places) = match args {
+        [Value::Number(value)] => (*value, 0.0_f64),
+        [Value::Number(value), Value::Number(places)] => (*value, *places),
+        [_] | [_, _] => return Err(ErrorKind::InvalidType.into()),
+        _ => return Err(ErrorKind::IncorrectCallArity.into()),
+    };
+
+    if places < 0.0_f64 || places.fract() != 0.0_f64 {
+        return Err(ErrorKind::InvalidDecimalPlaces.into());
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "places is a non-negative integer and large values saturate"
+    )]
+    let places = places as u32;
+
+    let rounding = settings.number_format.rounding;
+    Ok(Value::Number(format::round(value, places, rounding)))
+}
+
+/// Calls a native function which returns the argument which has an
+/// [`Ordering`] compared to every other argument. Unordered arguments (e.g.
+/// `NaN`) are skipped unless they are first.
+fn native_extremum(args: &[Value], ordering: Ordering) -> Result<Value, InterpretError> {
+    let [first, rest @ ..] = args else {
+        return Err(ErrorKind::IncorrectCallArity.into());
+    };
+
+    let mut extremum = first;
+
+    for arg in rest {
+        if arg.compare(extremum)? == Some(ordering) {
+            extremum = arg;
+        }
+    }
+
+    Ok(extremum.clone())
+}
+
+/// The native `range` function.
+fn native_range(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [start, end] => Ok(Range::new(start.clone(), end.clone())?.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `plot` function.
+fn native_plot(args: &[Value], caller: &mut dyn Caller) -> Result<Value, InterpretError> {
+    let (function, x_min, x_max) = match args {
+        [function, Value::Number(x_min), Value::Number(x_max)] => (function, *x_min, *x_max),
+        [_, _, _] => return Err(ErrorKind::InvalidType.into()),
+        _ => return Err(ErrorKind::IncorrectCallArity.into()),
+    };
+
+    if !(x_min.is_finite() && x_max.is_finite() && x_min < x_max) {
+        return Err(ErrorKind::InvalidPlotRange.into());
+    }
+
+    let (columns, rows) = plot::plot_size();
+    let mut ys = Vec::with_capacity(columns);
+
+    for column in 0..columns {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "plots have few enough columns to be represented exactly"
+        )]
+        let x = (x_max - x_min).mul_add(column as f64 / (columns - 1) as f64, x_min);
+
+        match caller.call(function, &[Value::Number(x)])? {
+            Value::Number(y) => ys.push(y),
+            _ => return Err(ErrorKind::InvalidType.into()),
+        }
+    }
+
+    for line in plot::render(&ys, x_min, x_max, rows, caller.settings()) {
+        outln!(caller.output(), "{line}");
+    }
+
+    Ok(function.clone())
+}
+
+/// The native `table` function.
+fn native_table(args: &[Value], caller: &mut dyn Caller) -> Result<Value, InterpretError> {
+    /// The greatest number of rows in a table.
+    const MAX_ROWS: f64 = 10_000.0;
+
+    /// The tolerance for rounding errors when counting rows, so that the end
+    /// of a range such as `0` to `0.3` in steps of `0.1` is included.
+    const ROW_TOLERANCE: f64 = 1e-9;
+
+    let (function, start, end, step) = match args {
+        [
+            function,
+            Value::Number(start),
+            Value::Number(end),
+            Value::Number(step),
+        ] => (function, *start, *end, *step),
+        [_, _, _, _] => return Err(ErrorKind::InvalidType.into()),
+        _ => return Err(ErrorKind::IncorrectCallArity.into()),
+    };
+
+    let last_row = ((end - start) / step + ROW_TOLERANCE).floor();
+
+    if !(step > 0.0_f64 && (0.0_f64..MAX_ROWS).contains(&last_row)) {
+        return Err(ErrorKind::InvalidTableRange.into());
+    }
+
+    // Inputs are rounded to the decimal places of the range and step so that
+    // rounding errors are not displayed (e.g. `0.30000000000000004`).
+    let places = [start, step]
+        .map(|n| n.to_string().split_once('.').map_or(0, |(_, f)| f.len()))
+        .into_iter()
+        .max()
+        .map_or(0, |p| u32::try_from(p).unwrap_or(u32::MAX));
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "the number of rows is a small non-negative integer"
+    )]
+    let row_count = last_row as u32 + 1;
+
+    let rounding = caller.settings().number_format.rounding;
+    let mut rows = Vec::new();
+
+    for row in 0..row_count {
+        let x = format::round(step.mul_add(row.into(), start), places, rounding);
+        let y = caller.call(function, &[Value::Number(x)])?;
+        let settings = caller.settings();
+        rows.push([
+            format::format_number(x, settings),
+            y.display(settings).to_string(),
+        ]);
+    }
+
+    for line in plot::render_table(["x", "f(x)"], &rows) {
+        outln!(caller.output(), "{line}");
+    }
+
+    Ok(function.clone())
+}
+
+/// The native `hist` function.
+fn native_hist(args: &[Value], caller: &dyn Caller) -> Result<Value, InterpretError> {
+    /// The greatest number of bins in a histogram.
+    const MAX_BINS: f64 = 1000.0;
+
+    let (xs, bins) = match args {
+        [Value::List(xs), Value::Number(bins)] => (xs, *bins),
+        [_, _] => return Err(ErrorKind::InvalidType.into()),
+        _ => return Err(ErrorKind::IncorrectCallArity.into()),
+    };
+
+    if !(bins.fract() == 0.0_f64 && (1.0_f64..=MAX_BINS).contains(&bins)) {
+        return Err(ErrorKind::InvalidBinCount.into());
+    }
+
+    let xs = xs
+        .iter()
+        .map(Value::as_float)
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ErrorKind::InvalidType)?;
+
+    let finite_xs = xs.iter().copied().filter(|x| x.is_finite());
+    let min = finite_xs.clone().fold(f64::INFINITY, f64::min);
+    let max = finite_xs.clone().fold(f64::NEG_INFINITY, f64::max);
+
+    if !min.is_finite() {
+        return Err(ErrorKind::EmptyHistogram.into());
+    }
+
+    // Bins are centered on a single distinct value.
+    let (min, max) = if min < max {
+        (min, max)
+    } else {
+        (min - 0.5_f64, min + 0.5_f64)
+    };
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "the number of bins is a small positive integer"
+    )]
+    let bins = bins as usize;
+
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "histograms have few enough bins to be represented exactly"
+    )]
+    let edges = (0..=bins)
+        .map(|edge| (max - min).mul_add(edge as f64 / bins as f64, min))
+        .collect::<Vec<_>>();
+
+    let mut counts = vec![0; bins];
+
+    for x in finite_xs {
+        // The greatest value is counted in the last bin.
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss,
+            reason = "bin positions are clamped to the histogram"
+        )]
+        let bin = ((x - min) / (max - min) * bins as f64) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+
+    let columns = plot::terminal_columns();
+
+    for line in plot::render_histogram(&edges, &counts, columns, caller.settings()) {
+        outln!(caller.output(), "{line}");
+    }
+
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "lists are not long enough to lose precision"
+    )]
+    let counts = counts.into_iter().map(|c| Value::Number(c as f64));
+    Ok(Value::List(counts.collect()))
+}
+
+/// Calls a native function which prints its arguments separated by spaces,
+/// optionally followed by a newline, with a [`Caller`]'s session and returns
+/// its last argument.
+fn native_print(
+    args: &[Value],
+    is_line: bool,
+    caller: &dyn Caller,
+) -> Result<Value, InterpretError> {
+    let Some(last) = args.last() else {
+        return Err(ErrorKind::IncorrectCallArity.into());
+    };
+
+    let text = args
+        .iter()
+        .map(|a| a.display(caller.settings()).to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if is_line {
+        outln!(caller.output(), "{text}");
+    } else {
+        out!(caller.output(), "{text}");
+    }
+
+    Ok(last.clone())
+}
+
 /// The native `sqrt` function.
 fn native_sqrt(args: &[Value]) -> Result<Value, InterpretError> {
     match args {
@@ -95,3 +912,148 @@ fn native_sqrt(args: &[Value]) -> Result<Value, InterpretError> {
         _ => Err(ErrorKind::IncorrectCallArity.into()),
     }
 }
+
+/// The native `len` function.
+fn native_len(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "lists are not long enough to lose precision"
+        )]
+        [Value::List(elems)] => Ok(Value::Number(elems.len() as f64)),
+        [_] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `pmap` function.
+fn native_pmap(args: &[Value], caller: &dyn Caller) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Str(source), Value::List(xs)] => {
+            let ys = engine::map_parallel(source, xs, *caller.settings(), caller.output())?;
+            Ok(Value::List(ys.into()))
+        }
+        [_, _] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `expr` function.
+fn native_expr(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Str(source)] => Ok(Expression::parse(source)?.into()),
+        [_] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// Calls a native function which rewrites an [`Expression`].
+fn native_rewrite(
+    args: &[Value],
+    rewrite: fn(&Expression) -> Result<Value, InterpretError>,
+) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Number(value)] => Ok(Value::Number(*value)),
+        [expression] => rewrite(
+            expression
+                .downcast_object::<Expression>()
+                .ok_or(ErrorKind::InvalidType)?,
+        ),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `subst` function.
+fn native_subst(args: &[Value]) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Number(value), Value::Str(_), _] => Ok(Value::Number(*value)),
+        [expression, Value::Str(name), value] => expression
+            .downcast_object::<Expression>()
+            .ok_or(ErrorKind::InvalidType)?
+            .substitute(Symbol::intern(name), value),
+        [_, _, _] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `solve` function.
+fn native_solve(args: &[Value], caller: &mut dyn Caller) -> Result<Value, InterpretError> {
+    match args {
+        [Value::Str(equation), Value::Str(name)] => {
+            Ok(symbolic::solve(equation, name, caller)?.into())
+        }
+        [_, _] => Err(ErrorKind::InvalidType.into()),
+        _ => Err(ErrorKind::IncorrectCallArity.into()),
+    }
+}
+
+/// The native `format` function.
+fn native_format(args: &[Value], settings: &Settings) -> Result<Value, InterpretError> {
+    let (template, args) = match args {
+        [Value::Str(template), args @ ..] => (template, args),
+        [_, ..] => return Err(ErrorKind::InvalidType.into()),
+        [] => return Err(ErrorKind::IncorrectCallArity.into()),
+    };
+
+    let mut args = args.iter();
+    let mut formatted = String::new();
+    let mut rest = &**template;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        let (text, remaining) = rest.split_at(index);
+        formatted.push_str(text);
+        rest = remaining;
+
+        if let Some(escaped) = rest.strip_prefix("{{") {
+            formatted.push('{');
+            rest = escaped;
+            continue;
+        } else if let Some(escaped) = rest.strip_prefix("}}") {
+            formatted.push('}');
+            rest = escaped;
+            continue;
+        }
+
+        let (placeholder, after) = rest
+            .strip_prefix('{')
+            .and_then(|r| r.split_once('}'))
+            .ok_or(ErrorKind::InvalidFormatString)?;
+
+        let spec = match placeholder.strip_prefix(':') {
+            None if placeholder.is_empty() => FormatSpec::new(),
+            None => return Err(ErrorKind::InvalidFormatString.into()),
+            Some(spec) => FormatSpec::parse(spec).ok_or(ErrorKind::InvalidFormatString)?,
+        };
+
+        let arg = args.next().ok_or(ErrorKind::IncorrectCallArity)?;
+        formatted.push_str(&format_arg(arg, spec, settings)?);
+        rest = after;
+    }
+
+    if args.next().is_some() {
+        return Err(ErrorKind::IncorrectCallArity.into());
+    }
+
+    formatted.push_str(rest);
+    Ok(Value::Str(formatted.into()))
+}
+
+/// Formats a [`Value`] with a [`FormatSpec`]. This function returns an
+/// [`InterpretError`] if the [`FormatSpec`] cannot be applied to the
+/// [`Value`].
+fn format_arg(
+    value: &Value,
+    spec: FormatSpec,
+    settings: &Settings,
+) -> Result<String, InterpretError> {
+    let formatted = match value {
+        Value::Number(value) => spec
+            .format_number(*value, settings.number_format)
+            .ok_or(ErrorKind::InvalidBaseNumber)?,
+        value => spec
+            .format_text(&value.display(settings).to_string())
+            .ok_or(ErrorKind::InvalidType)?,
+    };
+
+    Ok(formatted)
+}
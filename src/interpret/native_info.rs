@@ -0,0 +1,779 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::{arity::Arity, native::Native};
+
+/// Metadata for a [`Native`].
+pub struct NativeInfo {
+    /// The [`Native`].
+    pub native: Native,
+
+    /// The name of the global variable the [`Native`] is installed as.
+    pub name: &'static str,
+
+    /// The [`Param`]s.
+    pub params: &'static [Param],
+
+    /// Whether the last [`Param`] may be repeated.
+    pub is_variadic: bool,
+
+    /// The type of the return value.
+    pub returns: &'static str,
+
+    /// A description of the [`Native`].
+    pub description: &'static str,
+
+    /// The [`Category`].
+    pub category: Category,
+}
+
+/// A [`Native`]'s parameter.
+pub struct Param {
+    /// The parameter's name.
+    pub name: &'static str,
+
+    /// The type of the parameter's value.
+    pub kind: &'static str,
+
+    /// The parameter's default value, if it is optional.
+    pub default: Option<&'static str>,
+}
+
+impl Display for Param {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.kind)?;
+
+        self.default
+            .map_or(Ok(()), |default| write!(f, " = {default}"))
+    }
+}
+
+/// A category of [`Native`]s.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Numeric functions.
+    Math,
+
+    /// Symbolic expression functions.
+    Algebra,
+
+    /// Date and duration functions.
+    Time,
+
+    /// Currency functions.
+    Money,
+
+    /// Functions which print values, plots, or tables.
+    Output,
+
+    /// String functions.
+    Strings,
+
+    /// List functions.
+    Lists,
+
+    /// Functions which control when values are evaluated.
+    Evaluation,
+
+    /// Functions for debugging Clac.
+    Debug,
+}
+
+impl Category {
+    /// Every `Category` in display order.
+    pub const ALL: [Self; 9] = [
+        Self::Math,
+        Self::Algebra,
+        Self::Time,
+        Self::Money,
+        Self::Output,
+        Self::Strings,
+        Self::Lists,
+        Self::Evaluation,
+        Self::Debug,
+    ];
+
+    /// Returns the `Category`'s name.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Math => "math",
+            Self::Algebra => "algebra",
+            Self::Time => "time",
+            Self::Money => "money",
+            Self::Output => "output",
+            Self::Strings => "strings",
+            Self::Lists => "lists",
+            Self::Evaluation => "evaluation",
+            Self::Debug => "debug",
+        }
+    }
+}
+
+/// The [`NativeInfo`] of every [`Native`] in installation order.
+pub const NATIVES: &[NativeInfo] = &[
+    NativeInfo {
+        native: Native::Dump,
+        name: "__dump",
+        params: &[Param {
+            name: "f",
+            kind: "function",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "function",
+        description: "Prints `f`'s control flow graph as pseudo-assembly and returns `f`.",
+        category: Category::Debug,
+    },
+    NativeInfo {
+        native: Native::AsFraction,
+        name: "as_fraction",
+        params: &[Param {
+            name: "x",
+            kind: "number",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "number",
+        description: "Prints `x` as the nearest simple fraction and returns `x`.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::Base,
+        name: "base",
+        params: &[
+            Param {
+                name: "n",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "digits",
+                kind: "string",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns the number written in base `n` by the string `digits`.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::ToBase,
+        name: "to_base",
+        params: &[
+            Param {
+                name: "x",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "n",
+                kind: "number",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "string",
+        description: "Returns integer `x` written in base `n` as a string.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::Date,
+        name: "date",
+        params: &[
+            Param {
+                name: "year",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "month",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "day",
+                kind: "number",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "date",
+        description: "Returns the date for a year, month, and day.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Today,
+        name: "today",
+        params: &[],
+        is_variadic: false,
+        returns: "date",
+        description: "Returns the current date in UTC.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Year,
+        name: "year",
+        params: &[Param {
+            name: "d",
+            kind: "date",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns the year of `d`.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Month,
+        name: "month",
+        params: &[Param {
+            name: "d",
+            kind: "date",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns the month of `d`, from 1 to 12.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Day,
+        name: "day",
+        params: &[Param {
+            name: "d",
+            kind: "date",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns the day of the month of `d`, from 1 to 31.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Weekday,
+        name: "weekday",
+        params: &[Param {
+            name: "d",
+            kind: "date",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns the day of the week of `d`, from 1 for Monday to 7 for Sunday.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Weeks,
+        name: "weeks",
+        params: &[Param {
+            name: "n",
+            kind: "number",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "duration",
+        description: "Returns a duration of `n` weeks.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Days,
+        name: "days",
+        params: &[Param {
+            name: "n",
+            kind: "number",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "duration",
+        description: "Returns a duration of `n` days.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Hours,
+        name: "hours",
+        params: &[Param {
+            name: "n",
+            kind: "number",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "duration",
+        description: "Returns a duration of `n` hours.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Minutes,
+        name: "minutes",
+        params: &[Param {
+            name: "n",
+            kind: "number",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "duration",
+        description: "Returns a duration of `n` minutes.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Seconds,
+        name: "seconds",
+        params: &[Param {
+            name: "n",
+            kind: "number",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "duration",
+        description: "Returns a duration of `n` seconds.",
+        category: Category::Time,
+    },
+    NativeInfo {
+        native: Native::Currency,
+        name: "currency",
+        params: &[
+            Param {
+                name: "name",
+                kind: "string",
+                default: None,
+            },
+            Param {
+                name: "rate",
+                kind: "number",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "money",
+        description: "Returns one unit of a new currency named `name` which is worth `1 / rate` units of the base currency.",
+        category: Category::Money,
+    },
+    NativeInfo {
+        native: Native::SetRate,
+        name: "set_rate",
+        params: &[
+            Param {
+                name: "unit",
+                kind: "money",
+                default: None,
+            },
+            Param {
+                name: "rate",
+                kind: "number",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "money",
+        description: "Sets the exchange rate of the currency of `unit` and returns `unit`.",
+        category: Category::Money,
+    },
+    NativeInfo {
+        native: Native::Rates,
+        name: "rates",
+        params: &[Param {
+            name: "rates",
+            kind: "list",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "list",
+        description: "Sets the rate of the currency of each `(unit, rate)` tuple in `rates` and returns `rates`.",
+        category: Category::Money,
+    },
+    NativeInfo {
+        native: Native::Convert,
+        name: "convert",
+        params: &[
+            Param {
+                name: "amount",
+                kind: "money",
+                default: None,
+            },
+            Param {
+                name: "unit",
+                kind: "money",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "money",
+        description: "Returns `amount` converted to the currency of `unit`.",
+        category: Category::Money,
+    },
+    NativeInfo {
+        native: Native::Round,
+        name: "round",
+        params: &[
+            Param {
+                name: "x",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "places",
+                kind: "number",
+                default: Some("0"),
+            },
+        ],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns `x` rounded to `places` decimal places, or to an integer if `places` is not given, using the current rounding mode.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::Min,
+        name: "min",
+        params: &[Param {
+            name: "x",
+            kind: "any",
+            default: None,
+        }],
+        is_variadic: true,
+        returns: "any",
+        description: "Returns the least of one or more values which can be ordered.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::Max,
+        name: "max",
+        params: &[Param {
+            name: "x",
+            kind: "any",
+            default: None,
+        }],
+        is_variadic: true,
+        returns: "any",
+        description: "Returns the greatest of one or more values which can be ordered.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::Range,
+        name: "range",
+        params: &[
+            Param {
+                name: "start",
+                kind: "any",
+                default: None,
+            },
+            Param {
+                name: "end",
+                kind: "any",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "range",
+        description: "Returns an inclusive range from `start` to `end` for use with the `in` operator.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::Plot,
+        name: "plot",
+        params: &[
+            Param {
+                name: "f",
+                kind: "function",
+                default: None,
+            },
+            Param {
+                name: "x_min",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "x_max",
+                kind: "number",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "function",
+        description: "Prints a plot of `f(x)` for `x` from `x_min` to `x_max` which fits the terminal and returns `f`.",
+        category: Category::Output,
+    },
+    NativeInfo {
+        native: Native::Table,
+        name: "table",
+        params: &[
+            Param {
+                name: "f",
+                kind: "function",
+                default: None,
+            },
+            Param {
+                name: "a",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "b",
+                kind: "number",
+                default: None,
+            },
+            Param {
+                name: "step",
+                kind: "number",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "function",
+        description: "Prints a table of `x` and `f(x)` for `x` from `a` to `b` in increments of `step` and returns `f`.",
+        category: Category::Output,
+    },
+    NativeInfo {
+        native: Native::Hist,
+        name: "hist",
+        params: &[
+            Param {
+                name: "xs",
+                kind: "list",
+                default: None,
+            },
+            Param {
+                name: "bins",
+                kind: "number",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "list",
+        description: "Prints a histogram of the numbers in `xs` split into `bins` bins of equal width and returns the count in each bin.",
+        category: Category::Output,
+    },
+    NativeInfo {
+        native: Native::Print,
+        name: "print",
+        params: &[Param {
+            name: "x",
+            kind: "any",
+            default: None,
+        }],
+        is_variadic: true,
+        returns: "any",
+        description: "Prints one or more values separated by spaces without a newline and returns the last value.",
+        category: Category::Output,
+    },
+    NativeInfo {
+        native: Native::Println,
+        name: "println",
+        params: &[Param {
+            name: "x",
+            kind: "any",
+            default: None,
+        }],
+        is_variadic: true,
+        returns: "any",
+        description: "Prints one or more values separated by spaces followed by a newline and returns the last value.",
+        category: Category::Output,
+    },
+    NativeInfo {
+        native: Native::Sqrt,
+        name: "sqrt",
+        params: &[Param {
+            name: "n",
+            kind: "number",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns the square root of `n`.",
+        category: Category::Math,
+    },
+    NativeInfo {
+        native: Native::Format,
+        name: "format",
+        params: &[
+            Param {
+                name: "template",
+                kind: "string",
+                default: None,
+            },
+            Param {
+                name: "args",
+                kind: "any",
+                default: None,
+            },
+        ],
+        is_variadic: true,
+        returns: "string",
+        description: "Returns `template` with each `{}` or `{:spec}` placeholder replaced by the next argument formatted with the spec.",
+        category: Category::Strings,
+    },
+    NativeInfo {
+        native: Native::Len,
+        name: "len",
+        params: &[Param {
+            name: "xs",
+            kind: "list",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "number",
+        description: "Returns the number of elements in `xs`.",
+        category: Category::Lists,
+    },
+    NativeInfo {
+        native: Native::Pmap,
+        name: "pmap",
+        params: &[
+            Param {
+                name: "f",
+                kind: "string",
+                default: None,
+            },
+            Param {
+                name: "xs",
+                kind: "list",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "list",
+        description: "Returns a list of `f(x)` for each element `x` of `xs`, calling `f` on multiple threads. A call to `pmap` with code for `f` instead of a string quotes the code, which can only use native functions.",
+        category: Category::Lists,
+    },
+    NativeInfo {
+        native: Native::Force,
+        name: "force",
+        params: &[Param {
+            name: "x",
+            kind: "any",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "any",
+        description: "Returns the value of `x`, evaluating it first if it is a lazy value.",
+        category: Category::Evaluation,
+    },
+    NativeInfo {
+        native: Native::Expr,
+        name: "expr",
+        params: &[Param {
+            name: "source",
+            kind: "string",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "expression",
+        description: "Returns `source` as an unevaluated symbolic expression. A call to `expr` with code instead of a string quotes the code.",
+        category: Category::Algebra,
+    },
+    NativeInfo {
+        native: Native::Simplify,
+        name: "simplify",
+        params: &[Param {
+            name: "e",
+            kind: "expression",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "any",
+        description: "Returns `e` with like terms combined and constants folded, or a number if it is constant.",
+        category: Category::Algebra,
+    },
+    NativeInfo {
+        native: Native::Expand,
+        name: "expand",
+        params: &[Param {
+            name: "e",
+            kind: "expression",
+            default: None,
+        }],
+        is_variadic: false,
+        returns: "any",
+        description: "Returns `e` with products and small powers of sums multiplied out, or a number if it is constant.",
+        category: Category::Algebra,
+    },
+    NativeInfo {
+        native: Native::Subst,
+        name: "subst",
+        params: &[
+            Param {
+                name: "e",
+                kind: "expression",
+                default: None,
+            },
+            Param {
+                name: "name",
+                kind: "string",
+                default: None,
+            },
+            Param {
+                name: "value",
+                kind: "any",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "any",
+        description: "Returns `e` with the variable `name` replaced by `value` and simplified, or a number if it is constant.",
+        category: Category::Algebra,
+    },
+    NativeInfo {
+        native: Native::Solve,
+        name: "solve",
+        params: &[
+            Param {
+                name: "equation",
+                kind: "string",
+                default: None,
+            },
+            Param {
+                name: "name",
+                kind: "string",
+                default: None,
+            },
+        ],
+        is_variadic: false,
+        returns: "roots",
+        description: "Returns the real roots of `equation` when it is solved for the variable `name`. A call to `solve` with code instead of strings quotes the code, which cannot use local variables.",
+        category: Category::Algebra,
+    },
+];
+
+impl Native {
+    /// Returns a `Native` from its name. This function returns [`None`] if the
+    /// name does not correspond to a `Native`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        NATIVES.iter().find(|i| i.name == name).map(|i| i.native)
+    }
+
+    /// Returns the `Native`'s [`NativeInfo`].
+    pub fn info(self) -> &'static NativeInfo {
+        NATIVES
+            .iter()
+            .find(|i| i.native == self)
+            .expect("natives should have info")
+    }
+
+    /// Returns the `Native`'s name.
+    pub fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    /// Returns [`true`] if the `Native` accepts a number of arguments.
+    pub fn accepts_arity(self, arity: usize) -> bool {
+        self.arity().accepts(arity)
+    }
+
+    /// Returns the `Native`'s [`Arity`].
+    pub fn arity(self) -> Arity {
+        let info = self.info();
+        let min = info.params.iter().filter(|p| p.default.is_none()).count();
+        let max = (!info.is_variadic).then_some(info.params.len());
+        Arity::new(min, max)
+    }
+
+    /// Returns the `Native`'s signature.
+    pub fn signature(self) -> String {
+        let info = self.info();
+        let mut params: Vec<String> = info.params.iter().map(ToString::to_string).collect();
+
+        if info.is_variadic {
+            params.push("...".to_owned());
+        }
+
+        format!("{}({}) -> {}", info.name, params.join(", "), info.returns)
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    any::Any,
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::settings::Settings;
+
+use super::{InterpretError, errors::ErrorKind, value::Value};
+
+/// An arithmetic operator which can be overloaded by an [`Object`].
+#[derive(Clone, Copy)]
+pub enum ArithOp {
+    /// An addition.
+    Add,
+
+    /// A subtraction.
+    Subtract,
+
+    /// A multiplication.
+    Multiply,
+
+    /// A division.
+    Divide,
+}
+
+/// The side of an operation which an [`Object`] is on.
+#[derive(Clone, Copy)]
+pub enum Side {
+    /// The left-hand side.
+    Lhs,
+
+    /// The right-hand side.
+    Rhs,
+}
+
+/// A host-registered value type which can overload operators. Every operation
+/// is unsupported by default, so an `Object` only needs to implement the
+/// operations which make sense for its type.
+pub trait Object: Any + Display {
+    /// Returns the name of the `Object`'s type. `Object`s can only be compared
+    /// with `Object`s with the same type name.
+    fn type_name(&self) -> &'static str;
+
+    /// Formats the `Object` with a [`Formatter`] and a session's [`Settings`].
+    /// By default, the `Object` is displayed without the [`Settings`].
+    fn fmt_with(&self, f: &mut Formatter<'_>, _settings: &Settings) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+
+    /// Returns the result of an [`ArithOp`] between the `Object` and another
+    /// [`Value`], with the `Object` on a [`Side`]. This function returns
+    /// [`None`] if the operation is not supported.
+    fn arith(
+        &self,
+        _op: ArithOp,
+        _other: &Value,
+        _side: Side,
+    ) -> Option<Result<Value, InterpretError>> {
+        None
+    }
+
+    /// Returns the negation of the `Object`. This function returns [`None`] if
+    /// negation is not supported.
+    fn negate(&self) -> Option<Value> {
+        None
+    }
+
+    /// Returns [`true`] if the `Object` is equal to another `Object` with the
+    /// same type name. By default, `Object`s are only equal to themselves.
+    fn equals(&self, _other: &dyn Object) -> bool {
+        false
+    }
+
+    /// Compares the `Object` to another `Object` with the same type name. This
+    /// function returns an [`InterpretError`] if the `Object`s cannot be
+    /// compared.
+    fn compare(&self, _other: &dyn Object) -> Result<Option<Ordering>, InterpretError> {
+        Err(ErrorKind::InvalidType.into())
+    }
+
+    /// Returns [`true`] if the `Object` contains a [`Value`]. This function
+    /// returns [`None`] if membership tests are not supported.
+    fn contains(&self, _value: &Value) -> Option<Result<bool, InterpretError>> {
+        None
+    }
+}
+
+/// Returns a reference to an [`Object`] as a concrete type. This function
+/// returns [`None`] if the [`Object`] is not of the type.
+pub fn downcast_ref<T: Object>(object: &dyn Object) -> Option<&T> {
+    let object: &dyn Any = object;
+    object.downcast_ref()
+}
+
+/// Returns the result of an [`ArithOp`] between two [`Value`]s if either of
+/// them is an [`Object`] which supports it. The left-hand side is tried first.
+/// This function returns [`None`] if neither [`Value`] supports the operation.
+pub fn arith(op: ArithOp, lhs: &Value, rhs: &Value) -> Option<Result<Value, InterpretError>> {
+    if let Value::Object(object) = lhs
+        && let Some(result) = object.arith(op, rhs, Side::Lhs)
+    {
+        return Some(result);
+    }
+
+    if let Value::Object(object) = rhs {
+        return object.arith(op, lhs, Side::Rhs);
+    }
+
+    None
+}
@@ -0,0 +1,223 @@
+use std::rc::Rc;
+
+use crate::{ast::Literal, settings::Settings};
+
+use super::value::Value;
+
+/// The maximum number of elements of a list or tuple that are printed before
+/// the rest are summarized.
+const MAX_ELEMS: usize = 10;
+
+/// The maximum width of a line before a result is wrapped.
+const MAX_WIDTH: usize = 80;
+
+/// Formats a top-level result [`Value`] with a session's [`Settings`] for
+/// printing. Lists and tuples are capped at [`MAX_ELEMS`] elements (e.g.
+/// `[1, 2, … 990 more]`) and wrapped at [`MAX_WIDTH`] columns, and lists of
+/// lists with equal lengths are printed as aligned grids.
+pub fn pretty(value: &Value, settings: &Settings) -> String {
+    let separator = settings.number_format.element_separator();
+
+    match value {
+        Value::List(elems) => matrix(elems, settings)
+            .unwrap_or_else(|| wrap("[", &capped_elems(elems, settings), "]", separator)),
+        Value::Tuple(elems) => wrap(
+            "(",
+            &capped_elems(elems, settings),
+            &tuple_close(elems, settings),
+            separator,
+        ),
+        _ => value.display(settings).to_string(),
+    }
+}
+
+/// Formats a list's rows as an aligned grid if every row is a non-empty list
+/// of values other than lists or tuples, and every row has the same length.
+/// This function returns [`None`] if the rows are not a grid.
+fn matrix(rows: &[Value], settings: &Settings) -> Option<String> {
+    let rows = rows
+        .iter()
+        .map(|row| match row {
+            Value::List(cells) if !cells.is_empty() && !cells.iter().any(is_composite) => {
+                Some(cells)
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let columns = rows.first()?.len();
+
+    if rows.iter().any(|cells| cells.len() != columns) {
+        return None;
+    }
+
+    let cells = rows
+        .iter()
+        .take(MAX_ELEMS)
+        .map(|cells| capped_elems(cells, settings))
+        .collect::<Vec<_>>();
+
+    let mut widths = vec![0; cells[0].len()];
+
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let separator = settings.number_format.element_separator();
+
+    let mut lines = cells
+        .iter()
+        .map(|row| {
+            let row = row.iter().zip(&widths);
+            let row = row.map(|(cell, width)| format!("{cell:>width$}"));
+            format!(
+                "[{}]",
+                row.collect::<Vec<_>>().join(&format!("{separator} "))
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if rows.len() > MAX_ELEMS {
+        lines.push(format!("… {} more", rows.len() - MAX_ELEMS));
+    }
+
+    Some(format!("[{}]", lines.join(&format!("{separator}\n "))))
+}
+
+/// Returns a list's or tuple's first [`MAX_ELEMS`] elements formatted on one
+/// line each, followed by a summary of the rest if there are more.
+fn capped_elems(elems: &[Value], settings: &Settings) -> Vec<String> {
+    let mut items = elems
+        .iter()
+        .take(MAX_ELEMS)
+        .map(|elem| inline(elem, settings))
+        .collect::<Vec<_>>();
+
+    if elems.len() > MAX_ELEMS {
+        items.push(format!("… {} more", elems.len() - MAX_ELEMS));
+    }
+
+    items
+}
+
+/// Formats a list's or tuple's element on one line. String elements are
+/// quoted and nested lists and tuples are capped.
+fn inline(elem: &Value, settings: &Settings) -> String {
+    let separator = format!("{} ", settings.number_format.element_separator());
+
+    match elem {
+        Value::Str(value) => Literal::Str(Rc::clone(value)).to_string(),
+        Value::List(elems) => format!("[{}]", capped_elems(elems, settings).join(&separator)),
+        Value::Tuple(elems) => format!(
+            "({}{}",
+            capped_elems(elems, settings).join(&separator),
+            tuple_close(elems, settings)
+        ),
+        _ => elem.display(settings).to_string(),
+    }
+}
+
+/// Returns the closing bracket of a tuple's elements. Tuples with one element
+/// are closed with a trailing separator (e.g. `(1,)`) so that they are not
+/// mistaken for parenthesized values.
+pub fn tuple_close(elems: &[Value], settings: &Settings) -> String {
+    if elems.len() == 1 {
+        format!("{})", settings.number_format.element_separator())
+    } else {
+        String::from(")")
+    }
+}
+
+/// Joins items with a separator between opening and closing brackets, wrapping
+/// them onto indented lines if they do not fit within [`MAX_WIDTH`] columns.
+fn wrap(open: &str, items: &[String], close: &str, separator: char) -> String {
+    let line = format!("{open}{}{close}", items.join(&format!("{separator} ")));
+
+    if line.chars().count() <= MAX_WIDTH {
+        return line;
+    }
+
+    let indent = " ".repeat(open.chars().count());
+    let mut result = String::from(open);
+    let mut width = open.chars().count();
+
+    for (index, item) in items.iter().enumerate() {
+        let item_width = item.chars().count();
+
+        if index > 0 {
+            // Leave room for the separator and a trailing comma or bracket.
+            result.push(separator);
+
+            if width + item_width + 3 > MAX_WIDTH {
+                result.push('\n');
+                result.push_str(&indent);
+                width = indent.len();
+            } else {
+                result.push(' ');
+                width += 2;
+            }
+        }
+
+        result.push_str(item);
+        width += item_width;
+    }
+
+    result.push_str(close);
+    result
+}
+
+/// Returns `true` if a [`Value`] is a list or tuple.
+const fn is_composite(value: &Value) -> bool {
+    matches!(value, Value::List(_) | Value::Tuple(_))
+}
+
+/// Formats a top-level result [`Value`] with a session's [`Settings`] as rows
+/// of values joined by a separator if it is a non-empty list of lists or
+/// tuples with equal lengths. Values which contain the separator, quotes, or
+/// line breaks are quoted. Other results are pretty-printed.
+pub fn table(value: &Value, separator: char, settings: &Settings) -> String {
+    let Value::List(rows) = value else {
+        return pretty(value, settings);
+    };
+
+    let rows = rows
+        .iter()
+        .map(|row| match row {
+            Value::List(cells) | Value::Tuple(cells) if !cells.iter().any(is_composite) => {
+                Some(cells)
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+
+    let columns = rows
+        .as_ref()
+        .and_then(|rows| rows.first())
+        .map(|cells| cells.len());
+
+    match (rows, columns) {
+        (Some(rows), Some(columns)) if rows.iter().all(|cells| cells.len() == columns) => rows
+            .iter()
+            .map(|cells| {
+                let cells = cells.iter().map(|cell| field(cell, separator, settings));
+                cells.collect::<Vec<_>>().join(&separator.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => pretty(value, settings),
+    }
+}
+
+/// Formats a [`Value`] as a field of a row with a separator, quoting it if it
+/// contains the separator, quotes, or line breaks.
+fn field(value: &Value, separator: char, settings: &Settings) -> String {
+    let text = value.display(settings).to_string();
+
+    if text.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    rc::Rc,
+};
+
+use crate::settings::Settings;
+
+use super::{
+    InterpretError,
+    object::{self, Object},
+    value::Value,
+};
+
+/// An inclusive range between two values of the same orderable type.
+pub struct Range {
+    /// The start [`Value`].
+    start: Value,
+
+    /// The end [`Value`].
+    end: Value,
+}
+
+impl Range {
+    /// Creates a new `Range` from a start [`Value`] and an end [`Value`]. This
+    /// function returns an [`InterpretError`] if the [`Value`]s cannot be
+    /// compared.
+    pub fn new(start: Value, end: Value) -> Result<Self, InterpretError> {
+        start.compare(&end)?;
+        Ok(Self { start, end })
+    }
+
+    /// Returns [`true`] if the `Range` contains a [`Value`]. This function
+    /// returns an [`InterpretError`] if the [`Value`] cannot be compared with
+    /// the `Range`'s bounds.
+    fn contains_value(&self, value: &Value) -> Result<bool, InterpretError> {
+        let is_at_least = |lhs: &Value, rhs: &Value| {
+            lhs.compare(rhs)
+                .map(|o| matches!(o, Some(Ordering::Greater | Ordering::Equal)))
+        };
+
+        Ok(is_at_least(value, &self.start)? && is_at_least(&self.end, value)?)
+    }
+}
+
+impl Object for Range {
+    fn type_name(&self) -> &'static str {
+        "range"
+    }
+
+    fn fmt_with(&self, f: &mut Formatter<'_>, settings: &Settings) -> fmt::Result {
+        let (start, end) = (self.start.display(settings), self.end.display(settings));
+        write!(f, "range({start}, {end})")
+    }
+
+    fn equals(&self, other: &dyn Object) -> bool {
+        object::downcast_ref::<Self>(other)
+            .is_some_and(|other| self.start == other.start && self.end == other.end)
+    }
+
+    fn contains(&self, value: &Value) -> Option<Result<bool, InterpretError>> {
+        Some(self.contains_value(value))
+    }
+}
+
+impl From<Range> for Value {
+    fn from(value: Range) -> Self {
+        Self::Object(Rc::new(value))
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &Settings::new())
+    }
+}
@@ -0,0 +1,917 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    rc::Rc,
+    slice,
+};
+
+use crate::{
+    ast::{BinOp, Expr, ExprKind, Literal, UnOp},
+    format,
+    parse::{self, Operators},
+    settings::Settings,
+    symbols::Symbol,
+};
+
+use super::{
+    InterpretError,
+    errors::ErrorKind,
+    native::{Caller, Native},
+    object::{self, ArithOp, Object, Side},
+    value::Value,
+};
+
+/// The greatest integer power of a sum which is expanded by multiplication.
+const MAX_EXPANDED_POWER: f64 = 16.0;
+
+/// The smallest magnitude of a nonzero sample when searching for numeric
+/// roots.
+const MIN_SAMPLE: f64 = 0.001;
+
+/// The number of powers of ten between the smallest and greatest magnitudes of
+/// nonzero samples when searching for numeric roots.
+const SAMPLE_DECADES: u32 = 6;
+
+/// The number of samples per power of ten when searching for numeric roots.
+const SAMPLES_PER_DECADE: u32 = 200;
+
+/// The greatest magnitude of an equation's value at a numeric root, relative
+/// to its value at the samples around the root. Sign changes across poles
+/// have much greater values and are not roots.
+const ROOT_TOLERANCE: f64 = 1e-6;
+
+/// An unevaluated symbolic expression which can be rewritten algebraically.
+#[derive(Clone, PartialEq)]
+pub struct Expression(Sym);
+
+impl Expression {
+    /// Parses a new `Expression` from source code. This function returns an
+    /// [`InterpretError`] if the source code is not a single expression of
+    /// numbers, variables, arithmetic operators, and function calls.
+    pub fn parse(source: &str) -> Result<Self, InterpretError> {
+        Ok(Self(Sym::from_ast(&parse_expr(source)?)?))
+    }
+
+    /// Returns the `Expression` with like terms and factors combined and
+    /// constants folded as a [`Value`]. Products of sums are not expanded.
+    /// This function returns an [`InterpretError`] if a constant is divided by
+    /// zero.
+    pub fn simplify(&self) -> Result<Value, InterpretError> {
+        Ok(Self::into_value(normalize(&self.0, false)?))
+    }
+
+    /// Returns the `Expression` as a simplified sum of products as a
+    /// [`Value`]. Products of sums and small integer powers of sums are
+    /// multiplied out. This function returns an [`InterpretError`] if a
+    /// constant is divided by zero.
+    pub fn expand(&self) -> Result<Value, InterpretError> {
+        Ok(Self::into_value(normalize(&self.0, true)?))
+    }
+
+    /// Returns the `Expression` with a variable replaced by a number or
+    /// `Expression` [`Value`], simplified as a [`Value`]. This function
+    /// returns an [`InterpretError`] if the replacement is not a number or
+    /// `Expression`, or a constant is divided by zero.
+    pub fn substitute(&self, name: Symbol, value: &Value) -> Result<Value, InterpretError> {
+        let replacement = Sym::from_value(value)?;
+        let sym = self.0.substitute(name, &replacement);
+        Ok(Self::into_value(normalize(&sym, false)?))
+    }
+
+    /// Converts a [`Sum`] to a [`Value`], which is a number if the [`Sum`] is
+    /// constant.
+    fn into_value(sum: Sum) -> Value {
+        match denormalize(sum) {
+            Sym::Number(value) => Value::Number(value),
+            sym => Self(sym).into(),
+        }
+    }
+}
+
+impl Object for Expression {
+    fn type_name(&self) -> &'static str {
+        "expression"
+    }
+
+    fn arith(
+        &self,
+        op: ArithOp,
+        other: &Value,
+        side: Side,
+    ) -> Option<Result<Value, InterpretError>> {
+        let other = Sym::from_value(other).ok()?;
+
+        let (lhs, rhs) = match side {
+            Side::Lhs => (self.0.clone(), other),
+            Side::Rhs => (other, self.0.clone()),
+        };
+
+        let op = match op {
+            ArithOp::Add => SymOp::Add,
+            ArithOp::Subtract => SymOp::Subtract,
+            ArithOp::Multiply => SymOp::Multiply,
+            ArithOp::Divide => SymOp::Divide,
+        };
+
+        Some(Ok(Self(Sym::binary(op, lhs, rhs)).into()))
+    }
+
+    fn negate(&self) -> Option<Value> {
+        Some(Self(Sym::Negate(Box::new(self.0.clone()))).into())
+    }
+
+    fn equals(&self, other: &dyn Object) -> bool {
+        object::downcast_ref::<Self>(other).is_some_and(|other| self == other)
+    }
+}
+
+impl From<Expression> for Value {
+    fn from(value: Expression) -> Self {
+        Self::Object(Rc::new(value))
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The real roots of an equation in increasing order.
+pub struct Roots(Box<[f64]>);
+
+impl Object for Roots {
+    fn type_name(&self) -> &'static str {
+        "roots"
+    }
+
+    fn fmt_with(&self, f: &mut Formatter<'_>, settings: &Settings) -> fmt::Result {
+        f.write_str("{")?;
+
+        for (index, root) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+
+            f.write_str(&format::format_number(*root, settings))?;
+        }
+
+        f.write_str("}")
+    }
+
+    fn equals(&self, other: &dyn Object) -> bool {
+        object::downcast_ref::<Self>(other).is_some_and(|other| self.0 == other.0)
+    }
+
+    fn contains(&self, value: &Value) -> Option<Result<bool, InterpretError>> {
+        for root in &self.0 {
+            match value.equals(&Value::Number(*root)) {
+                Ok(false) => {}
+                result => return Some(result),
+            }
+        }
+
+        Some(Ok(false))
+    }
+}
+
+impl From<Roots> for Value {
+    fn from(value: Roots) -> Self {
+        Self::Object(Rc::new(value))
+    }
+}
+
+impl Display for Roots {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &Settings::new())
+    }
+}
+
+/// Solves an equation parsed from source code for a variable named by source
+/// code with a [`Caller`], and returns its real [`Roots`]. An expression
+/// without `==` is solved for where it is zero. Other variables are replaced
+/// by the values of global variables. Linear and quadratic equations are
+/// solved exactly, and other equations are solved numerically for roots where
+/// their sides cross between `-1000` and `1000`.
+///
+/// # Errors
+/// An error is returned if the equation cannot be used symbolically, the
+/// variable is not an identifier, the equation is true for every value, or the
+/// equation could not be evaluated numerically.
+pub fn solve(source: &str, name: &str, caller: &mut dyn Caller) -> Result<Roots, InterpretError> {
+    let ExprKind::Variable(name) = parse_expr(name)?.kind else {
+        return Err(ErrorKind::InvalidUnknown.into());
+    };
+
+    let equation = parse_expr(source)?;
+
+    let sym = match &equation.kind {
+        ExprKind::Binary(BinOp::Equal, lhs, rhs) => {
+            Sym::binary(SymOp::Subtract, Sym::from_ast(lhs)?, Sym::from_ast(rhs)?)
+        }
+        _ => Sym::from_ast(&equation)?,
+    };
+
+    let sum = normalize(&sym.resolve_globals(name, caller), true)?;
+
+    if sum.is_empty() {
+        return Err(ErrorKind::IndeterminateEquation.into());
+    }
+
+    let mut roots = match polynomial(&sum, name) {
+        Some(coefficients) if coefficients.len() <= 3 => polynomial_roots(&coefficients),
+        _ => numeric_roots(&denormalize(sum), name, caller)?,
+    };
+
+    roots.sort_by(f64::total_cmp);
+    Ok(Roots(roots.into_boxed_slice()))
+}
+
+/// Parses a single [`Expr`] from source code. This function returns an
+/// [`InterpretError`] if the source code is not a single expression.
+fn parse_expr(source: &str) -> Result<Expr, InterpretError> {
+    let ast = parse::parse_source(source, &Settings::new(), &mut Operators::new())
+        .map_err(|_errors| ErrorKind::InvalidExpression)?;
+    let mut exprs = ast.0.into_vec();
+
+    match (exprs.pop(), exprs.is_empty()) {
+        (Some(expr), true) => Ok(expr),
+        _ => Err(ErrorKind::InvalidExpression.into()),
+    }
+}
+
+/// Returns the coefficients of a [`Sum`] as a polynomial of a variable, from
+/// the constant term upwards. This function returns [`None`] if the [`Sum`] is
+/// not a polynomial of the variable with constant coefficients.
+fn polynomial(sum: &Sum, name: Symbol) -> Option<Vec<f64>> {
+    let mut coefficients = Vec::new();
+
+    for (coefficient, product) in sum {
+        let exponent = match product.as_slice() {
+            [] => 0.0_f64,
+            [(Sym::Variable(symbol), exponent)] if *symbol == name => *exponent,
+            _ => return None,
+        };
+
+        if exponent.fract() != 0.0_f64 || !(0.0_f64..=MAX_EXPANDED_POWER).contains(&exponent) {
+            return None;
+        }
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "exponent is an integer from 0 to the maximum expanded power"
+        )]
+        let degree = exponent as usize;
+
+        if coefficients.len() <= degree {
+            coefficients.resize(degree + 1, 0.0_f64);
+        }
+
+        coefficients[degree] += coefficient;
+    }
+
+    Some(coefficients)
+}
+
+/// Returns the real roots of a polynomial of at most degree two from its
+/// coefficients, from the constant term upwards.
+fn polynomial_roots(coefficients: &[f64]) -> Vec<f64> {
+    match *coefficients {
+        [c, b, a] => {
+            let discriminant = b.mul_add(b, -4.0 * a * c);
+
+            if discriminant < 0.0_f64 {
+                Vec::new()
+            } else if discriminant == 0.0_f64 {
+                vec![-b / (2.0 * a)]
+            } else {
+                // This form avoids cancellation when `b` is much larger than
+                // the other coefficients.
+                let q = -0.5 * (b + discriminant.sqrt().copysign(b));
+                vec![q / a, c / q]
+            }
+        }
+        [c, b] => vec![-c / b],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the real roots of a [`Sym`] of a variable, found by refining sign
+/// changes between samples with a [`Caller`]. Samples are spaced evenly by
+/// their magnitudes' logarithms, so that roots near zero are found precisely. This function returns an [`InterpretError`] if
+/// the [`Sym`] could not be evaluated.
+fn numeric_roots(
+    sym: &Sym,
+    name: Symbol,
+    caller: &mut dyn Caller,
+) -> Result<Vec<f64>, InterpretError> {
+    let positive_samples: Vec<f64> = (0..=SAMPLES_PER_DECADE * SAMPLE_DECADES)
+        .map(|i| MIN_SAMPLE * 10.0_f64.powf(f64::from(i) / f64::from(SAMPLES_PER_DECADE)))
+        .collect();
+
+    let samples = positive_samples
+        .iter()
+        .rev()
+        .map(|x| -x)
+        .chain([0.0_f64])
+        .chain(positive_samples.iter().copied());
+
+    let mut roots = Vec::new();
+    let mut previous: Option<(f64, f64)> = None;
+
+    for x in samples {
+        let y = sym.evaluate(name, x, caller)?;
+
+        if y == 0.0_f64 {
+            roots.push(x);
+        } else if let Some((previous_x, previous_y)) = previous
+            && y.is_finite()
+            && previous_y.is_finite()
+            && (y < 0.0_f64) != (previous_y < 0.0_f64)
+            && previous_y != 0.0_f64
+        {
+            let root = bisect(sym, name, (previous_x, previous_y), x, caller)?;
+            let residual = sym.evaluate(name, root, caller)?.abs();
+            let tolerance = ROOT_TOLERANCE * (1.0_f64 + y.abs().max(previous_y.abs()));
+
+            if residual <= tolerance {
+                // Rounding errors are removed from roots (e.g. `2.000000000000001`)
+                // if the rounded root is at least as close.
+                let rounded: f64 = format!("{root:.11e}").parse().unwrap_or(root);
+
+                if sym.evaluate(name, rounded, caller)?.abs() <= residual {
+                    roots.push(rounded);
+                } else {
+                    roots.push(root);
+                }
+            }
+        }
+
+        previous = Some((x, y));
+    }
+
+    Ok(roots)
+}
+
+/// Returns the point where a [`Sym`] of a variable changes sign between a
+/// lower bound with its value and an upper bound, found by bisection with a
+/// [`Caller`]. This function returns an [`InterpretError`] if the [`Sym`]
+/// could not be evaluated.
+fn bisect(
+    sym: &Sym,
+    name: Symbol,
+    lower: (f64, f64),
+    upper: f64,
+    caller: &mut dyn Caller,
+) -> Result<f64, InterpretError> {
+    let ((mut lower, lower_y), mut upper) = (lower, upper);
+
+    loop {
+        let middle = lower.midpoint(upper);
+
+        if middle <= lower || middle >= upper {
+            return Ok(middle);
+        }
+
+        let y = sym.evaluate(name, middle, caller)?;
+
+        if y == 0.0_f64 {
+            return Ok(middle);
+        } else if (y < 0.0_f64) == (lower_y < 0.0_f64) {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+}
+
+/// A node of an [`Expression`].
+#[derive(Clone, PartialEq)]
+enum Sym {
+    /// A number.
+    Number(f64),
+
+    /// A variable.
+    Variable(Symbol),
+
+    /// A negation.
+    Negate(Box<Self>),
+
+    /// A binary operation.
+    Binary(SymOp, Box<Self>, Box<Self>),
+
+    /// A function call.
+    Call(Symbol, Box<[Self]>),
+}
+
+impl Sym {
+    /// Creates a new binary operation `Sym` from a [`SymOp`] and two operand
+    /// `Sym`s.
+    fn binary(op: SymOp, lhs: Self, rhs: Self) -> Self {
+        Self::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    /// Converts an [`Expr`] to a `Sym`. This function returns an
+    /// [`InterpretError`] if the [`Expr`] cannot be used symbolically.
+    fn from_ast(expr: &Expr) -> Result<Self, InterpretError> {
+        let sym = match &expr.kind {
+            ExprKind::Literal(literal @ (Literal::Number(_) | Literal::Integer(_))) => {
+                Self::Number(Value::from(literal.clone()).as_float().unwrap_or_default())
+            }
+            ExprKind::Variable(symbol) => Self::Variable(*symbol),
+            ExprKind::Paren(expr) => Self::from_ast(expr)?,
+            ExprKind::Unary(UnOp::Negate, rhs) => Self::Negate(Box::new(Self::from_ast(rhs)?)),
+            ExprKind::Binary(op, lhs, rhs) => {
+                let op = match op {
+                    BinOp::Add => SymOp::Add,
+                    BinOp::Subtract => SymOp::Subtract,
+                    BinOp::Multiply => SymOp::Multiply,
+                    BinOp::Divide => SymOp::Divide,
+                    BinOp::Power => SymOp::Power,
+                    _ => return Err(ErrorKind::InvalidExpression.into()),
+                };
+
+                Self::binary(op, Self::from_ast(lhs)?, Self::from_ast(rhs)?)
+            }
+            ExprKind::Call(callee, list) => {
+                let ExprKind::Variable(symbol) = callee.kind else {
+                    return Err(ErrorKind::InvalidExpression.into());
+                };
+
+                let args = match &list.kind {
+                    ExprKind::Paren(arg) => slice::from_ref(&**arg),
+                    ExprKind::Tuple(args) => args,
+                    _ => slice::from_ref(&**list),
+                };
+
+                let args = args.iter().map(Self::from_ast).collect::<Result<_, _>>()?;
+                Self::Call(symbol, args)
+            }
+            _ => return Err(ErrorKind::InvalidExpression.into()),
+        };
+
+        Ok(sym)
+    }
+
+    /// Converts a number or [`Expression`] [`Value`] to a `Sym`. This function
+    /// returns an [`InterpretError`] if the [`Value`] is another type.
+    fn from_value(value: &Value) -> Result<Self, InterpretError> {
+        match value {
+            Value::Number(value) => Ok(Self::Number(*value)),
+            _ => value
+                .downcast_object::<Expression>()
+                .map(|expression| expression.0.clone())
+                .ok_or_else(|| ErrorKind::InvalidType.into()),
+        }
+    }
+
+    /// Returns the `Sym` with every variable except one replaced by the value
+    /// of a global variable with the same name from a [`Caller`], if it is a
+    /// number or an [`Expression`].
+    fn resolve_globals(&self, name: Symbol, caller: &dyn Caller) -> Self {
+        match self {
+            Self::Variable(symbol) if *symbol != name => caller
+                .global(*symbol)
+                .and_then(|v| Self::from_value(&v).ok())
+                .unwrap_or_else(|| self.clone()),
+            Self::Number(_) | Self::Variable(_) => self.clone(),
+            Self::Negate(rhs) => Self::Negate(Box::new(rhs.resolve_globals(name, caller))),
+            Self::Binary(op, lhs, rhs) => Self::binary(
+                *op,
+                lhs.resolve_globals(name, caller),
+                rhs.resolve_globals(name, caller),
+            ),
+            Self::Call(symbol, args) => Self::Call(
+                *symbol,
+                args.iter()
+                    .map(|a| a.resolve_globals(name, caller))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Evaluates the `Sym` as a number with a variable replaced by a number.
+    /// Functions are read from global variables or native functions and called
+    /// with a [`Caller`].
+    /// This function returns an [`InterpretError`] if the `Sym` has another
+    /// variable or a function call could not be evaluated as a number.
+    fn evaluate(
+        &self,
+        name: Symbol,
+        x: f64,
+        caller: &mut dyn Caller,
+    ) -> Result<f64, InterpretError> {
+        let value = match self {
+            Self::Number(value) => *value,
+            Self::Variable(symbol) if *symbol == name => x,
+            Self::Variable(symbol) => return Err(ErrorKind::ExtraUnknown(*symbol).into()),
+            Self::Negate(rhs) => -rhs.evaluate(name, x, caller)?,
+            Self::Binary(op, lhs, rhs) => {
+                let lhs = lhs.evaluate(name, x, caller)?;
+                let rhs = rhs.evaluate(name, x, caller)?;
+
+                match op {
+                    SymOp::Add => lhs + rhs,
+                    SymOp::Subtract => lhs - rhs,
+                    SymOp::Multiply => lhs * rhs,
+                    SymOp::Divide => lhs / rhs,
+                    SymOp::Power => lhs.powf(rhs),
+                }
+            }
+            Self::Call(symbol, args) => {
+                let function = caller
+                    .global(*symbol)
+                    .or_else(|| Native::from_name(&symbol.to_string()).map(Value::Native))
+                    .ok_or(ErrorKind::UndefinedFunction(*symbol))?;
+
+                let args = args
+                    .iter()
+                    .map(|a| a.evaluate(name, x, caller).map(Value::Number))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match caller.call(&function, &args)? {
+                    Value::Number(value) => value,
+                    _ => return Err(ErrorKind::InvalidType.into()),
+                }
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Returns the `Sym` with a variable replaced by another `Sym`.
+    fn substitute(&self, name: Symbol, replacement: &Self) -> Self {
+        match self {
+            Self::Variable(symbol) if *symbol == name => replacement.clone(),
+            Self::Number(_) | Self::Variable(_) => self.clone(),
+            Self::Negate(rhs) => Self::Negate(Box::new(rhs.substitute(name, replacement))),
+            Self::Binary(op, lhs, rhs) => Self::binary(
+                *op,
+                lhs.substitute(name, replacement),
+                rhs.substitute(name, replacement),
+            ),
+            Self::Call(symbol, args) => Self::Call(
+                *symbol,
+                args.iter()
+                    .map(|a| a.substitute(name, replacement))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the `Sym`'s precedence when it is displayed. Operands with a
+    /// lower precedence than their operator are parenthesized.
+    const fn precedence(&self) -> u8 {
+        match self {
+            Self::Binary(op, ..) => op.precedence(),
+            Self::Number(value) if value.is_sign_negative() => 3,
+            Self::Negate(_) => 3,
+            Self::Number(_) | Self::Variable(_) | Self::Call(..) => 5,
+        }
+    }
+}
+
+impl Display for Sym {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "{value}"),
+            Self::Variable(symbol) => write!(f, "{symbol}"),
+            Self::Negate(rhs) => {
+                f.write_str("-")?;
+                fmt_operand(f, rhs, rhs.precedence() < 3)
+            }
+            Self::Binary(op, lhs, rhs) => {
+                let precedence = op.precedence();
+
+                // Powers are right-associative, and the other operators are
+                // left-associative.
+                let grouping = match op {
+                    SymOp::Power => (
+                        lhs.precedence() <= precedence,
+                        rhs.precedence() < precedence,
+                    ),
+                    SymOp::Add | SymOp::Multiply => {
+                        (lhs.precedence() < precedence, rhs.precedence() < precedence)
+                    }
+                    SymOp::Subtract | SymOp::Divide => (
+                        lhs.precedence() < precedence,
+                        rhs.precedence() <= precedence,
+                    ),
+                };
+
+                fmt_operand(f, lhs, grouping.0)?;
+                write!(f, " {op} ")?;
+                fmt_operand(f, rhs, grouping.1)
+            }
+            Self::Call(symbol, args) => {
+                write!(f, "{symbol}(")?;
+
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    write!(f, "{arg}")?;
+                }
+
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// A binary operator in a [`Sym`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SymOp {
+    /// An addition.
+    Add,
+
+    /// A subtraction.
+    Subtract,
+
+    /// A multiplication.
+    Multiply,
+
+    /// A division.
+    Divide,
+
+    /// An exponentiation.
+    Power,
+}
+
+impl SymOp {
+    /// Returns the `SymOp`'s precedence when it is displayed.
+    const fn precedence(self) -> u8 {
+        match self {
+            Self::Add | Self::Subtract => 1,
+            Self::Multiply | Self::Divide => 2,
+            Self::Power => 4,
+        }
+    }
+}
+
+impl Display for SymOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Power => "^",
+        };
+
+        f.write_str(op)
+    }
+}
+
+/// Formats an operand [`Sym`] with a [`Formatter`], in parentheses if it is
+/// grouped. This function returns a [`fmt::Error`] if an error occurred.
+fn fmt_operand(f: &mut Formatter<'_>, operand: &Sym, is_grouped: bool) -> fmt::Result {
+    if is_grouped {
+        write!(f, "({operand})")
+    } else {
+        write!(f, "{operand}")
+    }
+}
+
+/// A product of factor [`Sym`]s raised to exponents, sorted by how the factors
+/// are displayed. Factors are never sums or products themselves.
+type Product = Vec<(Sym, f64)>;
+
+/// A sum of coefficients multiplied by [`Product`]s, with no zero
+/// coefficients and no repeated [`Product`]s. An empty `Sum` is zero.
+type Sum = Vec<(f64, Product)>;
+
+/// Converts a [`Sym`] to a [`Sum`], combining like terms and factors and
+/// folding constants. If products are expanded, then products of sums and
+/// small integer powers of sums are multiplied out. This function returns an
+/// [`InterpretError`] if a constant is divided by zero.
+fn normalize(sym: &Sym, is_expanded: bool) -> Result<Sum, InterpretError> {
+    let sum = match sym {
+        Sym::Number(value) => constant(*value),
+        Sym::Variable(_) => factor(sym.clone()),
+        Sym::Negate(rhs) => multiply(&constant(-1.0), &normalize(rhs, is_expanded)?),
+        Sym::Binary(op, lhs, rhs) => {
+            let lhs = normalize(lhs, is_expanded)?;
+            let rhs = normalize(rhs, is_expanded)?;
+
+            match op {
+                SymOp::Add => add(lhs, rhs),
+                SymOp::Subtract => add(lhs, multiply(&constant(-1.0), &rhs)),
+                SymOp::Multiply => {
+                    if is_expanded || as_constant(&lhs).is_some() || as_constant(&rhs).is_some() {
+                        multiply(&lhs, &rhs)
+                    } else {
+                        multiply(&group(lhs), &group(rhs))
+                    }
+                }
+                SymOp::Divide => {
+                    let lhs = if is_expanded { lhs } else { group(lhs) };
+                    multiply(&lhs, &reciprocal(group(rhs))?)
+                }
+                SymOp::Power => power(lhs, rhs, is_expanded),
+            }
+        }
+        Sym::Call(symbol, args) => {
+            let args = args
+                .iter()
+                .map(|a| normalize(a, is_expanded).map(denormalize))
+                .collect::<Result<_, _>>()?;
+
+            factor(Sym::Call(*symbol, args))
+        }
+    };
+
+    Ok(sum)
+}
+
+/// Returns a constant [`Sum`].
+fn constant(value: f64) -> Sum {
+    if value == 0.0_f64 {
+        Vec::new()
+    } else {
+        vec![(value, Vec::new())]
+    }
+}
+
+/// Returns a [`Sum`] of a single factor [`Sym`].
+fn factor(sym: Sym) -> Sum {
+    vec![(1.0, vec![(sym, 1.0)])]
+}
+
+/// Returns a [`Sum`]'s value if it is constant. This function returns [`None`]
+/// if the [`Sum`] is not constant.
+fn as_constant(sum: &Sum) -> Option<f64> {
+    match sum.as_slice() {
+        [] => Some(0.0),
+        [(value, product)] if product.is_empty() => Some(*value),
+        _ => None,
+    }
+}
+
+/// Returns a [`Sum`] as a single factor if it has more than one term.
+fn group(sum: Sum) -> Sum {
+    if sum.len() > 1 {
+        factor(denormalize(sum))
+    } else {
+        sum
+    }
+}
+
+/// Returns the sum of two [`Sum`]s.
+fn add(mut lhs: Sum, rhs: Sum) -> Sum {
+    for (coefficient, product) in rhs {
+        match lhs.iter_mut().find(|(_, p)| *p == product) {
+            Some(term) => term.0 += coefficient,
+            None => lhs.push((coefficient, product)),
+        }
+    }
+
+    lhs.retain(|(c, _)| *c != 0.0_f64);
+    lhs
+}
+
+/// Returns the product of two [`Sum`]s, with every term of one [`Sum`]
+/// multiplied by every term of the other.
+fn multiply(lhs: &Sum, rhs: &Sum) -> Sum {
+    let mut sum = Vec::new();
+
+    for (lhs_coefficient, lhs_product) in lhs {
+        for (rhs_coefficient, rhs_product) in rhs {
+            let mut product = lhs_product.clone();
+
+            for (factor, exponent) in rhs_product {
+                match product.iter_mut().find(|(f, _)| f == factor) {
+                    Some(entry) => entry.1 += exponent,
+                    None => product.push((factor.clone(), *exponent)),
+                }
+            }
+
+            product.retain(|(_, e)| *e != 0.0_f64);
+            product.sort_by_cached_key(|(f, _)| f.to_string());
+            sum = add(sum, vec![(lhs_coefficient * rhs_coefficient, product)]);
+        }
+    }
+
+    sum
+}
+
+/// Returns the reciprocal of a [`Sum`] with at most one term. This function
+/// returns an [`InterpretError`] if the [`Sum`] is zero.
+fn reciprocal(sum: Sum) -> Result<Sum, InterpretError> {
+    let Some((coefficient, product)) = sum.into_iter().next() else {
+        return Err(ErrorKind::DivideByZero.into());
+    };
+
+    let product = product.into_iter().map(|(f, e)| (f, -e)).collect();
+    Ok(vec![(coefficient.recip(), product)])
+}
+
+/// Returns a [`Sum`] raised to the power of another [`Sum`]. If products are
+/// expanded, then small integer powers of sums are multiplied out.
+fn power(base: Sum, exponent: Sum, is_expanded: bool) -> Sum {
+    let Some(exponent) = as_constant(&exponent) else {
+        let sym = Sym::binary(SymOp::Power, denormalize(base), denormalize(exponent));
+        return factor(sym);
+    };
+
+    let is_integer = exponent.fract() == 0.0_f64;
+
+    match base.as_slice() {
+        _ if exponent == 0.0_f64 => constant(1.0),
+        [] => constant(0.0_f64.powf(exponent)),
+        [(coefficient, product)] if is_integer || *coefficient > 0.0_f64 => {
+            let product = product.iter().map(|(f, e)| (f.clone(), e * exponent));
+            vec![(coefficient.powf(exponent), product.collect())]
+        }
+        [_, _, ..]
+            if is_expanded && is_integer && (1.0..=MAX_EXPANDED_POWER).contains(&exponent) =>
+        {
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "exponent is an integer from 1 to the maximum expanded power"
+            )]
+            let exponent = exponent as usize;
+
+            let mut sum = base.clone();
+
+            for _ in 1..exponent {
+                sum = multiply(&sum, &base);
+            }
+
+            sum
+        }
+        _ => vec![(1.0, vec![(denormalize(base), exponent)])],
+    }
+}
+
+/// Converts a [`Sum`] to a [`Sym`]. Terms are ordered by decreasing degree.
+fn denormalize(mut sum: Sum) -> Sym {
+    sum.sort_by(|(_, lhs), (_, rhs)| degree(rhs).total_cmp(&degree(lhs)));
+    let mut terms = sum.into_iter();
+
+    let Some((first_coefficient, first_product)) = terms.next() else {
+        return Sym::Number(0.0);
+    };
+
+    let mut sym = if first_coefficient < 0.0_f64 && !first_product.is_empty() {
+        Sym::Negate(Box::new(term(-first_coefficient, first_product)))
+    } else {
+        term(first_coefficient, first_product)
+    };
+
+    for (coefficient, product) in terms {
+        sym = if coefficient < 0.0_f64 {
+            Sym::binary(SymOp::Subtract, sym, term(-coefficient, product))
+        } else {
+            Sym::binary(SymOp::Add, sym, term(coefficient, product))
+        };
+    }
+
+    sym
+}
+
+/// Returns the total exponent of a [`Product`]'s factors.
+fn degree(product: &Product) -> f64 {
+    product.iter().map(|(_, e)| e).sum()
+}
+
+/// Converts a coefficient and a [`Product`] to a [`Sym`]. Factors with
+/// negative exponents are written as divisors.
+#[expect(
+    clippy::float_cmp,
+    reason = "only coefficients and exponents of exactly one are omitted"
+)]
+fn term(coefficient: f64, product: Product) -> Sym {
+    let mut numerator = (coefficient != 1.0_f64 || product.iter().all(|(_, e)| *e < 0.0_f64))
+        .then_some(Sym::Number(coefficient));
+
+    let mut denominator = None;
+
+    for (factor, exponent) in product {
+        let (part, exponent) = if exponent < 0.0_f64 {
+            (&mut denominator, -exponent)
+        } else {
+            (&mut numerator, exponent)
+        };
+
+        let factor = if exponent == 1.0_f64 {
+            factor
+        } else {
+            Sym::binary(SymOp::Power, factor, Sym::Number(exponent))
+        };
+
+        *part = Some(match part.take() {
+            None => factor,
+            Some(lhs) => Sym::binary(SymOp::Multiply, lhs, factor),
+        });
+    }
+
+    let numerator = numerator.unwrap_or(Sym::Number(1.0));
+
+    match denominator {
+        None => numerator,
+        Some(denominator) => Sym::binary(SymOp::Divide, numerator, denominator),
+    }
+}
@@ -0,0 +1,609 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::*;
+use crate::interpret::money::Money;
+
+/// Asserts that comparing two [`Value`]s for equality in either order produces
+/// an expected result, or [`None`] for an error.
+macro_rules! assert_equals {
+    ($lhs:expr, $rhs:expr, $expected:expr) => {
+        assert_eq!($lhs.equals(&$rhs).ok(), $expected);
+        assert_eq!($rhs.equals(&$lhs).ok(), $expected);
+    };
+}
+
+/// Tests that [`Value`]s with matching types are compared by value.
+#[test]
+fn values_are_compared_by_value() {
+    assert_equals!(Value::Number(1.5), Value::Number(1.5), Some(true));
+    assert_equals!(Value::Number(1.5), Value::Number(2.5), Some(false));
+    assert_equals!(
+        Value::Number(f64::NAN),
+        Value::Number(f64::NAN),
+        Some(false)
+    );
+    assert_equals!(Value::Bool(true), Value::Bool(true), Some(true));
+    assert_equals!(Value::Bool(true), Value::Bool(false), Some(false));
+    assert_equals!(Value::Date(86_400.0), Value::Date(86_400.0), Some(true));
+    assert_equals!(Value::Duration(60.0), Value::Duration(3600.0), Some(false));
+}
+
+/// Tests that [`Value`]s with mismatched types cannot be compared.
+#[test]
+fn mismatched_types_cannot_be_compared() {
+    let money = Value::from(Money::new_unit("usd", 1.0).expect("rate should be valid"));
+
+    assert_equals!(Value::Number(1.0), Value::Bool(true), None);
+    assert_equals!(Value::Date(0.0), Value::Duration(0.0), None);
+    assert_equals!(Value::Number(1.0), money, None);
+    assert_equals!(Value::Number(1.0), Value::Native(Native::Sqrt), None);
+}
+
+/// Tests that lists are compared element-wise.
+#[test]
+fn lists_are_compared_element_wise() {
+    let list = |elems: &[f64]| Value::List(elems.iter().copied().map(Value::Number).collect());
+
+    assert_equals!(
+        list(&[1.0_f64, 2.0_f64]),
+        list(&[1.0_f64, 2.0_f64]),
+        Some(true)
+    );
+    assert_equals!(
+        list(&[1.0_f64, 2.0_f64]),
+        list(&[2.0_f64, 1.0_f64]),
+        Some(false)
+    );
+    assert_equals!(list(&[1.0_f64]), list(&[1.0_f64, 1.0_f64]), Some(false));
+    assert_equals!(list(&[]), Value::Number(0.0_f64), None);
+}
+
+/// Tests that lists and tuples are ordered lexicographically, and that
+/// mismatched sequences or elements cannot be ordered.
+#[test]
+fn sequences_are_ordered_lexicographically() {
+    let list = |elems: &[f64]| Value::List(elems.iter().copied().map(Value::Number).collect());
+    let tuple = |elems: &[f64]| Value::Tuple(elems.iter().copied().map(Value::Number).collect());
+    let compare = |lhs: &Value, rhs: &Value| lhs.compare(rhs).map_err(|e| e.kind);
+
+    assert!(matches!(
+        compare(&list(&[1.0_f64, 2.0_f64]), &list(&[1.0_f64, 3.0_f64])),
+        Ok(Some(Ordering::Less))
+    ));
+    assert!(matches!(
+        compare(&tuple(&[2.0_f64]), &tuple(&[1.0_f64, 9.0_f64])),
+        Ok(Some(Ordering::Greater))
+    ));
+    assert!(matches!(
+        compare(&list(&[1.0_f64]), &list(&[1.0_f64, 0.0_f64])),
+        Ok(Some(Ordering::Less))
+    ));
+    assert!(matches!(
+        compare(&list(&[]), &list(&[])),
+        Ok(Some(Ordering::Equal))
+    ));
+    assert!(matches!(
+        compare(&list(&[1.0_f64]), &tuple(&[1.0_f64])),
+        Err(ErrorKind::MixedComparison("list", "tuple"))
+    ));
+    assert!(matches!(
+        compare(
+            &Value::List([Value::Bool(true)].into()),
+            &Value::List([Value::Bool(false)].into())
+        ),
+        Err(ErrorKind::InvalidType)
+    ));
+}
+
+/// Tests that lists can only be indexed by non-negative integers in bounds.
+#[test]
+fn lists_are_indexed_by_integers_in_bounds() {
+    let list = Value::List([Value::Bool(true), Value::Bool(false)].into());
+    let index = |index: f64| list.index(&Value::Number(index)).map_err(|e| e.kind);
+
+    assert!(matches!(index(1.0_f64), Ok(Value::Bool(false))));
+    assert!(matches!(index(-0.0_f64), Ok(Value::Bool(true))));
+    assert!(matches!(
+        index(2.0_f64),
+        Err(ErrorKind::IndexOutOfBounds(..))
+    ));
+    assert!(matches!(index(-1.0_f64), Err(ErrorKind::InvalidIndex)));
+    assert!(matches!(index(0.5_f64), Err(ErrorKind::InvalidIndex)));
+    assert!(matches!(index(f64::NAN), Err(ErrorKind::InvalidIndex)));
+    assert!(matches!(index(f64::INFINITY), Err(ErrorKind::InvalidIndex)));
+}
+
+/// Tests that tuples can only be unpacked into their number of elements.
+#[test]
+fn tuples_are_unpacked_by_length() {
+    let tuple = Value::Tuple([Value::Number(1.0_f64), Value::Bool(true)].into());
+    let unpack = |value: &Value, count| value.check_unpack(count).map_err(|e| e.kind);
+
+    assert!(matches!(unpack(&tuple, 2), Ok(())));
+    assert!(matches!(
+        unpack(&tuple, 3),
+        Err(ErrorKind::UnpackLength(2, 3))
+    ));
+    assert!(matches!(
+        unpack(&Value::List([].into()), 0),
+        Err(ErrorKind::UnpackedNonTuple("list"))
+    ));
+    assert_equals!(tuple, Value::List([].into()), None);
+}
+
+/// Tests that functions are compared by identity.
+#[test]
+fn functions_are_compared_by_identity() {
+    let sqrt = Value::Native(Native::Sqrt);
+
+    assert_equals!(sqrt, sqrt.clone(), Some(true));
+    assert_equals!(sqrt, Value::Native(Native::Round), Some(false));
+}
+
+/// Tests that [`Object`][object::Object]s are compared with their own
+/// equality rules.
+#[test]
+fn objects_are_compared_structurally() {
+    let usd = Value::from(Money::new_unit("usd", 1.0).expect("rate should be valid"));
+    let other_usd = Value::from(Money::new_unit("usd", 1.0).expect("rate should be valid"));
+
+    assert_equals!(usd, usd.clone(), Some(true));
+    assert_equals!(usd, other_usd, Some(false));
+}
+
+/// Tests that currency rates set together are all rejected if any of them is
+/// invalid.
+#[test]
+fn currency_rates_are_set_together() {
+    let usd = Money::new_unit("usd", 1.0).expect("rate should be valid");
+    let eur = Money::new_unit("eur", 0.5).expect("rate should be valid");
+
+    assert!(Money::set_rates(&[(&eur, 0.25_f64), (&usd, 0.0_f64)]).is_err());
+    assert_eq!(usd.convert(&eur).to_string(), "0.5 eur");
+
+    Money::set_rates(&[(&eur, 0.25_f64), (&usd, 2.0_f64)]).expect("rates should be valid");
+    assert_eq!(usd.convert(&eur).to_string(), "0.125 eur");
+    assert!(Money::new_unit(" ", 1.0).is_err());
+}
+
+/// A two-dimensional vector [`Object`] which supports addition, scaling,
+/// negation, and equality.
+struct Vector(f64, f64);
+
+impl Display for Vector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}, {}>", self.0, self.1)
+    }
+}
+
+impl Object for Vector {
+    fn type_name(&self) -> &'static str {
+        "vector"
+    }
+
+    fn arith(
+        &self,
+        op: ArithOp,
+        other: &Value,
+        _side: Side,
+    ) -> Option<Result<Value, InterpretError>> {
+        let result = match (op, other) {
+            (ArithOp::Add, Value::Object(_)) => {
+                let other = other.downcast_object::<Self>()?;
+                Self(self.0 + other.0, self.1 + other.1)
+            }
+            (ArithOp::Multiply, Value::Number(scale)) => Self(self.0 * scale, self.1 * scale),
+            _ => return None,
+        };
+
+        Some(Ok(Value::Object(Rc::new(result))))
+    }
+
+    fn negate(&self) -> Option<Value> {
+        Some(Value::Object(Rc::new(Self(-self.0, -self.1))))
+    }
+
+    #[expect(clippy::float_cmp, reason = "vectors are only equal if they are exact")]
+    fn equals(&self, other: &dyn Object) -> bool {
+        object::downcast_ref::<Self>(other).is_some_and(|o| o.0 == self.0 && o.1 == self.1)
+    }
+}
+
+/// Returns a new [`Vector`] [`Value`].
+fn vector(x: f64, y: f64) -> Value {
+    Value::Object(Rc::new(Vector(x, y)))
+}
+
+/// Tests that [`Object`]s overload only the operations which they implement.
+#[test]
+fn objects_overload_implemented_operations() {
+    let display = |value: &Value| value.display(&Settings::new()).to_string();
+
+    let sum = vector(1.0, 2.0).arith(ArithOp::Add, &vector(3.0, 4.0));
+    assert_eq!(sum.as_ref().map(display).ok().as_deref(), Some("<4, 6>"));
+
+    let scaled = Value::Number(2.0).arith(ArithOp::Multiply, &vector(1.5, -1.0));
+    assert_eq!(
+        scaled.as_ref().map(display).ok().as_deref(),
+        Some("<3, -2>")
+    );
+
+    let original = vector(1.0, 2.0);
+    let Value::Object(object) = &original else {
+        unreachable!("vector should be an object");
+    };
+    let negated = object.negate().expect("vectors should be negatable");
+    assert_eq!(display(&negated), "<-1, -2>");
+
+    assert_equals!(vector(1.0, 2.0), vector(1.0, 2.0), Some(true));
+    assert_equals!(vector(1.0, 2.0), vector(2.0, 1.0), Some(false));
+    assert_equals!(vector(1.0, 2.0), Value::Number(1.0), None);
+
+    let invalid = vector(1.0, 2.0).arith(ArithOp::Divide, &Value::Number(2.0));
+    assert!(invalid.is_err());
+    let ordering = vector(1.0, 2.0).compare(&vector(3.0, 4.0));
+    ordering.expect_err("vectors should not be ordered");
+    let contains = vector(1.0, 2.0).contains(&Value::Number(1.0));
+    contains.expect_err("vectors should not support membership tests");
+    assert!(vector(1.0, 2.0).downcast_object::<Money>().is_none());
+}
+
+/// Tests that [`Value`]s with orderable types are ordered and that mixed types
+/// cannot be ordered.
+#[test]
+fn values_are_ordered_by_type() {
+    use std::cmp::Ordering;
+
+    let ordering = |lhs: Value, rhs: Value| lhs.compare(&rhs).ok();
+
+    assert_eq!(
+        ordering(Value::Number(1.0), Value::Number(2.0_f64)),
+        Some(Some(Ordering::Less))
+    );
+
+    assert_eq!(
+        ordering(Value::Duration(60.0), Value::Duration(1.0)),
+        Some(Some(Ordering::Greater))
+    );
+
+    assert_eq!(
+        ordering(Value::Number(f64::NAN), Value::Number(1.0_f64)),
+        Some(None)
+    );
+
+    assert_eq!(ordering(Value::Bool(false), Value::Bool(true)), None);
+    assert_eq!(ordering(Value::Number(1.0), Value::Date(1.0)), None);
+}
+
+/// Asserts that calling the native `format` function with a template and
+/// arguments produces an expected string, or [`None`] for an error.
+macro_rules! assert_format {
+    ($template:literal, [$($arg:expr),* $(,)?], $expected:expr) => {
+        let args = [Value::Str($template.into()), $($arg),*];
+        let mut globals = Globals::new();
+        let mut interpreter = Interpreter::new(&mut globals);
+        let formatted = Native::Format.call(&args, &mut interpreter);
+        let formatted = formatted.ok().map(|v| v.display(&Settings::new()).to_string());
+        assert_eq!(formatted.as_deref(), $expected);
+    };
+}
+
+/// Tests that the native `format` function applies format specs.
+#[test]
+#[expect(
+    clippy::literal_string_with_formatting_args,
+    reason = "format templates are tested"
+)]
+fn format_specs_are_applied() {
+    assert_format!("{:.3}", [Value::Number(1.23456)], Some("1.235"));
+    assert_format!("{:.2e}", [Value::Number(12345.0)], Some("1.23e4"));
+    assert_format!("{:.1e}", [Value::Number(9.96)], Some("1.0e1"));
+    assert_format!("{:x}", [Value::Number(255.0)], Some("ff"));
+    assert_format!("{:X}", [Value::Number(-255.0)], Some("-FF"));
+    assert_format!("{:06.1}", [Value::Number(-2.5)], Some("-002.5"));
+    assert_format!("{:*^7}", [Value::Bool(true)], Some("*true**"));
+    assert_format!(
+        "{:>4}|{:<4}|",
+        [Value::Number(1.0), Value::Bool(true)],
+        Some("   1|true|")
+    );
+    assert_format!("{{{}}}", [Value::Number(1.0)], Some("{1}"));
+
+    assert_format!("{:x}", [Value::Number(1.5)], None);
+    assert_format!("{:.2}", [Value::Bool(true)], None);
+    assert_format!("{:.2x}", [Value::Number(1.0)], None);
+    assert_format!("{} {}", [Value::Number(1.0)], None);
+    assert_format!("{}", [Value::Number(1.0), Value::Number(2.0)], None);
+    assert_format!("{", [], None);
+    assert_format!("}", [], None);
+}
+
+/// Tests that numbers are displayed with the [`Settings`] of their session.
+#[test]
+fn numbers_are_displayed_with_session_settings() {
+    let mut settings = Settings::new();
+    settings.number_format.decimal_comma = true;
+    settings.number_format.decimals = Some(2);
+
+    let value = Value::List([Value::Number(1.5), Value::Number(2.0)].into());
+    assert_eq!(value.display(&settings).to_string(), "[1,50; 2,00]");
+    assert_eq!(value.display(&Settings::new()).to_string(), "[1.5, 2]");
+
+    let mut globals = Globals::new();
+    globals.settings_mut().number_format.decimals = Some(1);
+    let mut interpreter = Interpreter::new(&mut globals);
+    let args = [Value::Str("{}".into()), Value::Number(0.25)];
+    let formatted = Native::Format.call(&args, &mut interpreter);
+    let formatted = formatted
+        .ok()
+        .map(|v| v.display(&Settings::new()).to_string());
+    assert_eq!(formatted.as_deref(), Some("0.3"));
+}
+
+/// Tests that [`Native`] arity metadata matches the arguments that natives
+/// accept.
+#[test]
+fn native_arity_is_checked() {
+    assert!(!Native::Round.accepts_arity(0));
+    assert!(Native::Round.accepts_arity(1));
+    assert!(Native::Round.accepts_arity(2));
+    assert!(!Native::Round.accepts_arity(3));
+    assert!(Native::Today.accepts_arity(0));
+    assert!(!Native::Max.accepts_arity(0));
+    assert!(Native::Max.accepts_arity(5));
+
+    let mut globals = Globals::new();
+    let mut interpreter = Interpreter::new(&mut globals);
+    let args = [Value::Number(1.5), Value::Number(1.0)];
+
+    let rounded = Native::Round.call(&args, &mut interpreter);
+    assert_eq!(
+        rounded
+            .ok()
+            .map(|v| v.display(&Settings::new()).to_string())
+            .as_deref(),
+        Some("1.5")
+    );
+    assert!(matches!(
+        Native::Sqrt
+            .call(&args, &mut interpreter)
+            .map_err(|e| e.kind),
+        Err(ErrorKind::IncorrectCallArity)
+    ));
+}
+
+/// Tests that [`Native::Base`] rejects strings with signs or digits outside
+/// of the base.
+#[test]
+fn bases_reject_invalid_digits() {
+    let mut globals = Globals::new();
+    let mut interpreter = Interpreter::new(&mut globals);
+
+    for digits in ["+1", "fg", "", "-"] {
+        let args = [Value::Number(16.0), Value::Str(digits.into())];
+
+        assert!(matches!(
+            Native::Base
+                .call(&args, &mut interpreter)
+                .map_err(|e| e.kind),
+            Err(ErrorKind::InvalidDigits(16))
+        ));
+    }
+}
+
+/// Tests that [`Arity`]s of global functions are available before their
+/// calls are interpreted.
+#[test]
+fn global_arities_are_known() {
+    let mut globals = Globals::new();
+    install_natives(&mut globals);
+
+    let arity = |name| globals.arity(Symbol::intern(name));
+    assert_eq!(arity("sqrt"), Some(Arity::exact(1)));
+    assert_eq!(arity("round"), Some(Arity::new(1, Some(2))));
+    assert_eq!(
+        arity("max").map(|a| a.to_string()).as_deref(),
+        Some("1 or more")
+    );
+    assert_eq!(arity("undefined"), None);
+}
+
+/// Tests that global variables are kept in definition order.
+#[test]
+fn globals_are_kept_in_definition_order() {
+    let mut globals = Globals::new();
+    let names = |g: &Globals| g.symbols().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    for name in ["c", "a", "b", "d"] {
+        globals.assign(Symbol::intern(name), Value::Number(0.0_f64));
+    }
+
+    globals.assign(Symbol::intern("a"), Value::Number(1.0_f64));
+    assert_eq!(names(&globals), ["c", "a", "b", "d"]);
+
+    assert!(globals.remove(Symbol::intern("a")));
+    assert_eq!(names(&globals), ["c", "b", "d"]);
+    assert!(matches!(
+        globals.read(Symbol::intern("d")),
+        Some(Value::Number(0.0_f64))
+    ));
+
+    globals.assign(Symbol::intern("a"), Value::Number(2.0_f64));
+    assert_eq!(names(&globals), ["c", "b", "d", "a"]);
+}
+
+/// Tests that global variables are read from slots indexed by their
+/// [`Symbol`]s, including [`Symbol`]s beyond the last slot.
+#[test]
+fn globals_are_stored_in_symbol_slots() {
+    let mut globals = Globals::new();
+    let first = Symbol::intern("globals_are_stored_in_symbol_slots_first");
+    let second = Symbol::intern("globals_are_stored_in_symbol_slots_second");
+    assert_ne!(first.index(), second.index());
+    assert_eq!(first.index(), Symbol::intern(&first.to_string()).index());
+
+    assert!(globals.read(second).is_none());
+    assert!(!globals.contains(second));
+    assert!(!globals.remove(second));
+
+    globals.assign(second, Value::Bool(true));
+    assert!(globals.read(first).is_none());
+    assert!(matches!(globals.read(second), Some(Value::Bool(true))));
+
+    globals.assign(first, Value::Bool(false));
+    assert!(matches!(globals.read(first), Some(Value::Bool(false))));
+    assert!(globals.remove(second));
+    assert!(!globals.remove(second));
+    assert!(!globals.contains(second));
+    assert!(globals.contains(first));
+}
+
+/// Tests that [`Snapshot`]s of global variables can be restored and forked
+/// without being changed.
+#[test]
+fn snapshots_can_be_restored_and_forked() {
+    let mut globals = Globals::new();
+    let (x, y) = (Symbol::intern("x"), Symbol::intern("y"));
+    globals.assign(x, Value::Number(1.0_f64));
+    globals.set_source(x, "x = 1");
+
+    let snapshot = globals.snapshot();
+    globals.assign(x, Value::Number(2.0_f64));
+    globals.assign(y, Value::Number(3.0_f64));
+
+    let mut fork = snapshot.fork();
+    fork.remove(x);
+    assert_eq!(snapshot.variable_count(), 1);
+
+    globals.restore(&snapshot);
+    assert!(matches!(globals.read(x), Some(Value::Number(1.0_f64))));
+    assert!(!globals.contains(y));
+    assert_eq!(globals.source(x), Some("x = 1"));
+    assert!(globals.take_assigned().is_empty());
+}
+
+/// Asserts that rewriting a symbolic expression parsed from source code with a
+/// function produces an expected result.
+macro_rules! assert_rewrite {
+    ($source:literal, $rewrite:expr, $expected:literal) => {
+        let expression = symbolic::Expression::parse($source).expect("source should parse");
+        let rewritten = $rewrite(&expression).map(|v| v.display(&Settings::new()).to_string());
+        assert_eq!(rewritten.ok().as_deref(), Some($expected));
+    };
+}
+
+/// Tests that symbolic expressions are simplified and expanded.
+#[test]
+fn symbolic_expressions_are_rewritten() {
+    use symbolic::Expression;
+
+    assert_rewrite!("x + x", Expression::simplify, "2 * x");
+    assert_rewrite!("x * x * 3 - x / x", Expression::simplify, "3 * x ^ 2 - 1");
+    assert_rewrite!("(x + 1) * (x + 1)", Expression::simplify, "(x + 1) ^ 2");
+    assert_rewrite!("(x + 1) ^ 2", Expression::expand, "x ^ 2 + 2 * x + 1");
+    assert_rewrite!("(x - y) * (x + y)", Expression::expand, "x ^ 2 - y ^ 2");
+    assert_rewrite!("2 * (3 + 4)", Expression::simplify, "14");
+
+    let expression = Expression::parse("x ^ 2 + y").expect("source should parse");
+    let substituted = expression.substitute(Symbol::intern("x"), &Value::Number(3.0));
+    assert_eq!(
+        substituted
+            .ok()
+            .map(|v| v.display(&Settings::new()).to_string())
+            .as_deref(),
+        Some("y + 9")
+    );
+}
+
+/// A [`Caller`] without global variables which cannot call
+/// functions.
+#[derive(Default)]
+struct NoGlobals(Output);
+
+impl Caller for NoGlobals {
+    fn call(&mut self, _callee: &Value, _args: &[Value]) -> Result<Value, InterpretError> {
+        Err(ErrorKind::InvalidType.into())
+    }
+
+    fn global(&self, _symbol: Symbol) -> Option<Value> {
+        None
+    }
+
+    fn settings(&self) -> &Settings {
+        const SETTINGS: Settings = Settings::new();
+        &SETTINGS
+    }
+
+    fn output(&self) -> &Output {
+        &self.0
+    }
+}
+
+/// Asserts that solving an equation parsed from source code for `x` produces
+/// expected roots, or [`None`] for an error.
+macro_rules! assert_roots {
+    ($source:literal, $expected:expr) => {
+        let roots = symbolic::solve($source, "x", &mut NoGlobals::default()).map(|r| r.to_string());
+        assert_eq!(roots.ok().as_deref(), $expected);
+    };
+}
+
+/// Tests that equations are solved exactly and numerically.
+#[test]
+fn equations_are_solved() {
+    assert_roots!("x ^ 2 - 4 == 0", Some("{-2, 2}"));
+    assert_roots!("2 * x + 3 == 7", Some("{2}"));
+    assert_roots!("x ^ 2 - 2 * x + 1", Some("{1}"));
+    assert_roots!("x ^ 2 == -1", Some("{}"));
+    assert_roots!("x ^ 3 - 6 * x ^ 2 + 11 * x - 6 == 0", Some("{1, 2, 3}"));
+    assert_roots!("1 / x == 0", Some("{}"));
+    assert_roots!("x == x", None);
+    assert_roots!("x * y == 1", None);
+}
+
+/// Interprets a [`Cfg`] with a single [`BasicBlock`][crate::cfg::BasicBlock]
+/// and returns its error message, if any.
+fn interpret_block(instructions: Vec<Instruction>, terminator: Terminator) -> Option<String> {
+    let mut cfg = Cfg::new("".into());
+    let block = cfg.basic_block_mut(Label::default());
+    block.instructions = instructions;
+    block.terminator = terminator;
+
+    let mut globals = Globals::new();
+    interpret_cfg(&cfg, &mut globals)
+        .err()
+        .map(|e| e.to_string())
+}
+
+/// Tests that broken interpreter invariants are reported as internal errors
+/// instead of panicking.
+#[test]
+fn broken_invariants_are_internal_errors() {
+    assert_eq!(interpret_block(Vec::new(), Terminator::Halt), None);
+
+    let cases = [
+        (vec![Instruction::Add], Terminator::Halt, "stack is empty"),
+        (
+            vec![Instruction::Pop(1)],
+            Terminator::Halt,
+            "popped past the bottom of the stack",
+        ),
+        (
+            vec![Instruction::PopUpvars(1)],
+            Terminator::Halt,
+            "popped past the bottom of the upvars",
+        ),
+        (
+            vec![Instruction::PushLiteral(Literal::Bool(true))],
+            Terminator::Return,
+            "returned without a caller",
+        ),
+    ];
+
+    for (instructions, terminator, message) in cases {
+        assert_eq!(
+            interpret_block(instructions, terminator),
+            Some(format!("internal error: {message}"))
+        );
+    }
+}
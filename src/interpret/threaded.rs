@@ -0,0 +1,161 @@
+use std::fmt::{self, Debug, Formatter};
+
+use crate::{
+    ast::Literal,
+    cfg::{BasicBlock, Comparison, Instruction},
+};
+
+use super::{InterpretError, Interpreter, errors::ErrorKind, object::ArithOp, thunk, value::Value};
+
+/// A function which interprets one kind of [`Instruction`].
+type Handler = fn(&mut Interpreter<'_>, &Instruction) -> Result<(), InterpretError>;
+
+/// A [`BasicBlock`]'s [`Instruction`]s translated to direct-threaded code. Each
+/// [`Instruction`] is paired with a [`Handler`] which is chosen once, so the
+/// interpreter does not need to match every [`Instruction`] again each time
+/// it is interpreted.
+pub struct ThreadedCode(Box<[Handler]>);
+
+impl ThreadedCode {
+    /// Creates new `ThreadedCode` from a [`BasicBlock`].
+    pub fn new(basic_block: &BasicBlock) -> Self {
+        let handlers = basic_block.instructions.iter().map(handler).collect();
+        Self(handlers)
+    }
+}
+
+impl Debug for ThreadedCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadedCode").finish_non_exhaustive()
+    }
+}
+
+impl Interpreter<'_> {
+    /// Interprets a [`BasicBlock`]'s [`Instruction`]s with its
+    /// [`ThreadedCode`]. This function returns the index of the [`Instruction`]
+    /// and an [`InterpretError`] if an error occurred.
+    pub(super) fn interpret_threaded(
+        &mut self,
+        basic_block: &BasicBlock,
+    ) -> Result<(), (usize, InterpretError)> {
+        let code = basic_block
+            .threaded
+            .get_or_init(|| ThreadedCode::new(basic_block));
+
+        for (index, (handler, instruction)) in
+            code.0.iter().zip(&basic_block.instructions).enumerate()
+        {
+            handler(self, instruction).map_err(|e| (index, e))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the [`Handler`] for an [`Instruction`]. Hot [`Instruction`]s have
+/// specialized [`Handler`]s, and all other [`Instruction`]s fall back to the
+/// match-based interpreter.
+fn handler(instruction: &Instruction) -> Handler {
+    match instruction {
+        Instruction::PushLiteral(Literal::Number(_)) => push_number,
+        Instruction::PushLocal(_) => push_local,
+        Instruction::Add => add,
+        Instruction::Subtract => subtract,
+        Instruction::Multiply => multiply,
+        Instruction::AddLocalNumber(..) => add_local_number,
+        Instruction::Less => less,
+        Instruction::Greater => greater,
+        _ => fallback,
+    }
+}
+
+/// Interprets any [`Instruction`] with the match-based interpreter.
+fn fallback(
+    interpreter: &mut Interpreter<'_>,
+    instruction: &Instruction,
+) -> Result<(), InterpretError> {
+    interpreter.interpret_instruction(instruction)
+}
+
+/// Interprets an [`Instruction::PushLiteral`] with a number.
+fn push_number(
+    interpreter: &mut Interpreter<'_>,
+    instruction: &Instruction,
+) -> Result<(), InterpretError> {
+    let Instruction::PushLiteral(Literal::Number(value)) = instruction else {
+        return Err(ErrorKind::Internal("mismatched threaded handler").into());
+    };
+
+    interpreter.push_number(*value);
+    Ok(())
+}
+
+/// Interprets an [`Instruction::PushLocal`].
+fn push_local(
+    interpreter: &mut Interpreter<'_>,
+    instruction: &Instruction,
+) -> Result<(), InterpretError> {
+    let Instruction::PushLocal(offset) = instruction else {
+        return Err(ErrorKind::Internal("mismatched threaded handler").into());
+    };
+
+    let value = interpreter.local(*offset)?;
+    interpreter.push_forced(value)
+}
+
+/// Interprets an [`Instruction::Add`].
+fn add(interpreter: &mut Interpreter<'_>, _: &Instruction) -> Result<(), InterpretError> {
+    match (interpreter.pop()?, interpreter.pop()?) {
+        (Value::Number(rhs), Value::Number(lhs)) => interpreter.push_number(lhs + rhs),
+        (rhs, lhs) => interpreter.push_arith(ArithOp::Add, &lhs, &rhs)?,
+    }
+
+    Ok(())
+}
+
+/// Interprets an [`Instruction::Subtract`].
+fn subtract(interpreter: &mut Interpreter<'_>, _: &Instruction) -> Result<(), InterpretError> {
+    match (interpreter.pop()?, interpreter.pop()?) {
+        (Value::Number(rhs), Value::Number(lhs)) => interpreter.push_number(lhs - rhs),
+        (rhs, lhs) => interpreter.push_arith(ArithOp::Subtract, &lhs, &rhs)?,
+    }
+
+    Ok(())
+}
+
+/// Interprets an [`Instruction::Multiply`].
+fn multiply(interpreter: &mut Interpreter<'_>, _: &Instruction) -> Result<(), InterpretError> {
+    match (interpreter.pop()?, interpreter.pop()?) {
+        (Value::Number(rhs), Value::Number(lhs)) => interpreter.push_number(lhs * rhs),
+        (rhs, lhs) => interpreter.push_arith(ArithOp::Multiply, &lhs, &rhs)?,
+    }
+
+    Ok(())
+}
+
+/// Interprets an [`Instruction::Less`].
+fn less(interpreter: &mut Interpreter<'_>, _: &Instruction) -> Result<(), InterpretError> {
+    interpreter.push_comparison(Comparison::Less)
+}
+
+/// Interprets an [`Instruction::Greater`].
+fn greater(interpreter: &mut Interpreter<'_>, _: &Instruction) -> Result<(), InterpretError> {
+    interpreter.push_comparison(Comparison::Greater)
+}
+
+/// Interprets an [`Instruction::AddLocalNumber`].
+fn add_local_number(
+    interpreter: &mut Interpreter<'_>,
+    instruction: &Instruction,
+) -> Result<(), InterpretError> {
+    let Instruction::AddLocalNumber(offset, rhs) = instruction else {
+        return Err(ErrorKind::Internal("mismatched threaded handler").into());
+    };
+
+    match thunk::force(interpreter.local(*offset)?, interpreter)? {
+        Value::Number(lhs) => interpreter.push_number(lhs + rhs),
+        lhs => interpreter.push_arith(ArithOp::Add, &lhs, &Value::Number(*rhs))?,
+    }
+
+    Ok(())
+}
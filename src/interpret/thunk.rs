@@ -0,0 +1,78 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::settings::Settings;
+
+use super::{InterpretError, errors::ErrorKind, native::Caller, value::Value};
+
+/// A lazy [`Value`] which is evaluated by calling a function with no
+/// parameters when it is first forced.
+pub struct Thunk(RefCell<State>);
+
+impl Thunk {
+    /// Creates a new `Thunk` from a function [`Value`] with no parameters.
+    pub const fn new(function: Value) -> Self {
+        Self(RefCell::new(State::Pending(function)))
+    }
+
+    /// Returns the `Thunk`'s [`Value`], calling its function with a [`Caller`]
+    /// if it has not already been forced. This function returns an
+    /// [`InterpretError`] if an error occurred or the `Thunk` depends on
+    /// itself.
+    pub fn force(&self, caller: &mut dyn Caller) -> Result<Value, InterpretError> {
+        let function = match self.0.replace(State::Forcing) {
+            State::Pending(function) => function,
+            State::Forcing => return Err(ErrorKind::CyclicLazyValue.into()),
+            State::Forced(value) => {
+                self.0.replace(State::Forced(value.clone()));
+                return Ok(value);
+            }
+        };
+
+        match caller.call(&function, &[]).and_then(|v| force(v, caller)) {
+            Ok(value) => {
+                self.0.replace(State::Forced(value.clone()));
+                Ok(value)
+            }
+            Err(error) => {
+                // Restore the function so that forcing can be retried.
+                self.0.replace(State::Pending(function));
+                Err(error)
+            }
+        }
+    }
+
+    /// Formats the `Thunk` with a [`Formatter`] and a session's [`Settings`].
+    /// A forced `Thunk` is displayed as its [`Value`]. This function returns a
+    /// [`fmt::Error`] if an error occurred.
+    pub fn fmt_with(&self, f: &mut Formatter<'_>, settings: &Settings) -> fmt::Result {
+        match &*self.0.borrow() {
+            State::Forced(value) => Display::fmt(&value.display(settings), f),
+            State::Pending(_) | State::Forcing => f.write_str("<lazy value>"),
+        }
+    }
+}
+
+/// A [`Thunk`]'s state.
+enum State {
+    /// The [`Thunk`] has not been forced and holds its function [`Value`].
+    Pending(Value),
+
+    /// The [`Thunk`] is being forced.
+    Forcing,
+
+    /// The [`Thunk`] has been forced and holds its [`Value`].
+    Forced(Value),
+}
+
+/// Forces a [`Value`] with a [`Caller`] if it is a [`Thunk`], or returns the
+/// [`Value`] unchanged. This function returns an [`InterpretError`] if an
+/// error occurred.
+pub fn force(value: Value, caller: &mut dyn Caller) -> Result<Value, InterpretError> {
+    match value {
+        Value::Thunk(thunk) => thunk.force(caller),
+        value => Ok(value),
+    }
+}
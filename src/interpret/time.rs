@@ -0,0 +1,198 @@
+use crate::calendar;
+
+use super::{InterpretError, errors::ErrorKind, value::Value};
+
+/// The number of seconds in a minute.
+pub const SECS_PER_MINUTE: f64 = 60.0;
+
+/// The number of seconds in an hour.
+pub const SECS_PER_HOUR: f64 = 3600.0;
+
+/// The number of seconds in a day.
+pub const SECS_PER_DAY: f64 = 86_400.0;
+
+/// The number of seconds in a week.
+pub const SECS_PER_WEEK: f64 = 604_800.0;
+
+/// Returns the sum of two non-number [`Value`]s. This function returns an
+/// [`InterpretError`] if the [`Value`]s cannot be added.
+pub fn add(lhs: &Value, rhs: &Value) -> Result<Value, InterpretError> {
+    let value = match (lhs, rhs) {
+        (Value::Date(date), Value::Duration(duration))
+        | (Value::Duration(duration), Value::Date(date)) => Value::Date(date + duration),
+        (Value::Duration(lhs), Value::Duration(rhs)) => Value::Duration(lhs + rhs),
+        _ => return Err(ErrorKind::InvalidType.into()),
+    };
+
+    Ok(value)
+}
+
+/// Returns the difference between two non-number [`Value`]s. This function
+/// returns an [`InterpretError`] if the [`Value`]s cannot be subtracted.
+pub fn subtract(lhs: &Value, rhs: &Value) -> Result<Value, InterpretError> {
+    let value = match (lhs, rhs) {
+        (Value::Date(lhs), Value::Date(rhs)) | (Value::Duration(lhs), Value::Duration(rhs)) => {
+            Value::Duration(lhs - rhs)
+        }
+        (Value::Date(date), Value::Duration(duration)) => Value::Date(date - duration),
+        _ => return Err(ErrorKind::InvalidType.into()),
+    };
+
+    Ok(value)
+}
+
+/// Returns the product of two non-number [`Value`]s. This function returns an
+/// [`InterpretError`] if the [`Value`]s cannot be multiplied.
+pub fn multiply(lhs: &Value, rhs: &Value) -> Result<Value, InterpretError> {
+    match (lhs, rhs) {
+        (Value::Duration(duration), Value::Number(factor))
+        | (Value::Number(factor), Value::Duration(duration)) => {
+            Ok(Value::Duration(duration * factor))
+        }
+        _ => Err(ErrorKind::InvalidType.into()),
+    }
+}
+
+/// Returns the quotient of two non-number [`Value`]s. Dividing a duration by
+/// another duration returns a number. This function returns an
+/// [`InterpretError`] if the [`Value`]s cannot be divided.
+pub fn divide(lhs: &Value, rhs: &Value) -> Result<Value, InterpretError> {
+    let (duration, divisor) = match (lhs, rhs) {
+        (Value::Duration(duration), Value::Number(divisor) | Value::Duration(divisor)) => {
+            (*duration, *divisor)
+        }
+        _ => return Err(ErrorKind::InvalidType.into()),
+    };
+
+    if !divisor.is_normal() {
+        return Err(ErrorKind::DivideByZero.into());
+    }
+
+    let quotient = duration / divisor;
+
+    if matches!(rhs, Value::Duration(_)) {
+        Ok(Value::Number(quotient))
+    } else {
+        Ok(Value::Duration(quotient))
+    }
+}
+
+/// Returns a date from a year, month, and day. This function returns an
+/// [`InterpretError`] if the date is invalid.
+pub fn date(year: f64, month: f64, day: f64) -> Result<f64, InterpretError> {
+    const MAX_YEAR: f64 = 1_000_000.0;
+
+    let is_valid = [year, month, day].iter().all(|n| n.fract() == 0.0_f64)
+        && year.abs() <= MAX_YEAR
+        && (1.0_f64..=12.0_f64).contains(&month)
+        && (1.0_f64..=31.0_f64).contains(&day);
+
+    if !is_valid {
+        return Err(ErrorKind::InvalidDate.into());
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "date components have been checked"
+    )]
+    let (year, month, day) = (year as i64, month as u32, day as u32);
+
+    // Days past the end of the month are caught by converting the date back.
+    let days = calendar::days_from_civil(year, month, day);
+
+    if calendar::civil_from_days(days) != (year, month, day) {
+        return Err(ErrorKind::InvalidDate.into());
+    }
+
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "days are within the range of exactly representable integers"
+    )]
+    Ok(days as f64 * SECS_PER_DAY)
+}
+
+/// Returns the `(year, month, day)` of a date. This function returns an
+/// [`InterpretError`] if the date is too far from the Unix epoch.
+pub fn date_parts(date: f64) -> Result<(i64, u32, u32), InterpretError> {
+    let days = days_from_date(date)?;
+    Ok(calendar::civil_from_days(days))
+}
+
+/// Returns the ISO weekday of a date, from 1 for Monday to 7 for Sunday. This
+/// function returns an [`InterpretError`] if the date is too far from the Unix
+/// epoch.
+pub fn weekday(date: f64) -> Result<i64, InterpretError> {
+    // The Unix epoch was a Thursday.
+    let days = days_from_date(date)?;
+    Ok((days + 3).rem_euclid(7) + 1)
+}
+
+/// Formats a date as `YYYY-MM-DD`, followed by `HH:MM:SS` if the date is not
+/// at midnight.
+pub fn format_date(date: f64) -> String {
+    let Ok((year, month, day)) = date_parts(date) else {
+        return "invalid date".to_owned();
+    };
+
+    let formatted = format!("{year:04}-{month:02}-{day:02}");
+    let secs = date.rem_euclid(SECS_PER_DAY);
+
+    if secs == 0.0_f64 {
+        return formatted;
+    }
+
+    let hour = (secs / SECS_PER_HOUR).floor();
+    let minute = (secs % SECS_PER_HOUR / SECS_PER_MINUTE).floor();
+    let second = secs % SECS_PER_MINUTE;
+    format!("{formatted} {hour:02}:{minute:02}:{second:02}")
+}
+
+/// Formats a duration as days, hours, minutes, and seconds (e.g. `1d 2h 30m`).
+pub fn format_duration(duration: f64) -> String {
+    if !duration.is_finite() {
+        return format!("{duration}s");
+    }
+
+    let mut secs = duration.abs();
+    let mut parts = Vec::new();
+
+    for (unit_secs, suffix) in [
+        (SECS_PER_DAY, 'd'),
+        (SECS_PER_HOUR, 'h'),
+        (SECS_PER_MINUTE, 'm'),
+    ] {
+        let count = (secs / unit_secs).floor();
+        secs -= count * unit_secs;
+
+        if count != 0.0_f64 {
+            parts.push(format!("{count}{suffix}"));
+        }
+    }
+
+    if secs != 0.0_f64 || parts.is_empty() {
+        parts.push(format!("{secs}s"));
+    }
+
+    let sign = if duration < 0.0_f64 { "-" } else { "" };
+    format!("{sign}{}", parts.join(" "))
+}
+
+/// Returns the number of whole days between the Unix epoch and a date. This
+/// function returns an [`InterpretError`] if the date is too far from the Unix
+/// epoch.
+fn days_from_date(date: f64) -> Result<i64, InterpretError> {
+    const MAX_DAYS: f64 = 400_000_000.0;
+
+    let days = (date / SECS_PER_DAY).floor();
+
+    if !(-MAX_DAYS..=MAX_DAYS).contains(&days) {
+        return Err(ErrorKind::InvalidDate.into());
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "days have been checked to be in range"
+    )]
+    Ok(days as i64)
+}
@@ -4,9 +4,18 @@ use std::{
     rc::Rc,
 };
 
-use crate::{ast::Literal, cfg::Function};
+use crate::{ast::Literal, cfg::Function, format, integer::IntegerMode, settings::Settings};
 
-use super::native::Native;
+use super::{
+    InterpretError,
+    arity::Arity,
+    errors::ErrorKind,
+    native::Native,
+    object::{self, ArithOp, Object},
+    pretty,
+    thunk::Thunk,
+    time,
+};
 
 /// A runtime value.
 #[derive(Clone)]
@@ -14,9 +23,30 @@ pub enum Value {
     /// A number.
     Number(f64),
 
+    /// An integer number in an integer mode.
+    Integer(i128),
+
     /// A Boolean value.
     Bool(bool),
 
+    /// A string.
+    Str(Rc<str>),
+
+    /// A list.
+    List(Rc<[Self]>),
+
+    /// A tuple.
+    Tuple(Rc<[Self]>),
+
+    /// A date as the number of seconds since the Unix epoch in UTC.
+    Date(f64),
+
+    /// A duration as a number of seconds.
+    Duration(f64),
+
+    /// A host-registered [`Object`].
+    Object(Rc<dyn Object>),
+
     /// A [`Function`].
     Function(Rc<Function>),
 
@@ -25,30 +55,247 @@ pub enum Value {
 
     /// A [`Native`].
     Native(Native),
+
+    /// A lazy [`Thunk`].
+    Thunk(Rc<Thunk>),
 }
 
 impl Value {
-    /// Returns [`true`] if the `Value`'s [`ValueType`] matches another
-    /// `Value`'s [`ValueType`].
-    pub fn matches_value_type(&self, other: &Self) -> bool {
-        self.value_type() == other.value_type()
+    /// Returns [`true`] if the `Value` is structurally equal to another
+    /// `Value`. Values are compared by value, and composite values are
+    /// compared element-wise. Functions are compared by identity, and closures
+    /// are also compared by the values they capture. This function returns an
+    /// [`InterpretError`] if the `Value`s do not have matching types.
+    pub fn equals(&self, other: &Self) -> Result<bool, InterpretError> {
+        if self.value_type() == other.value_type() {
+            Ok(self == other)
+        } else {
+            Err(ErrorKind::InvalidType.into())
+        }
+    }
+
+    /// Returns the ordering between the `Value` and another `Value`, or
+    /// [`None`] if they are unordered (e.g. `NaN`). Lists and tuples are
+    /// ordered lexicographically. This function returns an [`InterpretError`]
+    /// if the `Value`s have different types or their type cannot be ordered.
+    pub fn compare(&self, other: &Self) -> Result<Option<Ordering>, InterpretError> {
+        match (self, other) {
+            (Self::Number(_) | Self::Integer(_), Self::Number(_) | Self::Integer(_))
+            | (Self::Date(_), Self::Date(_))
+            | (Self::Duration(_), Self::Duration(_))
+            | (Self::Str(_), Self::Str(_)) => Ok(self.partial_cmp(other)),
+            (Self::List(lhs), Self::List(rhs)) | (Self::Tuple(lhs), Self::Tuple(rhs)) => {
+                for (lhs_elem, rhs_elem) in lhs.iter().zip(rhs.iter()) {
+                    match lhs_elem.compare(rhs_elem)? {
+                        Some(Ordering::Equal) => {}
+                        ordering => return Ok(ordering),
+                    }
+                }
+
+                // A prefix is ordered before the longer sequence.
+                Ok(Some(lhs.len().cmp(&rhs.len())))
+            }
+            (Self::Object(lhs), Self::Object(rhs)) if lhs.type_name() == rhs.type_name() => {
+                lhs.compare(rhs.as_ref())
+            }
+            _ => {
+                let (lhs_type, rhs_type) = (self.value_type(), other.value_type());
+
+                if lhs_type == rhs_type {
+                    Err(ErrorKind::InvalidType.into())
+                } else {
+                    Err(ErrorKind::MixedComparison(lhs_type.name(), rhs_type.name()).into())
+                }
+            }
+        }
+    }
+
+    /// Returns [`true`] if the `Value` contains another `Value`. This function
+    /// returns an [`InterpretError`] if the `Value` is not a collection or the
+    /// other `Value` cannot be tested for membership.
+    pub fn contains(&self, element: &Self) -> Result<bool, InterpretError> {
+        match (self, element) {
+            (Self::Str(string), Self::Str(substring)) => Ok(string.contains(&**substring)),
+            (Self::List(elems), _) => Ok(elems.contains(element)),
+            (Self::Object(object), _) => object
+                .contains(element)
+                .unwrap_or_else(|| Err(ErrorKind::InvalidType.into())),
+            _ => Err(ErrorKind::InvalidType.into()),
+        }
+    }
+
+    /// Returns the result of an [`ArithOp`] between the `Value` and another
+    /// `Value` when they are not both numbers. This function returns an
+    /// [`InterpretError`] if the operation is not supported.
+    pub fn arith(&self, op: ArithOp, rhs: &Self) -> Result<Self, InterpretError> {
+        // Integers are operated on as floats outside of an integer mode.
+        if matches!(self, Self::Integer(_)) || matches!(rhs, Self::Integer(_)) {
+            return self.to_float().arith(op, &rhs.to_float());
+        }
+
+        if let Some(result) = object::arith(op, self, rhs) {
+            return result;
+        }
+
+        match op {
+            ArithOp::Add => match (self, rhs) {
+                (Self::Str(lhs), Self::Str(rhs)) => Ok(Self::Str(format!("{lhs}{rhs}").into())),
+                (Self::List(lhs), Self::List(rhs)) => {
+                    Ok(Self::List(lhs.iter().chain(rhs.iter()).cloned().collect()))
+                }
+                _ => time::add(self, rhs),
+            },
+            ArithOp::Subtract => time::subtract(self, rhs),
+            ArithOp::Multiply => time::multiply(self, rhs),
+            ArithOp::Divide => time::divide(self, rhs),
+        }
+    }
+
+    /// Returns the element of a list or tuple `Value` at an index `Value`.
+    /// This function returns an [`InterpretError`] if the `Value` is not a
+    /// list or tuple or the index is not an integer in bounds.
+    pub fn index(&self, index: &Self) -> Result<Self, InterpretError> {
+        let (Self::List(elems) | Self::Tuple(elems), Some(index)) = (self, index.as_float()) else {
+            return Err(ErrorKind::InvalidType.into());
+        };
+
+        if index.fract() != 0.0_f64 || index < 0.0_f64 {
+            return Err(ErrorKind::InvalidIndex.into());
+        }
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "index is a non-negative integer and out of range indices saturate"
+        )]
+        let offset = index as usize;
+
+        elems.get(offset).cloned().ok_or_else(|| {
+            let name = self.value_type().name();
+            ErrorKind::IndexOutOfBounds(index, name, elems.len()).into()
+        })
+    }
+
+    /// Checks that the `Value` is a tuple which can be unpacked into a number
+    /// of variables. This function returns an [`InterpretError`] if the
+    /// `Value` is not a tuple or has a different length.
+    pub fn check_unpack(&self, count: usize) -> Result<(), InterpretError> {
+        match self {
+            Self::Tuple(elems) if elems.len() == count => Ok(()),
+            Self::Tuple(elems) => Err(ErrorKind::UnpackLength(elems.len(), count).into()),
+            _ => Err(ErrorKind::UnpackedNonTuple(self.value_type().name()).into()),
+        }
+    }
+
+    /// Returns a reference to the `Value`'s [`Object`] if it is an [`Object`]
+    /// of a type. This function returns [`None`] if the `Value` is not an
+    /// [`Object`] of the type.
+    pub fn downcast_object<T: Object>(&self) -> Option<&T> {
+        match self {
+            Self::Object(object) => object::downcast_ref(object.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Value`'s [`Arity`]. This function returns [`None`] if the
+    /// `Value` is not a function.
+    pub fn arity(&self) -> Option<Arity> {
+        match self {
+            Self::Function(function) => Some(Arity::exact(function.arity)),
+            Self::Closure(closure) => Some(Arity::exact(closure.function.arity)),
+            Self::Native(native) => Some(native.arity()),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Value` as a float if it is a number. This function returns
+    /// [`None`] if the `Value` is not a number.
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "large integers are rounded like any other number"
+    )]
+    pub const fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            Self::Integer(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Value` as an integer in an [`IntegerMode`] if it is a
+    /// number. This function returns [`None`] if the `Value` is not a number.
+    pub fn as_integer(&self, mode: IntegerMode) -> Option<i128> {
+        match self {
+            Self::Number(value) => Some(mode.truncate(*value)),
+            Self::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Value` with integers converted to floats, including the
+    /// elements of lists and tuples.
+    pub fn to_float(&self) -> Self {
+        match self {
+            Self::Integer(_) => Self::Number(self.as_float().unwrap_or_default()),
+            Self::List(elems) if elems.iter().any(Self::contains_integer) => {
+                Self::List(elems.iter().map(Self::to_float).collect())
+            }
+            Self::Tuple(elems) if elems.iter().any(Self::contains_integer) => {
+                Self::Tuple(elems.iter().map(Self::to_float).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns [`true`] if the `Value` is an integer or a list or tuple which
+    /// contains an integer.
+    fn contains_integer(&self) -> bool {
+        match self {
+            Self::Integer(_) => true,
+            Self::List(elems) | Self::Tuple(elems) => elems.iter().any(Self::contains_integer),
+            _ => false,
+        }
+    }
+
+    /// Returns the `Value` with numbers converted to integers in an
+    /// [`IntegerMode`], including the elements of lists and tuples.
+    pub fn to_integer(&self, mode: IntegerMode) -> Self {
+        match self {
+            Self::Number(value) => Self::Integer(mode.truncate(*value)),
+            Self::List(elems) => Self::List(elems.iter().map(|e| e.to_integer(mode)).collect()),
+            Self::Tuple(elems) => Self::Tuple(elems.iter().map(|e| e.to_integer(mode)).collect()),
+            _ => self.clone(),
+        }
     }
 
     /// Returns the `Value`'s [`ValueType`].
-    const fn value_type(&self) -> ValueType {
+    fn value_type(&self) -> ValueType {
         match self {
-            Self::Number(_) => ValueType::Number,
+            Self::Number(_) | Self::Integer(_) => ValueType::Number,
             Self::Bool(_) => ValueType::Bool,
+            Self::Str(_) => ValueType::Str,
+            Self::List(_) => ValueType::List,
+            Self::Tuple(_) => ValueType::Tuple,
+            Self::Date(_) => ValueType::Date,
+            Self::Duration(_) => ValueType::Duration,
+            Self::Object(object) => ValueType::Object(object.type_name()),
             Self::Function(_) | Self::Closure(_) | Self::Native(_) => ValueType::Function,
+            Self::Thunk(_) => ValueType::Thunk,
         }
     }
 }
 
 impl From<Literal> for Value {
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "large integers are rounded like any other number"
+    )]
     fn from(value: Literal) -> Self {
         match value {
             Literal::Number(value) => Self::Number(value),
+            Literal::Integer(value) => Self::Number(value as f64),
             Literal::Bool(value) => Self::Bool(value),
+            Literal::Str(value) => Self::Str(value),
         }
     }
 }
@@ -56,8 +303,20 @@ impl From<Literal> for Value {
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Self::Number(lhs), Self::Number(rhs)) => lhs == rhs,
+            (Self::Number(lhs), Self::Number(rhs))
+            | (Self::Date(lhs), Self::Date(rhs))
+            | (Self::Duration(lhs), Self::Duration(rhs)) => lhs == rhs,
+            (Self::Integer(lhs), Self::Integer(rhs)) => lhs == rhs,
+            (Self::Integer(_), Self::Number(_)) | (Self::Number(_), Self::Integer(_)) => {
+                self.as_float() == other.as_float()
+            }
             (Self::Bool(lhs), Self::Bool(rhs)) => lhs == rhs,
+            (Self::Str(lhs), Self::Str(rhs)) => lhs == rhs,
+            (Self::List(lhs), Self::List(rhs)) | (Self::Tuple(lhs), Self::Tuple(rhs)) => lhs == rhs,
+            (Self::Object(lhs), Self::Object(rhs)) => {
+                Rc::ptr_eq(lhs, rhs)
+                    || lhs.type_name() == rhs.type_name() && lhs.equals(rhs.as_ref())
+            }
             (Self::Function(lhs), Self::Function(rhs)) => Rc::ptr_eq(lhs, rhs),
             (Self::Closure(lhs), Self::Closure(rhs)) => {
                 if Rc::ptr_eq(lhs, rhs) {
@@ -83,12 +342,21 @@ impl PartialEq for Value {
                 true
             }
             (Self::Native(lhs), Self::Native(rhs)) => lhs == rhs,
+            (Self::Thunk(lhs), Self::Thunk(rhs)) => Rc::ptr_eq(lhs, rhs),
             (
                 Self::Number(_)
+                | Self::Integer(_)
                 | Self::Bool(_)
+                | Self::Str(_)
+                | Self::List(_)
+                | Self::Tuple(_)
+                | Self::Date(_)
+                | Self::Duration(_)
+                | Self::Object(_)
                 | Self::Function(_)
                 | Self::Closure(_)
-                | Self::Native(_),
+                | Self::Native(_)
+                | Self::Thunk(_),
                 _,
             ) => false,
         }
@@ -98,22 +366,128 @@ impl PartialEq for Value {
 impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
-            (Self::Number(lhs), Self::Number(rhs)) => lhs.partial_cmp(rhs),
+            (Self::Number(lhs), Self::Number(rhs))
+            | (Self::Date(lhs), Self::Date(rhs))
+            | (Self::Duration(lhs), Self::Duration(rhs)) => lhs.partial_cmp(rhs),
+            (Self::Integer(lhs), Self::Integer(rhs)) => lhs.partial_cmp(rhs),
+            (Self::Integer(_), Self::Number(_)) | (Self::Number(_), Self::Integer(_)) => {
+                self.as_float().partial_cmp(&other.as_float())
+            }
+            (Self::Str(lhs), Self::Str(rhs)) => lhs.partial_cmp(rhs),
+            (Self::List(lhs), Self::List(rhs)) | (Self::Tuple(lhs), Self::Tuple(rhs)) => {
+                lhs.partial_cmp(rhs)
+            }
+            (Self::Object(lhs), Self::Object(rhs)) if lhs.type_name() == rhs.type_name() => {
+                lhs.compare(rhs.as_ref()).ok().flatten()
+            }
             (lhs, rhs) => (lhs == rhs).then_some(Ordering::Equal),
         }
     }
 }
 
-impl Display for Value {
+impl Value {
+    /// Returns a displayable representation of the `Value` which formats
+    /// numbers with a session's [`Settings`].
+    pub const fn display<'value>(&'value self, settings: &'value Settings) -> Displayed<'value> {
+        Displayed {
+            value: self,
+            settings,
+        }
+    }
+}
+
+/// A [`Value`] which is displayed with a session's [`Settings`].
+pub struct Displayed<'value> {
+    /// The [`Value`].
+    value: &'value Value,
+
+    /// The [`Settings`].
+    settings: &'value Settings,
+}
+
+impl Display for Displayed<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Number(value) => Display::fmt(value, f),
-            Self::Bool(value) => Display::fmt(value, f),
-            Self::Function(_) | Self::Closure(_) | Self::Native(_) => f.write_str("function"),
+        let settings = self.settings;
+
+        match self.value {
+            Value::Number(value) => f.write_str(&format::format_number(*value, settings)),
+            Value::Integer(value) => f.write_str(&format::format_integer(*value, settings)),
+            Value::Bool(value) => Display::fmt(value, f),
+            Value::Str(value) => f.write_str(value),
+            Value::List(elems) => fmt_elems(f, "[", elems, "]", settings),
+            Value::Tuple(elems) => {
+                let close = pretty::tuple_close(elems, settings);
+                fmt_elems(f, "(", elems, &close, settings)
+            }
+            Value::Date(date) => f.write_str(&time::format_date(*date)),
+            Value::Duration(duration) => f.write_str(&time::format_duration(*duration)),
+            Value::Object(object) => object.fmt_with(f, settings),
+            Value::Function(function) => fmt_function(f, function),
+            Value::Closure(closure) => {
+                let function = &closure.function;
+                f.write_str("<closure ")?;
+
+                match function.name {
+                    None => fmt_function(f, function)?,
+                    Some(name) => write!(f, "{name}/{}", function.arity)?,
+                }
+
+                if !function.captures.is_empty() {
+                    let captures = function.captures.iter().map(ToString::to_string);
+                    write!(f, " capturing {}", captures.collect::<Vec<_>>().join(", "))?;
+                }
+
+                f.write_str(">")
+            }
+            Value::Native(native) => write!(f, "<native {}>", native.name()),
+            Value::Thunk(thunk) => thunk.fmt_with(f, settings),
         }
     }
 }
 
+/// Formats a list's or tuple's elements between opening and closing brackets
+/// with a [`Formatter`] and a session's [`Settings`] (e.g. `[1, 2, 3]` or
+/// `(1, 2)`). String elements are quoted so that they can be told apart from
+/// other values, and elements are separated by semicolons if numbers have a
+/// decimal comma. This function returns a [`fmt::Error`] if an error occurred.
+fn fmt_elems(
+    f: &mut Formatter<'_>,
+    open: &str,
+    elems: &[Value],
+    close: &str,
+    settings: &Settings,
+) -> fmt::Result {
+    let separator = settings.number_format.element_separator();
+    f.write_str(open)?;
+
+    for (index, elem) in elems.iter().enumerate() {
+        if index > 0 {
+            write!(f, "{separator} ")?;
+        }
+
+        match elem {
+            Value::Str(value) => Display::fmt(&Literal::Str(Rc::clone(value)), f)?,
+            _ => Display::fmt(&elem.display(settings), f)?,
+        }
+    }
+
+    f.write_str(close)
+}
+
+/// Formats a [`Function`]'s signature with a [`Formatter`] (e.g. `f(x) = …` or
+/// `(x, y) -> …`). This function returns a [`fmt::Error`] if an error
+/// occurred.
+fn fmt_function(f: &mut Formatter<'_>, function: &Function) -> fmt::Result {
+    let params = function.params.iter().map(ToString::to_string);
+    let params = params.collect::<Vec<_>>().join(", ");
+
+    match (function.name, function.params.as_ref()) {
+        (Some(name), _) => write!(f, "{name}({params}) = …"),
+        (None, [_]) => write!(f, "{params} -> …"),
+        (None, _) => write!(f, "({params}) -> …"),
+    }
+}
+
 /// A [`Function`] with captured upvars.
 pub struct Closure {
     /// The [`Function`].
@@ -132,6 +506,45 @@ enum ValueType {
     /// A Boolean value.
     Bool,
 
+    /// A string.
+    Str,
+
+    /// A list.
+    List,
+
+    /// A tuple.
+    Tuple,
+
+    /// A date.
+    Date,
+
+    /// A duration.
+    Duration,
+
+    /// An [`Object`] with a type name.
+    Object(&'static str),
+
     /// A [`Function`], [`Closure`], or [`Native`].
     Function,
+
+    /// A [`Thunk`].
+    Thunk,
+}
+
+impl ValueType {
+    /// Returns the `ValueType`'s name.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Number => "number",
+            Self::Bool => "bool",
+            Self::Str => "string",
+            Self::List => "list",
+            Self::Tuple => "tuple",
+            Self::Date => "date",
+            Self::Duration => "duration",
+            Self::Object(name) => name,
+            Self::Function => "function",
+            Self::Thunk => "lazy value",
+        }
+    }
 }
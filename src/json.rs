@@ -0,0 +1,334 @@
+use std::{
+    fmt::{self, Display, Formatter, Write as _},
+    iter::Peekable,
+};
+
+/// A JSON value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    /// A null value.
+    Null,
+
+    /// A Boolean value.
+    Bool(bool),
+
+    /// A number.
+    Number(f64),
+
+    /// A string.
+    Str(String),
+
+    /// An array of `Json` values.
+    Array(Vec<Self>),
+
+    /// An object of `Json` values in insertion order.
+    Object(Vec<(String, Self)>),
+}
+
+impl Json {
+    /// Parses a `Json` value from text. This function returns [`None`] if the
+    /// text is not a single valid JSON value.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parser = Parser {
+            chars: text.chars().peekable(),
+        };
+
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        parser.chars.peek().is_none().then_some(value)
+    }
+
+    /// Creates a new object `Json` value from key-value pairs.
+    pub fn object<const N: usize>(pairs: [(&str, Self); N]) -> Self {
+        Self::Object(pairs.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+    }
+
+    /// Returns a reference to the value of a key in an object `Json` value.
+    /// This function returns [`None`] if the `Json` value is not an object or
+    /// it does not contain the key.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(pairs) => pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Json` value as a string slice. This function returns
+    /// [`None`] if the `Json` value is not a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Json` value as a Boolean value. This function returns
+    /// [`None`] if the `Json` value is not a Boolean value.
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Json` value as a number. This function returns [`None`] if
+    /// the `Json` value is not a number.
+    pub const fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(value) => Display::fmt(value, f),
+            // JSON has no representation of infinities or NaN.
+            Self::Number(value) if !value.is_finite() => f.write_str("null"),
+            Self::Number(value) => Display::fmt(value, f),
+            Self::Str(value) => fmt_string(f, value),
+            Self::Array(values) => {
+                f.write_char('[')?;
+
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    Display::fmt(value, f)?;
+                }
+
+                f.write_char(']')
+            }
+            Self::Object(pairs) => {
+                f.write_char('{')?;
+
+                for (index, (key, value)) in pairs.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    fmt_string(f, key)?;
+                    f.write_char(':')?;
+                    Display::fmt(value, f)?;
+                }
+
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// Formats a string as a quoted JSON string with a [`Formatter`]. This
+/// function returns a [`fmt::Error`] if an error occurred.
+fn fmt_string(f: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+
+    for char in value.chars() {
+        match char {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\0'..='\x1f' => write!(f, "\\u{:04x}", u32::from(char))?,
+            _ => f.write_char(char)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+/// A structure which parses [`Json`] values from text.
+struct Parser<I: Iterator<Item = char>> {
+    /// The [`Iterator`] over the text's [`char`]s.
+    chars: Peekable<I>,
+}
+
+impl<I: Iterator<Item = char>> Parser<I> {
+    /// Parses a [`Json`] value. This function returns [`None`] if the value
+    /// is invalid.
+    fn parse_value(&mut self) -> Option<Json> {
+        self.skip_whitespace();
+
+        match self.chars.next()? {
+            'n' => self.expect_word("ull").then_some(Json::Null),
+            't' => self.expect_word("rue").then_some(Json::Bool(true)),
+            'f' => self.expect_word("alse").then_some(Json::Bool(false)),
+            '"' => self.parse_string().map(Json::Str),
+            '[' => self.parse_array(),
+            '{' => self.parse_object(),
+            char @ ('-' | '0'..='9') => self.parse_number(char),
+            _ => None,
+        }
+    }
+
+    /// Parses the rest of an array [`Json`] value after its opening bracket.
+    /// This function returns [`None`] if the array is invalid.
+    fn parse_array(&mut self) -> Option<Json> {
+        let mut values = Vec::new();
+        self.skip_whitespace();
+
+        if self.chars.next_if_eq(&']').is_some() {
+            return Some(Json::Array(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.chars.next()? {
+                ',' => {}
+                ']' => return Some(Json::Array(values)),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Parses the rest of an object [`Json`] value after its opening brace.
+    /// This function returns [`None`] if the object is invalid.
+    fn parse_object(&mut self) -> Option<Json> {
+        let mut pairs = Vec::new();
+        self.skip_whitespace();
+
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Some(Json::Object(pairs));
+        }
+
+        loop {
+            self.skip_whitespace();
+            self.chars.next_if_eq(&'"')?;
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.chars.next_if_eq(&':')?;
+            pairs.push((key, self.parse_value()?));
+            self.skip_whitespace();
+
+            match self.chars.next()? {
+                ',' => {}
+                '}' => return Some(Json::Object(pairs)),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Parses the rest of a string after its opening quote. This function
+    /// returns [`None`] if the string is invalid.
+    fn parse_string(&mut self) -> Option<String> {
+        let mut value = String::new();
+
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(value),
+                '\\' => {
+                    let char = match self.chars.next()? {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\x08',
+                        'f' => '\x0c',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.parse_unicode_escape()?,
+                        _ => return None,
+                    };
+
+                    value.push(char);
+                }
+                char => value.push(char),
+            }
+        }
+    }
+
+    /// Parses the rest of a `\u` escape sequence, including a second escape
+    /// sequence for a surrogate pair. This function returns [`None`] if the
+    /// escape sequence is invalid.
+    fn parse_unicode_escape(&mut self) -> Option<char> {
+        let high = self.parse_hex_code()?;
+
+        if !(0xd800..0xdc00).contains(&high) {
+            return char::from_u32(high);
+        }
+
+        if self.chars.next()? != '\\' || self.chars.next()? != 'u' {
+            return None;
+        }
+
+        let low = self.parse_hex_code()?;
+
+        if !(0xdc00..0xe000).contains(&low) {
+            return None;
+        }
+
+        char::from_u32(0x1_0000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+    }
+
+    /// Parses four hexadecimal digits. This function returns [`None`] if the
+    /// digits are invalid.
+    fn parse_hex_code(&mut self) -> Option<u32> {
+        let mut code = 0;
+
+        for _ in 0..4_u8 {
+            code = code * 16 + self.chars.next()?.to_digit(16)?;
+        }
+
+        Some(code)
+    }
+
+    /// Parses the rest of a number [`Json`] value after its first [`char`].
+    /// This function returns [`None`] if the number is invalid.
+    fn parse_number(&mut self, first: char) -> Option<Json> {
+        let mut text = String::from(first);
+
+        while let Some(char) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+        {
+            text.push(char);
+        }
+
+        text.parse().ok().map(Json::Number)
+    }
+
+    /// Consumes the [`char`]s of a word, returning [`true`] if they matched.
+    fn expect_word(&mut self, word: &str) -> bool {
+        word.chars().all(|c| self.chars.next_if_eq(&c).is_some())
+    }
+
+    /// Skips whitespace [`char`]s.
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .next_if(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+            .is_some()
+        {}
+    }
+}
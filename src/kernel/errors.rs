@@ -0,0 +1,27 @@
+use std::io;
+
+use thiserror::Error;
+
+/// An error caught while starting a Jupyter kernel.
+#[derive(Debug, Error)]
+pub enum KernelError {
+    /// No connection file path was given.
+    #[error("usage: clac-kernel <connection file>")]
+    NoConnectionFile,
+
+    /// The connection file could not be read or a port could not be bound.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// The connection file is not valid JSON or is missing a field.
+    #[error("connection file is invalid or missing '{0}'")]
+    InvalidConnectionFile(&'static str),
+
+    /// The connection file uses a transport other than TCP.
+    #[error("unsupported transport '{0}'")]
+    UnsupportedTransport(String),
+
+    /// The connection file uses a signature scheme other than HMAC-SHA256.
+    #[error("unsupported signature scheme '{0}'")]
+    UnsupportedSignatureScheme(String),
+}
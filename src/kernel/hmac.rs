@@ -0,0 +1,209 @@
+use std::fmt::{self, Write as _};
+
+/// The size of a SHA-256 block in bytes.
+const BLOCK_SIZE: usize = 64;
+
+/// The initial SHA-256 hash state.
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// The SHA-256 round constants.
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+
+/// Returns the HMAC-SHA256 of the concatenation of message parts with a key as
+/// lowercase hexadecimal.
+pub fn hmac_sha256_hex(key: &[u8], parts: &[&[u8]]) -> String {
+    let mut key_block = [0_u8; BLOCK_SIZE];
+
+    if key.len() > BLOCK_SIZE {
+        key_block[..32].copy_from_slice(&sha256(&[key]));
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let inner_key = key_block.map(|b| b ^ 0x36);
+    let outer_key = key_block.map(|b| b ^ 0x5c);
+
+    let mut inner_parts = vec![inner_key.as_slice()];
+    inner_parts.extend_from_slice(parts);
+    let inner_hash = sha256(&inner_parts);
+    let hash = sha256(&[&outer_key, &inner_hash]);
+
+    hash.iter().fold(String::with_capacity(64), |mut hex, b| {
+        let _: fmt::Result = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+/// Returns [`true`] if two byte slices are equal. The comparison takes the
+/// same time wherever the slices differ, so it does not reveal how much of a
+/// signature was guessed correctly.
+pub fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    lhs.len() == rhs.len() && lhs.iter().zip(rhs).fold(0, |d, (l, r)| d | (l ^ r)) == 0
+}
+
+/// Returns the SHA-256 hash of the concatenation of message parts.
+fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut state = INITIAL_STATE;
+    let mut block = Vec::with_capacity(BLOCK_SIZE);
+    let mut length: u64 = 0;
+
+    for byte in parts.iter().flat_map(|p| p.iter()) {
+        block.push(*byte);
+        length += 8;
+
+        if block.len() == BLOCK_SIZE {
+            compress(&mut state, &block);
+            block.clear();
+        }
+    }
+
+    // The message is padded with a single set bit, zeros, and its length in
+    // bits so that it fills a whole number of blocks.
+    block.push(0x80);
+
+    if block.len() > BLOCK_SIZE - 8 {
+        block.resize(BLOCK_SIZE, 0);
+        compress(&mut state, &block);
+        block.clear();
+    }
+
+    block.resize(BLOCK_SIZE - 8, 0);
+    block.extend_from_slice(&length.to_be_bytes());
+    compress(&mut state, &block);
+
+    let mut hash = [0; 32];
+
+    for (chunk, word) in hash.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+
+    hash
+}
+
+/// Updates a SHA-256 hash state with a block.
+#[expect(
+    clippy::many_single_char_names,
+    reason = "working variables are named as in the SHA-256 specification"
+)]
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut schedule = [0; 64];
+
+    for (word, chunk) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    for index in 16..64 {
+        let prev_15 = schedule[index - 15];
+        let prev_2 = schedule[index - 2];
+        let sigma_0 = prev_15.rotate_right(7) ^ prev_15.rotate_right(18) ^ (prev_15 >> 3_u32);
+        let sigma_1 = prev_2.rotate_right(17) ^ prev_2.rotate_right(19) ^ (prev_2 >> 10_u32);
+
+        schedule[index] = schedule[index - 16]
+            .wrapping_add(sigma_0)
+            .wrapping_add(schedule[index - 7])
+            .wrapping_add(sigma_1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+
+    for (constant, word) in ROUND_CONSTANTS.iter().zip(schedule) {
+        let sum_1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let choice = (e & f) ^ (!e & g);
+        let temp_1 = h
+            .wrapping_add(sum_1)
+            .wrapping_add(choice)
+            .wrapping_add(*constant)
+            .wrapping_add(word);
+
+        let sum_0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let majority = (a & b) ^ (a & c) ^ (b & c);
+        let temp_2 = sum_0.wrapping_add(majority);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp_1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp_1.wrapping_add(temp_2);
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
@@ -0,0 +1,432 @@
+#[cfg(test)]
+mod tests;
+
+mod errors;
+mod hmac;
+mod queues;
+mod session;
+mod zmtp;
+
+use std::{
+    cell::RefCell,
+    env, fs,
+    net::{TcpListener, TcpStream},
+    process::ExitCode,
+    rc::Rc,
+    thread,
+};
+
+use crate::{
+    embed::{Engine, EvalError, Observer},
+    json::Json,
+    lex::{self, BracketError},
+};
+
+use self::{
+    errors::KernelError,
+    queues::{QueueSender, Queues},
+    session::{PROTOCOL_VERSION, Session},
+    zmtp::{Connection, Message, Publisher, SocketType},
+};
+
+/// Runs a Jupyter kernel with the connection file path that the process was
+/// started with, and returns an [`ExitCode`] for whether it started and shut
+/// down successfully.
+#[must_use]
+pub fn run_kernel() -> ExitCode {
+    match start_kernel() {
+        Ok(mut kernel) => {
+            kernel.run();
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Starts a [`Kernel`] by reading its connection file and binding its
+/// sockets. This function returns a [`KernelError`] if the [`Kernel`] could
+/// not be started.
+fn start_kernel() -> Result<Kernel, KernelError> {
+    let path = env::args_os().nth(1).ok_or(KernelError::NoConnectionFile)?;
+
+    let text = fs::read_to_string(path)?;
+    let config = Json::parse(&text).ok_or(KernelError::InvalidConnectionFile("JSON"))?;
+
+    let field = |name: &'static str| {
+        config
+            .get(name)
+            .ok_or(KernelError::InvalidConnectionFile(name))
+    };
+
+    let transport = field("transport")?.as_str().unwrap_or_default();
+
+    if transport != "tcp" {
+        return Err(KernelError::UnsupportedTransport(transport.to_owned()));
+    }
+
+    let scheme = field("signature_scheme")?.as_str().unwrap_or_default();
+
+    if scheme != "hmac-sha256" {
+        return Err(KernelError::UnsupportedSignatureScheme(scheme.to_owned()));
+    }
+
+    let ip = field("ip")?
+        .as_str()
+        .ok_or(KernelError::InvalidConnectionFile("ip"))?;
+
+    let key = field("key")?
+        .as_str()
+        .ok_or(KernelError::InvalidConnectionFile("key"))?;
+
+    let bind = |name: &'static str| {
+        let port = field(name)?
+            .as_number()
+            .ok_or(KernelError::InvalidConnectionFile(name))?;
+
+        Ok::<_, KernelError>(TcpListener::bind(format!("{ip}:{port}"))?)
+    };
+
+    let (queues, shell_sender, control_sender) = Queues::new();
+    let publisher = Publisher::default();
+
+    for (name, sender) in [
+        ("shell_port", shell_sender),
+        ("control_port", control_sender),
+    ] {
+        listen(bind(name)?, SocketType::Router, move |c| {
+            forward_requests(c, &sender);
+        });
+    }
+
+    listen(bind("stdin_port")?, SocketType::Router, discard_messages);
+
+    let iopub_publisher = publisher.clone();
+    listen(bind("iopub_port")?, SocketType::Pub, move |c| {
+        iopub_publisher.subscribe(c);
+    });
+
+    listen(bind("hb_port")?, SocketType::Rep, echo_messages);
+
+    Ok(Kernel::new(
+        Session::new(key.as_bytes().to_vec()),
+        publisher,
+        queues,
+    ))
+}
+
+/// Accepts [`Connection`]s as a [`SocketType`] on a [`TcpListener`] in a new
+/// thread, and handles each [`Connection`] with a function in its own thread.
+fn listen<F: Fn(Connection) + Clone + Send + 'static>(
+    listener: TcpListener,
+    socket_type: SocketType,
+    handler: F,
+) {
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let handler = handler.clone();
+            thread::spawn(move || accept(stream, socket_type, handler));
+        }
+    });
+}
+
+/// Performs a handshake over a [`TcpStream`] as a [`SocketType`], and handles
+/// the [`Connection`] with a function if it succeeded.
+fn accept<F: Fn(Connection)>(stream: TcpStream, socket_type: SocketType, handler: F) {
+    if let Ok(connection) = Connection::accept(stream, socket_type) {
+        handler(connection);
+    }
+}
+
+/// Sends each [`Message`] read from a [`Connection`] to the [`Kernel`]'s
+/// queue with a handle to reply on, until the [`Connection`] is closed.
+fn forward_requests(mut connection: Connection, sender: &QueueSender<(Message, Connection)>) {
+    while let Ok(message) = connection.read_message() {
+        let Ok(reply) = connection.try_clone() else {
+            return;
+        };
+
+        if !sender.send((message, reply)) {
+            return;
+        }
+    }
+}
+
+/// Reads and discards each [`Message`] from a [`Connection`] until it is
+/// closed.
+fn discard_messages(mut connection: Connection) {
+    while connection.read_message().is_ok() {}
+}
+
+/// Replies to each [`Message`] from a [`Connection`] with the same
+/// [`Message`] until it is closed.
+fn echo_messages(mut connection: Connection) {
+    while let Ok(message) = connection.read_message() {
+        if connection.write_message(&message).is_err() {
+            return;
+        }
+    }
+}
+
+/// A Jupyter kernel with a persistent [`Engine`].
+struct Kernel {
+    /// The [`Session`] which signs and verifies messages.
+    session: Rc<Session>,
+
+    /// The [`Publisher`] for messages on the `iopub` channel.
+    publisher: Publisher,
+
+    /// The [`Queues`] of [`Message`]s from the shell and control channels.
+    queues: Queues<(Message, Connection)>,
+
+    /// The [`Engine`] which evaluates source code.
+    engine: Engine,
+
+    /// The header of the request which is being handled.
+    parent: Rc<RefCell<Json>>,
+
+    /// The number of `execute_request`s which have been handled.
+    execution_count: u32,
+}
+
+impl Kernel {
+    /// Creates a new `Kernel` from a [`Session`], a [`Publisher`], and
+    /// [`Queues`] of [`Message`]s.
+    fn new(session: Session, publisher: Publisher, queues: Queues<(Message, Connection)>) -> Self {
+        let session = Rc::new(session);
+        let parent = Rc::new(RefCell::new(Json::object([])));
+        let mut engine = Engine::new();
+
+        engine.set_observer(StreamObserver {
+            session: Rc::clone(&session),
+            publisher: publisher.clone(),
+            parent: Rc::clone(&parent),
+        });
+
+        Self {
+            session,
+            publisher,
+            queues,
+            engine,
+            parent,
+            execution_count: 0,
+        }
+    }
+
+    /// Handles requests until a `shutdown_request` is handled or every
+    /// channel is closed.
+    fn run(&mut self) {
+        while let Some((message, mut connection)) = self.queues.recv() {
+            let Some(request) = self.session.decode(&message) else {
+                continue;
+            };
+
+            self.parent.replace(request.header.clone());
+            self.publish(
+                "status",
+                &Json::object([("execution_state", "busy".into())]),
+            );
+
+            let content = match request.msg_type() {
+                "kernel_info_request" => Some(kernel_info()),
+                "execute_request" => Some(self.execute(&request.content)),
+                "is_complete_request" => Some(is_complete(&request.content)),
+                "comm_info_request" => Some(Json::object([
+                    ("status", "ok".into()),
+                    ("comms", Json::object([])),
+                ])),
+                "history_request" => Some(Json::object([
+                    ("status", "ok".into()),
+                    ("history", Json::Array(Vec::new())),
+                ])),
+                "shutdown_request" => Some(Json::object([
+                    ("status", "ok".into()),
+                    (
+                        "restart",
+                        request
+                            .content
+                            .get("restart")
+                            .cloned()
+                            .unwrap_or(Json::Bool(false)),
+                    ),
+                ])),
+                _ => None,
+            };
+
+            if let Some(content) = content {
+                let msg_type = request.msg_type().replace("_request", "_reply");
+                let reply = self
+                    .session
+                    .encode(&request.ids, &msg_type, &request.header, &content);
+
+                // A reply which could not be sent is dropped because the
+                // client has disconnected.
+                let _: Result<_, _> = connection.write_message(&reply);
+            }
+
+            self.publish(
+                "status",
+                &Json::object([("execution_state", "idle".into())]),
+            );
+
+            if request.msg_type() == "shutdown_request" {
+                return;
+            }
+        }
+    }
+
+    /// Executes the code of an `execute_request` and returns the content of
+    /// its reply.
+    fn execute(&mut self, content: &Json) -> Json {
+        let code = content
+            .get("code")
+            .and_then(Json::as_str)
+            .unwrap_or_default();
+        let silent = content.get("silent").and_then(Json::as_bool) == Some(true);
+
+        if !silent {
+            self.execution_count += 1;
+        }
+
+        let count = Json::from(self.execution_count);
+
+        self.publish(
+            "execute_input",
+            &Json::object([("code", code.into()), ("execution_count", count.clone())]),
+        );
+
+        let evaluation = self.engine.eval(code);
+
+        for warning in &evaluation.warnings {
+            let text = format!("Warning: {warning}\n");
+            let stream = Json::object([("name", "stderr".into()), ("text", text.into())]);
+            self.publish("stream", &stream);
+        }
+
+        match evaluation.into_result() {
+            Ok(results) => {
+                if !silent && !results.is_empty() {
+                    let text: Vec<_> = results.iter().map(|r| self.engine.format(r)).collect();
+
+                    self.publish(
+                        "execute_result",
+                        &Json::object([
+                            ("execution_count", count.clone()),
+                            (
+                                "data",
+                                Json::object([("text/plain", text.join("\n").into())]),
+                            ),
+                            ("metadata", Json::object([])),
+                        ]),
+                    );
+                }
+
+                Json::object([
+                    ("status", "ok".into()),
+                    ("execution_count", count),
+                    ("user_expressions", Json::object([])),
+                ])
+            }
+            Err(errors) => {
+                let [ename, evalue, traceback] = error_fields(&errors);
+                let error = [ename.clone(), evalue.clone(), traceback.clone()];
+                self.publish("error", &Json::object(error));
+
+                Json::object([
+                    ("status", "error".into()),
+                    ename,
+                    evalue,
+                    traceback,
+                    ("execution_count", count),
+                ])
+            }
+        }
+    }
+
+    /// Publishes a message with a message type and content on the `iopub`
+    /// channel.
+    fn publish(&self, msg_type: &str, content: &Json) {
+        let parent = self.parent.borrow();
+        let message = self.session.encode(&[], msg_type, &parent, content);
+        self.publisher.publish(&message);
+    }
+}
+
+/// An [`Observer`] which publishes printed text as stream messages.
+struct StreamObserver {
+    /// The [`Session`] which signs messages.
+    session: Rc<Session>,
+
+    /// The [`Publisher`] for messages on the `iopub` channel.
+    publisher: Publisher,
+
+    /// The header of the request which is being handled.
+    parent: Rc<RefCell<Json>>,
+}
+
+impl Observer for StreamObserver {
+    fn printed(&mut self, text: &str) {
+        let content = Json::object([("name", "stdout".into()), ("text", text.into())]);
+        let parent = self.parent.borrow();
+        let message = self.session.encode(&[], "stream", &parent, &content);
+        self.publisher.publish(&message);
+    }
+}
+
+/// Returns the content of a `kernel_info_reply`.
+fn kernel_info() -> Json {
+    let version = env!("CARGO_PKG_VERSION");
+
+    Json::object([
+        ("status", "ok".into()),
+        ("protocol_version", PROTOCOL_VERSION.into()),
+        ("implementation", "clac".into()),
+        ("implementation_version", version.into()),
+        (
+            "language_info",
+            Json::object([
+                ("name", "clac".into()),
+                ("version", version.into()),
+                ("mimetype", "text/x-clac".into()),
+                ("file_extension", ".clac".into()),
+            ]),
+        ),
+        ("banner", format!("Clac {version}").into()),
+        ("help_links", Json::Array(Vec::new())),
+    ])
+}
+
+/// Returns the content of an `is_complete_reply` for the code of an
+/// `is_complete_request`.
+fn is_complete(content: &Json) -> Json {
+    let code = content
+        .get("code")
+        .and_then(Json::as_str)
+        .unwrap_or_default();
+
+    match lex::check_brackets(code) {
+        Ok(()) => Json::object([("status", "complete".into())]),
+        Err(BracketError::Unclosed(..)) => {
+            Json::object([("status", "incomplete".into()), ("indent", "".into())])
+        }
+        Err(_) => Json::object([("status", "invalid".into())]),
+    }
+}
+
+/// Returns the fields of an error message for [`EvalError`]s.
+fn error_fields(errors: &[EvalError]) -> [(&'static str, Json); 3] {
+    let ename = errors.first().map_or("error", EvalError::stage);
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    let traceback = messages
+        .iter()
+        .map(|m| format!("Error: {m}").into())
+        .collect();
+
+    [
+        ("ename", ename.into()),
+        ("evalue", messages.join("\n").into()),
+        ("traceback", Json::Array(traceback)),
+    ]
+}
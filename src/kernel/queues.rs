@@ -0,0 +1,82 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// A handle for sending requests to one of the [`Queues`].
+pub struct QueueSender<T> {
+    /// The sender for the queue.
+    queue: Sender<T>,
+
+    /// The sender for a notification of each queued request.
+    ready: Sender<()>,
+}
+
+impl<T> QueueSender<T> {
+    /// Sends a request to the queue. This function returns [`false`] if the
+    /// [`Queues`] have been dropped.
+    pub fn send(&self, request: T) -> bool {
+        // The request is queued before the notification so that it can always
+        // be received after the notification.
+        self.queue.send(request).is_ok() && self.ready.send(()).is_ok()
+    }
+}
+
+// `Clone` is not derived so that requests do not need to implement `Clone`.
+impl<T> Clone for QueueSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            ready: self.ready.clone(),
+        }
+    }
+}
+
+/// Separate queues of requests for the shell and control channels. Control
+/// requests are received before any shell requests which are waiting to be
+/// handled, so they are not delayed by a backlog of executions.
+pub struct Queues<T> {
+    /// The receiver for shell requests.
+    shell: Receiver<T>,
+
+    /// The receiver for control requests.
+    control: Receiver<T>,
+
+    /// The receiver for a notification of each queued request.
+    ready: Receiver<()>,
+}
+
+impl<T> Queues<T> {
+    /// Creates new `Queues` and [`QueueSender`]s for the shell and control
+    /// channels.
+    pub fn new() -> (Self, QueueSender<T>, QueueSender<T>) {
+        let (shell_sender, shell) = mpsc::channel();
+        let (control_sender, control) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+
+        let shell_sender = QueueSender {
+            queue: shell_sender,
+            ready: ready_sender.clone(),
+        };
+
+        let control_sender = QueueSender {
+            queue: control_sender,
+            ready: ready_sender,
+        };
+
+        let queues = Self {
+            shell,
+            control,
+            ready,
+        };
+
+        (queues, shell_sender, control_sender)
+    }
+
+    /// Waits for the next request, preferring control requests. This function
+    /// returns [`None`] if every [`QueueSender`] has been dropped.
+    pub fn recv(&self) -> Option<T> {
+        self.ready.recv().ok()?;
+        self.control
+            .try_recv()
+            .or_else(|_| self.shell.try_recv())
+            .ok()
+    }
+}
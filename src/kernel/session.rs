@@ -0,0 +1,158 @@
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    hash::BuildHasher as _,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{calendar, json::Json};
+
+use super::{hmac, zmtp::Message};
+
+/// The delimiter between a message's routing identities and its contents.
+const DELIMITER: &[u8] = b"<IDS|MSG>";
+
+/// The version of the Jupyter messaging protocol which is implemented.
+pub const PROTOCOL_VERSION: &str = "5.3";
+
+/// A message received from a Jupyter client.
+pub struct Request {
+    /// The routing identities to reply to.
+    pub ids: Vec<Vec<u8>>,
+
+    /// The header.
+    pub header: Json,
+
+    /// The content.
+    pub content: Json,
+}
+
+impl Request {
+    /// Returns the `Request`'s message type, or an empty string if it has no
+    /// message type.
+    pub fn msg_type(&self) -> &str {
+        self.header
+            .get("msg_type")
+            .and_then(Json::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// A session which signs and verifies messages with a key.
+pub struct Session {
+    /// The session's identifier.
+    id: String,
+
+    /// The key used to sign messages.
+    key: Vec<u8>,
+
+    /// The number of messages which have been created.
+    message_count: Cell<u64>,
+}
+
+impl Session {
+    /// Creates a new `Session` with a key and a random identifier.
+    pub fn new(key: Vec<u8>) -> Self {
+        let id = format!("{:016x}", RandomState::new().hash_one(SystemTime::now()));
+
+        Self {
+            id,
+            key,
+            message_count: Cell::new(0),
+        }
+    }
+
+    /// Decodes a [`Request`] from a [`Message`]. This function returns
+    /// [`None`] if the [`Message`] is malformed or its signature is invalid.
+    pub fn decode(&self, message: &[Vec<u8>]) -> Option<Request> {
+        let delimiter = message.iter().position(|f| f == DELIMITER)?;
+        let [signature, header, parent, metadata, content, ..] = &message[delimiter + 1..] else {
+            return None;
+        };
+
+        if !self.key.is_empty() {
+            let expected = hmac::hmac_sha256_hex(&self.key, &[header, parent, metadata, content]);
+
+            if !hmac::constant_time_eq(signature, expected.as_bytes()) {
+                return None;
+            }
+        }
+
+        let parse = |frame: &[u8]| Json::parse(str::from_utf8(frame).ok()?);
+
+        Some(Request {
+            header: parse(header)?,
+            content: parse(content)?,
+            ids: message[..delimiter].to_vec(),
+        })
+    }
+
+    /// Encodes a new signed [`Message`] with routing identities, a message
+    /// type, a parent header, and content.
+    pub fn encode(
+        &self,
+        ids: &[Vec<u8>],
+        msg_type: &str,
+        parent: &Json,
+        content: &Json,
+    ) -> Message {
+        let header = self.header(msg_type).to_string();
+        let parent = parent.to_string();
+        let metadata = Json::object([]).to_string();
+        let content = content.to_string();
+
+        let signature = if self.key.is_empty() {
+            String::new()
+        } else {
+            hmac::hmac_sha256_hex(
+                &self.key,
+                &[
+                    header.as_bytes(),
+                    parent.as_bytes(),
+                    metadata.as_bytes(),
+                    content.as_bytes(),
+                ],
+            )
+        };
+
+        let mut message = ids.to_vec();
+        message.push(DELIMITER.to_vec());
+
+        for frame in [signature, header, parent, metadata, content] {
+            message.push(frame.into_bytes());
+        }
+
+        message
+    }
+
+    /// Creates a new header [`Json`] value for a message type.
+    fn header(&self, msg_type: &str) -> Json {
+        let count = self.message_count.get() + 1;
+        self.message_count.set(count);
+
+        Json::object([
+            ("msg_id", format!("{}-{count}", self.id).into()),
+            ("session", self.id.as_str().into()),
+            ("username", "clac".into()),
+            ("date", timestamp().into()),
+            ("msg_type", msg_type.into()),
+            ("version", PROTOCOL_VERSION.into()),
+        ])
+    }
+}
+
+/// Returns the current UTC time as an ISO 8601 timestamp.
+fn timestamp() -> String {
+    const SECS_PER_DAY: u64 = 86_400;
+
+    let duration = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let secs = duration.as_secs();
+    let (days, secs_of_day) = (secs / SECS_PER_DAY, secs % SECS_PER_DAY);
+    let (year, month, day) = calendar::civil_from_days(days.cast_signed());
+    let (hour, minute, second) = (secs_of_day / 3600, secs_of_day / 60 % 60, secs_of_day % 60);
+    let micros = duration.subsec_micros();
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{micros:06}Z")
+}
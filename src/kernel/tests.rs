@@ -0,0 +1,170 @@
+use std::{
+    io::{self, Read as _, Write as _},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::json::Json;
+
+use super::{
+    hmac,
+    queues::Queues,
+    zmtp::{Connection, SocketType},
+};
+
+/// Tests that HMAC-SHA256 signatures match known test vectors.
+#[test]
+fn hmac_signatures_match_test_vectors() {
+    // See: https://www.rfc-editor.org/rfc/rfc4231#section-4.3
+    assert_eq!(
+        hmac::hmac_sha256_hex(b"Jefe", &[b"what do ya want ", b"for nothing?"]),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+    );
+
+    // See: https://www.rfc-editor.org/rfc/rfc4231#section-4.7
+    assert_eq!(
+        hmac::hmac_sha256_hex(
+            &[0xaa; 131],
+            &[b"Test Using Larger Than Block-Size Key - Hash Key First"],
+        ),
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+    );
+
+    assert!(hmac::constant_time_eq(b"abc", b"abc"));
+    assert!(!hmac::constant_time_eq(b"abc", b"abd"));
+    assert!(!hmac::constant_time_eq(b"abc", b"ab"));
+}
+
+/// Tests that JSON values are parsed and serialized.
+#[test]
+fn json_values_are_round_tripped() {
+    let text = r#"{"a":[1,-2.5,true,false,null],"b":"\"\\\né😀"}"#;
+    let value = Json::parse(text).expect("JSON should be valid");
+
+    assert_eq!(
+        value.get("b").and_then(Json::as_str),
+        Some("\"\\\n\u{e9}\u{1f600}")
+    );
+    assert_eq!(
+        value.to_string(),
+        "{\"a\":[1,-2.5,true,false,null],\"b\":\"\\\"\\\\\\n\u{e9}\u{1f600}\"}",
+    );
+
+    for invalid in ["", "[1,]", "{\"a\"}", "nul", "\"abc", "1 2"] {
+        assert_eq!(Json::parse(invalid), None, "{invalid:?} should be invalid");
+    }
+}
+
+/// Tests that control requests are received before waiting shell requests,
+/// and that requests on each channel are received in order.
+#[test]
+fn control_requests_are_received_first() {
+    let (queues, shell, control) = Queues::new();
+    assert!(shell.send("execute 1"));
+    assert!(shell.send("execute 2"));
+    assert!(control.send("shutdown"));
+
+    assert_eq!(queues.recv(), Some("shutdown"));
+    assert_eq!(queues.recv(), Some("execute 1"));
+    assert!(shell.send("execute 3"));
+    assert_eq!(queues.recv(), Some("execute 2"));
+    assert_eq!(queues.recv(), Some("execute 3"));
+
+    drop((shell, control));
+    assert_eq!(queues.recv(), None);
+}
+
+/// Returns a ZMTP 3.0 greeting with a security mechanism name.
+fn greeting(mechanism: &[u8]) -> [u8; 64] {
+    let mut greeting = [0; 64];
+    greeting[0] = 0xff;
+    greeting[9] = 0x7f;
+    greeting[10] = 3;
+    greeting[12..12 + mechanism.len()].copy_from_slice(mechanism);
+    greeting
+}
+
+/// Connects a client [`TcpStream`] which sends bytes to a [`Connection`]
+/// accepted as a [`SocketType`], and returns both ends.
+fn connect(socket_type: SocketType, bytes: &[u8]) -> (TcpStream, io::Result<Connection>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+    let address = listener
+        .local_addr()
+        .expect("listener should have an address");
+    let mut client = TcpStream::connect(address).expect("client should connect");
+    client.write_all(bytes).expect("client should write");
+
+    let (stream, _) = listener.accept().expect("listener should accept");
+    (client, Connection::accept(stream, socket_type))
+}
+
+/// Reads a number of bytes from a [`TcpStream`].
+fn read_bytes(stream: &mut TcpStream, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    stream.read_exact(&mut bytes).expect("bytes should be read");
+    bytes
+}
+
+/// Tests that ZMTP handshakes exchange greetings and `READY` commands with
+/// the `NULL` security mechanism.
+#[test]
+fn zmtp_handshakes_exchange_greetings() {
+    let mut bytes = greeting(b"NULL").to_vec();
+    bytes.extend_from_slice(b"\x04\x06\x05READY");
+
+    let (mut client, connection) = connect(SocketType::Router, &bytes);
+    connection.expect("handshake should succeed");
+    assert_eq!(read_bytes(&mut client, 64), greeting(b"NULL"));
+
+    let mut ready = b"\x04\x1c\x05READY\x0bSocket-Type".to_vec();
+    ready.extend_from_slice(b"\x00\x00\x00\x06ROUTER");
+    assert_eq!(read_bytes(&mut client, ready.len()), ready);
+}
+
+/// Tests that ZMTP handshakes are rejected if the peer does not speak ZMTP 3
+/// or uses a security mechanism other than `NULL`.
+#[test]
+fn zmtp_handshakes_reject_unsupported_peers() {
+    let mut old_version = greeting(b"NULL");
+    old_version[10] = 2;
+
+    for bytes in [[0; 64], old_version, greeting(b"PLAIN")] {
+        let (_client, connection) = connect(SocketType::Rep, &bytes);
+        assert!(connection.is_err());
+    }
+}
+
+/// Tests that ZMTP frames with short and long sizes are read and written as
+/// multipart messages, that commands between frames are skipped, and that
+/// frames which are too large are rejected.
+#[test]
+fn zmtp_frames_are_read_and_written() {
+    let long_frame = vec![7; 300];
+    let mut bytes = greeting(b"NULL").to_vec();
+    bytes.extend_from_slice(b"\x04\x06\x05READY");
+    bytes.extend_from_slice(b"\x01\x02id");
+    bytes.extend_from_slice(b"\x04\x04PING");
+    bytes.extend_from_slice(b"\x02\x00\x00\x00\x00\x00\x00\x01\x2c");
+    bytes.extend_from_slice(&long_frame);
+    bytes.extend_from_slice(b"\x02\x00\x00\x00\x00\x10\x00\x00\x00");
+
+    let (mut client, connection) = connect(SocketType::Pub, &bytes);
+    let mut connection = connection.expect("handshake should succeed");
+    read_bytes(&mut client, 64 + 2 + 25);
+
+    let message = connection.read_message().expect("message should be read");
+    assert_eq!(message, [b"id".to_vec(), long_frame.clone()]);
+    connection
+        .read_message()
+        .expect_err("frame should be too large");
+
+    connection
+        .write_message(&[b"a".as_slice(), &long_frame])
+        .expect("message should be written");
+
+    assert_eq!(read_bytes(&mut client, 3), b"\x01\x01a");
+    assert_eq!(
+        read_bytes(&mut client, 9),
+        b"\x02\x00\x00\x00\x00\x00\x00\x01\x2c"
+    );
+    assert_eq!(read_bytes(&mut client, 300), long_frame);
+}
@@ -0,0 +1,211 @@
+use std::{
+    io::{self, Read as _, Write as _},
+    net::TcpStream,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// The largest frame size which will be read, in bytes.
+const MAX_FRAME_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The flag for a frame which is followed by more frames in its message.
+const MORE_FLAG: u8 = 0x01;
+
+/// The flag for a frame with an 8-byte size.
+const LONG_FLAG: u8 = 0x02;
+
+/// The flag for a command frame.
+const COMMAND_FLAG: u8 = 0x04;
+
+/// A multipart message of frames.
+pub type Message = Vec<Vec<u8>>;
+
+/// A type of `ZeroMQ` socket which the kernel can act as.
+#[derive(Clone, Copy)]
+pub enum SocketType {
+    /// A socket which replies to requests from any number of peers.
+    Router,
+
+    /// A socket which publishes messages to subscribed peers.
+    Pub,
+
+    /// A socket which replies to requests from a single peer at a time.
+    Rep,
+}
+
+impl SocketType {
+    /// Returns the `SocketType`'s name as it is sent in a handshake.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Router => "ROUTER",
+            Self::Pub => "PUB",
+            Self::Rep => "REP",
+        }
+    }
+}
+
+/// A connection to a `ZeroMQ` peer over TCP using ZMTP 3.0 with the `NULL`
+/// security mechanism.
+pub struct Connection(TcpStream);
+
+impl Connection {
+    /// Creates a new `Connection` by performing a handshake over a
+    /// [`TcpStream`] as a [`SocketType`]. This function returns an
+    /// [`io::Error`] if the handshake failed.
+    pub fn accept(mut stream: TcpStream, socket_type: SocketType) -> io::Result<Self> {
+        // The greeting is a signature, a version, a mechanism name, and an
+        // unused server flag and filler.
+        let mut greeting = [0_u8; 64];
+        greeting[0] = 0xff;
+        greeting[9] = 0x7f;
+        greeting[10] = 3;
+        greeting[12..16].copy_from_slice(b"NULL");
+        stream.write_all(&greeting)?;
+
+        let mut peer_greeting = [0_u8; 64];
+        stream.read_exact(&mut peer_greeting)?;
+
+        if peer_greeting[0] != 0xff || peer_greeting[9] & 1 != 1 || peer_greeting[10] < 3 {
+            return Err(io::Error::other("peer does not speak ZMTP 3"));
+        }
+
+        if peer_greeting[12..32] != greeting[12..32] {
+            return Err(io::Error::other(
+                "peer uses an unsupported security mechanism",
+            ));
+        }
+
+        let mut connection = Self(stream);
+        connection.write_ready(socket_type)?;
+
+        // The peer's ready command is ignored because the kernel accepts
+        // messages from any socket type.
+        connection.read_frame()?;
+        Ok(connection)
+    }
+
+    /// Returns a new handle to the `Connection` which can be written to from
+    /// another thread. This function returns an [`io::Error`] if the handle
+    /// could not be created.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        self.0.try_clone().map(Self)
+    }
+
+    /// Reads the next [`Message`], skipping any commands. This function
+    /// returns an [`io::Error`] if the [`Message`] could not be read.
+    pub fn read_message(&mut self) -> io::Result<Message> {
+        let mut message = Vec::new();
+
+        loop {
+            let (flags, body) = self.read_frame()?;
+
+            if flags & COMMAND_FLAG != 0 {
+                continue;
+            }
+
+            message.push(body);
+
+            if flags & MORE_FLAG == 0 {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Writes a [`Message`] of frames. This function returns an [`io::Error`]
+    /// if the [`Message`] could not be written.
+    pub fn write_message<F: AsRef<[u8]>>(&mut self, frames: &[F]) -> io::Result<()> {
+        let mut buffer = Vec::new();
+
+        for (index, frame) in frames.iter().enumerate() {
+            let flags = if index + 1 < frames.len() {
+                MORE_FLAG
+            } else {
+                0
+            };
+            encode_frame(&mut buffer, flags, frame.as_ref());
+        }
+
+        self.0.write_all(&buffer)
+    }
+
+    /// Writes a `READY` command with the `Connection`'s [`SocketType`]. This
+    /// function returns an [`io::Error`] if the command could not be written.
+    fn write_ready(&mut self, socket_type: SocketType) -> io::Result<()> {
+        let name = socket_type.name();
+        let mut body = Vec::new();
+        body.push(5);
+        body.extend_from_slice(b"READY");
+        body.push(11);
+        body.extend_from_slice(b"Socket-Type");
+        body.extend_from_slice(&u32::try_from(name.len()).unwrap_or(0).to_be_bytes());
+        body.extend_from_slice(name.as_bytes());
+
+        let mut buffer = Vec::new();
+        encode_frame(&mut buffer, COMMAND_FLAG, &body);
+        self.0.write_all(&buffer)
+    }
+
+    /// Reads a frame and returns its flags and body. This function returns an
+    /// [`io::Error`] if the frame could not be read or is too large.
+    fn read_frame(&mut self) -> io::Result<(u8, Vec<u8>)> {
+        let mut flags = [0];
+        self.0.read_exact(&mut flags)?;
+        let [flags] = flags;
+
+        let size = if flags & LONG_FLAG == 0 {
+            let mut size = [0];
+            self.0.read_exact(&mut size)?;
+            u64::from(size[0])
+        } else {
+            let mut size = [0; 8];
+            self.0.read_exact(&mut size)?;
+            u64::from_be_bytes(size)
+        };
+
+        if size > MAX_FRAME_SIZE {
+            return Err(io::Error::other("frame is too large"));
+        }
+
+        let size = usize::try_from(size).map_err(io::Error::other)?;
+        let mut body = vec![0; size];
+        self.0.read_exact(&mut body)?;
+        Ok((flags, body))
+    }
+}
+
+/// A set of [`Connection`]s to subscribers which [`Message`]s are published
+/// to. Subscriptions are not filtered, so every subscriber receives every
+/// [`Message`].
+#[derive(Clone, Default)]
+pub struct Publisher(Arc<Mutex<Vec<Connection>>>);
+
+impl Publisher {
+    /// Adds a subscriber [`Connection`] to the `Publisher`.
+    pub fn subscribe(&self, connection: Connection) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(connection);
+    }
+
+    /// Publishes a [`Message`] of frames to every subscriber. Subscribers
+    /// which could not be written to are removed.
+    pub fn publish<F: AsRef<[u8]>>(&self, frames: &[F]) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain_mut(|c| c.write_message(frames).is_ok());
+    }
+}
+
+/// Appends a frame with flags and a body to a buffer.
+fn encode_frame(buffer: &mut Vec<u8>, flags: u8, body: &[u8]) {
+    if let Ok(size) = u8::try_from(body.len()) {
+        buffer.push(flags);
+        buffer.push(size);
+    } else {
+        buffer.push(flags | LONG_FLAG);
+        buffer.extend_from_slice(&(body.len() as u64).to_be_bytes());
+    }
+
+    buffer.extend_from_slice(body);
+}
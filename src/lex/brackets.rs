@@ -0,0 +1,87 @@
+use thiserror::Error;
+
+use crate::{spans::Span, tokens::Token};
+
+use super::Lexer;
+
+/// An error caught while matching brackets.
+#[derive(Debug, Error)]
+pub enum BracketError {
+    /// An opening bracket was not closed.
+    #[error("unclosed '{0}'")]
+    Unclosed(char, Span),
+
+    /// A closing bracket had no opening bracket.
+    #[error("unmatched '{0}'")]
+    Unmatched(char, Span),
+
+    /// A closing bracket did not match the most recent opening bracket.
+    #[error("mismatched '{1}', expected '{0}' first")]
+    Mismatched(char, char, Span),
+}
+
+impl BracketError {
+    /// Returns the [`Span`] of the bracket where the `BracketError` occurred.
+    pub const fn span(&self) -> Span {
+        match self {
+            Self::Unclosed(_, span) | Self::Unmatched(_, span) | Self::Mismatched(_, _, span) => {
+                *span
+            }
+        }
+    }
+}
+
+/// Checks that the parentheses, braces, and square brackets in source code are
+/// balanced. Any other errors are ignored so that they can be reported by the
+/// parser. This function returns a [`BracketError`] for the first unbalanced
+/// bracket.
+pub fn check_brackets(source: &str) -> Result<(), BracketError> {
+    let mut lexer = Lexer::new(source, false);
+    let mut open_brackets: Vec<(char, Span)> = Vec::new();
+
+    loop {
+        let close = match lexer.next_token() {
+            Ok(Token::Eof) => break,
+            Ok(Token::OpenParen) => {
+                open_brackets.push(('(', lexer.span()));
+                continue;
+            }
+            Ok(Token::OpenBrace) => {
+                open_brackets.push(('{', lexer.span()));
+                continue;
+            }
+            Ok(Token::OpenBracket) => {
+                open_brackets.push(('[', lexer.span()));
+                continue;
+            }
+            Ok(Token::CloseParen) => ')',
+            Ok(Token::CloseBrace) => '}',
+            Ok(Token::CloseBracket) => ']',
+            _ => continue,
+        };
+
+        let span = lexer.span();
+
+        match open_brackets.pop() {
+            None => return Err(BracketError::Unmatched(close, span)),
+            Some((open, _)) if closing_bracket(open) != close => {
+                return Err(BracketError::Mismatched(closing_bracket(open), close, span));
+            }
+            Some(_) => {}
+        }
+    }
+
+    match open_brackets.pop() {
+        None => Ok(()),
+        Some((open, span)) => Err(BracketError::Unclosed(open, span)),
+    }
+}
+
+/// Returns the closing bracket [`char`] for an opening bracket [`char`].
+const fn closing_bracket(open: char) -> char {
+    match open {
+        '(' => ')',
+        '[' => ']',
+        _ => '}',
+    }
+}
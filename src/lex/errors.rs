@@ -10,6 +10,18 @@ pub enum ErrorKind {
     #[error("unexpected character {0:?}")]
     UnexpectedChar(char),
 
+    /// A raw string literal was not closed before the end of source code.
+    #[error("raw string literal is missing a closing '`'")]
+    UnterminatedString,
+
+    /// A quoted string literal was not closed before the end of source code.
+    #[error("string literal is missing a closing '\"'")]
+    UnterminatedQuotedString,
+
+    /// An invalid escape sequence was encountered in a quoted string literal.
+    #[error("invalid escape sequence '\\{0}' in string literal")]
+    InvalidEscape(char),
+
     /// A bitwise and (`&`) operator was encountered.
     #[error("the '&' operator is not supported, did you mean '&&'?")]
     BitwiseAnd,
@@ -0,0 +1,118 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{ast::Literal, tokens::Token};
+
+/// A word with a special meaning in source code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keyword {
+    /// The `false` literal.
+    False,
+
+    /// The `in` operator.
+    In,
+
+    /// The `lazy` expression.
+    Lazy,
+
+    /// The `left` custom operator modifier.
+    Left,
+
+    /// The `let` expression.
+    Let,
+
+    /// The `loop` expression.
+    Loop,
+
+    /// The `operator` statement.
+    Operator,
+
+    /// The `otherwise` function clause guard.
+    Otherwise,
+
+    /// The `recur` expression.
+    Recur,
+
+    /// The `right` custom operator modifier.
+    Right,
+
+    /// The `sum` custom operator modifier.
+    Sum,
+
+    /// The `term` custom operator modifier.
+    Term,
+
+    /// The `true` literal.
+    True,
+
+    /// The `when` function clause guard.
+    When,
+
+    /// The `while` expression.
+    While,
+}
+
+impl Keyword {
+    /// Every `Keyword`.
+    pub const ALL: [Self; 15] = [
+        Self::False,
+        Self::In,
+        Self::Lazy,
+        Self::Left,
+        Self::Let,
+        Self::Loop,
+        Self::Operator,
+        Self::Otherwise,
+        Self::Recur,
+        Self::Right,
+        Self::Sum,
+        Self::Term,
+        Self::True,
+        Self::When,
+        Self::While,
+    ];
+
+    /// Returns a `Keyword` from its name. This function returns [`None`] if
+    /// the name is not a `Keyword`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == name)
+    }
+
+    /// Returns the `Keyword`'s name.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::False => "false",
+            Self::In => "in",
+            Self::Lazy => "lazy",
+            Self::Left => "left",
+            Self::Let => "let",
+            Self::Loop => "loop",
+            Self::Operator => "operator",
+            Self::Otherwise => "otherwise",
+            Self::Recur => "recur",
+            Self::Right => "right",
+            Self::Sum => "sum",
+            Self::Term => "term",
+            Self::True => "true",
+            Self::When => "when",
+            Self::While => "while",
+        }
+    }
+
+    /// Returns the [`Token`] of a reserved `Keyword`. This function returns
+    /// [`None`] if the `Keyword` is contextual. Contextual `Keyword`s are
+    /// read as identifiers and only have a special meaning where the parser
+    /// expects them, so they can still be used as variable names.
+    pub const fn token(self) -> Option<Token> {
+        match self {
+            Self::False => Some(Token::Literal(Literal::Bool(false))),
+            Self::True => Some(Token::Literal(Literal::Bool(true))),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Keyword {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
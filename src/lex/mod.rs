@@ -1,15 +1,41 @@
 #[cfg(test)]
 mod tests;
 
+mod brackets;
 mod errors;
+mod keywords;
 mod scan;
 
 use thiserror::Error;
 
-use crate::{ast::Literal, symbols::Symbol, tokens::Token};
+use crate::{ast::Literal, format::SI_PREFIXES, spans::Span, symbols::Symbol, tokens::Token};
+
+pub use self::{
+    brackets::{BracketError, check_brackets},
+    keywords::Keyword,
+};
 
 use self::{errors::ErrorKind, scan::Scanner};
 
+/// Reads every [`Token`] from source code with its [`Span`], ending with
+/// [`Token::Eof`]. SI prefix suffixes are recognized on number [`Token`]s if
+/// they are enabled. This function returns a [`LexError`] with the [`Span`] where
+/// it occurred if a [`Token`] could not be read.
+pub fn tokenize(source: &str, si_suffixes: bool) -> Result<Vec<(Token, Span)>, (LexError, Span)> {
+    let mut lexer = Lexer::new(source, si_suffixes);
+    let mut tokens = Vec::new();
+
+    loop {
+        let token = lexer.next_token().map_err(|e| (e, lexer.span()))?;
+        let is_eof = matches!(token, Token::Eof);
+        tokens.push((token, lexer.span()));
+
+        if is_eof {
+            return Ok(tokens);
+        }
+    }
+}
+
 /// An error caught while reading a [`Token`].
 #[derive(Debug, Error)]
 #[repr(transparent)]
@@ -17,19 +43,30 @@ use self::{errors::ErrorKind, scan::Scanner};
 pub struct LexError(ErrorKind);
 
 /// A structure which reads a stream of [`Token`]s from source code.
+#[derive(Clone)]
 pub struct Lexer<'src> {
     /// The [`Scanner`].
     scanner: Scanner<'src>,
+
+    /// Whether SI prefix suffixes are recognized on number [`Token`]s.
+    si_suffixes: bool,
 }
 
 impl<'src> Lexer<'src> {
-    /// Creates a new `Lexer` from source code.
-    pub fn new(source: &'src str) -> Self {
+    /// Creates a new `Lexer` from source code and whether SI prefix suffixes
+    /// (e.g. `4.7k`) are recognized on number [`Token`]s.
+    pub fn new(source: &'src str, si_suffixes: bool) -> Self {
         Self {
             scanner: Scanner::new(source),
+            si_suffixes,
         }
     }
 
+    /// Returns the [`Span`] of the most recently read [`Token`].
+    pub fn span(&self) -> Span {
+        self.scanner.lexeme_span()
+    }
+
     /// Returns the next [`Token`]. This function returns a [`LexError`] if a
     /// [`Token`] could not be read.
     pub fn next_token(&mut self) -> Result<Token, LexError> {
@@ -47,18 +84,43 @@ impl<'src> Lexer<'src> {
             ')' => Token::CloseParen,
             '{' => Token::OpenBrace,
             '}' => Token::CloseBrace,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
             ',' => Token::Comma,
-            '+' => Token::Plus,
+            ';' => Token::Semicolon,
+            '+' => {
+                if self.scanner.eat('=') {
+                    Token::PlusEquals
+                } else {
+                    Token::Plus
+                }
+            }
             '-' => {
                 if self.scanner.eat('>') {
                     Token::MinusGreater
+                } else if self.scanner.eat('=') {
+                    Token::MinusEquals
                 } else {
                     Token::Minus
                 }
             }
-            '*' => Token::Star,
-            '/' => Token::Slash,
+            '*' => {
+                if self.scanner.eat('=') {
+                    Token::StarEquals
+                } else {
+                    Token::Star
+                }
+            }
+            '/' => {
+                if self.scanner.eat('=') {
+                    Token::SlashEquals
+                } else {
+                    Token::Slash
+                }
+            }
             '^' => Token::Caret,
+            '²' => Token::Superscript(2.0),
+            '³' => Token::Superscript(3.0),
             '=' => {
                 if self.scanner.eat('=') {
                     Token::EqualsEquals
@@ -103,6 +165,9 @@ impl<'src> Lexer<'src> {
             }
             '?' => Token::Question,
             ':' => Token::Colon,
+            '`' => self.next_raw_string_token()?,
+            '"' => self.next_quoted_string_token()?,
+            c if is_char_custom_op(c) => Token::CustomOp(Symbol::intern(self.scanner.lexeme())),
             _ => return Err(ErrorKind::UnexpectedChar(char).into()),
         };
 
@@ -117,9 +182,93 @@ impl<'src> Lexer<'src> {
             self.scanner.eat_while(is_char_digit);
         }
 
-        let value = self.scanner.lexeme();
-        let value = value.parse().expect("value should be a valid float");
-        Token::Literal(Literal::Number(value))
+        let digits = self.scanner.lexeme();
+
+        let Some(exponent) = self.eat_si_suffix() else {
+            return number_literal(digits);
+        };
+
+        let value = format!("{digits}e{exponent}").parse();
+        Token::Literal(Literal::Number(
+            value.expect("value should be a valid float"),
+        ))
+    }
+
+    /// Consumes an SI prefix suffix after a number if SI prefix suffixes are
+    /// enabled and returns its exponent. This function returns [`None`] if no
+    /// suffix was consumed. A suffix must not be followed by an identifier
+    /// continuation, so that `2max` is not read as `2m` followed by `ax`.
+    fn eat_si_suffix(&mut self) -> Option<i32> {
+        if !self.si_suffixes
+            || self
+                .scanner
+                .peek_second()
+                .is_some_and(is_char_word_continue)
+        {
+            return None;
+        }
+
+        let exponent = self.scanner.peek().and_then(si_prefix_exponent)?;
+        self.scanner.bump();
+        Some(exponent)
+    }
+
+    /// Returns the next raw string [`Token`] after consuming its opening
+    /// backtick. Raw strings may contain newlines and do not process escape
+    /// sequences. This function returns a [`LexError`] if the raw string is
+    /// not closed.
+    fn next_raw_string_token(&mut self) -> Result<Token, LexError> {
+        self.scanner.eat_while(|c| c != '`');
+
+        if !self.scanner.eat('`') {
+            return Err(ErrorKind::UnterminatedString.into());
+        }
+
+        let value = self
+            .scanner
+            .lexeme()
+            .strip_prefix('`')
+            .and_then(|s| s.strip_suffix('`'))
+            .expect("raw string should be delimited by backticks");
+
+        Ok(Token::Literal(Literal::Str(value.into())))
+    }
+
+    /// Returns the next quoted string [`Token`] after consuming its opening
+    /// double quote. Quoted strings may contain newlines and process escape
+    /// sequences. This function returns a [`LexError`] if the quoted string is
+    /// not closed or contains an invalid escape sequence.
+    fn next_quoted_string_token(&mut self) -> Result<Token, LexError> {
+        let mut value = String::new();
+        let mut invalid_escape = None;
+
+        loop {
+            match self.scanner.bump() {
+                None => return Err(ErrorKind::UnterminatedQuotedString.into()),
+                Some('"') => break,
+                Some('\\') => {
+                    let Some(escape) = self.scanner.bump() else {
+                        return Err(ErrorKind::UnterminatedQuotedString.into());
+                    };
+
+                    // The rest of the string is consumed after an invalid
+                    // escape sequence so that lexing can continue after it.
+                    match unescape_char(escape) {
+                        Some(char) => value.push(char),
+                        None => {
+                            invalid_escape.get_or_insert(escape);
+                        }
+                    }
+                }
+                Some(char) => value.push(char),
+            }
+        }
+
+        if let Some(escape) = invalid_escape {
+            return Err(ErrorKind::InvalidEscape(escape).into());
+        }
+
+        Ok(Token::Literal(Literal::Str(value.into())))
     }
 
     /// Returns the next keyword or identifier [`Token`] after consuming its
@@ -127,12 +276,29 @@ impl<'src> Lexer<'src> {
     fn next_word_token(&mut self) -> Token {
         self.scanner.eat_while(is_char_word_continue);
 
-        match self.scanner.lexeme() {
-            "false" => Token::Literal(Literal::Bool(false)),
-            "true" => Token::Literal(Literal::Bool(true)),
-            name => Token::Ident(Symbol::intern(name)),
-        }
+        let name = self.scanner.lexeme();
+
+        Keyword::from_name(name)
+            .and_then(Keyword::token)
+            .unwrap_or_else(|| Token::Ident(Symbol::intern(name)))
+    }
+}
+
+/// Returns a number literal [`Token`] from its digits. Integers which are too
+/// large to be represented exactly as floats are kept as integers, so that they
+/// are exact in integer modes.
+fn number_literal(digits: &str) -> Token {
+    /// The largest integer below which all integers are exact floats.
+    const MAX_EXACT_INTEGER: i128 = 1 << f64::MANTISSA_DIGITS;
+
+    if let Ok(value) = digits.parse::<i128>()
+        && value > MAX_EXACT_INTEGER
+    {
+        return Token::Literal(Literal::Integer(value));
     }
+
+    let value = digits.parse().expect("value should be a valid float");
+    Token::Literal(Literal::Number(value))
 }
 
 /// Returns [`true`] if a [`char`] is a digit.
@@ -140,6 +306,17 @@ const fn is_char_digit(char: char) -> bool {
     char.is_ascii_digit()
 }
 
+/// Returns the exponent of an SI prefix [`char`]. The letter `u` is accepted
+/// as an alternative to `µ`. This function returns [`None`] if the [`char`] is
+/// not an SI prefix.
+fn si_prefix_exponent(char: char) -> Option<i32> {
+    let char = if char == 'u' { 'µ' } else { char };
+
+    SI_PREFIXES
+        .iter()
+        .find_map(|&(prefix, exponent)| (prefix == char).then_some(exponent))
+}
+
 /// Returns [`true`] if a [`char`] is a keyword or identifier start.
 const fn is_char_word_start(char: char) -> bool {
     char.is_ascii_alphabetic() || char == '_'
@@ -149,3 +326,31 @@ const fn is_char_word_start(char: char) -> bool {
 const fn is_char_word_continue(char: char) -> bool {
     is_char_word_start(char) || is_char_digit(char)
 }
+
+/// Returns the [`char`] represented by an escape sequence from the [`char`]
+/// after its backslash. This function returns [`None`] if the escape sequence
+/// is invalid.
+const fn unescape_char(char: char) -> Option<char> {
+    let char = match char {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        '\\' | '"' => char,
+        _ => return None,
+    };
+
+    Some(char)
+}
+
+/// Returns [`true`] if a [`char`] is a custom operator symbol. Custom operator
+/// symbols are taken from the Unicode mathematical operator and symbol blocks.
+const fn is_char_custom_op(char: char) -> bool {
+    matches!(
+        char,
+        '\u{2200}'..='\u{22ff}'
+            | '\u{27c0}'..='\u{27ef}'
+            | '\u{2980}'..='\u{29ff}'
+            | '\u{2a00}'..='\u{2aff}'
+    )
+}
@@ -3,8 +3,14 @@ mod tests;
 
 use std::str::Chars;
 
+use crate::spans::Span;
+
 /// A structure which reads lexemes from source code.
+#[derive(Clone)]
 pub struct Scanner<'src> {
+    /// The length of source code in bytes.
+    source_len: usize,
+
     /// The [`Iterator`] over source code [`char`]s.
     chars: Chars<'src>,
 
@@ -17,6 +23,7 @@ impl<'src> Scanner<'src> {
     /// Creates a new `Scanner` from source code.
     pub fn new(source: &'src str) -> Self {
         Self {
+            source_len: source.len(),
             chars: source.chars(),
             rest: source,
         }
@@ -33,6 +40,13 @@ impl<'src> Scanner<'src> {
         &self.rest[..length]
     }
 
+    /// Returns the current lexeme's [`Span`].
+    pub fn lexeme_span(&self) -> Span {
+        let start = self.source_len - self.rest.len();
+        let end = self.source_len - self.chars.as_str().len();
+        Span::new(start, end)
+    }
+
     /// Begins a new lexeme.
     pub fn begin_lexeme(&mut self) {
         self.rest = self.chars.as_str();
@@ -68,7 +82,13 @@ impl<'src> Scanner<'src> {
 
     /// Returns the next [`char`] without consuming it. This function returns
     /// [`None`] if the `Scanner` is at the end of source code.
-    fn peek(&self) -> Option<char> {
+    pub fn peek(&self) -> Option<char> {
         self.chars.clone().next()
     }
+
+    /// Returns the [`char`] after the next [`char`] without consuming it. This
+    /// function returns [`None`] if there is no such [`char`].
+    pub fn peek_second(&self) -> Option<char> {
+        self.chars.clone().nth(1)
+    }
 }
@@ -49,6 +49,25 @@ fn lexemes_are_scanned_at_expected_boundaries() {
     assert_eq!(scanner.rest, scanner.lexeme());
 }
 
+/// Tests that lexeme [`Span`]s have expected byte offsets, including non-ASCII
+/// [`char`]s.
+#[test]
+fn lexeme_spans_have_expected_offsets() {
+    let mut scanner = Scanner::new("ab☕c");
+    assert_eq!(scanner.lexeme_span(), Span::new(0, 0));
+    scanner.eat_while(char::is_alphabetic);
+    assert_eq!(scanner.lexeme_span(), Span::new(0, 2));
+
+    scanner.begin_lexeme();
+    assert_eq!(scanner.lexeme_span(), Span::new(2, 2));
+    assert_eq!(scanner.bump(), Some('☕'));
+    assert_eq!(scanner.lexeme_span(), Span::new(2, 5));
+
+    scanner.begin_lexeme();
+    scanner.eat_while(is_char_not_eof);
+    assert_eq!(scanner.lexeme_span(), Span::new(5, 6));
+}
+
 /// Tests that [`Scanner::eat_while`] terminates at the end of source code.
 #[test]
 fn eat_while_terminates_at_eof() {
@@ -1,12 +1,21 @@
 use super::*;
 
-/// Asserts that source code produces an expected stream of [`Token`]s.
+/// Asserts that source code produces an expected stream of [`Token`]s. SI
+/// prefix suffixes are recognized if the source code is prefixed with `si`.
 macro_rules! assert_tokens {
+    (si $src:literal, [$($tok:pat $(if $guard:expr)?),* $(,)?]) => {
+        let mut lexer = Lexer::new($src, true);
+        $(assert!(matches!(lexer.next_token(), $tok $(if $guard)?));)*
+        assert!(matches!(lexer.next_token(), Ok(Token::Eof)));
+    };
     ($src:literal, [$($tok:pat $(if $guard:expr)?),* $(,)?]) => {
-        let mut lexer = Lexer::new($src);
+        let mut lexer = Lexer::new($src, false);
         $(assert!(matches!(lexer.next_token(), $tok $(if $guard)?));)*
         assert!(matches!(lexer.next_token(), Ok(Token::Eof)));
     };
+    (si $src:literal, Ok[$($tok:pat $(if $guard:expr)?),* $(,)?]) => {
+        assert_tokens!(si $src, [$(Ok($tok) $(if $guard)?),*]);
+    };
     ($src:literal, Ok[$($tok:pat $(if $guard:expr)?),* $(,)?]) => {
         assert_tokens!($src, [$(Ok($tok) $(if $guard)?),*]);
     };
@@ -90,10 +99,28 @@ fn non_ascii_chars_are_scanned() {
     );
 }
 
+/// Tests that compound assignment [`Token`]s are produced.
+#[test]
+fn compound_assignment_tokens_are_produced() {
+    assert_tokens!(
+        "+= -= *= /= + = ->=",
+        Ok[
+            Token::PlusEquals,
+            Token::MinusEquals,
+            Token::StarEquals,
+            Token::SlashEquals,
+            Token::Plus,
+            Token::Equals,
+            Token::MinusGreater,
+            Token::Equals,
+        ]
+    );
+}
+
 /// Tests that source code produces trailing EOF [`Token`]s.
 #[test]
 fn trailing_eof_tokens_are_produced() {
-    let mut lexer = Lexer::new("1 2 3");
+    let mut lexer = Lexer::new("1 2 3", false);
     assert!(matches!(
         lexer.next_token(),
         Ok(Token::Literal(Literal::Number(1.0_f64))),
@@ -164,6 +191,28 @@ fn all_tokens_are_produced() {
         ]
     );
 
+    assert_tokens!(
+        "x² + y³",
+        Ok[
+            Token::Ident(s) if s.to_string() == "x",
+            Token::Superscript(2.0_f64),
+            Token::Plus,
+            Token::Ident(s) if s.to_string() == "y",
+            Token::Superscript(3.0_f64),
+        ]
+    );
+
+    assert_tokens!(
+        "x = 1; x",
+        Ok[
+            Token::Ident(s) if s.to_string() == "x",
+            Token::Equals,
+            Token::Literal(Literal::Number(1.0_f64)),
+            Token::Semicolon,
+            Token::Ident(s) if s.to_string() == "x",
+        ]
+    );
+
     assert_tokens!(
         "foo && bar || baz",
         Ok[
@@ -195,6 +244,22 @@ fn all_tokens_are_produced() {
     );
 }
 
+/// Tests that bracket [`Token`]s are produced.
+#[test]
+fn bracket_tokens_are_produced() {
+    assert_tokens!(
+        "[xs][0]",
+        Ok[
+            Token::OpenBracket,
+            Token::Ident(s) if s.to_string() == "xs",
+            Token::CloseBracket,
+            Token::OpenBracket,
+            Token::Literal(Literal::Number(0.0_f64)),
+            Token::CloseBracket,
+        ]
+    );
+}
+
 /// Tests that integer number [`Token`]s are produced.
 #[test]
 fn integers_tokens_are_produced() {
@@ -274,6 +339,38 @@ fn decimal_tokens_are_accurate() {
     );
 }
 
+/// Tests that SI prefix suffixes are only read on number [`Token`]s when they
+/// are enabled.
+#[test]
+fn si_suffixes_are_read_when_enabled() {
+    assert_tokens!(
+        "4.7k",
+        Ok[
+            Token::Literal(Literal::Number(4.7_f64)),
+            Token::Ident(s) if s.to_string() == "k",
+        ]
+    );
+
+    assert_tokens!(
+        si "4.7k, 100m, 2u, 3µ, 1Q, 2max,",
+        Ok[
+            Token::Literal(Literal::Number(4700.0_f64)),
+            Token::Comma,
+            Token::Literal(Literal::Number(0.1_f64)),
+            Token::Comma,
+            Token::Literal(Literal::Number(0.000_002_f64)),
+            Token::Comma,
+            Token::Literal(Literal::Number(0.000_003_f64)),
+            Token::Comma,
+            Token::Literal(Literal::Number(1e30_f64)),
+            Token::Comma,
+            Token::Literal(Literal::Number(2.0_f64)),
+            Token::Ident(s) if s.to_string() == "max",
+            Token::Comma,
+        ]
+    );
+}
+
 /// Tests that keyword [`Token`]s are length-sensitive.
 #[test]
 fn keywords_are_length_sensitive() {
@@ -346,10 +443,58 @@ fn keywords_are_case_sensitive() {
     );
 }
 
+/// Tests that custom operator [`Token`]s are produced from mathematical
+/// symbols, and that operator keywords are read as identifiers.
+#[test]
+fn custom_operator_tokens_are_produced() {
+    assert_tokens!(
+        "operator ⊕ a∘b ⨁☕ in",
+        [
+            Ok(Token::Ident(s)) if s.to_string() == "operator",
+            Ok(Token::CustomOp(s)) if s.to_string() == "⊕",
+            Ok(Token::Ident(s)) if s.to_string() == "a",
+            Ok(Token::CustomOp(s)) if s.to_string() == "∘",
+            Ok(Token::Ident(s)) if s.to_string() == "b",
+            Ok(Token::CustomOp(s)) if s.to_string() == "⨁",
+            Err(LexError(ErrorKind::UnexpectedChar('☕'))),
+            Ok(Token::Ident(s)) if s.to_string() == "in",
+        ]
+    );
+}
+
+/// Tests that raw string [`Token`]s preserve their contents exactly.
+#[test]
+fn raw_string_tokens_are_produced() {
+    assert_tokens!(
+        "`` `a\nb` `\\n {x}` `open",
+        [
+            Ok(Token::Literal(Literal::Str(s))) if s.is_empty(),
+            Ok(Token::Literal(Literal::Str(s))) if &*s == "a\nb",
+            Ok(Token::Literal(Literal::Str(s))) if &*s == "\\n {x}",
+            Err(LexError(ErrorKind::UnterminatedString)),
+        ]
+    );
+}
+
+/// Tests that quoted string [`Token`]s process escape sequences.
+#[test]
+fn quoted_string_tokens_are_unescaped() {
+    assert_tokens!(
+        r#""" "a\tb\n" "\"\\" "\q" "open"#,
+        [
+            Ok(Token::Literal(Literal::Str(s))) if s.is_empty(),
+            Ok(Token::Literal(Literal::Str(s))) if &*s == "a\tb\n",
+            Ok(Token::Literal(Literal::Str(s))) if &*s == "\"\\",
+            Err(LexError(ErrorKind::InvalidEscape('q'))),
+            Err(LexError(ErrorKind::UnterminatedQuotedString)),
+        ]
+    );
+}
+
 /// Tests that [`Symbol`]s are reused for equal names and are case-sensitive.
 #[test]
 fn symbols_are_reused_and_case_sensitive() {
-    let mut lexer = Lexer::new("foo foo FOO FOO bar");
+    let mut lexer = Lexer::new("foo foo FOO FOO bar", false);
 
     /// Returns the next [`Symbol`] from the [`Lexer`].
     macro_rules! next_symbol {
@@ -379,3 +524,84 @@ fn symbols_are_reused_and_case_sensitive() {
 
     assert!(matches!(lexer.next_token(), Ok(Token::Eof)));
 }
+
+/// Tests that balanced brackets are accepted.
+#[test]
+fn balanced_brackets_are_accepted() {
+    assert!(check_brackets("").is_ok(), "empty source is balanced");
+    assert!(
+        check_brackets("f((1 + 2) * {[3]})").is_ok(),
+        "source is balanced"
+    );
+    assert!(check_brackets("`(` + `}`").is_ok(), "strings are ignored");
+}
+
+/// Tests that unbalanced brackets are reported with their [`Span`]s.
+#[test]
+fn unbalanced_brackets_are_reported() {
+    assert!(matches!(
+        check_brackets("sqrt((1 + 2)"),
+        Err(BracketError::Unclosed('(', span)) if span == Span::new(4, 5),
+    ));
+
+    assert!(matches!(
+        check_brackets("1 + 2)"),
+        Err(BracketError::Unmatched(')', span)) if span == Span::new(5, 6),
+    ));
+
+    assert!(matches!(
+        check_brackets("{(1 + 2})"),
+        Err(BracketError::Mismatched(')', '}', span)) if span == Span::new(7, 8),
+    ));
+
+    assert!(matches!(
+        check_brackets("[1, 2)"),
+        Err(BracketError::Mismatched(']', ')', span)) if span == Span::new(5, 6),
+    ));
+}
+
+/// Tests that every [`Token`] is read with its [`Span`], ending with
+/// [`Token::Eof`].
+#[test]
+fn tokens_are_read_with_spans() {
+    let tokens = tokenize("x² + 12", false).expect("source code should be valid");
+    let spans: Vec<Span> = tokens.iter().map(|(_, span)| *span).collect();
+
+    assert!(matches!(tokens.last(), Some((Token::Eof, _))));
+    assert_eq!(
+        spans,
+        [
+            Span::new(0, 1),
+            Span::new(1, 3),
+            Span::new(4, 5),
+            Span::new(6, 8),
+            Span::new(8, 8),
+        ]
+    );
+
+    assert!(tokenize("1 & 2", false).ok().is_none());
+}
+
+/// Tests that reserved keywords produce keyword [`Token`]s and that contextual
+/// keywords produce identifier [`Token`]s.
+#[test]
+fn contextual_keywords_are_identifiers() {
+    assert_tokens!(
+        "true in operator left right sum term",
+        Ok[
+            Token::Literal(Literal::Bool(true)),
+            Token::Ident(s) if s.to_string() == "in",
+            Token::Ident(s) if s.to_string() == "operator",
+            Token::Ident(s) if s.to_string() == "left",
+            Token::Ident(s) if s.to_string() == "right",
+            Token::Ident(s) if s.to_string() == "sum",
+            Token::Ident(s) if s.to_string() == "term",
+        ]
+    );
+
+    for keyword in Keyword::ALL {
+        let is_ident = matches!(keyword.token(), None | Some(Token::Ident(_)));
+        let token = Lexer::new(keyword.name(), false).next_token().ok();
+        assert_eq!(matches!(token, Some(Token::Ident(_))), is_ident);
+    }
+}
@@ -0,0 +1,615 @@
+#[cfg(test)]
+mod tests;
+
+mod args;
+mod ast;
+mod calendar;
+mod cfg;
+mod compile;
+mod completions;
+mod embed;
+mod engine;
+mod errors;
+mod format;
+mod help;
+mod hir;
+mod integer;
+mod interpret;
+mod json;
+mod kernel;
+mod lex;
+mod limits;
+mod locals;
+mod lower;
+mod output;
+mod parse;
+#[cfg(feature = "playground")]
+mod playground;
+mod plot;
+mod reactive;
+mod repl;
+mod serve;
+mod settings;
+mod source;
+mod spans;
+mod symbols;
+mod terminal;
+mod tokens;
+mod warnings;
+
+pub use self::{
+    embed::{
+        Engine, EvalError, EvalWarning, Evaluation, FunctionHandle, HostObject, ObjectHandle,
+        Observer, SettingError, Value, VariableProvider,
+    },
+    interpret::{ArithOp, Side},
+    kernel::run_kernel,
+};
+
+use std::{
+    env, fs,
+    io::{self, Read as _, Write as _},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    rc::Rc,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    args::{Command, Options},
+    ast::Expr,
+    engine::EnginePool,
+    errors::ClacError,
+    hir::Hir,
+    interpret::{Globals, InterpretError, StatementHook},
+    locals::LocalTable,
+    lower::GlobalMode,
+    output::{Echo, Output, errln, outln},
+    parse::Operators,
+    settings::Settings,
+    source::Origin,
+    spans::SourceSpan,
+    warnings::Warning,
+};
+
+/// Runs Clac's command line interface with the arguments that the process was
+/// started with, and returns an [`ExitCode`] for whether it succeeded.
+#[must_use]
+pub fn run_cli() -> ExitCode {
+    let options = match args::parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let settings = session_settings(&options);
+
+    match &options.command {
+        Some(Command::Completions(shell)) => {
+            print!("{}", completions::generate(*shell));
+            return ExitCode::SUCCESS;
+        }
+        Some(Command::Fmt(path)) => return run_fmt(path.as_deref(), &settings),
+        Some(Command::Grammar) => {
+            print!("{}", parse::grammar_ebnf());
+            return ExitCode::SUCCESS;
+        }
+        Some(Command::Repl | Command::Serve) | None => {}
+    }
+
+    if matches!(options.command, Some(Command::Serve)) {
+        return serve::run_server(options.port.unwrap_or(serve::DEFAULT_PORT), settings);
+    }
+
+    let mut globals = new_globals(settings);
+
+    if let Some(path) = &options.log
+        && let Err(error) = globals.output().start_logging(Some(path))
+    {
+        eprintln!("Could not open transcript '{}': {error}", path.display());
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(path) = &options.output
+        && let Err(error) = globals.output().redirect(path)
+    {
+        eprintln!("Could not open output file '{}': {error}", path.display());
+        return ExitCode::FAILURE;
+    }
+
+    let mode = options.mode;
+
+    if let Some(path) = &options.watch {
+        run_watch(path, &mut globals, mode, options.keep_globals);
+    }
+
+    if let [_, _, ..] = options.files.as_slice() {
+        return run_files(
+            &options.files,
+            &options.preload,
+            mode,
+            options.jobs,
+            globals.output(),
+            settings,
+        );
+    }
+
+    // Preloaded files are always executed so that their definitions are
+    // available to checked code.
+    for path in &options.preload {
+        match fs::read_to_string(path) {
+            Ok(source) => {
+                let origin = Origin::File(path);
+                run_source(
+                    &source,
+                    origin,
+                    &mut globals,
+                    Mode::Execute,
+                    GlobalMode::Sealed,
+                );
+            }
+            Err(error) => {
+                eprintln!("Could not read '{}': {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let (source, origin) = match (options.files.first(), options.code.as_slice()) {
+        (Some(path), _) => match fs::read_to_string(path) {
+            Ok(source) => (source, Origin::File(path)),
+            Err(error) => {
+                eprintln!("Could not read '{}': {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        (None, []) if mode != Mode::Execute => {
+            let mut source = String::new();
+
+            if let Err(error) = io::stdin().read_to_string(&mut source) {
+                eprintln!("Could not read source code: {error}");
+                return ExitCode::FAILURE;
+            }
+
+            (source, Origin::Args)
+        }
+        (None, []) => {
+            // Results are always echoed in the REPL, so the echo option only
+            // applies to scripts and code arguments.
+            globals.settings_mut().echo = Echo::On;
+            repl::run_repl(&mut globals);
+            return ExitCode::SUCCESS;
+        }
+        (None, code) => (code.join(" "), Origin::Args),
+    };
+
+    globals.output().log_input(&source);
+
+    if run_source(&source, origin, &mut globals, mode, GlobalMode::Sealed) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Returns the [`Settings`] for sessions started from the command line with
+/// [`Options`]. The recursion limit is read from an environment variable if it
+/// was not given.
+fn session_settings(options: &Options) -> Settings {
+    let mut limits = options.limits;
+    limits.recursion = limits.recursion.or_else(limits::read_recursion_var);
+
+    Settings {
+        number_format: options.number_format,
+        integer_mode: options.integer_mode,
+        si_suffixes: options.si_suffixes,
+        native_overrides: options.native_overrides,
+        limits,
+        reactive: false,
+        echo: options.echo,
+        result_format: options.result_format,
+    }
+}
+
+/// Prints a script file, or source code from standard input if no path is
+/// given, in a consistent format. This function returns an [`ExitCode`] for
+/// whether the source code could be formatted. The source code is parsed with
+/// [`Settings`].
+fn run_fmt(path: Option<&Path>, settings: &Settings) -> ExitCode {
+    let source = path.map_or_else(|| io::read_to_string(io::stdin()), fs::read_to_string);
+
+    let source = match source {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("Could not read source code: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match parse::parse_source(&source, settings, &mut Operators::new()) {
+        Ok(ast) => {
+            print!("{}", ast::pretty_print(&ast, &source));
+            ExitCode::SUCCESS
+        }
+        Err(errors) => {
+            let shared_source: Rc<str> = source.into();
+            let origin = path.map_or(Origin::Args, Origin::File);
+
+            for error in errors {
+                let span = SourceSpan {
+                    source: Rc::clone(&shared_source),
+                    span: error.span(),
+                };
+
+                eprintln!("{}{}", ClacError::from(error), origin.locate(Some(&span)));
+            }
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Creates new [`Globals`] with natives installed and a session's
+/// [`Settings`].
+fn new_globals(settings: Settings) -> Globals {
+    let mut globals = Globals::new();
+    interpret::install_natives(&mut globals);
+    *globals.settings_mut() = settings;
+    globals
+}
+
+/// Runs script files in a [`Mode`] with [`Settings`] in parallel on an
+/// [`EnginePool`] with a number of threads, or one thread per available CPU if
+/// no number is given. Each script file is run after the preloaded files, and
+/// their output is printed in order to an [`Output`]. This function returns an
+/// [`ExitCode`] for whether every script file ran without errors.
+fn run_files(
+    paths: &[PathBuf],
+    preload: &[PathBuf],
+    mode: Mode,
+    jobs: Option<NonZeroUsize>,
+    output: &Output,
+    settings: Settings,
+) -> ExitCode {
+    let mut sources = Vec::with_capacity(preload.len());
+
+    for path in preload {
+        match fs::read_to_string(path) {
+            Ok(source) => sources.push((path.clone(), source)),
+            Err(error) => {
+                eprintln!("Could not read '{}': {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let jobs = jobs
+        .or_else(|| thread::available_parallelism().ok())
+        .unwrap_or(NonZeroUsize::MIN);
+
+    let jobs = NonZeroUsize::new(paths.len()).map_or(jobs, |n| jobs.min(n));
+    let pool = EnginePool::new(jobs, settings, sources);
+
+    let replies: Vec<_> = paths
+        .iter()
+        .map(|p| pool.run_file(p.clone(), mode))
+        .collect();
+
+    let mut is_ok = true;
+
+    for reply in replies {
+        let Ok((capture, is_file_ok)) = reply.recv() else {
+            errln!(output, "Error: internal error, please report this as a bug");
+            return ExitCode::FAILURE;
+        };
+
+        output.print_capture(capture);
+        is_ok &= is_file_ok;
+    }
+
+    if is_ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Runs a script file with [`Globals`] in a [`Mode`] whenever it is modified.
+/// The [`Globals`] are reset before each run unless they should be kept.
+fn run_watch(path: &Path, globals: &mut Globals, mode: Mode, keep_globals: bool) -> ! {
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+    let mut last_modified = None;
+
+    loop {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+
+        if modified != last_modified {
+            last_modified = modified;
+
+            // Clear the screen and move the cursor to the top left. Standard
+            // output is flushed so that the screen is cleared before errors
+            // are printed to standard error.
+            print!("\x1b[2J\x1b[H");
+            io::stdout()
+                .flush()
+                .expect("flushing stdout should not fail");
+
+            run_watched_file(path, globals, mode, keep_globals);
+            println!("\n[Watching '{}' for changes...]", path.display());
+            io::stdout()
+                .flush()
+                .expect("flushing stdout should not fail");
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Runs a watched script file with [`Globals`] in a [`Mode`]. The [`Globals`]
+/// are reset first unless they should be kept, in which case the script file
+/// can redefine them.
+fn run_watched_file(path: &Path, globals: &mut Globals, mode: Mode, keep_globals: bool) {
+    match fs::read_to_string(path) {
+        Ok(source) => {
+            let global_mode = if keep_globals {
+                GlobalMode::Redefinable
+            } else {
+                let output = globals.output().clone();
+                *globals = new_globals(*globals.settings());
+                globals.set_output(output);
+                GlobalMode::Sealed
+            };
+
+            run_source(&source, Origin::File(path), globals, mode, global_mode);
+        }
+        Err(error) => errln!(
+            globals.output(),
+            "Could not read '{}': {error}",
+            path.display()
+        ),
+    }
+}
+
+/// A mode for running source code.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Mode {
+    /// Compiles and executes source code.
+    #[default]
+    Execute,
+
+    /// Compiles source code without executing it.
+    Check,
+
+    /// Prints the [`Token`][tokens::Token]s of source code with their
+    /// [`Span`][spans::Span]s without compiling it.
+    DumpTokens,
+
+    /// Prints the [`Ast`][ast::Ast] of source code without lowering it.
+    DumpAst,
+
+    /// Prints the [`Hir`][hir::Hir] of source code without compiling it.
+    DumpHir,
+
+    /// Prints the [`Cfg`][cfg::Cfg] of source code without executing it.
+    DumpCfg,
+}
+
+/// Runs source code from an [`Origin`] with [`Globals`] in a [`Mode`] and a
+/// [`GlobalMode`]. Errors are printed with their location in the [`Origin`].
+/// This function returns [`true`] if no errors occurred.
+fn run_source(
+    source: &str,
+    origin: Origin<'_>,
+    globals: &mut Globals,
+    mode: Mode,
+    global_mode: GlobalMode,
+) -> bool {
+    let Err(errors) = try_run_source(source, origin, globals, mode, global_mode, None) else {
+        return true;
+    };
+
+    for error in errors {
+        errln!(globals.output(), "{error}{}", origin.locate(error.span()));
+    }
+
+    false
+}
+
+/// Prints the [`Token`][tokens::Token]s of source code with their
+/// [`Span`][spans::Span]s to the [`Globals`]' output. This function returns a
+/// [`LexError`][lex::LexError] with a [`Span`][spans::Span] if the source code
+/// could not be tokenized.
+fn dump_tokens(source: &str, globals: &Globals) -> Result<(), (lex::LexError, spans::Span)> {
+    for (token, span) in lex::tokenize(source, globals.settings().si_suffixes)? {
+        let range = format!("{}..{}", span.start, span.end);
+        let token = token.to_string();
+        outln!(
+            globals.output(),
+            "{range:<10} {token:<24} {:?}",
+            span.slice(source)
+        );
+    }
+
+    Ok(())
+}
+
+/// The results and [`Warning`]s of source code which were collected instead of
+/// being printed.
+#[derive(Default)]
+struct Collected {
+    /// The results of top-level expressions.
+    results: Vec<interpret::Value>,
+
+    /// The [`Warning`]s.
+    warnings: Vec<Warning>,
+}
+
+/// Runs source code from an [`Origin`] with [`Globals`] in a [`Mode`] and a
+/// [`GlobalMode`]. The results of top-level expressions and any [`Warning`]s
+/// are appended to [`Collected`] if it is given. Otherwise, results are printed
+/// and [`Warning`]s are printed with their location in the [`Origin`]. This
+/// function returns every [`ClacError`] which was reported if the source code
+/// could not be run.
+fn try_run_source(
+    source: &str,
+    origin: Origin<'_>,
+    globals: &mut Globals,
+    mode: Mode,
+    global_mode: GlobalMode,
+    mut collected: Option<&mut Collected>,
+) -> Result<(), Vec<ClacError>> {
+    // Warnings and errors from before interpreting are located in this source
+    // code.
+    let shared_source: Rc<str> = source.into();
+    let at = |span| SourceSpan {
+        source: Rc::clone(&shared_source),
+        span,
+    };
+
+    if mode == Mode::DumpTokens {
+        return dump_tokens(source, globals)
+            .map_err(|(e, span)| vec![ClacError::from(e).at(at(span))]);
+    }
+
+    let settings = *globals.settings();
+    let ast =
+        parse::parse_source(source, &settings, globals.operators_mut()).map_err(|errors| {
+            let errors = errors.into_iter().map(|e| {
+                let span = e.span();
+                ClacError::from(e).at(at(span))
+            });
+
+            errors.collect::<Vec<_>>()
+        })?;
+
+    settings
+        .limits
+        .check_depth(&ast)
+        .map_err(|e| vec![e.into()])?;
+
+    if mode == Mode::DumpAst {
+        outln!(globals.output(), "{ast}");
+        return Ok(());
+    }
+
+    let mut locals = LocalTable::new();
+    let mut warnings = Vec::new();
+    let hir = lower::lower_ast(&ast, globals, &mut locals, global_mode, &mut warnings);
+
+    match collected.as_deref_mut() {
+        None => {
+            for warning in warnings {
+                errln!(
+                    globals.output(),
+                    "{warning}{}",
+                    origin.locate(Some(&at(warning.span())))
+                );
+            }
+        }
+        Some(collected) => collected.warnings.extend(warnings),
+    }
+
+    let hir = hir.map_err(|errors| {
+        let errors = errors.into_iter().map(|e| {
+            let span = e.span();
+            ClacError::from(e).at(at(span))
+        });
+
+        errors.collect::<Vec<_>>()
+    })?;
+
+    if mode == Mode::DumpHir {
+        outln!(globals.output(), "{}", hir.display(&locals));
+        return Ok(());
+    }
+
+    let cfg = compile::compile_hir(&hir, &locals, &shared_source);
+    let limits = settings.limits;
+    limits
+        .check_instructions(cfg.instruction_count())
+        .map_err(|e| vec![e.into()])?;
+
+    if mode == Mode::DumpCfg {
+        outln!(globals.output(), "{cfg}");
+        return Ok(());
+    }
+
+    if matches!(mode, Mode::Execute) {
+        globals.take_assigned();
+        let result = match collected.map(|c| &mut c.results) {
+            None => interpret::interpret_cfg(&cfg, globals),
+            Some(results) => match globals.statement_hook() {
+                None => interpret::evaluate_cfg(&cfg, globals).map(|r| results.extend(r)),
+                Some(statement_hook) => evaluate_stmts(
+                    &hir,
+                    &locals,
+                    &shared_source,
+                    globals,
+                    &statement_hook,
+                    results,
+                ),
+            },
+        };
+
+        // Record the source code of global variables which were assigned
+        // before any error occurred. The source code is printed from the AST
+        // so that it is written consistently.
+        let mut assignments = hir.global_assignments();
+
+        let assigned = globals.take_assigned();
+
+        for symbol in &assigned {
+            if let Some((_, span)) = assignments.find(|(s, _)| s == symbol) {
+                let source = ast
+                    .stmt(span)
+                    .map_or_else(|| span.slice(source).to_owned(), Expr::to_source);
+
+                globals.set_source(*symbol, &source);
+            }
+        }
+
+        if settings.reactive {
+            reactive::record_formulas(&hir, &locals, &shared_source, globals, &assigned);
+        } else {
+            for symbol in &assigned {
+                globals.remove_formula(*symbol);
+            }
+        }
+
+        result.map_err(|e| vec![e.into()])?;
+
+        if settings.reactive {
+            reactive::recalculate(globals, &assigned).map_err(|e| vec![e.into()])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluates the top-level statements of [`Hir`] one at a time with
+/// [`Globals`], appending their results to a [`Vec`] and running a
+/// [`StatementHook`] after each statement. The [`Hir`] is given with its
+/// [`LocalTable`] and source code. This function returns an
+/// [`InterpretError`] if an error occurred.
+fn evaluate_stmts(
+    hir: &Hir,
+    locals: &LocalTable,
+    source: &Rc<str>,
+    globals: &mut Globals,
+    statement_hook: &StatementHook,
+    results: &mut Vec<interpret::Value>,
+) -> Result<(), InterpretError> {
+    for stmt in &hir.0 {
+        let cfg = compile::compile_stmt(stmt, locals, source);
+        let stmt_results = interpret::evaluate_cfg(&cfg, globals)?;
+        statement_hook(stmt_results.last());
+        results.extend(stmt_results);
+    }
+
+    Ok(())
+}
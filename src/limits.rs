@@ -0,0 +1,127 @@
+use std::env;
+
+use thiserror::Error;
+
+use crate::ast::Ast;
+
+/// Limits on the size of source code and compiled code. Source code which
+/// exceeds a limit is rejected before it is run, so that untrusted source code
+/// can be bounded at compile time. The number of errors reported for rejected
+/// source code, the depth of nested function calls, the number of steps run,
+/// and the length of lists and strings are also limited.
+#[derive(Clone, Copy, Default)]
+pub struct Limits {
+    /// The maximum number of tokens, if any.
+    pub tokens: Option<usize>,
+
+    /// The maximum nesting depth, if any. The nesting depth is the greatest
+    /// number of brackets around any part of the source code (see
+    /// [`Expr::depth`][crate::ast::Expr::depth]).
+    pub depth: Option<usize>,
+
+    /// The maximum number of compiled instructions, if any.
+    pub instructions: Option<usize>,
+
+    /// The maximum number of errors reported from one pass, if not
+    /// [`DEFAULT_MAX_ERRORS`].
+    pub errors: Option<usize>,
+
+    /// The maximum number of nested function calls, if not
+    /// [`DEFAULT_MAX_RECURSION`].
+    pub recursion: Option<usize>,
+
+    /// The maximum number of steps run by one evaluation, if any. A step is
+    /// one basic block of compiled code, so every loop iteration and function
+    /// call takes at least one step.
+    pub steps: Option<usize>,
+
+    /// The maximum length of a list or string built by code, if not
+    /// [`DEFAULT_MAX_LENGTH`]. Lists are measured in elements and strings are
+    /// measured in bytes.
+    pub length: Option<usize>,
+}
+
+impl Limits {
+    /// `Limits` with no maximums.
+    pub const UNLIMITED: Self = Self {
+        tokens: None,
+        depth: None,
+        instructions: None,
+        errors: None,
+        recursion: None,
+        steps: None,
+        length: None,
+    };
+
+    /// Returns the maximum number of errors reported from one pass. Errors
+    /// after the maximum are discarded so that a long line of broken code does
+    /// not flood the output.
+    pub fn max_errors(self) -> usize {
+        self.errors.unwrap_or(DEFAULT_MAX_ERRORS)
+    }
+
+    /// Returns the maximum number of nested function calls. Calls past the
+    /// maximum are stopped with an error instead of growing the stack until
+    /// Clac runs out of memory.
+    pub fn max_recursion(self) -> usize {
+        self.recursion.unwrap_or(DEFAULT_MAX_RECURSION)
+    }
+
+    /// Returns the maximum length of a list or string built by code. Longer
+    /// values are stopped with an error instead of growing until Clac runs out
+    /// of memory, which can happen within a few steps because each step can
+    /// double a value's length.
+    pub fn max_length(self) -> usize {
+        self.length.unwrap_or(DEFAULT_MAX_LENGTH)
+    }
+
+    /// Checks an [`Ast`]'s nesting depth against the `Limits`. This function
+    /// returns a [`LimitError`] if the [`Ast`] is nested too deeply.
+    pub fn check_depth(self, ast: &Ast) -> Result<(), LimitError> {
+        match self.depth {
+            Some(max) if ast.depth() > max => Err(LimitError::TooDeep(max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a number of compiled instructions against the `Limits`. This
+    /// function returns a [`LimitError`] if there are too many instructions.
+    pub const fn check_instructions(self, count: usize) -> Result<(), LimitError> {
+        match self.instructions {
+            Some(max) if count > max => Err(LimitError::TooManyInstructions(max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The default maximum number of errors reported from one pass.
+const DEFAULT_MAX_ERRORS: usize = 10;
+
+/// The default maximum number of nested function calls.
+const DEFAULT_MAX_RECURSION: usize = 100_000;
+
+/// The default maximum length of a list or string built by code.
+const DEFAULT_MAX_LENGTH: usize = 10_000_000;
+
+/// The environment variable which sets the maximum number of nested function
+/// calls if it is not set on the command line.
+pub const RECURSION_VAR: &str = "CLAC_MAX_RECURSION";
+
+/// An error caught when compiled code exceeds a limit.
+#[derive(Debug, Error)]
+pub enum LimitError {
+    /// Source code was nested more deeply than the maximum.
+    #[error("code is nested more than {0} levels deep")]
+    TooDeep(usize),
+
+    /// Compiled code had more instructions than the maximum.
+    #[error("code compiles to more than {0} instructions")]
+    TooManyInstructions(usize),
+}
+
+/// Reads the maximum number of nested function calls from the
+/// [`RECURSION_VAR`] environment variable. This function returns [`None`] if
+/// the environment variable is not set to a valid number.
+pub fn read_recursion_var() -> Option<usize> {
+    env::var(RECURSION_VAR).ok()?.trim().parse().ok()
+}
@@ -1,8 +1,18 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::symbols::Symbol;
+
 /// A unique identifier for a local variable.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct Local(usize);
 
+impl Display for Local {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
 /// A table of [`Local`]s.
 #[derive(Default)]
 pub struct LocalTable {
@@ -26,9 +36,10 @@ impl LocalTable {
         &mut self.data[local.0]
     }
 
-    /// Declares a new [`Local`] at a function depth.
-    pub fn declare_local(&mut self, function_depth: usize) -> Local {
+    /// Declares a new [`Local`] with a [`Symbol`] at a function depth.
+    pub fn declare_local(&mut self, symbol: Symbol, function_depth: usize) -> Local {
         self.data.push(Data {
+            symbol,
             function_depth,
             is_upvar: false,
         });
@@ -39,6 +50,9 @@ impl LocalTable {
 
 /// A [`Local`]'s data.
 pub struct Data {
+    /// The [`Symbol`] the [`Local`] was declared with.
+    pub symbol: Symbol,
+
     /// The function depth where the [`Local`] is declared.
     pub function_depth: usize,
 
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::symbols::Symbol;
+use crate::{interpret::Arity, symbols::Symbol};
 
 /// A kind of [`LowerError`][super::LowerError].
 #[derive(Debug, Error)]
@@ -9,22 +9,69 @@ pub enum ErrorKind {
     #[error("{0}")]
     UsedStmt(ExprArea),
 
-    /// A tuple was used as a standalone value.
-    #[error("tuple values are not supported")]
-    TupleValue,
-
     /// An invalid target was assigned to.
     #[error("can only assign to variables and function signatures")]
     InvalidAssignTarget,
 
+    /// A tuple was unpacked into a target which is not a variable.
+    #[error("tuples can only be unpacked into variables")]
+    InvalidUnpackTarget,
+
     /// A function was defined without an identifier name.
     #[error("function names must be identifiers")]
     InvalidFunctionName,
 
-    /// A function was defined without an identifier parameter.
-    #[error("function parameters must be identifiers")]
+    /// A function was defined with a parameter which is not an identifier or
+    /// a tuple of identifiers.
+    #[error("function parameters must be identifiers or tuples of identifiers")]
     InvalidParam,
 
+    /// A custom operator was defined without exactly two parameters.
+    #[error("custom operators must have exactly two parameters")]
+    InvalidOperatorParams,
+
+    /// A guard was used outside of a function definition.
+    #[error("guards can only be used in function definitions")]
+    MisplacedGuard,
+
+    /// A function's clauses did not end with a clause which always matches.
+    #[error("clauses of function '{0}' are not exhaustive, add an 'otherwise' clause")]
+    NonExhaustiveClauses(Symbol),
+
+    /// A function had clauses after a clause which always matches.
+    #[error("clauses of function '{0}' after a clause which always matches are unreachable")]
+    UnreachableClause(Symbol),
+
+    /// A function's clauses had different numbers of parameters.
+    #[error("clauses of function '{0}' must have the same number of parameters")]
+    MismatchedClauseParams(Symbol),
+
+    /// A function clause had a parameter pattern which is not an identifier, a
+    /// tuple, or a literal.
+    #[error("function parameter patterns must be identifiers, tuples, or literals")]
+    InvalidPattern,
+
+    /// A loop variable was defined without an identifier and a value.
+    #[error("loop variables must be defined with an identifier and a value")]
+    InvalidLoopVariable,
+
+    /// A `recur` expression was used outside of a loop.
+    #[error("'recur' can only be used inside a loop")]
+    MisplacedRecur,
+
+    /// A `recur` expression was not the last expression evaluated by a loop.
+    #[error("'recur' can only be used as the value of a loop")]
+    NonTailRecur,
+
+    /// A `recur` expression did not have a value for each loop variable.
+    #[error("'recur' must have a value for each loop variable")]
+    IncorrectRecurArity,
+
+    /// A global function was called with a number of arguments it does not
+    /// accept.
+    #[error("function '{0}' expects {1} argument(s), but {2} were given")]
+    IncorrectCallArity(Symbol, Arity, usize),
+
     /// A function was defined with a duplicate parameter.
     #[error("function parameter '{0}' is duplicated")]
     DuplicateParam(Symbol),
@@ -33,9 +80,28 @@ pub enum ErrorKind {
     #[error("variable '{0}' is already defined")]
     AlreadyDefinedVariable(Symbol),
 
+    /// A global variable bound to a native function was redefined without
+    /// allowing native overrides.
+    #[error("native function '{0}' cannot be redefined without '--allow-native-overrides'")]
+    ProtectedNative(Symbol),
+
     /// An undefined variable was used.
     #[error("variable '{0}' is undefined")]
     UndefinedVariable(Symbol),
+
+    /// An undefined variable was updated with a compound assignment.
+    #[error("cannot update undefined variable '{0}', define it with '=' first")]
+    UpdatedUndefinedVariable(Symbol),
+
+    /// Code quoted by a native function used a local variable from outside of
+    /// the code.
+    #[error("code quoted by '{0}' cannot use local variable '{1}'")]
+    QuotedLocal(Symbol, Symbol),
+
+    /// A function passed to `pmap` used a global variable other than a native
+    /// function.
+    #[error("functions passed to 'pmap' run on new threads and cannot use global variable '{0}'")]
+    ParallelGlobal(Symbol),
 }
 
 /// An area where an expression must be used instead of a statement.
@@ -53,6 +119,18 @@ pub enum ExprArea {
     #[error("functions must return a value")]
     FunctionBody,
 
+    /// A `let` expression's body.
+    #[error("let expressions must have a value")]
+    LetBody,
+
+    /// A `lazy` expression's value.
+    #[error("lazy expressions must have a value")]
+    Lazy,
+
+    /// A loop's body.
+    #[error("loops must have a value")]
+    LoopBody,
+
     /// A callee.
     #[error("statements cannot be called")]
     Callee,
@@ -61,6 +139,14 @@ pub enum ExprArea {
     #[error("statements cannot be used as call arguments")]
     Arg,
 
+    /// A list element.
+    #[error("statements cannot be used as list elements")]
+    ListElement,
+
+    /// A tuple element.
+    #[error("statements cannot be used as tuple elements")]
+    TupleElement,
+
     /// An operand.
     #[error("statements cannot be used as operands")]
     Operand,
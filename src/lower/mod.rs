@@ -1,16 +1,23 @@
 mod errors;
 mod scopes;
 
-use std::slice;
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    rc::Rc,
+    slice,
+};
 
 use thiserror::Error;
 
 use crate::{
-    ast::{Ast, BinOp, Expr, Literal, LogicOp, UnOp},
+    ast::{Ast, BinOp, Expr, ExprKind, Literal, LogicOp, UnOp},
     hir::{self, Hir},
-    interpret::Globals,
-    locals::LocalTable,
+    interpret::{Arity, Globals, Native, Value},
+    locals::{Local, LocalTable},
+    spans::Span,
     symbols::Symbol,
+    warnings::{Warning, WarningKind},
 };
 
 use self::{
@@ -18,15 +25,41 @@ use self::{
     scopes::{ScopeStack, Variable},
 };
 
-/// An error caught while lowering an [`Ast`].
+/// An error caught while lowering an [`Ast`] with the [`Span`] where it
+/// occurred.
 #[derive(Debug, Error)]
-#[repr(transparent)]
-#[error(transparent)]
-pub struct LowerError(Box<ErrorKind>);
+#[error("{0}")]
+pub struct LowerError(Box<ErrorKind>, Span);
+
+impl LowerError {
+    /// Returns the [`Span`] where the `LowerError` occurred.
+    pub const fn span(&self) -> Span {
+        self.1
+    }
+}
+
+/// A rule for defining global variables which are already defined.
+#[derive(Clone, Copy)]
+pub enum GlobalMode {
+    /// Global variables can only be defined once.
+    Sealed,
+
+    /// Global variables can be redefined with a [`Warning`].
+    Redefinable,
+}
 
-/// Lower an [`Ast`] to [`Hir`] with [`Globals`] and a [`LocalTable`]. This
-/// function returns a [`LowerError`] if the [`Ast`] could not be lowered.
-pub fn lower_ast(ast: &Ast, globals: &Globals, locals: &mut LocalTable) -> Result<Hir, LowerError> {
+/// Lower an [`Ast`] to [`Hir`] with [`Globals`], a [`LocalTable`], and a
+/// [`GlobalMode`]. Undefined global variables which are read are assigned
+/// from the [`Globals`]' provider if it has them. Any [`Warning`]s are
+/// appended to a [`Vec`]. This function returns every [`LowerError`], up to
+/// the maximum number of errors, if the [`Ast`] could not be lowered.
+pub fn lower_ast(
+    ast: &Ast,
+    globals: &mut Globals,
+    locals: &mut LocalTable,
+    global_mode: GlobalMode,
+    warnings: &mut Vec<Warning>,
+) -> Result<Hir, Vec<LowerError>> {
     let mut scopes = ScopeStack::new(locals);
 
     for symbol in globals.symbols() {
@@ -38,7 +71,35 @@ pub fn lower_ast(ast: &Ast, globals: &Globals, locals: &mut LocalTable) -> Resul
         );
     }
 
-    let mut lowerer = Lowerer::new(scopes);
+    let arities = globals
+        .symbols()
+        .filter_map(|s| Some((s, globals.arity(s)?)))
+        .collect();
+
+    // Native functions can only be redefined by accident if globals are
+    // redefinable, unless the session's settings allow it.
+    let protected_natives = match global_mode {
+        GlobalMode::Redefinable if !globals.settings().native_overrides => globals
+            .symbols()
+            .filter(|s| globals.is_native(*s))
+            .collect(),
+        _ => HashSet::new(),
+    };
+
+    let quoting_natives = globals
+        .symbols()
+        .filter_map(|s| Some((s, globals.native(s)?)))
+        .filter(|(_, native)| native.quoted_arg_count() > 0)
+        .collect();
+
+    let mut lowerer = Lowerer::new(
+        scopes,
+        globals,
+        global_mode,
+        arities,
+        protected_natives,
+        quoting_natives,
+    );
     let ir = lowerer.lower_ast(ast);
 
     debug_assert!(
@@ -46,24 +107,89 @@ pub fn lower_ast(ast: &Ast, globals: &Globals, locals: &mut LocalTable) -> Resul
         "scope stack should be empty after lowering"
     );
 
-    lowerer.error.map_or(Ok(ir), Err)
+    warnings.append(&mut lowerer.warnings);
+    let errors = lowerer.errors;
+
+    for (symbol, value) in lowerer.provided {
+        globals.assign(symbol, value);
+    }
+
+    if errors.is_empty() {
+        Ok(ir)
+    } else {
+        Err(errors)
+    }
 }
 
 /// A structure which lowers an [`Ast`] to [`Hir`].
-struct Lowerer<'loc> {
+struct Lowerer<'loc, 'glb> {
     /// The [`ScopeStack`].
     scopes: ScopeStack<'loc>,
 
-    /// The first [`LowerError`], if any.
-    error: Option<LowerError>,
+    /// The [`Globals`], which provide undefined global variables.
+    globals: &'glb Globals,
+
+    /// The [`Symbol`]s and [`Value`]s of global variables which were provided
+    /// while lowering.
+    provided: Vec<(Symbol, Value)>,
+
+    /// The [`GlobalMode`].
+    global_mode: GlobalMode,
+
+    /// The [`LowerError`]s, up to the maximum number of errors.
+    errors: Vec<LowerError>,
+
+    /// The [`Span`] of the [`Expr`] being lowered, which errors are reported
+    /// at.
+    span: Span,
+
+    /// The [`Warning`]s.
+    warnings: Vec<Warning>,
+
+    /// The map of global function [`Symbol`]s to their known [`Arity`]s.
+    global_arities: HashMap<Symbol, Arity>,
+
+    /// The [`Symbol`]s of global native functions which cannot be redefined.
+    protected_natives: HashSet<Symbol>,
+
+    /// The map of [`Symbol`]s of global native functions whose arguments are
+    /// quoted to their [`Native`]s.
+    quoting_natives: HashMap<Symbol, Native>,
+
+    /// The stack of variable counts of loops which can be recurred to, or
+    /// [`None`] for function boundaries.
+    loop_arities: Vec<Option<usize>>,
+
+    /// The [`WhileBody`] of the innermost while loop being lowered, if any.
+    while_body: Option<WhileBody>,
 }
 
-impl<'loc> Lowerer<'loc> {
-    /// Creates a new `Lowerer` from a [`ScopeStack`].
-    const fn new(scopes: ScopeStack<'loc>) -> Self {
+impl<'loc, 'glb> Lowerer<'loc, 'glb> {
+    /// Creates a new `Lowerer` from a [`ScopeStack`], [`Globals`], a
+    /// [`GlobalMode`], the known [`Arity`]s of global functions, the
+    /// [`Symbol`]s of global native functions which cannot be redefined, and
+    /// the numbers of quoted arguments of global native functions.
+    const fn new(
+        scopes: ScopeStack<'loc>,
+        globals: &'glb Globals,
+        global_mode: GlobalMode,
+        global_arities: HashMap<Symbol, Arity>,
+        protected_natives: HashSet<Symbol>,
+        quoting_natives: HashMap<Symbol, Native>,
+    ) -> Self {
         Self {
             scopes,
-            error: None,
+            globals,
+            provided: Vec::new(),
+            global_mode,
+            errors: Vec::new(),
+            span: Span::new(0, 0),
+            warnings: Vec::new(),
+            global_arities,
+            protected_natives,
+            quoting_natives,
+            loop_arities: Vec::new(),
+            while_body: None,
         }
     }
 
@@ -76,11 +202,66 @@ impl<'loc> Lowerer<'loc> {
     /// Lowers a sequence of statement [`Expr`]s to a sequence of
     /// [`hir::Stmt`]s.
     fn lower_sequence(&mut self, stmts: &[Expr]) -> Vec<hir::Stmt> {
-        let mut lowered_stmts = Vec::with_capacity(stmts.len());
+        self.lower_sequence_tail(stmts, None)
+    }
 
-        for stmt in stmts {
-            let stmt = self.lower_stmt(stmt);
+    /// Lowers a sequence of statement [`Expr`]s to a sequence of
+    /// [`hir::Stmt`]s, optionally ending with a recur [`hir::Expr`] of the
+    /// current values of a while loop's variable [`Symbol`]s.
+    fn lower_sequence_tail(&mut self, stmts: &[Expr], tail: Option<&[Symbol]>) -> Vec<hir::Stmt> {
+        // Local while loops take the rest of their sequence as their exit, so
+        // that it can use the final values of their variables.
+        if !self.scopes.is_global_scope()
+            && let Some(index) = stmts.iter().position(|s| while_parts(s).is_some())
+            && let Some((cond, body)) = while_parts(&stmts[index])
+        {
+            let mut lowered_stmts = self.lower_sequence(&stmts[..index]);
+            let span = stmts[index].span;
+            let outer_span = mem::replace(&mut self.span, span);
+            let stmt = self.lower_stmt_while(cond, body, &stmts[index + 1..], tail, span);
+            self.span = outer_span;
             lowered_stmts.push(stmt);
+            return lowered_stmts;
+        }
+
+        let mut lowered_stmts = Vec::with_capacity(stmts.len());
+
+        // Consecutive clauses of the same function are merged into a single
+        // function definition when they have guards or literal patterns.
+        let groups = stmts.chunk_by(|a, b| {
+            let (Some(a), Some(b)) = (Clause::from_stmt(a), Clause::from_stmt(b)) else {
+                return false;
+            };
+
+            a.name == b.name && (a.is_merged() || b.is_merged())
+        });
+
+        for group in groups {
+            let clauses: Vec<Clause<'_>> = group.iter().filter_map(Clause::from_stmt).collect();
+
+            if let (Some(first), Some(last)) = (group.first(), group.last())
+                && clauses.iter().any(Clause::is_merged)
+            {
+                let span = first.span.to(last.span);
+                let outer_span = mem::replace(&mut self.span, span);
+                let stmt = self.lower_stmt_clauses(&clauses, span);
+                self.span = outer_span;
+                lowered_stmts.push(stmt);
+                continue;
+            }
+
+            for stmt in group {
+                let stmt = self.lower_stmt(stmt);
+                lowered_stmts.push(stmt);
+            }
+        }
+
+        if let Some(symbols) = tail {
+            let args = symbols
+                .iter()
+                .map(|s| self.lower_expr_variable(*s, self.span));
+            let recur = hir::Expr::Recur(args.collect());
+            lowered_stmts.push(hir::Stmt::Expr(Box::new(recur)));
         }
 
         lowered_stmts
@@ -103,45 +284,104 @@ impl<'loc> Lowerer<'loc> {
     /// Lowers an [`Expr`] to an [`hir::Expr`] in an [`ExprArea`].
     fn lower_expr(&mut self, expr: &Expr, area: ExprArea) -> hir::Expr {
         match self.lower_node(expr) {
-            Node::Stmt(_) => self.error_expr(ErrorKind::UsedStmt(area)),
+            Node::Stmt(_) => {
+                self.report_error(ErrorKind::UsedStmt(area), expr.span);
+                synthetic_expr()
+            }
             Node::Expr(expr) => expr,
         }
     }
 
-    /// Lowers an [`Expr`] to a [`Node`].
+    /// Lowers an [`Expr`] to a [`Node`]. Errors are reported at the [`Expr`]'s
+    /// [`Span`] unless a nested [`Expr`] is being lowered.
     fn lower_node(&mut self, expr: &Expr) -> Node {
-        let expr = match expr {
-            Expr::Literal(literal) => hir::Expr::Literal(*literal),
-            Expr::Variable(symbol) => self.lower_expr_variable(*symbol),
-            Expr::Paren(expr) => self.lower_expr(expr, ExprArea::Paren),
-            Expr::Tuple(_) => self.error_expr(ErrorKind::TupleValue),
-            Expr::Block(stmts) => return self.lower_expr_block(stmts),
-            Expr::Assign(target, source) => return self.lower_expr_assign(target, source).into(),
-            Expr::Function(list, body) => self.lower_expr_function(None, list, body),
-            Expr::Call(callee, list) => self.lower_expr_call(callee, list),
-            Expr::Unary(op, rhs) => self.lower_expr_unary(*op, rhs),
-            Expr::Binary(op, lhs, rhs) => self.lower_expr_binary(*op, lhs, rhs),
-            Expr::Logic(op, lhs, rhs) => self.lower_expr_logic(*op, lhs, rhs),
-            Expr::Cond(cond, then, or) => self.lower_expr_cond(cond, then, or),
+        let outer_span = mem::replace(&mut self.span, expr.span);
+        let node = self.lower_node_kind(expr);
+        self.span = outer_span;
+        node
+    }
+
+    /// Lowers an [`Expr`]'s [`ExprKind`] to a [`Node`].
+    fn lower_node_kind(&mut self, expr: &Expr) -> Node {
+        let span = expr.span;
+
+        let expr = match &expr.kind {
+            ExprKind::Literal(literal) => hir::Expr::Literal(literal.clone()),
+            ExprKind::Variable(symbol) => self.lower_expr_variable(*symbol, span),
+            ExprKind::Paren(expr) => self.lower_expr(expr, ExprArea::Paren),
+            ExprKind::Tuple(elems) => self.lower_expr_tuple(elems),
+            ExprKind::Block(stmts) => return self.lower_expr_block(stmts),
+            ExprKind::Assign(target, source) => {
+                return self.lower_expr_assign(target, source, span).into();
+            }
+            ExprKind::CompoundAssign(op, target, source) => {
+                return self
+                    .lower_stmt_compound_assign(*op, target, source, span)
+                    .into();
+            }
+            ExprKind::Function(list, body) => self.lower_expr_function(None, list, body),
+            ExprKind::Call(callee, list) => self.lower_expr_call(callee, list, span),
+            ExprKind::List(elems) => self.lower_expr_list(elems),
+            ExprKind::Index(list, index) => self.lower_expr_index(list, index, span),
+            ExprKind::Unary(op, rhs) => self.lower_expr_unary(*op, rhs, span),
+            ExprKind::Binary(op, lhs, rhs) => self.lower_expr_binary(*op, lhs, rhs, span),
+            ExprKind::Logic(op, lhs, rhs) => self.lower_expr_logic(*op, lhs, rhs),
+            ExprKind::Let(target, value, body) => self.lower_expr_let(target, value, body),
+            ExprKind::Lazy(expr) => self.lower_expr_lazy(expr),
+            ExprKind::Loop(list, body) => self.lower_expr_loop(list, body),
+            ExprKind::Recur(list) => self.lower_expr_recur(list),
+            ExprKind::While(cond, body) => {
+                return self.lower_stmt_while(cond, body, &[], None, span).into();
+            }
+            ExprKind::Cond(cond, then, or) => self.lower_expr_cond(cond, then, or),
+            ExprKind::Silent(stmt) => return self.lower_stmt_silent(stmt).into(),
+            ExprKind::Guarded(..) => self.error_expr(ErrorKind::MisplacedGuard),
+            ExprKind::Operator(symbol, lhs, rhs) => {
+                self.lower_expr_operator(*symbol, lhs, rhs, span)
+            }
+            ExprKind::DefineOperator(symbol, _, list, body) => {
+                return self
+                    .lower_stmt_define_operator(*symbol, list, body, span)
+                    .into();
+            }
         };
 
         expr.into()
     }
 
+    /// Lowers a silent statement [`Expr`] to an [`hir::Stmt`] which is never
+    /// printed.
+    fn lower_stmt_silent(&mut self, stmt: &Expr) -> hir::Stmt {
+        match self.lower_node(stmt) {
+            Node::Stmt(stmt) => stmt,
+            Node::Expr(expr) => hir::Stmt::Expr(Box::new(expr)),
+        }
+    }
+
     /// Lowers a variable [`Expr`] to an [`hir::Expr`].
-    fn lower_expr_variable(&mut self, symbol: Symbol) -> hir::Expr {
+    fn lower_expr_variable(&mut self, symbol: Symbol, span: Span) -> hir::Expr {
         match self.scopes.variable(symbol) {
-            None => self.error_expr(ErrorKind::UndefinedVariable(symbol)),
-            Some(Variable::Global) => hir::Expr::Global(symbol),
+            None => match self.globals.provided(symbol) {
+                Some(value) => {
+                    self.scopes.declare_global(symbol);
+                    self.provided.push((symbol, value));
+                    hir::Expr::Global(symbol, span)
+                }
+                None => self.error_expr(ErrorKind::UndefinedVariable(symbol)),
+            },
+            Some(Variable::Global) => hir::Expr::Global(symbol, span),
             Some(Variable::Local(local)) => hir::Expr::Local(local),
         }
     }
 
     /// Lowers a block [`Expr`] to a [`Node`].
     fn lower_expr_block(&mut self, stmts: &[Expr]) -> Node {
+        // Compound assignments may push extra scopes which are popped with the
+        // block's scope.
+        let depth = self.scopes.depth();
         self.scopes.push_block_scope();
         let mut stmts = self.lower_sequence(stmts);
-        self.scopes.pop_block_scope();
+        self.scopes.truncate(depth);
 
         match stmts.pop() {
             None => hir::Stmt::Block(Box::new([])).into(),
@@ -154,73 +394,473 @@ impl<'loc> Lowerer<'loc> {
     }
 
     /// Lowers an assignment [`Expr`] to an [`hir::Stmt`].
-    fn lower_expr_assign(&mut self, target: &Expr, source: &Expr) -> hir::Stmt {
-        let (symbol, value) = match target {
-            Expr::Variable(symbol) => {
+    fn lower_expr_assign(&mut self, target: &Expr, source: &Expr, span: Span) -> hir::Stmt {
+        let (symbol, value) = match &target.kind {
+            ExprKind::Variable(symbol) => {
                 let value = self.lower_expr(source, ExprArea::AssignSource);
                 (*symbol, value)
             }
-            Expr::Call(callee, list) => {
-                let Expr::Variable(symbol) = callee.as_ref() else {
+            ExprKind::Call(callee, list) => {
+                let ExprKind::Variable(symbol) = callee.kind else {
                     return self.error_stmt(ErrorKind::InvalidFunctionName);
                 };
 
-                let symbol = *symbol;
                 let value = self.lower_expr_function(Some(symbol), list, source);
                 (symbol, value)
             }
+            ExprKind::Tuple(targets) => return self.lower_stmt_unpack(targets, source, span),
             _ => return self.error_stmt(ErrorKind::InvalidAssignTarget),
         };
 
-        match self.scopes.declare_variable(symbol) {
-            None => self.error_stmt(ErrorKind::AlreadyDefinedVariable(symbol)),
-            Some(Variable::Global) => hir::Stmt::AssignGlobal(symbol, Box::new(value)),
-            Some(Variable::Local(local)) => hir::Stmt::DefineLocal(local, Box::new(value)),
+        self.define_variable(symbol, value, span)
+    }
+
+    /// Lowers a tuple assignment which unpacks a source [`Expr`] into target
+    /// variable [`Expr`]s to an [`hir::Stmt`].
+    fn lower_stmt_unpack(&mut self, targets: &[Expr], source: &Expr, span: Span) -> hir::Stmt {
+        let mut symbols = Vec::with_capacity(targets.len());
+
+        for target in targets {
+            let ExprKind::Variable(symbol) = target.kind else {
+                return self.error_stmt(ErrorKind::InvalidUnpackTarget);
+            };
+
+            symbols.push(symbol);
+        }
+
+        let is_global_scope = self.scopes.is_global_scope();
+        let value = self.lower_expr(source, ExprArea::AssignSource);
+        let value = hir::Expr::Unpack(Box::new(value), symbols.len(), span);
+
+        // The tuple is stored in a hidden local so that its source is only
+        // evaluated once.
+        let tuple = self.scopes.declare_hidden_local(Symbol::intern("tuple"));
+        let mut stmts = vec![hir::Stmt::DefineLocal(tuple, Box::new(value))];
+
+        for (index, symbol) in symbols.into_iter().enumerate() {
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "tuples are not long enough to lose precision"
+            )]
+            let index = hir::Expr::Literal(Literal::Number(index as f64));
+
+            let tuple = Box::new(hir::Expr::Local(tuple));
+            let elem = hir::Expr::Index(tuple, Box::new(index), span);
+            let stmt = self.define_variable(symbol, elem, span);
+            stmts.push(stmt);
+        }
+
+        // Global variables are assigned in a block so that the hidden local is
+        // popped, but local variables must stay in the current scope.
+        if is_global_scope {
+            hir::Stmt::Block(stmts.into_boxed_slice())
+        } else {
+            hir::Stmt::Sequence(stmts.into_boxed_slice())
         }
     }
 
-    /// Lowers a function [`Expr`] to an [`hir::Expr`].
-    fn lower_expr_function(&mut self, name: Option<Symbol>, list: &Expr, body: &Expr) -> hir::Expr {
-        self.scopes.push_function_scope();
+    /// Returns the [`ErrorKind`] for updating a defined global variable from
+    /// its [`Symbol`] with a compound assignment or a while loop, if it cannot
+    /// be updated. Updating a global variable is allowed in every
+    /// [`GlobalMode`], but native functions can only be redefined when they
+    /// are not protected.
+    fn global_update_error(&self, symbol: Symbol) -> Option<ErrorKind> {
+        if self.protected_natives.contains(&symbol) {
+            Some(ErrorKind::ProtectedNative(symbol))
+        } else if matches!(self.global_mode, GlobalMode::Sealed) && self.globals.is_native(symbol) {
+            Some(ErrorKind::AlreadyDefinedVariable(symbol))
+        } else {
+            None
+        }
+    }
 
-        let name = name.map(|s| {
-            let Some(Variable::Local(local)) = self.scopes.declare_variable(s) else {
-                unreachable!("there should be an empty function scope");
+    /// Lowers a compound assignment [`Expr`] to an [`hir::Stmt`] which updates
+    /// an already defined variable with a [`BinOp`].
+    fn lower_stmt_compound_assign(
+        &mut self,
+        op: BinOp,
+        target: &Expr,
+        source: &Expr,
+        span: Span,
+    ) -> hir::Stmt {
+        let ExprKind::Variable(symbol) = target.kind else {
+            return self.error_stmt(ErrorKind::InvalidAssignTarget);
+        };
+
+        let Some(variable) = self.scopes.variable(symbol) else {
+            return self.error_stmt(ErrorKind::UpdatedUndefinedVariable(symbol));
+        };
+
+        let value = self.lower_expr_binary(op, target, source, span);
+
+        match variable {
+            Variable::Global => {
+                if let Some(error) = self.global_update_error(symbol) {
+                    return self.error_stmt(error);
+                }
+
+                self.global_arities.remove(&symbol);
+                hir::Stmt::AssignGlobal(symbol, Box::new(value), span)
+            }
+            Variable::Local(_)
+                if self.while_body.as_ref().is_some_and(|b| {
+                    b.depth == self.scopes.depth() && b.symbols.contains(&symbol)
+                }) =>
+            {
+                self.define_variable(symbol, value, span)
+            }
+            Variable::Local(_) => {
+                // Local variables are immutable, so the updated value is bound
+                // to a new local in a new scope.
+                self.scopes.push_block_scope();
+
+                let Some(Variable::Local(local)) = self.scopes.declare_variable(symbol) else {
+                    unreachable!("there should be an empty block scope");
+                };
+
+                hir::Stmt::DefineLocal(local, Box::new(value))
+            }
+        }
+    }
+
+    /// Lowers a function's [`Clause`]s spanning a [`Span`] to a single
+    /// function definition [`hir::Stmt`].
+    fn lower_stmt_clauses(&mut self, clauses: &[Clause<'_>], span: Span) -> hir::Stmt {
+        let Some(first) = clauses.first() else {
+            unreachable!("there should be at least one clause");
+        };
+
+        let name = first.name;
+        let arity = first.params().len();
+
+        if clauses.iter().any(|c| c.params().len() != arity) {
+            return self.error_definition(name, ErrorKind::MismatchedClauseParams(name), span);
+        }
+
+        match clauses.iter().position(|c| !c.is_refutable()) {
+            None => {
+                return self.error_definition(name, ErrorKind::NonExhaustiveClauses(name), span);
+            }
+            Some(index) if index + 1 < clauses.len() => {
+                return self.error_definition(name, ErrorKind::UnreachableClause(name), span);
+            }
+            Some(_) => {}
+        }
+
+        let function = self.push_function(Some(name));
+
+        // The function's parameters are hidden so that each clause can bind
+        // its own parameter names.
+        let params: Vec<Local> = (0..arity)
+            .map(|index| {
+                let symbol = clauses
+                    .iter()
+                    .find_map(|c| match c.params().get(index)?.kind {
+                        ExprKind::Variable(symbol) => Some(symbol),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| Symbol::intern("_"));
+
+                self.scopes.declare_hidden_local(symbol)
+            })
+            .collect();
+
+        let body = self.lower_clause_chain(clauses, &params);
+        self.pop_function();
+        let value = hir::Expr::Function(function, params.into_boxed_slice(), Box::new(body));
+        self.define_variable(name, value, span)
+    }
+
+    /// Lowers [`Clause`]s with a function's parameter [`Local`]s to a chain of
+    /// conditional [`hir::Expr`]s.
+    fn lower_clause_chain(&mut self, clauses: &[Clause<'_>], params: &[Local]) -> hir::Expr {
+        let Some((clause, rest)) = clauses.split_first() else {
+            unreachable!("there should be an irrefutable clause");
+        };
+
+        self.scopes.push_block_scope();
+        let mut bindings = Vec::new();
+        let mut checks = Vec::new();
+
+        for (pattern, &param) in clause.params().iter().zip(params) {
+            if let Some(literal) = pattern_literal(pattern) {
+                let param = Box::new(hir::Expr::Local(param));
+                let literal = Box::new(hir::Expr::Literal(literal));
+                let check = hir::Expr::Binary(BinOp::Equal, param, literal, pattern.span);
+                checks.push((check, pattern.span));
+                continue;
+            }
+
+            self.bind_pattern(pattern, hir::Expr::Local(param), &mut bindings);
+        }
+
+        if let Some(guard) = clause.guard {
+            let check = self.lower_expr(guard, ExprArea::Condition);
+
+            if let Some(value) = const_bool(&check) {
+                self.report_warning(WarningKind::ConstantCondition(value), guard.span);
+            }
+            checks.push((check, guard.span));
+        }
+
+        let value = self.lower_expr(clause.value, ExprArea::FunctionBody);
+        self.scopes.pop_block_scope();
+
+        // Every check must pass for the clause to be chosen.
+        let cond = checks
+            .into_iter()
+            .rev()
+            .reduce(|(then_expr, _), (cond, span)| {
+                let else_expr = hir::Expr::Literal(Literal::Bool(false));
+                let cond = hir::Expr::Cond(
+                    Box::new(cond),
+                    Box::new(then_expr),
+                    Box::new(else_expr),
+                    span,
+                );
+
+                (cond, span)
+            });
+
+        let body = match cond {
+            Some((cond, span)) if !rest.is_empty() => {
+                let else_expr = self.lower_clause_chain(rest, params);
+                hir::Expr::Cond(Box::new(cond), Box::new(value), Box::new(else_expr), span)
+            }
+            _ => value,
+        };
+
+        if bindings.is_empty() {
+            body
+        } else {
+            hir::Expr::Block(bindings.into_boxed_slice(), Box::new(body))
+        }
+    }
+
+    /// Lowers a custom operator definition [`Expr`] to an [`hir::Stmt`].
+    fn lower_stmt_define_operator(
+        &mut self,
+        symbol: Symbol,
+        list: &Expr,
+        body: &Expr,
+        span: Span,
+    ) -> hir::Stmt {
+        if slice_list(list).len() != 2 {
+            return self.error_stmt(ErrorKind::InvalidOperatorParams);
+        }
+
+        let value = self.lower_expr_function(Some(symbol), list, body);
+        self.define_variable(symbol, value, span)
+    }
+
+    /// Defines a variable with a [`Symbol`] and an [`hir::Expr`] value and
+    /// returns its definition [`hir::Stmt`].
+    fn define_variable(&mut self, symbol: Symbol, value: hir::Expr, span: Span) -> hir::Stmt {
+        // While loop variables are rebound in a new scope for each assignment.
+        if let Some(body) = &mut self.while_body
+            && body.depth == self.scopes.depth()
+            && body.symbols.contains(&symbol)
+        {
+            self.scopes.push_block_scope();
+            body.depth += 1;
+
+            let Some(Variable::Local(local)) = self.scopes.declare_variable(symbol) else {
+                unreachable!("there should be an empty block scope");
             };
 
-            local
-        });
+            return hir::Stmt::DefineLocal(local, Box::new(value));
+        }
 
-        self.scopes.push_param_scope();
+        if self.scopes.is_global_scope() {
+            if let hir::Expr::Function(_, params, _) = &value {
+                self.global_arities
+                    .insert(symbol, Arity::exact(params.len()));
+            } else {
+                self.global_arities.remove(&symbol);
+            }
+        }
+
+        let shadowed = self.scopes.shadowed(symbol);
+
+        match self.scopes.declare_variable(symbol) {
+            None if self.scopes.is_global_scope() && self.protected_natives.contains(&symbol) => {
+                self.error_stmt(ErrorKind::ProtectedNative(symbol))
+            }
+            None if self.scopes.is_global_scope()
+                && matches!(self.global_mode, GlobalMode::Redefinable) =>
+            {
+                // Reassigning global variables is expected in reactive mode.
+                if !self.globals.settings().reactive {
+                    self.report_warning(WarningKind::RedefinedVariable(symbol), span);
+                }
+
+                hir::Stmt::AssignGlobal(symbol, Box::new(value), span)
+            }
+            None => self.error_stmt(ErrorKind::AlreadyDefinedVariable(symbol)),
+            Some(Variable::Global) => hir::Stmt::AssignGlobal(symbol, Box::new(value), span),
+            Some(Variable::Local(local)) => {
+                if let Some(shadowed) = shadowed {
+                    self.report_warning(WarningKind::ShadowedVariable(symbol, shadowed), span);
+                }
+
+                hir::Stmt::DefineLocal(local, Box::new(value))
+            }
+        }
+    }
+
+    /// Lowers a function [`Expr`] to an [`hir::Expr`]. Tuple parameters are
+    /// unpacked into their elements' variables before the body.
+    fn lower_expr_function(&mut self, name: Option<Symbol>, list: &Expr, body: &Expr) -> hir::Expr {
         let params = slice_list(list);
-        let mut lowered_params = Vec::with_capacity(params.len());
+        let mut symbols = Vec::new();
 
         for param in params {
-            let Expr::Variable(symbol) = param else {
-                self.scopes.pop_param_scope();
-                self.scopes.pop_function_scope();
+            if !pattern_symbols(param, &mut symbols) {
                 return self.error_expr(ErrorKind::InvalidParam);
-            };
+            }
+        }
 
-            let Some(Variable::Local(local)) = self.scopes.declare_variable(*symbol) else {
-                self.scopes.pop_param_scope();
-                self.scopes.pop_function_scope();
+        for (index, symbol) in symbols.iter().enumerate() {
+            if symbols[..index].contains(symbol) {
                 return self.error_expr(ErrorKind::DuplicateParam(*symbol));
+            }
+        }
+
+        let name = self.push_function(name);
+        let mut lowered_params = Vec::with_capacity(params.len());
+        let mut tuple_params = Vec::new();
+
+        for param in params {
+            let local = if let ExprKind::Variable(symbol) = param.kind {
+                if let Some(shadowed) = self.scopes.shadowed(symbol) {
+                    let warning = WarningKind::ShadowedVariable(symbol, shadowed);
+                    self.report_warning(warning, param.span);
+                }
+
+                let Some(Variable::Local(local)) = self.scopes.declare_variable(symbol) else {
+                    unreachable!("parameters should not be duplicated");
+                };
+
+                local
+            } else {
+                let local = self.scopes.declare_hidden_local(Symbol::intern("tuple"));
+                tuple_params.push((param, local));
+                local
             };
 
             lowered_params.push(local);
         }
 
-        let body = self.lower_expr(body, ExprArea::FunctionBody);
+        let body = if tuple_params.is_empty() {
+            self.lower_expr(body, ExprArea::FunctionBody)
+        } else {
+            self.scopes.push_block_scope();
+            let mut bindings = Vec::new();
+
+            for (param, local) in tuple_params {
+                self.bind_pattern(param, hir::Expr::Local(local), &mut bindings);
+            }
+
+            let body = self.lower_expr(body, ExprArea::FunctionBody);
+            self.scopes.pop_block_scope();
+            hir::Expr::Block(bindings.into_boxed_slice(), Box::new(body))
+        };
+
+        self.pop_function();
+        hir::Expr::Function(name, lowered_params.into_boxed_slice(), Box::new(body))
+    }
+
+    /// Binds a variable or tuple parameter pattern [`Expr`] to a value
+    /// [`hir::Expr`] by appending local variable definitions to bindings.
+    /// Tuple patterns check that the value is a tuple with a matching length,
+    /// and bind each of its elements to their patterns.
+    fn bind_pattern(&mut self, pattern: &Expr, value: hir::Expr, bindings: &mut Vec<hir::Stmt>) {
+        match &pattern.kind {
+            ExprKind::Variable(symbol) => {
+                if let Some(shadowed) = self.scopes.shadowed(*symbol) {
+                    let warning = WarningKind::ShadowedVariable(*symbol, shadowed);
+                    self.report_warning(warning, pattern.span);
+                }
+
+                let Some(Variable::Local(local)) = self.scopes.declare_variable(*symbol) else {
+                    self.report_error(ErrorKind::DuplicateParam(*symbol), self.span);
+                    return;
+                };
+
+                bindings.push(hir::Stmt::DefineLocal(local, Box::new(value)));
+            }
+            ExprKind::Tuple(elems) => {
+                // The tuple is stored in a hidden local so that it can be
+                // indexed for each element.
+                let span = pattern.span;
+                let tuple = self.scopes.declare_hidden_local(Symbol::intern("tuple"));
+                let value = hir::Expr::Unpack(Box::new(value), elems.len(), span);
+                bindings.push(hir::Stmt::DefineLocal(tuple, Box::new(value)));
+
+                for (index, elem) in elems.iter().enumerate() {
+                    #[expect(
+                        clippy::cast_precision_loss,
+                        reason = "tuples are not long enough to lose precision"
+                    )]
+                    let index = hir::Expr::Literal(Literal::Number(index as f64));
+
+                    let tuple = Box::new(hir::Expr::Local(tuple));
+                    let elem_value = hir::Expr::Index(tuple, Box::new(index), span);
+                    self.bind_pattern(elem, elem_value, bindings);
+                }
+            }
+            _ => self.report_error(ErrorKind::InvalidPattern, self.span),
+        }
+    }
+
+    /// Pushes the scopes of a function with an optional name [`Symbol`] and
+    /// returns the name's [`Local`], if any.
+    fn push_function(&mut self, name: Option<Symbol>) -> Option<Local> {
+        self.scopes.push_function_scope();
+
+        let name = name.map(|s| {
+            let Some(Variable::Local(local)) = self.scopes.declare_variable(s) else {
+                unreachable!("there should be an empty function scope");
+            };
+
+            local
+        });
+
+        self.scopes.push_param_scope();
+        self.loop_arities.push(None);
+        name
+    }
+
+    /// Pops the scopes of a function.
+    fn pop_function(&mut self) {
+        self.loop_arities.pop();
         self.scopes.pop_param_scope();
         self.scopes.pop_function_scope();
-        hir::Expr::Function(name, lowered_params.into_boxed_slice(), Box::new(body))
     }
 
     /// Lowers a function call [`Expr`] to an [`hir::Expr`].
-    fn lower_expr_call(&mut self, callee: &Expr, list: &Expr) -> hir::Expr {
+    fn lower_expr_call(&mut self, callee: &Expr, list: &Expr, span: Span) -> hir::Expr {
+        if let Some(args) = self.quoted_args(callee, list) {
+            let callee = self.lower_expr(callee, ExprArea::Callee);
+            return hir::Expr::Call(Box::new(callee), args, span);
+        }
+
         let callee = self.lower_expr(callee, ExprArea::Callee);
         let args = slice_list(list);
+
+        // Functions may be redefined with a different arity before calls in
+        // their bodies run if globals are redefinable.
+        let checks_arity =
+            matches!(self.global_mode, GlobalMode::Sealed) || !self.scopes.is_in_function();
+
+        if checks_arity
+            && let hir::Expr::Global(symbol, _) = callee
+            && let Some(arity) = self.global_arities.get(&symbol).copied()
+            && !arity.accepts(args.len())
+        {
+            return self.error_expr(ErrorKind::IncorrectCallArity(symbol, arity, args.len()));
+        }
+
         let mut lowered_args = Vec::with_capacity(args.len());
 
         for arg in args {
@@ -228,32 +868,181 @@ impl<'loc> Lowerer<'loc> {
             lowered_args.push(arg);
         }
 
-        hir::Expr::Call(Box::new(callee), lowered_args.into_boxed_slice())
+        hir::Expr::Call(Box::new(callee), lowered_args.into_boxed_slice(), span)
+    }
+
+    /// Lowers a list [`Expr`] to an [`hir::Expr`].
+    fn lower_expr_list(&mut self, elems: &[Expr]) -> hir::Expr {
+        let elems = elems
+            .iter()
+            .map(|elem| self.lower_expr(elem, ExprArea::ListElement))
+            .collect();
+
+        hir::Expr::List(elems)
+    }
+
+    /// Lowers a tuple [`Expr`] to an [`hir::Expr`].
+    fn lower_expr_tuple(&mut self, elems: &[Expr]) -> hir::Expr {
+        let elems = elems
+            .iter()
+            .map(|elem| self.lower_expr(elem, ExprArea::TupleElement))
+            .collect();
+
+        hir::Expr::Tuple(elems)
+    }
+
+    /// Lowers an index [`Expr`] to an [`hir::Expr`].
+    fn lower_expr_index(&mut self, list: &Expr, index: &Expr, span: Span) -> hir::Expr {
+        let list = self.lower_expr(list, ExprArea::Operand);
+        let index = self.lower_expr(index, ExprArea::Operand);
+        hir::Expr::Index(Box::new(list), Box::new(index), span)
+    }
+
+    /// Returns a call's arguments with its leading arguments as string literal
+    /// [`hir::Expr`]s of their source code if the callee is a global native
+    /// function whose arguments are quoted. This function returns [`None`] if
+    /// no arguments are quoted.
+    fn quoted_args(&mut self, callee: &Expr, list: &Expr) -> Option<Box<[hir::Expr]>> {
+        let ExprKind::Variable(symbol) = callee.kind else {
+            return None;
+        };
+
+        let native = *self.quoting_natives.get(&symbol)?;
+
+        if !matches!(self.scopes.variable(symbol), Some(Variable::Global)) {
+            return None;
+        }
+
+        let count = native.quoted_arg_count();
+        let args = slice_list(list);
+
+        if native.isolates_quoted_code() {
+            self.check_isolated_code(native, symbol, &args[..count.min(args.len())]);
+        }
+
+        // String arguments are passed unchanged so that code can also be
+        // given as a string.
+        let args = args.iter().enumerate().map(|(index, arg)| match &arg.kind {
+            _ if index >= count => self.lower_expr(arg, ExprArea::Arg),
+            ExprKind::Literal(Literal::Str(source)) => {
+                hir::Expr::Literal(Literal::Str(Rc::clone(source)))
+            }
+            _ => hir::Expr::Literal(Literal::Str(arg.to_source().into())),
+        });
+
+        Some(args.collect())
+    }
+
+    /// Reports an error for each variable used by code quoted by a [`Native`]
+    /// which the code cannot access when it is run. Quoted code cannot use
+    /// local variables, and functions passed to `pmap` can only use native
+    /// functions. Later quoted arguments which are variables name unknowns, so
+    /// they are not read.
+    fn check_isolated_code(&mut self, native: Native, name: Symbol, quoted: &[Expr]) {
+        let Some((code, unknowns)) = quoted.split_first() else {
+            return;
+        };
+
+        let mut used = Vec::new();
+        let mut bound = HashSet::new();
+        collect_quoted_variables(code, false, &mut used, &mut bound);
+
+        for unknown in unknowns {
+            if let ExprKind::Variable(symbol) = unknown.kind {
+                bound.insert(symbol);
+            }
+        }
+
+        for (symbol, span) in used {
+            // Each variable is only reported once.
+            if !bound.insert(symbol) {
+                continue;
+            }
+
+            if self.scopes.is_local(symbol) {
+                self.report_error(ErrorKind::QuotedLocal(name, symbol), span);
+            } else if matches!(native, Native::Pmap)
+                && self.globals.native(symbol).is_none()
+                && (self.scopes.variable(symbol).is_some()
+                    || self.globals.provided(symbol).is_some())
+            {
+                self.report_error(ErrorKind::ParallelGlobal(symbol), span);
+            }
+        }
+    }
+
+    /// Lowers a custom operator [`Expr`] to a call to the operator's function
+    /// [`hir::Expr`].
+    fn lower_expr_operator(
+        &mut self,
+        symbol: Symbol,
+        lhs: &Expr,
+        rhs: &Expr,
+        span: Span,
+    ) -> hir::Expr {
+        let callee = self.lower_expr_variable(symbol, span);
+        let lhs = self.lower_expr(lhs, ExprArea::Operand);
+        let rhs = self.lower_expr(rhs, ExprArea::Operand);
+        hir::Expr::Call(Box::new(callee), Box::new([lhs, rhs]), span)
     }
 
     /// Lowers a unary [`Expr`] to an [`hir::Expr`].
-    fn lower_expr_unary(&mut self, op: UnOp, rhs: &Expr) -> hir::Expr {
+    fn lower_expr_unary(&mut self, op: UnOp, rhs: &Expr, span: Span) -> hir::Expr {
         let rhs = self.lower_expr(rhs, ExprArea::Operand);
-        hir::Expr::Unary(op, Box::new(rhs))
+        hir::Expr::Unary(op, Box::new(rhs), span)
     }
 
     /// Lowers a binary [`Expr`] to an [`hir::Expr`].
-    fn lower_expr_binary(&mut self, op: BinOp, lhs: &Expr, rhs: &Expr) -> hir::Expr {
-        let lhs = self.lower_expr(lhs, ExprArea::Operand);
-        let rhs = self.lower_expr(rhs, ExprArea::Operand);
-        hir::Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+    fn lower_expr_binary(&mut self, op: BinOp, lhs: &Expr, rhs: &Expr, span: Span) -> hir::Expr {
+        // Chains of operations such as `1 + 2 + 3` nest to the left and can be
+        // thousands of operations long, so they are lowered in a loop instead
+        // of recursing into each left operand.
+        let mut operations = vec![(op, rhs, span)];
+        let mut first = lhs;
+
+        if let Some(result) = self_comparison_result(op, lhs, rhs) {
+            self.report_warning(WarningKind::SelfComparison(result), span);
+        }
+
+        while let ExprKind::Binary(inner_op, inner_lhs, inner_rhs) = &first.kind {
+            if let Some(result) = self_comparison_result(*inner_op, inner_lhs, inner_rhs) {
+                self.report_warning(WarningKind::SelfComparison(result), first.span);
+            }
+
+            operations.push((*inner_op, inner_rhs, first.span));
+            first = inner_lhs;
+        }
+
+        let mut chain = self.lower_expr(first, ExprArea::Operand);
+
+        for (link_op, link_rhs, link_span) in operations.into_iter().rev() {
+            let outer_span = mem::replace(&mut self.span, link_span);
+            let operand = self.lower_expr(link_rhs, ExprArea::Operand);
+            self.span = outer_span;
+            chain = hir::Expr::Binary(link_op, Box::new(chain), Box::new(operand), link_span);
+        }
+
+        chain
     }
 
     /// Lowers a short-circuiting logical [`Expr`] to an [`hir::Expr`].
     fn lower_expr_logic(&mut self, op: LogicOp, lhs: &Expr, rhs: &Expr) -> hir::Expr {
+        let (lhs_span, rhs_span) = (lhs.span, rhs.span);
         let lhs = self.lower_expr(lhs, ExprArea::Operand);
         let rhs = self.lower_expr(rhs, ExprArea::Operand);
 
+        for (operand, span) in [(&lhs, lhs_span), (&rhs, rhs_span)] {
+            if let Some(value) = const_bool(operand) {
+                self.report_warning(WarningKind::ConstantCondition(value), span);
+            }
+        }
+
         // HACK: Dynamic type check for right-hand side.
         let rhs = hir::Expr::Binary(
             BinOp::Equal,
             Box::new(rhs),
             Box::new(hir::Expr::Literal(Literal::Bool(true))),
+            rhs_span,
         );
 
         let (then_expr, else_expr) = match op {
@@ -261,39 +1050,384 @@ impl<'loc> Lowerer<'loc> {
             LogicOp::Or => (hir::Expr::Literal(Literal::Bool(true)), rhs),
         };
 
-        hir::Expr::Cond(Box::new(lhs), Box::new(then_expr), Box::new(else_expr))
+        hir::Expr::Cond(
+            Box::new(lhs),
+            Box::new(then_expr),
+            Box::new(else_expr),
+            lhs_span,
+        )
+    }
+
+    /// Lowers a `let` [`Expr`] to a block [`hir::Expr`].
+    fn lower_expr_let(&mut self, target: &Expr, value: &Expr, body: &Expr) -> hir::Expr {
+        self.scopes.push_block_scope();
+        let stmt = self.lower_expr_assign(target, value, target.span.to(value.span));
+        let body = self.lower_expr(body, ExprArea::LetBody);
+        self.scopes.pop_block_scope();
+        hir::Expr::Block(Box::new([stmt]), Box::new(body))
+    }
+
+    /// Lowers a `lazy` [`Expr`] to a lazy [`hir::Expr`] of a function with no
+    /// parameters.
+    fn lower_expr_lazy(&mut self, expr: &Expr) -> hir::Expr {
+        self.push_function(None);
+        let body = self.lower_expr(expr, ExprArea::Lazy);
+        self.pop_function();
+        let function = hir::Expr::Function(None, Box::new([]), Box::new(body));
+        hir::Expr::Lazy(Box::new(function))
+    }
+
+    /// Lowers a `loop` [`Expr`] to a loop [`hir::Expr`].
+    fn lower_expr_loop(&mut self, list: &Expr, body: &Expr) -> hir::Expr {
+        let definitions = slice_list(list);
+        let mut symbols = Vec::with_capacity(definitions.len());
+        let mut values = Vec::with_capacity(definitions.len());
+
+        // Initial values are lowered outside of the loop's scope, so they
+        // cannot refer to other loop variables.
+        for definition in definitions {
+            let ExprKind::Assign(target, value) = &definition.kind else {
+                return self.error_expr(ErrorKind::InvalidLoopVariable);
+            };
+
+            let ExprKind::Variable(symbol) = target.kind else {
+                return self.error_expr(ErrorKind::InvalidLoopVariable);
+            };
+
+            symbols.push((symbol, definition.span));
+            values.push(self.lower_expr(value, ExprArea::AssignSource));
+        }
+
+        self.scopes.push_block_scope();
+        let mut locals = Vec::with_capacity(symbols.len());
+
+        for (symbol, span) in symbols {
+            if let Some(shadowed) = self.scopes.shadowed(symbol) {
+                self.report_warning(WarningKind::ShadowedVariable(symbol, shadowed), span);
+            }
+
+            let Some(Variable::Local(local)) = self.scopes.declare_variable(symbol) else {
+                self.scopes.pop_block_scope();
+                return self.error_expr(ErrorKind::AlreadyDefinedVariable(symbol));
+            };
+
+            locals.push(local);
+        }
+
+        self.loop_arities.push(Some(locals.len()));
+        let body = self.lower_expr(body, ExprArea::LoopBody);
+        self.loop_arities.pop();
+        self.scopes.pop_block_scope();
+
+        if has_non_tail_recur(&body, true) {
+            return self.error_expr(ErrorKind::NonTailRecur);
+        }
+
+        hir::Expr::Loop(
+            locals.into_boxed_slice(),
+            values.into_boxed_slice(),
+            Box::new(body),
+        )
+    }
+
+    /// Lowers a `recur` [`Expr`] to a recur [`hir::Expr`].
+    fn lower_expr_recur(&mut self, list: &Expr) -> hir::Expr {
+        let Some(Some(arity)) = self.loop_arities.last().copied() else {
+            return self.error_expr(ErrorKind::MisplacedRecur);
+        };
+
+        let args = slice_list(list);
+
+        if args.len() != arity {
+            return self.error_expr(ErrorKind::IncorrectRecurArity);
+        }
+
+        let args = args.iter().map(|a| self.lower_expr(a, ExprArea::Arg));
+        hir::Expr::Recur(args.collect())
+    }
+
+    /// Lowers a `while` [`Expr`] spanning a [`Span`] to an [`hir::Stmt`]. The
+    /// variables assigned in the loop's body are rebound for each iteration.
+    /// Local while loops are followed by the rest of their sequence and an
+    /// optional recur tail, which are lowered as the loop's exit. Redefinable
+    /// global variables are assigned their final values instead.
+    fn lower_stmt_while(
+        &mut self,
+        cond: &Expr,
+        body: &Expr,
+        rest: &[Expr],
+        tail: Option<&[Symbol]>,
+        span: Span,
+    ) -> hir::Stmt {
+        let is_global = self.scopes.is_global_scope();
+
+        let ExprKind::Block(body) = &body.kind else {
+            unreachable!("while loop bodies should be blocks");
+        };
+
+        let mut symbols = Vec::new();
+        collect_assigned_symbols(body, &mut symbols);
+        symbols.retain(|s| self.scopes.variable(*s).is_some());
+
+        if is_global && let Some(error) = symbols.iter().find_map(|s| self.global_update_error(*s))
+        {
+            return self.error_stmt(error);
+        }
+
+        let values = symbols.iter().map(|s| self.lower_expr_variable(*s, span));
+        let values: Box<[hir::Expr]> = values.collect();
+        let is_rebinding = self
+            .while_body
+            .as_ref()
+            .is_some_and(|b| b.depth == self.scopes.depth());
+
+        self.scopes.push_block_scope();
+        let loop_depth = self.scopes.depth();
+
+        let locals: Box<[Local]> = symbols
+            .iter()
+            .map(|s| {
+                let Some(Variable::Local(local)) = self.scopes.declare_variable(*s) else {
+                    unreachable!("there should be an empty block scope");
+                };
+
+                local
+            })
+            .collect();
+
+        self.loop_arities.push(None);
+        let cond_span = cond.span;
+        let cond = self.lower_expr(cond, ExprArea::Condition);
+
+        if let Some(value) = const_bool(&cond) {
+            self.report_warning(WarningKind::ConstantCondition(value), cond_span);
+        }
+
+        self.scopes.push_block_scope();
+        let body_depth = self.scopes.depth();
+
+        let outer_body = self.while_body.replace(WhileBody {
+            symbols: symbols.clone(),
+            depth: body_depth,
+        });
+
+        let mut body = self.lower_sequence_tail(body, Some(&symbols));
+        self.while_body = outer_body;
+        self.scopes.truncate(body_depth - 1);
+        self.loop_arities.pop();
+
+        let Some(hir::Stmt::Expr(recur)) = body.pop() else {
+            unreachable!("while loop bodies should end with a recur expression");
+        };
+
+        let body = hir::Expr::Block(body.into_boxed_slice(), recur);
+
+        let (exit, is_value) = if is_global {
+            let stmts = symbols.iter().zip(&locals).map(|(symbol, local)| {
+                self.global_arities.remove(symbol);
+                let value = Box::new(hir::Expr::Local(*local));
+                hir::Stmt::AssignGlobal(*symbol, value, span)
+            });
+
+            let value = Box::new(hir::Expr::Literal(Literal::Bool(false)));
+            (hir::Expr::Block(stmts.collect(), value), false)
+        } else {
+            // The rest of the sequence can rebind the variables of an outer
+            // while loop if the while loop is directly in its body.
+            let outer_depth = self
+                .while_body
+                .as_mut()
+                .filter(|_| is_rebinding)
+                .map(|b| mem::replace(&mut b.depth, loop_depth));
+
+            let mut stmts = self.lower_sequence_tail(rest, tail);
+
+            if let (Some(while_body), Some(depth)) = (&mut self.while_body, outer_depth) {
+                while_body.depth = depth;
+            }
+
+            self.scopes.truncate(loop_depth);
+
+            match stmts.pop() {
+                Some(hir::Stmt::Expr(value)) => (hir::Expr::Block(stmts.into(), value), true),
+                stmt => {
+                    stmts.extend(stmt);
+                    let value = Box::new(hir::Expr::Literal(Literal::Bool(false)));
+                    (hir::Expr::Block(stmts.into(), value), false)
+                }
+            }
+        };
+
+        self.scopes.pop_block_scope();
+
+        let expr = hir::Expr::While(
+            locals,
+            values,
+            Box::new(cond),
+            Box::new(body),
+            Box::new(exit),
+            cond_span,
+        );
+
+        // While loops whose exit has no value are statements.
+        if is_value {
+            hir::Stmt::Expr(Box::new(expr))
+        } else {
+            hir::Stmt::Block(Box::new([hir::Stmt::Expr(Box::new(expr))]))
+        }
     }
 
     /// Lowers a ternary conditional [`Expr`] to an [`hir::Expr`].
     fn lower_expr_cond(&mut self, cond: &Expr, then_expr: &Expr, else_expr: &Expr) -> hir::Expr {
+        if then_expr.to_string() == else_expr.to_string() {
+            self.report_warning(WarningKind::IdenticalBranches, cond.span);
+        }
+
+        let cond_span = cond.span;
+        let (then_span, else_span) = (then_expr.span, else_expr.span);
         let cond = self.lower_expr(cond, ExprArea::Condition);
         let then_expr = self.lower_expr(then_expr, ExprArea::Operand);
         let else_expr = self.lower_expr(else_expr, ExprArea::Operand);
-        hir::Expr::Cond(Box::new(cond), Box::new(then_expr), Box::new(else_expr))
+
+        // Fold conditions with a constant value into their live branch. The
+        // dead branch is still lowered so that it can report errors.
+        match const_bool(&cond) {
+            Some(true) => {
+                self.report_warning(WarningKind::DeadBranch, else_span);
+                return then_expr;
+            }
+            Some(false) => {
+                self.report_warning(WarningKind::DeadBranch, then_span);
+                return else_expr;
+            }
+            None => {}
+        }
+
+        hir::Expr::Cond(
+            Box::new(cond),
+            Box::new(then_expr),
+            Box::new(else_expr),
+            cond_span,
+        )
     }
 
-    /// Reports an [`ErrorKind`] and creates a new synthetic [`hir::Stmt`] for
-    /// error recovery.
+    /// Reports an [`ErrorKind`] at the current [`Span`] and creates a new
+    /// synthetic [`hir::Stmt`] for error recovery.
     fn error_stmt(&mut self, error: ErrorKind) -> hir::Stmt {
-        self.report_error(error);
+        self.report_error(error, self.span);
         hir::Stmt::Block(Box::new([]))
     }
 
-    /// Reports an [`ErrorKind`] and creates a new synthetic [`hir::Expr`] for
-    /// error recovery.
+    /// Reports an [`ErrorKind`] at the current [`Span`] and defines a variable
+    /// from its [`Symbol`] with a synthetic value for error recovery, so that
+    /// later uses of the variable are not also reported as undefined.
+    fn error_definition(&mut self, symbol: Symbol, error: ErrorKind, span: Span) -> hir::Stmt {
+        let value = self.error_expr(error);
+        self.define_variable(symbol, value, span)
+    }
+
+    /// Reports an [`ErrorKind`] at the current [`Span`] and creates a new
+    /// synthetic [`hir::Expr`] for error recovery.
     fn error_expr(&mut self, error: ErrorKind) -> hir::Expr {
-        self.report_error(error);
-        hir::Expr::Literal(Literal::Number(0.0))
+        self.report_error(error, self.span);
+        synthetic_expr()
+    }
+
+    /// Reports a [`WarningKind`] at a source code [`Span`].
+    fn report_warning(&mut self, kind: WarningKind, span: Span) {
+        self.warnings.push(Warning::new(kind, span));
     }
 
-    /// Reports an [`ErrorKind`].
+    /// Reports an [`ErrorKind`] at a source code [`Span`].
     #[cold]
-    fn report_error(&mut self, error: ErrorKind) {
-        self.error
-            .get_or_insert_with(|| LowerError(Box::new(error)));
+    fn report_error(&mut self, error: ErrorKind, span: Span) {
+        if self.errors.len() < self.globals.settings().limits.max_errors() {
+            self.errors.push(LowerError(Box::new(error), span));
+        }
     }
 }
 
+/// A clause of a function definition.
+struct Clause<'ast> {
+    /// The function's name.
+    name: Symbol,
+
+    /// The function's parameter list [`Expr`].
+    list: &'ast Expr,
+
+    /// The clause's value [`Expr`].
+    value: &'ast Expr,
+
+    /// The clause's guard condition [`Expr`], if any.
+    guard: Option<&'ast Expr>,
+
+    /// Whether the clause was given a `when` or `otherwise` guard.
+    is_guarded: bool,
+}
+
+impl<'ast> Clause<'ast> {
+    /// Creates a new `Clause` from a statement [`Expr`]. Silent statements are
+    /// unwrapped. This function returns [`None`] if the statement is not a
+    /// named function definition.
+    fn from_stmt(stmt: &'ast Expr) -> Option<Self> {
+        let stmt = match &stmt.kind {
+            ExprKind::Silent(stmt) => stmt,
+            _ => stmt,
+        };
+
+        let ExprKind::Assign(target, source) = &stmt.kind else {
+            return None;
+        };
+
+        let ExprKind::Call(callee, list) = &target.kind else {
+            return None;
+        };
+
+        let ExprKind::Variable(name) = callee.kind else {
+            return None;
+        };
+
+        let (value, guard, is_guarded) = match &source.kind {
+            ExprKind::Guarded(value, guard) => (&**value, guard.as_deref(), true),
+            _ => (&**source, None, false),
+        };
+
+        Some(Self {
+            name,
+            list,
+            value,
+            guard,
+            is_guarded,
+        })
+    }
+
+    /// Returns the `Clause`'s parameter pattern [`Expr`]s.
+    const fn params(&self) -> &'ast [Expr] {
+        slice_list(self.list)
+    }
+
+    /// Returns [`true`] if the `Clause` may not match its arguments because it
+    /// has a guard condition or a literal pattern.
+    fn is_refutable(&self) -> bool {
+        self.guard.is_some() || self.params().iter().any(|p| pattern_literal(p).is_some())
+    }
+
+    /// Returns [`true`] if the `Clause` must be merged with the neighboring
+    /// clauses of the same function.
+    fn is_merged(&self) -> bool {
+        self.is_guarded || self.is_refutable()
+    }
+}
+
+/// The body of a while loop being lowered.
+struct WhileBody {
+    /// The [`Symbol`]s of the while loop's variables.
+    symbols: Vec<Symbol>,
+
+    /// The scope depth where assignments rebind the while loop's variables.
+    depth: usize,
+}
+
 /// An [`Hir`] node which is either an [`hir::Stmt`] or an [`hir::Expr`].
 enum Node {
     /// An [`hir::Stmt`].
@@ -315,12 +1449,223 @@ impl From<hir::Expr> for Node {
     }
 }
 
+/// Returns a `while` statement [`Expr`]'s condition and body [`Expr`]s. Silent
+/// statements are unwrapped. This function returns [`None`] if the statement is
+/// not a `while` [`Expr`].
+fn while_parts(stmt: &Expr) -> Option<(&Expr, &Expr)> {
+    let stmt = match &stmt.kind {
+        ExprKind::Silent(stmt) => stmt,
+        _ => stmt,
+    };
+
+    match &stmt.kind {
+        ExprKind::While(cond, body) => Some((cond, body)),
+        _ => None,
+    }
+}
+
+/// Appends the [`Symbol`]s of the variables assigned by statement [`Expr`]s,
+/// including the bodies of nested `while` [`Expr`]s, to a [`Vec`] without
+/// duplicates.
+fn collect_assigned_symbols(stmts: &[Expr], symbols: &mut Vec<Symbol>) {
+    for stmt in stmts {
+        if let Some((_, body)) = while_parts(stmt) {
+            if let ExprKind::Block(body) = &body.kind {
+                collect_assigned_symbols(body, symbols);
+            }
+
+            continue;
+        }
+
+        let target = match &stmt.kind {
+            ExprKind::Silent(stmt) => stmt,
+            _ => stmt,
+        };
+
+        let (ExprKind::Assign(target, _) | ExprKind::CompoundAssign(_, target, _)) = &target.kind
+        else {
+            continue;
+        };
+
+        let targets = match &target.kind {
+            ExprKind::Tuple(targets) => &**targets,
+            _ => slice::from_ref(&**target),
+        };
+
+        for variable in targets {
+            let symbol = match &variable.kind {
+                ExprKind::Variable(symbol) => *symbol,
+                ExprKind::Call(callee, _) => match callee.kind {
+                    ExprKind::Variable(symbol) => symbol,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+    }
+}
+
+/// Creates a new synthetic [`hir::Expr`] for error recovery.
+const fn synthetic_expr() -> hir::Expr {
+    hir::Expr::Literal(Literal::Number(0.0))
+}
+
+/// Returns the constant value of a Boolean [`hir::Expr`]. This function returns
+/// [`None`] if the [`hir::Expr`] does not have a constant Boolean value.
+fn const_bool(expr: &hir::Expr) -> Option<bool> {
+    match expr {
+        hir::Expr::Literal(Literal::Bool(value)) => Some(*value),
+        hir::Expr::Unary(UnOp::Not, rhs, _) => const_bool(rhs).map(|v| !v),
+        _ => None,
+    }
+}
+
+/// Returns the result of a comparison [`BinOp`] between an operand [`Expr`]
+/// and itself. This function returns [`None`] if the [`BinOp`] is not a
+/// comparison or the operands are not the same variable or literal.
+fn self_comparison_result(op: BinOp, lhs: &Expr, rhs: &Expr) -> Option<bool> {
+    let is_same = match (&lhs.kind, &rhs.kind) {
+        (ExprKind::Variable(lhs), ExprKind::Variable(rhs)) => lhs == rhs,
+        (ExprKind::Literal(lhs), ExprKind::Literal(rhs)) => lhs.to_string() == rhs.to_string(),
+        _ => false,
+    };
+
+    if !is_same {
+        return None;
+    }
+
+    match op {
+        BinOp::Equal | BinOp::LessEqual | BinOp::GreaterEqual => Some(true),
+        BinOp::NotEqual | BinOp::Less | BinOp::Greater => Some(false),
+        BinOp::Add
+        | BinOp::Subtract
+        | BinOp::Multiply
+        | BinOp::Divide
+        | BinOp::Power
+        | BinOp::In => None,
+    }
+}
+
+/// Appends the variable [`Symbol`]s bound by a variable or tuple parameter
+/// pattern [`Expr`] to a [`Vec`]. This function returns [`false`] if the
+/// pattern contains anything other than variables and tuples.
+fn pattern_symbols(pattern: &Expr, symbols: &mut Vec<Symbol>) -> bool {
+    match &pattern.kind {
+        ExprKind::Variable(symbol) => {
+            symbols.push(*symbol);
+            true
+        }
+        ExprKind::Tuple(elems) => elems.iter().all(|e| pattern_symbols(e, symbols)),
+        _ => false,
+    }
+}
+
+/// Collects the variables used by quoted code with their [`Span`]s and the
+/// variables which it binds. Every variable in an assignment target, function
+/// signature, or `let` binding is counted as bound anywhere in the code.
+fn collect_quoted_variables(
+    expr: &Expr,
+    is_target: bool,
+    used: &mut Vec<(Symbol, Span)>,
+    bound: &mut HashSet<Symbol>,
+) {
+    match &expr.kind {
+        ExprKind::Variable(symbol) if is_target => {
+            bound.insert(*symbol);
+        }
+        ExprKind::Variable(symbol) => used.push((*symbol, expr.span)),
+        ExprKind::Assign(target, value)
+        | ExprKind::CompoundAssign(_, target, value)
+        | ExprKind::Function(target, value)
+        | ExprKind::DefineOperator(_, _, target, value) => {
+            collect_quoted_variables(target, true, used, bound);
+            collect_quoted_variables(value, is_target, used, bound);
+        }
+        ExprKind::Let(target, value, body) => {
+            collect_quoted_variables(target, true, used, bound);
+            collect_quoted_variables(value, is_target, used, bound);
+            collect_quoted_variables(body, is_target, used, bound);
+        }
+        ExprKind::Operator(symbol, ..) if !is_target => {
+            used.push((*symbol, expr.span));
+            expr.for_each_child(|e| collect_quoted_variables(e, is_target, used, bound));
+        }
+        _ => expr.for_each_child(|e| collect_quoted_variables(e, is_target, used, bound)),
+    }
+}
+
+/// Returns the [`Literal`] matched by a function parameter pattern [`Expr`].
+/// Negated number literals are accepted. This function returns [`None`] if the
+/// pattern is not a literal.
+fn pattern_literal(pattern: &Expr) -> Option<Literal> {
+    match &pattern.kind {
+        ExprKind::Literal(literal) => Some(literal.clone()),
+        ExprKind::Unary(UnOp::Negate, rhs) => match rhs.kind {
+            ExprKind::Literal(Literal::Number(value)) => Some(Literal::Number(-value)),
+            ExprKind::Literal(Literal::Integer(value)) => Some(Literal::Integer(-value)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns [`true`] if an [`hir::Expr`] contains a recur [`hir::Expr`] which
+/// is not in a tail position. Functions and the bodies of inner loops are not
+/// searched because they cannot recur to an outer loop.
+fn has_non_tail_recur(expr: &hir::Expr, is_tail: bool) -> bool {
+    let any = |exprs: &[hir::Expr]| exprs.iter().any(|e| has_non_tail_recur(e, false));
+
+    match expr {
+        hir::Expr::Literal(_)
+        | hir::Expr::Global(..)
+        | hir::Expr::Local(_)
+        | hir::Expr::Function(..) => false,
+        hir::Expr::Block(stmts, expr) => {
+            stmts.iter().any(stmt_has_recur) || has_non_tail_recur(expr, is_tail)
+        }
+        hir::Expr::Call(callee, args, _) => has_non_tail_recur(callee, false) || any(args),
+        hir::Expr::List(elems) | hir::Expr::Tuple(elems) => any(elems),
+        hir::Expr::Unpack(rhs, ..) | hir::Expr::Unary(_, rhs, _) | hir::Expr::Lazy(rhs) => {
+            has_non_tail_recur(rhs, false)
+        }
+        hir::Expr::Binary(_, lhs, rhs, _) | hir::Expr::Index(lhs, rhs, _) => {
+            has_non_tail_recur(lhs, false) || has_non_tail_recur(rhs, false)
+        }
+        hir::Expr::Loop(_, values, _) => any(values),
+        hir::Expr::Recur(args) => !is_tail || any(args),
+        hir::Expr::While(_, values, cond, _, exit, _) => {
+            any(values) || has_non_tail_recur(cond, false) || has_non_tail_recur(exit, is_tail)
+        }
+        hir::Expr::Cond(cond, then_expr, else_expr, _) => {
+            has_non_tail_recur(cond, false)
+                || has_non_tail_recur(then_expr, is_tail)
+                || has_non_tail_recur(else_expr, is_tail)
+        }
+    }
+}
+
+/// Returns [`true`] if an [`hir::Stmt`] contains a recur [`hir::Expr`] for the
+/// same loop. Statements are never in a tail position.
+fn stmt_has_recur(stmt: &hir::Stmt) -> bool {
+    match stmt {
+        hir::Stmt::Block(stmts) | hir::Stmt::Sequence(stmts) => stmts.iter().any(stmt_has_recur),
+        hir::Stmt::AssignGlobal(_, expr, _)
+        | hir::Stmt::DefineLocal(_, expr)
+        | hir::Stmt::Print(expr)
+        | hir::Stmt::Expr(expr) => has_non_tail_recur(expr, false),
+    }
+}
+
 /// Returns a function parameter or call argument list [`Expr`] as a slice of
 /// parameter or argument [`Expr`]s.
 const fn slice_list(list: &Expr) -> &[Expr] {
-    match list {
-        Expr::Paren(elem) => slice::from_ref(elem),
-        Expr::Tuple(elems) => elems,
-        elem => slice::from_ref(elem),
+    match &list.kind {
+        ExprKind::Paren(elem) => slice::from_ref(elem),
+        ExprKind::Tuple(elems) => elems,
+        _ => slice::from_ref(list),
     }
 }
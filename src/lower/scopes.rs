@@ -1,8 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::{
+    interpret::Native,
     locals::{Local, LocalTable},
     symbols::Symbol,
+    warnings::Shadowed,
 };
 
 /// A variable's storage kind.
@@ -28,6 +30,9 @@ pub struct ScopeStack<'loc> {
 
     /// The stack of local scopes mapping [`Symbol`]s to [`Local`]s.
     local_scopes: Vec<HashMap<Symbol, Local>>,
+
+    /// The indices of local scopes which are function parameter scopes.
+    param_scopes: Vec<usize>,
 }
 
 impl<'loc> ScopeStack<'loc> {
@@ -38,6 +43,7 @@ impl<'loc> ScopeStack<'loc> {
             function_depth: 0,
             global_symbols: HashSet::new(),
             local_scopes: Vec::new(),
+            param_scopes: Vec::new(),
         }
     }
 
@@ -46,6 +52,11 @@ impl<'loc> ScopeStack<'loc> {
         self.local_scopes.is_empty()
     }
 
+    /// Returns [`true`] if the current scope is inside a function.
+    pub const fn is_in_function(&self) -> bool {
+        self.function_depth > 0
+    }
+
     /// Returns a [`Variable`] from its [`Symbol`]. This function returns
     /// [`None`] if the [`Symbol`] is not declared in any accessible scope.
     pub fn variable(&mut self, symbol: Symbol) -> Option<Variable> {
@@ -73,6 +84,22 @@ impl<'loc> ScopeStack<'loc> {
             .then_some(Variable::Global)
     }
 
+    /// Returns [`true`] if a [`Symbol`] is declared as a local variable in any
+    /// accessible scope.
+    pub fn is_local(&self, symbol: Symbol) -> bool {
+        self.local_scopes.iter().any(|s| s.contains_key(&symbol))
+    }
+
+    /// Returns the number of local scopes in the `ScopeStack`.
+    pub const fn depth(&self) -> usize {
+        self.local_scopes.len()
+    }
+
+    /// Pops local scopes from the `ScopeStack` until a depth remains.
+    pub fn truncate(&mut self, depth: usize) {
+        self.local_scopes.truncate(depth);
+    }
+
     /// Pushes a new function scope to the `ScopeStack`.
     pub fn push_function_scope(&mut self) {
         self.function_depth += 1;
@@ -89,6 +116,7 @@ impl<'loc> ScopeStack<'loc> {
     /// Pushes a new function parameter scope to the `ScopeStack`.
     pub fn push_param_scope(&mut self) {
         debug_assert!(self.function_depth > 0, "there should be a function scope");
+        self.param_scopes.push(self.local_scopes.len());
         self.push_block_scope();
     }
 
@@ -96,6 +124,7 @@ impl<'loc> ScopeStack<'loc> {
     pub fn pop_param_scope(&mut self) {
         debug_assert!(self.function_depth > 0, "there should be a function scope");
         self.pop_block_scope();
+        self.param_scopes.pop();
     }
 
     /// Pushes a new block scope to the `ScopeStack`.
@@ -113,6 +142,34 @@ impl<'loc> ScopeStack<'loc> {
         self.local_scopes.truncate(self.local_scopes.len() - 1);
     }
 
+    /// Returns the kind of variable that a new local variable with a [`Symbol`]
+    /// would shadow if it were declared in the current scope. This function
+    /// returns [`None`] if the current scope is the global scope or no
+    /// variable would be shadowed.
+    pub fn shadowed(&self, symbol: Symbol) -> Option<Shadowed> {
+        let current_index = self.local_scopes.len().checked_sub(1)?;
+        let outer_scopes = self.local_scopes[..current_index].iter().enumerate();
+
+        for (index, local_scope) in outer_scopes.rev() {
+            if local_scope.contains_key(&symbol) {
+                let is_param = self.param_scopes.contains(&index);
+                return Some(if is_param {
+                    Shadowed::Param
+                } else {
+                    Shadowed::Local
+                });
+            }
+        }
+
+        if !self.global_symbols.contains(&symbol) {
+            None
+        } else if Native::from_name(&symbol.to_string()).is_some() {
+            Some(Shadowed::Native)
+        } else {
+            Some(Shadowed::Global)
+        }
+    }
+
     /// Declares a new [`Variable`] in the current scope from its [`Symbol`].
     /// This function returns [`None`] if the [`Symbol`] is already declared in
     /// the current scope.
@@ -122,7 +179,7 @@ impl<'loc> ScopeStack<'loc> {
                 return None;
             }
 
-            let local = self.locals.declare_local(self.function_depth);
+            let local = self.locals.declare_local(symbol, self.function_depth);
             local_scope.insert(symbol, local);
             Some(Variable::Local(local))
         } else {
@@ -131,4 +188,16 @@ impl<'loc> ScopeStack<'loc> {
                 .then_some(Variable::Global)
         }
     }
+
+    /// Declares a global variable from its [`Symbol`] from any scope.
+    pub fn declare_global(&mut self, symbol: Symbol) {
+        self.global_symbols.insert(symbol);
+    }
+
+    /// Declares a new hidden [`Local`] in the current function from a
+    /// [`Symbol`]. The [`Local`] is not declared in any scope, so it cannot be
+    /// accessed by name.
+    pub fn declare_hidden_local(&mut self, symbol: Symbol) -> Local {
+        self.locals.declare_local(symbol, self.function_depth)
+    }
 }
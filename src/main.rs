@@ -1,90 +1,10 @@
-mod ast;
-mod cfg;
-mod compile;
-mod errors;
-mod hir;
-mod interpret;
-mod lex;
-mod locals;
-mod lower;
-mod parse;
-mod symbols;
-mod tokens;
+use std::process::ExitCode;
 
-use std::{
-    env,
-    io::{self, Write as _},
-};
-
-use crate::{errors::ClacError, interpret::Globals, locals::LocalTable};
+// The binary only runs the library's command line interface, so it does not use
+// the library's dependencies.
+use thiserror as _;
 
 /// Runs Clac.
-fn main() {
-    let mut globals = Globals::new();
-    interpret::install_natives(&mut globals);
-
-    let mut args = env::args().skip(1);
-
-    match args.next() {
-        None => run_repl(&mut globals),
-        Some(mut source) => {
-            for arg in args {
-                source.push(' ');
-                source.push_str(&arg);
-            }
-
-            execute_source(&source, &mut globals);
-        }
-    }
-}
-
-/// Runs Clac in REPL mode with [`Globals`].
-fn run_repl(globals: &mut Globals) {
-    const EXIT_SHORTCUT: &str = cfg_select! {
-        windows => "Ctrl+Z",
-        _ => "Ctrl+D",
-    };
-
-    println!("Clac - Functional command line calculator\nEnter [{EXIT_SHORTCUT}] to exit.");
-    let mut source = String::new();
-
-    loop {
-        print!("\nclac> ");
-        io::stdout()
-            .flush()
-            .expect("flushing stdout should not fail");
-
-        source.clear();
-
-        if let Err(error) = io::stdin().read_line(&mut source) {
-            eprintln!("Could not read line: {error}");
-            continue;
-        }
-
-        if source.is_empty() {
-            break;
-        }
-
-        execute_source(&source, globals);
-    }
-
-    println!("\nReceived [{EXIT_SHORTCUT}], exiting...");
-}
-
-/// Executes source code with [`Globals`].
-fn execute_source(source: &str, globals: &mut Globals) {
-    if let Err(error) = try_execute_source(source, globals) {
-        eprintln!("{error}");
-    }
-}
-
-/// Executes source code with [`Globals`]. This function returns a [`ClacError`]
-/// if the source code could not be executed.
-fn try_execute_source(source: &str, globals: &mut Globals) -> Result<(), ClacError> {
-    let ast = parse::parse_source(source)?;
-    let mut locals = LocalTable::new();
-    let hir = lower::lower_ast(&ast, globals, &mut locals)?;
-    let cfg = compile::compile_hir(&hir, &locals);
-    interpret::interpret_cfg(&cfg, globals)?;
-    Ok(())
+fn main() -> ExitCode {
+    clac::run_cli()
 }
@@ -0,0 +1,344 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, Write as _},
+    mem,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::calendar;
+
+/// Prints formatted text without a newline to an [`Output`].
+macro_rules! out {
+    ($output:expr, $($arg:tt)*) => {
+        $output.print(format_args!($($arg)*))
+    };
+}
+
+/// Prints a formatted line to an [`Output`].
+macro_rules! outln {
+    ($output:expr, $($arg:tt)*) => {
+        $output.print_line(false, format_args!($($arg)*))
+    };
+}
+
+/// Prints a formatted line to an [`Output`]'s errors.
+macro_rules! errln {
+    ($output:expr, $($arg:tt)*) => {
+        $output.print_line(true, format_args!($($arg)*))
+    };
+}
+
+pub(crate) use errln;
+pub(crate) use out;
+pub(crate) use outln;
+
+/// A mode for printing top-level results.
+#[derive(Clone, Copy, Default)]
+pub enum Echo {
+    /// Top-level results are printed.
+    #[default]
+    On,
+
+    /// Only explicit output (e.g. from `print`) is printed.
+    Off,
+}
+
+impl Echo {
+    /// Returns an `Echo` mode from its name. This function returns [`None`] if
+    /// the name does not correspond to an `Echo` mode.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "on" => Some(Self::On),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
+/// A format for printing top-level results.
+#[derive(Clone, Copy, Default)]
+pub enum ResultFormat {
+    /// Results are pretty-printed.
+    #[default]
+    Pretty,
+
+    /// Tabular results are printed as rows of comma-separated values.
+    Csv,
+
+    /// Tabular results are printed as rows of tab-separated values.
+    Tsv,
+}
+
+impl ResultFormat {
+    /// Returns a `ResultFormat` from its name. This function returns [`None`]
+    /// if the name does not correspond to a `ResultFormat`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pretty" => Some(Self::Pretty),
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            _ => None,
+        }
+    }
+
+    /// Returns the `ResultFormat`'s separator between values in a row. This
+    /// function returns [`None`] if the `ResultFormat` does not print rows.
+    pub const fn separator(self) -> Option<char> {
+        match self {
+            Self::Pretty => None,
+            Self::Csv => Some(','),
+            Self::Tsv => Some('\t'),
+        }
+    }
+}
+
+/// A destination for output.
+#[derive(Default)]
+enum Destination {
+    /// Standard output.
+    #[default]
+    Stdout,
+
+    /// A file which output is redirected to.
+    File(File),
+
+    /// A [`Capture`] which output and errors are appended to.
+    Capture(Capture),
+
+    /// A function which observes output instead of it being printed.
+    Observer(Box<dyn FnMut(&str)>),
+
+    /// A destination which could not be written to. Output is discarded so
+    /// that the error is only reported once.
+    Closed,
+}
+
+/// Output and errors which were captured so that they can be printed later,
+/// possibly from another thread.
+pub struct Capture(Vec<(bool, String)>);
+
+impl Capture {
+    /// Returns the captured output without the captured errors.
+    #[cfg(test)]
+    pub fn output(&self) -> String {
+        let output = self.0.iter().filter(|(e, _)| !e).map(|(_, t)| t.as_str());
+        output.collect()
+    }
+
+    /// Returns the captured errors without the captured output.
+    #[cfg(test)]
+    pub fn errors(&self) -> String {
+        let errors = self.0.iter().filter(|(e, _)| *e).map(|(_, t)| t.as_str());
+        errors.collect()
+    }
+}
+
+/// A log of input and output which is appended to a file.
+#[derive(Default)]
+struct Transcript {
+    /// The path to the transcript file, if any.
+    path: Option<PathBuf>,
+
+    /// The open transcript file, if logging is enabled.
+    file: Option<File>,
+}
+
+/// A session's destination for output and errors, and its transcript. Clones
+/// of an `Output` share the same destination and transcript.
+#[derive(Clone, Default)]
+pub struct Output(Rc<RefCell<OutputState>>);
+
+/// The shared state of an [`Output`].
+#[derive(Default)]
+struct OutputState {
+    /// The [`Destination`].
+    destination: Destination,
+
+    /// The [`Transcript`].
+    transcript: Transcript,
+}
+
+impl Output {
+    /// Prints formatted arguments as a line to either the output or the
+    /// errors, then appends them to the transcript if logging is enabled.
+    pub fn print_line(&self, is_error: bool, args: fmt::Arguments<'_>) {
+        if is_error {
+            self.write_error(format_args!("{args}\n"));
+        } else {
+            self.write_output(format_args!("{args}\n"));
+        }
+
+        self.log_output(format_args!("{args}\n"));
+    }
+
+    /// Prints formatted arguments without a newline to the output, then
+    /// appends them to the transcript if logging is enabled.
+    pub fn print(&self, args: fmt::Arguments<'_>) {
+        self.write_output(args);
+        self.log_output(args);
+    }
+
+    /// Prints a [`Capture`]'s output and errors in the order they were
+    /// captured.
+    pub fn print_capture(&self, capture: Capture) {
+        for (is_error, text) in capture.0 {
+            if is_error {
+                self.write_error(format_args!("{text}"));
+            } else {
+                self.write_output(format_args!("{text}"));
+            }
+
+            self.log_output(format_args!("{text}"));
+        }
+    }
+
+    /// Redirects output from standard output to a new file. Errors are still
+    /// printed to standard error. This function returns an [`io::Error`] if
+    /// the file could not be created.
+    pub fn redirect(&self, path: &Path) -> io::Result<()> {
+        let file = File::create(path)?;
+        self.0.borrow_mut().destination = Destination::File(file);
+        Ok(())
+    }
+
+    /// Starts capturing output and errors instead of printing them.
+    pub fn start_capture(&self) {
+        self.0.borrow_mut().destination = Destination::Capture(Capture(Vec::new()));
+    }
+
+    /// Returns the output and errors captured since the last call to
+    /// [`Output::start_capture`] or [`Output::take_capture`].
+    pub fn take_capture(&self) -> Capture {
+        match &mut self.0.borrow_mut().destination {
+            Destination::Capture(capture) => Capture(mem::take(&mut capture.0)),
+            _ => Capture(Vec::new()),
+        }
+    }
+
+    /// Passes output to an observer function instead of printing it. Errors
+    /// are still printed to standard error.
+    pub fn observe(&self, observer: Box<dyn FnMut(&str)>) {
+        self.0.borrow_mut().destination = Destination::Observer(observer);
+    }
+
+    /// Writes formatted arguments to the [`Capture`] if output is captured, or
+    /// to standard error otherwise.
+    fn write_error(&self, args: fmt::Arguments<'_>) {
+        match &mut self.0.borrow_mut().destination {
+            Destination::Capture(capture) => capture.0.push((true, args.to_string())),
+            _ => eprint!("{args}"),
+        }
+    }
+
+    /// Writes formatted arguments to the output file if output is redirected,
+    /// or to standard output otherwise.
+    fn write_output(&self, args: fmt::Arguments<'_>) {
+        let mut state = self.0.borrow_mut();
+
+        let result = match &mut state.destination {
+            Destination::Stdout => {
+                // Standard output is flushed so that text without a newline is
+                // displayed immediately.
+                let mut stdout = io::stdout().lock();
+                stdout.write_fmt(args).and_then(|()| stdout.flush())
+            }
+            Destination::File(file) => file.write_fmt(args),
+            Destination::Capture(capture) => {
+                capture.0.push((false, args.to_string()));
+                Ok(())
+            }
+            Destination::Observer(observer) => {
+                observer(&args.to_string());
+                Ok(())
+            }
+            Destination::Closed => Ok(()),
+        };
+
+        if let Err(error) = result {
+            eprintln!("Could not write output: {error}");
+            state.destination = Destination::Closed;
+        }
+    }
+
+    /// Appends formatted arguments to the transcript if logging is enabled.
+    fn log_output(&self, args: fmt::Arguments<'_>) {
+        let transcript = &mut self.0.borrow_mut().transcript;
+
+        if let Some(file) = &mut transcript.file
+            && let Err(error) = write!(file, "{args}")
+        {
+            eprintln!("Could not write to transcript: {error}");
+            transcript.file = None;
+        }
+    }
+
+    /// Prints a top-level result as a line if the [`Echo`] mode is on.
+    pub fn echo(&self, result: &dyn fmt::Display, echo: Echo) {
+        if matches!(echo, Echo::On) {
+            self.print_line(false, format_args!("{result}"));
+        }
+    }
+
+    /// Appends a line of input to the transcript with a timestamp if logging
+    /// is enabled.
+    pub fn log_input(&self, input: &str) {
+        let transcript = &mut self.0.borrow_mut().transcript;
+
+        if let Some(file) = &mut transcript.file
+            && let Err(error) = writeln!(file, "[{}] clac> {}", timestamp(), input.trim_end())
+        {
+            eprintln!("Could not write to transcript: {error}");
+            transcript.file = None;
+        }
+    }
+
+    /// Starts logging to a transcript file. If no path is given, then the
+    /// most recent transcript file is used. This function returns an
+    /// [`io::Error`] if the transcript file could not be opened.
+    pub fn start_logging(&self, path: Option<&Path>) -> io::Result<()> {
+        let transcript = &mut self.0.borrow_mut().transcript;
+
+        let path = match path {
+            Some(path) => path.to_owned(),
+            None => transcript
+                .path
+                .clone()
+                .ok_or_else(|| io::Error::other("no transcript file has been given"))?,
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        transcript.path = Some(path);
+        transcript.file = Some(file);
+        Ok(())
+    }
+
+    /// Stops logging to the transcript file.
+    pub fn stop_logging(&self) {
+        self.0.borrow_mut().transcript.file = None;
+    }
+
+    /// Returns the path to the transcript file if logging is enabled.
+    pub fn logging_path(&self) -> Option<PathBuf> {
+        let transcript = &self.0.borrow().transcript;
+        transcript.file.as_ref()?;
+        transcript.path.clone()
+    }
+}
+
+/// Returns the current UTC time as a `YYYY-MM-DD HH:MM:SS` timestamp.
+fn timestamp() -> String {
+    const SECS_PER_DAY: u64 = 86_400;
+
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let (days, secs_of_day) = (secs / SECS_PER_DAY, secs % SECS_PER_DAY);
+    let (year, month, day) = calendar::civil_from_days(days.cast_signed());
+    let (hour, minute, second) = (secs_of_day / 3600, secs_of_day / 60 % 60, secs_of_day % 60);
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}")
+}
@@ -1,7 +1,8 @@
 use thiserror::Error;
 
 use crate::{
-    lex::LexError,
+    lex::{Keyword, LexError},
+    symbols::Symbol,
     tokens::{Token, TokenType},
 };
 
@@ -17,6 +18,11 @@ pub enum ErrorKind {
     #[error("expected {0}, got {1}")]
     UnexpectedToken(TokenType, Token),
 
+    /// A [`Token`] which is not an expected contextual [`Keyword`] was
+    /// encountered.
+    #[error("expected '{0}', got {1}")]
+    ExpectedKeyword(Keyword, Token),
+
     /// A [`Token`] which does not begin an expected [`Expr`][crate::ast::Expr]
     /// was encountered.
     #[error("expected an expression, got {0}")]
@@ -26,7 +32,28 @@ pub enum ErrorKind {
     #[error("assignments cannot be chained")]
     ChainedAssignment,
 
-    /// A chained comparison was encountered.
-    #[error("comparisons cannot be chained")]
-    ChainedComparison,
+    /// A chained comparison was encountered with a suggested replacement.
+    #[error("comparisons cannot be chained, did you mean '{0}'?")]
+    ChainedComparison(String),
+
+    /// Source code was nested too deeply to be parsed safely.
+    #[error("expression is nested too deeply")]
+    TooDeep,
+
+    /// An expression chained more operations, calls, or indices than the
+    /// maximum.
+    #[error("expression chains more than {0} operations, calls, or indices")]
+    TooLong(usize),
+
+    /// Source code had more [`Token`]s than the maximum.
+    #[error("source code has more than {0} tokens")]
+    TooManyTokens(usize),
+
+    /// An undefined custom operator was used.
+    #[error("operator '{0}' is undefined")]
+    UndefinedOperator(Symbol),
+
+    /// An unknown modifier was used in a custom operator definition.
+    #[error("unknown operator modifier '{0}', expected 'sum', 'term', 'left', or 'right'")]
+    InvalidOperatorModifier(Symbol),
 }
@@ -0,0 +1,301 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+    ast::{BinOp, Expr, ExprKind, LogicOp},
+    lex::Keyword,
+    symbols::Symbol,
+    tokens::TokenType,
+};
+
+use super::operators::Precedence;
+
+/// The levels of binary operators from the lowest precedence to the highest
+/// precedence. Operators at levels past the end of the table are parsed by
+/// hand.
+pub const INFIX_LEVELS: &[InfixLevel] = &[
+    InfixLevel {
+        name: "or",
+        operators: &[(
+            InfixToken::Type(TokenType::PipePipe),
+            InfixOp::Logic(LogicOp::Or),
+        )],
+        grouping: Grouping::Left,
+        custom: None,
+    },
+    InfixLevel {
+        name: "and",
+        operators: &[(
+            InfixToken::Type(TokenType::AndAnd),
+            InfixOp::Logic(LogicOp::And),
+        )],
+        grouping: Grouping::Left,
+        custom: None,
+    },
+    InfixLevel {
+        name: "comparison",
+        operators: &[
+            (
+                InfixToken::Type(TokenType::EqualsEquals),
+                InfixOp::Binary(BinOp::Equal),
+            ),
+            (
+                InfixToken::Type(TokenType::BangEquals),
+                InfixOp::Binary(BinOp::NotEqual),
+            ),
+            (
+                InfixToken::Type(TokenType::Less),
+                InfixOp::Binary(BinOp::Less),
+            ),
+            (
+                InfixToken::Type(TokenType::LessEquals),
+                InfixOp::Binary(BinOp::LessEqual),
+            ),
+            (
+                InfixToken::Type(TokenType::Greater),
+                InfixOp::Binary(BinOp::Greater),
+            ),
+            (
+                InfixToken::Type(TokenType::GreaterEquals),
+                InfixOp::Binary(BinOp::GreaterEqual),
+            ),
+            (InfixToken::Keyword(Keyword::In), InfixOp::Binary(BinOp::In)),
+        ],
+        grouping: Grouping::None,
+        custom: None,
+    },
+    InfixLevel {
+        name: "sum",
+        operators: &[
+            (
+                InfixToken::Type(TokenType::Plus),
+                InfixOp::Binary(BinOp::Add),
+            ),
+            (
+                InfixToken::Type(TokenType::Minus),
+                InfixOp::Binary(BinOp::Subtract),
+            ),
+        ],
+        grouping: Grouping::Left,
+        custom: Some(Precedence::Sum),
+    },
+    InfixLevel {
+        name: "term",
+        operators: &[
+            (
+                InfixToken::Type(TokenType::Star),
+                InfixOp::Binary(BinOp::Multiply),
+            ),
+            (
+                InfixToken::Type(TokenType::Slash),
+                InfixOp::Binary(BinOp::Divide),
+            ),
+        ],
+        grouping: Grouping::Left,
+        custom: Some(Precedence::Term),
+    },
+];
+
+/// A level of binary operators with the same precedence.
+pub struct InfixLevel {
+    /// The name of the level's grammar rule without its `expr_` prefix.
+    pub name: &'static str,
+
+    /// The [`InfixToken`]s and [`InfixOp`]s of the level's operators.
+    pub operators: &'static [(InfixToken, InfixOp)],
+
+    /// The [`Grouping`] of the level's operators.
+    pub grouping: Grouping,
+
+    /// The [`Precedence`] of custom operators parsed at the level, if any.
+    pub custom: Option<Precedence>,
+}
+
+impl InfixLevel {
+    /// Returns the [`InfixOp`] at the `InfixLevel` for an [`InfixToken`]. This
+    /// function returns [`None`] if the [`InfixToken`] is not an operator at
+    /// the `InfixLevel`.
+    pub fn operator(&self, token: InfixToken) -> Option<InfixOp> {
+        self.operators
+            .iter()
+            .find(|(t, _)| *t == token)
+            .map(|(_, op)| *op)
+    }
+}
+
+/// A [`Token`][crate::tokens::Token] which may be a binary operator.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InfixToken {
+    /// A [`Token`][crate::tokens::Token] with a [`TokenType`].
+    Type(TokenType),
+
+    /// An identifier for a contextual [`Keyword`].
+    Keyword(Keyword),
+}
+
+/// A binary operator parsed at a level of [`INFIX_LEVELS`].
+#[derive(Clone, Copy)]
+pub enum InfixOp {
+    /// A [`BinOp`].
+    Binary(BinOp),
+
+    /// A [`LogicOp`].
+    Logic(LogicOp),
+
+    /// A custom operator with a [`Symbol`].
+    Custom(Symbol),
+}
+
+impl InfixOp {
+    /// Creates a new [`Expr`] for the `InfixOp` spanning both of its operands.
+    pub fn expr(self, lhs: Expr, rhs: Expr) -> Expr {
+        let span = lhs.span.to(rhs.span);
+        let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+
+        let kind = match self {
+            Self::Binary(op) => ExprKind::Binary(op, lhs, rhs),
+            Self::Logic(op) => ExprKind::Logic(op, lhs, rhs),
+            Self::Custom(symbol) => ExprKind::Operator(symbol, lhs, rhs),
+        };
+
+        Expr::new(kind, span)
+    }
+}
+
+impl Display for InfixOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary(op) => Display::fmt(op, f),
+            Self::Logic(op) => Display::fmt(op, f),
+            Self::Custom(symbol) => Display::fmt(symbol, f),
+        }
+    }
+}
+
+/// A way of grouping a chain of operators with the same precedence.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Operations are grouped from the left (e.g. `(a - b) - c`).
+    Left,
+
+    /// Operations cannot be chained (e.g. `a < b < c` is an error).
+    None,
+}
+
+/// Returns the grammar in EBNF. The rules for binary operators are generated
+/// from [`INFIX_LEVELS`].
+pub fn grammar_ebnf() -> String {
+    let first_infix = INFIX_LEVELS
+        .first()
+        .map_or_else(|| "expr_prefix".to_owned(), |l| format!("expr_{}", l.name));
+
+    let stmts = [
+        ("program", "sequence, Eof".to_owned()),
+        ("sequence", r#"{ stmt, [ "," | ";" ] }"#.to_owned()),
+        ("stmt", "stmt_operator | expr".to_owned()),
+        (
+            "stmt_operator",
+            r#""operator", CustomOp, { Ident }, expr_paren, "=", expr_mapping"#.to_owned(),
+        ),
+        ("expr", "expr_assignment".to_owned()),
+    ];
+
+    let mut exprs = vec![
+        (
+            "expr_assignment".to_owned(),
+            r#"expr_mapping, [ "=", expr_mapping, [ "when", expr_mapping | "otherwise" ] | ( "+=" | "-=" | "*=" | "/=" ), expr_mapping ]"#
+                .to_owned(),
+        ),
+        (
+            "expr_mapping".to_owned(),
+            format!(r#"{first_infix}, [ ( "->" | "?", expr, ":" ), expr_mapping ]"#),
+        ),
+    ];
+
+    for (index, level) in INFIX_LEVELS.iter().enumerate() {
+        let next = INFIX_LEVELS
+            .get(index + 1)
+            .map_or_else(|| "expr_prefix".to_owned(), |l| format!("expr_{}", l.name));
+
+        let mut operators: Vec<String> = level
+            .operators
+            .iter()
+            .map(|(_, op)| format!(r#""{op}""#))
+            .collect();
+
+        if level.custom.is_some() {
+            operators.push("CustomOp".to_owned());
+        }
+
+        let operators = match operators.as_slice() {
+            [operator] => operator.clone(),
+            _ => format!("( {} )", operators.join(" | ")),
+        };
+
+        let rule = match level.grouping {
+            Grouping::Left => format!("{next}, {{ {operators}, {next} }}"),
+            Grouping::None => format!("{next}, [ {operators}, {next} ]"),
+        };
+
+        exprs.push((format!("expr_{}", level.name), rule));
+    }
+
+    exprs.extend([
+        (
+            "expr_prefix".to_owned(),
+            r#"( "-" | "!" ), expr_prefix | expr_power"#.to_owned(),
+        ),
+        (
+            "expr_power".to_owned(),
+            r#"expr_call, [ Superscript ], [ "^", expr_prefix ]"#.to_owned(),
+        ),
+        (
+            "expr_call".to_owned(),
+            r#"expr_primary, { expr_paren, [ "{", sequence, "}" ] | "[", expr, "]" }"#.to_owned(),
+        ),
+        (
+            "expr_primary".to_owned(),
+            r#"expr_paren | expr_list | "{", sequence, "}" | expr_let | expr_lazy | expr_loop | expr_recur | expr_while | Literal | Ident"#
+                .to_owned(),
+        ),
+        (
+            "expr_let".to_owned(),
+            r#""let", expr_prefix, "=", expr_mapping, "in", expr_mapping"#.to_owned(),
+        ),
+        ("expr_lazy".to_owned(), r#""lazy", expr_mapping"#.to_owned()),
+        (
+            "expr_loop".to_owned(),
+            r#""loop", expr_paren, "{", sequence, "}""#.to_owned(),
+        ),
+        ("expr_recur".to_owned(), r#""recur", expr_paren"#.to_owned()),
+        (
+            "expr_while".to_owned(),
+            r#""while", expr_mapping, "{", sequence, "}""#.to_owned(),
+        ),
+        (
+            "expr_paren".to_owned(),
+            r#""(", [ expr, { ",", expr }, [ "," ] ], ")""#.to_owned(),
+        ),
+        (
+            "expr_list".to_owned(),
+            r#""[", [ expr, { ",", expr }, [ "," ] ], "]""#.to_owned(),
+        ),
+    ]);
+
+    let stmts: Vec<(String, String)> = stmts
+        .into_iter()
+        .map(|(name, rule)| (name.to_owned(), rule))
+        .collect();
+
+    format!("{}\n{}", format_rules(&stmts), format_rules(&exprs))
+}
+
+/// Formats EBNF rules as lines with their equals signs aligned.
+fn format_rules(rules: &[(String, String)]) -> String {
+    let width = rules.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+    let lines: Vec<String> = rules
+        .iter()
+        .map(|(name, rule)| format!("{name:width$} = {rule} ;\n"))
+        .collect();
+
+    lines.concat()
+}
@@ -2,52 +2,137 @@
 mod tests;
 
 mod errors;
+mod grammar;
+mod operators;
 
 use std::mem;
 
 use thiserror::Error;
 
 use crate::{
-    ast::{Ast, BinOp, Expr, Literal, LogicOp, UnOp},
-    lex::Lexer,
+    ast::{Ast, BinOp, Expr, ExprKind, Literal, UnOp},
+    lex::{Keyword, Lexer},
+    settings::Settings,
+    spans::Span,
+    symbols::Symbol,
     tokens::{Token, TokenType},
 };
 
-use self::errors::ErrorKind;
+pub use self::{grammar::grammar_ebnf, operators::Operators};
 
-/// An error caught while parsing an [`Ast`].
+use self::{
+    errors::ErrorKind,
+    grammar::{Grouping, INFIX_LEVELS, InfixOp, InfixToken},
+    operators::{Associativity, Fixity, Precedence},
+};
+
+/// The maximum depth of nested parsing functions. Deeper source code is
+/// rejected so that parsing and the recursive passes after it do not overflow
+/// the stack.
+const MAX_DEPTH: usize = 500;
+
+/// The maximum number of operations, calls, or indices chained in one
+/// expression. Chains are parsed in loops, but each link still nests the
+/// [`Ast`] one level deeper, so longer chains are rejected so that the
+/// recursive passes after parsing do not overflow the stack.
+const MAX_CHAIN_LENGTH: usize = MAX_DEPTH * 4;
+
+/// An error caught while parsing an [`Ast`] with the [`Span`] where it
+/// occurred.
 #[derive(Debug, Error)]
-#[repr(transparent)]
-#[error(transparent)]
-pub struct ParseError(Box<ErrorKind>);
-
-/// Parses an [`Ast`] from source code. This function returns a [`ParseError`]
-/// if an [`Ast`] could not be parsed.
-pub fn parse_source(source: &str) -> Result<Ast, ParseError> {
-    let mut parser = Parser::new(source);
+#[error("{0}")]
+pub struct ParseError(Box<ErrorKind>, Span);
+
+impl ParseError {
+    /// Returns the [`Span`] where the `ParseError` occurred.
+    pub const fn span(&self) -> Span {
+        self.1
+    }
+}
+
+/// Parses an [`Ast`] from source code with a session's [`Settings`] and
+/// [`Operators`]. Custom operators defined by the source code are added to the
+/// [`Operators`]. This function returns every [`ParseError`], up to the
+/// maximum number of errors, if an [`Ast`] could not be parsed.
+pub fn parse_source(
+    source: &str,
+    settings: &Settings,
+    operators: &mut Operators,
+) -> Result<Ast, Vec<ParseError>> {
+    let mut parser = Parser::new(source, settings, operators);
     let ast = parser.parse_ast();
-    parser.error.map_or(Ok(ast), Err)
+
+    if parser.errors.is_empty() {
+        Ok(ast)
+    } else {
+        Err(parser.errors)
+    }
 }
 
 /// A structure which parses an [`Ast`] from source code.
-struct Parser<'src> {
+struct Parser<'src, 'ops> {
+    /// The source code.
+    source: &'src str,
+
+    /// The [`Operators`].
+    operators: &'ops mut Operators,
+
     /// The [`Lexer`].
     lexer: Lexer<'src>,
 
     /// The next [`Token`].
     next_token: Token,
 
-    /// The first [`ParseError`], if any.
-    error: Option<ParseError>,
+    /// The next [`Token`]'s [`Span`].
+    next_span: Span,
+
+    /// The most recently consumed [`Token`]'s [`Span`].
+    previous_span: Span,
+
+    /// Whether `in` can be parsed as a binary operator. This is disabled in
+    /// the value of a `let` expression so that `in` ends the value.
+    is_in_allowed: bool,
+
+    /// Whether a block can be passed as a call's last argument. This is
+    /// disabled in the condition of a `while` expression so that the block
+    /// is parsed as the loop's body.
+    is_block_arg_allowed: bool,
+
+    /// The current depth of nested parsing functions.
+    depth: usize,
+
+    /// The number of [`Token`]s read, excluding [`Token::Eof`].
+    token_count: usize,
+
+    /// The maximum number of [`Token`]s, if any.
+    max_tokens: Option<usize>,
+
+    /// The maximum number of [`ParseError`]s.
+    max_errors: usize,
+
+    /// The [`ParseError`]s, up to the maximum number of errors.
+    errors: Vec<ParseError>,
 }
 
-impl<'src> Parser<'src> {
-    /// Creates a new `Parser` from source code.
-    fn new(source: &'src str) -> Self {
+impl<'src, 'ops> Parser<'src, 'ops> {
+    /// Creates a new `Parser` from source code with a session's [`Settings`]
+    /// and [`Operators`].
+    fn new(source: &'src str, settings: &Settings, operators: &'ops mut Operators) -> Self {
+        let limits = settings.limits;
         let mut parser = Self {
-            lexer: Lexer::new(source),
+            source,
+            operators,
+            lexer: Lexer::new(source, settings.si_suffixes),
             next_token: Token::Eof,
-            error: None,
+            next_span: Span::default(),
+            previous_span: Span::default(),
+            is_in_allowed: true,
+            is_block_arg_allowed: true,
+            depth: 0,
+            token_count: 0,
+            max_tokens: limits.tokens,
+            max_errors: limits.max_errors(),
+            errors: Vec::new(),
         };
 
         parser.bump();
@@ -63,20 +148,90 @@ impl<'src> Parser<'src> {
     /// Parses a sequence of statement [`Expr`]s until the next [`Token`]
     /// matches a terminator [`TokenType`].
     fn parse_sequence(&mut self, terminator: TokenType) -> Box<[Expr]> {
+        let is_in_allowed = mem::replace(&mut self.is_in_allowed, true);
+        let is_block_arg_allowed = mem::replace(&mut self.is_block_arg_allowed, true);
         let mut stmts = Vec::new();
 
         while !self.is_terminated(terminator) {
-            let stmt = self.parse_stmt();
+            let mut stmt = self.parse_stmt();
+
+            if self.eat(TokenType::Semicolon) {
+                let span = stmt.span;
+                stmt = Expr::new(ExprKind::Silent(Box::new(stmt)), span);
+            } else {
+                self.eat(TokenType::Comma);
+            }
+
             stmts.push(stmt);
-            self.eat(TokenType::Comma);
         }
 
+        self.is_in_allowed = is_in_allowed;
+        self.is_block_arg_allowed = is_block_arg_allowed;
         stmts.into_boxed_slice()
     }
 
     /// Parses a statement [`Expr`].
     fn parse_stmt(&mut self) -> Expr {
-        self.parse_expr()
+        // `operator` is a variable unless it is followed by an operator name.
+        if self.peek_keyword() == Some(Keyword::Operator)
+            && matches!(self.peek_second(), TokenType::CustomOp | TokenType::Ident)
+        {
+            self.parse_stmt_define_operator()
+        } else {
+            self.parse_expr()
+        }
+    }
+
+    /// Parses a custom operator definition statement [`Expr`].
+    fn parse_stmt_define_operator(&mut self) -> Expr {
+        let start_span = self.next_span;
+        self.bump(); // Consume the `operator` keyword.
+
+        let symbol = match self.bump() {
+            Token::CustomOp(symbol) => symbol,
+            token => {
+                let error = ErrorKind::UnexpectedToken(TokenType::CustomOp, token);
+                self.report_error(error, self.previous_span);
+                return Expr::new(error_expr_kind(), self.span_from(start_span));
+            }
+        };
+
+        let mut fixity = Fixity::default();
+        let mut modifiers = Vec::new();
+
+        while let Token::Ident(modifier) = self.next_token {
+            self.bump(); // Consume the modifier.
+            modifiers.push(modifier);
+
+            match Keyword::from_name(&modifier.to_string()) {
+                Some(Keyword::Sum) => fixity.precedence = Precedence::Sum,
+                Some(Keyword::Term) => fixity.precedence = Precedence::Term,
+                Some(Keyword::Left) => fixity.associativity = Associativity::Left,
+                Some(Keyword::Right) => fixity.associativity = Associativity::Right,
+                _ => self.report_error(
+                    ErrorKind::InvalidOperatorModifier(modifier),
+                    self.previous_span,
+                ),
+            }
+        }
+
+        // The operator is defined before its body is parsed so that it can be
+        // used recursively and by later source code.
+        self.operators.define(symbol, fixity);
+
+        let open_span = self.next_span;
+        self.expect(TokenType::OpenParen);
+        let list = self.parse_expr_paren();
+        let list = Expr::new(list, self.span_from(open_span));
+        self.expect(TokenType::Equals);
+        let body = self.parse_expr_mapping();
+        let kind = ExprKind::DefineOperator(
+            symbol,
+            modifiers.into_boxed_slice(),
+            Box::new(list),
+            Box::new(body),
+        );
+        Expr::new(kind, self.span_from(start_span))
     }
 
     /// Parses an [`Expr`].
@@ -88,14 +243,31 @@ impl<'src> Parser<'src> {
     fn parse_expr_assignment(&mut self) -> Expr {
         let lhs = self.parse_expr_mapping();
 
-        if self.eat(TokenType::Equals) {
+        if let Some(op) = compound_assign_op(self.peek()) {
+            self.bump(); // Consume the compound assignment token.
             let source = self.parse_expr_mapping();
+            let span = lhs.span.to(source.span);
+            let kind = ExprKind::CompoundAssign(op, Box::new(lhs), Box::new(source));
+            Expr::new(kind, span)
+        } else if self.eat(TokenType::Equals) {
+            let mut source = self.parse_expr_mapping();
+
+            if self.eat_keyword(Keyword::When) {
+                let guard = self.parse_expr_mapping();
+                let span = source.span.to(guard.span);
+                let kind = ExprKind::Guarded(Box::new(source), Some(Box::new(guard)));
+                source = Expr::new(kind, span);
+            } else if self.eat_keyword(Keyword::Otherwise) {
+                let span = self.span_from(source.span);
+                source = Expr::new(ExprKind::Guarded(Box::new(source), None), span);
+            }
 
             if self.peek() == TokenType::Equals {
-                self.report_error(ErrorKind::ChainedAssignment);
+                self.report_error(ErrorKind::ChainedAssignment, self.next_span);
             }
 
-            Expr::Assign(Box::new(lhs), Box::new(source))
+            let span = lhs.span.to(source.span);
+            Expr::new(ExprKind::Assign(Box::new(lhs), Box::new(source)), span)
         } else {
             lhs
         }
@@ -103,134 +275,264 @@ impl<'src> Parser<'src> {
 
     /// Parses a function [`Expr`] or a ternary conditional [`Expr`].
     fn parse_expr_mapping(&mut self) -> Expr {
-        let lhs = self.parse_expr_or();
+        if !self.enter_nested() {
+            return Expr::new(error_expr_kind(), self.next_span);
+        }
+
+        let lhs = self.parse_expr_infix(0);
 
-        match self.peek() {
+        let expr = match self.peek() {
             TokenType::MinusGreater => {
                 self.bump(); // Consume the operator token.
                 let body = self.parse_expr_mapping();
-                Expr::Function(Box::new(lhs), Box::new(body))
+                let span = lhs.span.to(body.span);
+                Expr::new(ExprKind::Function(Box::new(lhs), Box::new(body)), span)
             }
             TokenType::Question => {
                 self.bump(); // Consume the operator token.
                 let then_expr = self.parse_expr();
                 self.expect(TokenType::Colon);
                 let else_expr = self.parse_expr_mapping();
-                Expr::Cond(Box::new(lhs), Box::new(then_expr), Box::new(else_expr))
+                let span = lhs.span.to(else_expr.span);
+                let kind = ExprKind::Cond(Box::new(lhs), Box::new(then_expr), Box::new(else_expr));
+                Expr::new(kind, span)
             }
             _ => lhs,
-        }
-    }
-
-    /// Parses a logical or [`Expr`].
-    fn parse_expr_or(&mut self) -> Expr {
-        let mut lhs = self.parse_expr_and();
-
-        while self.eat(TokenType::PipePipe) {
-            let rhs = self.parse_expr_and();
-            lhs = Expr::Logic(LogicOp::Or, Box::new(lhs), Box::new(rhs));
-        }
+        };
 
-        lhs
+        self.depth -= 1;
+        expr
     }
 
-    /// Parses a logical and [`Expr`].
-    fn parse_expr_and(&mut self) -> Expr {
-        let mut lhs = self.parse_expr_comparison();
-
-        while self.eat(TokenType::AndAnd) {
-            let rhs = self.parse_expr_comparison();
-            lhs = Expr::Logic(LogicOp::And, Box::new(lhs), Box::new(rhs));
+    /// Parses a binary operation [`Expr`] with operators at or above an
+    /// index into [`INFIX_LEVELS`]. Indices past the end of [`INFIX_LEVELS`]
+    /// parse a prefix [`Expr`].
+    fn parse_expr_infix(&mut self, min_index: usize) -> Expr {
+        if !self.enter_nested() {
+            return Expr::new(error_expr_kind(), self.next_span);
         }
 
-        lhs
-    }
+        let mut lhs = self.parse_expr_prefix();
+        let mut chain_length = 0;
 
-    /// Parses a comparison [`Expr`].
-    pub fn parse_expr_comparison(&mut self) -> Expr {
-        let lhs = self.parse_expr_sum();
+        // Chained operations are parsed in a loop, so they do not count as
+        // nesting.
+        while let Some((index, op, associativity)) = self.peek_infix_op() {
+            if index < min_index || !self.extend_chain(&mut chain_length) {
+                break;
+            }
 
-        if let Some(op) = BinOp::comparison_from_token_type(self.peek()) {
             self.bump(); // Consume the operator token.
-            let rhs = self.parse_expr_sum();
 
-            if BinOp::comparison_from_token_type(self.peek()).is_some() {
-                self.report_error(ErrorKind::ChainedComparison);
-            }
+            let rhs = match associativity {
+                Associativity::Left => self.parse_expr_infix(index + 1),
+                Associativity::Right => self.parse_expr_infix(index),
+            };
 
-            Expr::Binary(op, Box::new(lhs), Box::new(rhs))
-        } else {
-            lhs
-        }
-    }
+            let is_non_associative = INFIX_LEVELS
+                .get(index)
+                .is_some_and(|l| l.grouping == Grouping::None);
 
-    /// Parses a sum [`Expr`].
-    pub fn parse_expr_sum(&mut self) -> Expr {
-        let mut lhs = self.parse_expr_term();
+            if is_non_associative
+                && let Some((_, next_op, _)) = self.peek_infix_op().filter(|(i, ..)| *i == index)
+            {
+                self.bump(); // Consume the operator token.
+                let next_rhs = self.parse_expr_infix(index + 1);
+                let suggestion = format!(
+                    "{lhs} {op} {rhs} && {rhs} {next_op} {next_rhs}",
+                    lhs = lhs.to_source(),
+                    rhs = rhs.to_source(),
+                    next_rhs = next_rhs.to_source(),
+                );
+
+                let span = lhs.span.to(next_rhs.span);
+                self.report_error(ErrorKind::ChainedComparison(suggestion), span);
+            }
 
-        while let Some(op) = BinOp::sum_from_token_type(self.peek()) {
-            self.bump(); // Consume the operator token.
-            let rhs = self.parse_expr_term();
-            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+            lhs = op.expr(lhs, rhs);
         }
 
+        self.depth -= 1;
         lhs
     }
 
-    /// Parses a term [`Expr`].
-    pub fn parse_expr_term(&mut self) -> Expr {
-        let mut lhs = self.parse_expr_prefix();
+    /// Parses a prefix [`Expr`].
+    fn parse_expr_prefix(&mut self) -> Expr {
+        let start_span = self.next_span;
 
-        while let Some(op) = BinOp::term_from_token_type(self.peek()) {
-            self.bump(); // Consume the operator token.
-            let rhs = self.parse_expr_prefix();
-            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        if !self.enter_nested() {
+            return Expr::new(error_expr_kind(), start_span);
         }
 
-        lhs
-    }
-
-    /// Parses a prefix [`Expr`].
-    fn parse_expr_prefix(&mut self) -> Expr {
-        let mut lhs = match self.bump() {
-            Token::Literal(literal) => Expr::Literal(literal),
-            Token::Ident(symbol) => Expr::Variable(symbol),
+        let kind = match self.bump() {
+            Token::Literal(literal) => ExprKind::Literal(literal),
+            Token::Ident(symbol) => self.parse_expr_ident(symbol),
             Token::OpenParen => self.parse_expr_paren(),
+            Token::OpenBracket => self.parse_expr_list(),
             Token::OpenBrace => {
                 let stmts = self.parse_sequence(TokenType::CloseBrace);
                 self.expect(TokenType::CloseBrace);
-                Expr::Block(stmts)
+                ExprKind::Block(stmts)
             }
             Token::Minus => {
                 let rhs = self.parse_expr_prefix();
-                Expr::Unary(UnOp::Negate, Box::new(rhs))
+                ExprKind::Unary(UnOp::Negate, Box::new(rhs))
             }
             Token::Bang => {
                 let rhs = self.parse_expr_prefix();
-                Expr::Unary(UnOp::Not, Box::new(rhs))
+                ExprKind::Unary(UnOp::Not, Box::new(rhs))
+            }
+            Token::CustomOp(symbol) => {
+                self.report_error(ErrorKind::UndefinedOperator(symbol), self.previous_span);
+                error_expr_kind()
             }
             token => {
-                self.report_error(ErrorKind::ExpectedExpr(token));
-                error_expr()
+                self.report_error(ErrorKind::ExpectedExpr(token), self.previous_span);
+                error_expr_kind()
             }
         };
 
-        while self.eat(TokenType::OpenParen) {
-            let list = self.parse_expr_paren();
-            lhs = Expr::Call(Box::new(lhs), Box::new(list));
+        let mut lhs = Expr::new(kind, self.span_from(start_span));
+        let mut chain_length = 0;
+
+        loop {
+            let is_index = self.peek() == TokenType::OpenBracket && !self.is_next_on_new_line();
+
+            if !is_index && self.peek() != TokenType::OpenParen {
+                break;
+            }
+
+            if !self.extend_chain(&mut chain_length) {
+                break;
+            }
+
+            self.bump(); // Consume the opening parenthesis or bracket.
+            let open_span = self.previous_span;
+
+            if is_index {
+                let is_in_allowed = mem::replace(&mut self.is_in_allowed, true);
+                let is_block_arg_allowed = mem::replace(&mut self.is_block_arg_allowed, true);
+                let index = self.parse_expr();
+                self.expect(TokenType::CloseBracket);
+                self.is_in_allowed = is_in_allowed;
+                self.is_block_arg_allowed = is_block_arg_allowed;
+                let span = lhs.span.to(self.previous_span);
+                lhs = Expr::new(ExprKind::Index(Box::new(lhs), Box::new(index)), span);
+                continue;
+            }
+
+            let mut list = self.parse_expr_paren();
+
+            // A block on the same line as a call's closing parenthesis is
+            // passed as the call's last argument (e.g. `f(a) { x -> x }`).
+            if self.is_block_arg_allowed
+                && self.peek() == TokenType::OpenBrace
+                && !self.is_next_on_new_line()
+            {
+                let block_span = self.next_span;
+                self.bump(); // Consume the opening brace.
+                let stmts = self.parse_sequence(TokenType::CloseBrace);
+                self.expect(TokenType::CloseBrace);
+                let block = Expr::new(ExprKind::Block(stmts), self.span_from(block_span));
+                list = trailing_block_list(list, block);
+            }
+
+            let list = Expr::new(list, self.span_from(open_span));
+            let span = lhs.span.to(list.span);
+            lhs = Expr::new(ExprKind::Call(Box::new(lhs), Box::new(list)), span);
+        }
+
+        // Superscript exponents (e.g. `x²`) are shorthand for powers.
+        if let Token::Superscript(exponent) = self.next_token {
+            self.bump(); // Consume the superscript token.
+            let exponent = ExprKind::Literal(Literal::Number(exponent));
+            let rhs = Expr::new(exponent, self.previous_span);
+            lhs = binary_expr(BinOp::Power, lhs, rhs);
         }
 
         if self.eat(TokenType::Caret) {
             let rhs = self.parse_expr_prefix();
-            lhs = Expr::Binary(BinOp::Power, Box::new(lhs), Box::new(rhs));
+            lhs = binary_expr(BinOp::Power, lhs, rhs);
         }
 
+        self.depth -= 1;
         lhs
     }
 
-    /// Parses a parenthesized [`Expr`] or a tuple [`Expr`] after consuming its
-    /// opening parenthesis.
-    fn parse_expr_paren(&mut self) -> Expr {
+    /// Parses a variable [`ExprKind`] or a keyword [`ExprKind`] after
+    /// consuming its identifier [`Symbol`]. Identifiers for contextual
+    /// [`Keyword`]s are only parsed as keywords if the next [`Token`] can
+    /// follow the keyword, so they can still be used as variable names (e.g.
+    /// `loop = 1`).
+    fn parse_expr_ident(&mut self, symbol: Symbol) -> ExprKind {
+        let is_expr_next = is_expr_start(self.peek());
+        let is_paren_next = self.peek() == TokenType::OpenParen;
+
+        match Keyword::from_name(&symbol.to_string()) {
+            Some(Keyword::Let) if is_expr_next => self.parse_expr_let(),
+            Some(Keyword::Lazy) if is_expr_next => {
+                let expr = self.parse_expr_mapping();
+                ExprKind::Lazy(Box::new(expr))
+            }
+            Some(Keyword::Loop) if is_paren_next => self.parse_expr_loop(),
+            Some(Keyword::While) if is_expr_next => self.parse_expr_while(),
+            Some(Keyword::Recur) if is_paren_next => {
+                let list = self.parse_list();
+                ExprKind::Recur(Box::new(list))
+            }
+            _ => ExprKind::Variable(symbol),
+        }
+    }
+
+    /// Parses a `let` [`ExprKind`] after consuming its `let` keyword.
+    fn parse_expr_let(&mut self) -> ExprKind {
+        let target = self.parse_expr_prefix();
+        self.expect(TokenType::Equals);
+        let is_in_allowed = mem::replace(&mut self.is_in_allowed, false);
+        let value = self.parse_expr_mapping();
+        self.is_in_allowed = is_in_allowed;
+        self.expect_keyword(Keyword::In);
+        let body = self.parse_expr_mapping();
+        ExprKind::Let(Box::new(target), Box::new(value), Box::new(body))
+    }
+
+    /// Parses a `loop` [`ExprKind`] after consuming its `loop` keyword.
+    fn parse_expr_loop(&mut self) -> ExprKind {
+        let list = self.parse_list();
+        let start_span = self.next_span;
+        self.expect(TokenType::OpenBrace);
+        let stmts = self.parse_sequence(TokenType::CloseBrace);
+        self.expect(TokenType::CloseBrace);
+        let body = Expr::new(ExprKind::Block(stmts), self.span_from(start_span));
+        ExprKind::Loop(Box::new(list), Box::new(body))
+    }
+
+    /// Parses a `while` [`ExprKind`] after consuming its `while` keyword.
+    fn parse_expr_while(&mut self) -> ExprKind {
+        let is_block_arg_allowed = mem::replace(&mut self.is_block_arg_allowed, false);
+        let cond = self.parse_expr_mapping();
+        self.is_block_arg_allowed = is_block_arg_allowed;
+        let start_span = self.next_span;
+        self.expect(TokenType::OpenBrace);
+        let stmts = self.parse_sequence(TokenType::CloseBrace);
+        self.expect(TokenType::CloseBrace);
+        let body = Expr::new(ExprKind::Block(stmts), self.span_from(start_span));
+        ExprKind::While(Box::new(cond), Box::new(body))
+    }
+
+    /// Parses a parenthesized list [`Expr`] including its opening parenthesis.
+    fn parse_list(&mut self) -> Expr {
+        let start_span = self.next_span;
+        self.expect(TokenType::OpenParen);
+        let list = self.parse_expr_paren();
+        Expr::new(list, self.span_from(start_span))
+    }
+
+    /// Parses a parenthesized [`ExprKind`] or a tuple [`ExprKind`] after
+    /// consuming its opening parenthesis.
+    fn parse_expr_paren(&mut self) -> ExprKind {
+        let is_in_allowed = mem::replace(&mut self.is_in_allowed, true);
+        let is_block_arg_allowed = mem::replace(&mut self.is_block_arg_allowed, true);
         let mut exprs = Vec::new();
 
         let is_empty_or_has_trailing_comma = loop {
@@ -247,16 +549,39 @@ impl<'src> Parser<'src> {
         };
 
         self.expect(TokenType::CloseParen);
+        self.is_in_allowed = is_in_allowed;
+        self.is_block_arg_allowed = is_block_arg_allowed;
 
         if is_empty_or_has_trailing_comma || exprs.len() != 1 {
-            Expr::Tuple(exprs.into_boxed_slice())
+            ExprKind::Tuple(exprs.into_boxed_slice())
         } else {
             let expr = exprs
                 .pop()
                 .expect("parentheses should contain one expression");
 
-            Expr::Paren(Box::new(expr))
+            ExprKind::Paren(Box::new(expr))
+        }
+    }
+
+    /// Parses a list [`ExprKind`] after consuming its opening bracket.
+    fn parse_expr_list(&mut self) -> ExprKind {
+        let is_in_allowed = mem::replace(&mut self.is_in_allowed, true);
+        let is_block_arg_allowed = mem::replace(&mut self.is_block_arg_allowed, true);
+        let mut exprs = Vec::new();
+
+        while !self.is_terminated(TokenType::CloseBracket) {
+            let expr = self.parse_expr();
+            exprs.push(expr);
+
+            if !self.eat(TokenType::Comma) {
+                break;
+            }
         }
+
+        self.expect(TokenType::CloseBracket);
+        self.is_in_allowed = is_in_allowed;
+        self.is_block_arg_allowed = is_block_arg_allowed;
+        ExprKind::List(exprs.into_boxed_slice())
     }
 
     /// Returns the next [`Token`]'s [`TokenType`].
@@ -264,6 +589,84 @@ impl<'src> Parser<'src> {
         self.next_token.token_type()
     }
 
+    /// Returns the index into [`INFIX_LEVELS`], [`InfixOp`], and
+    /// [`Associativity`] of the next [`Token`]. This function returns [`None`]
+    /// if the next [`Token`] is not a binary operator or a defined custom
+    /// operator.
+    fn peek_infix_op(&self) -> Option<(usize, InfixOp, Associativity)> {
+        if let Token::CustomOp(symbol) = self.next_token {
+            let fixity = self.operators.fixity(symbol)?;
+
+            let index = INFIX_LEVELS
+                .iter()
+                .position(|l| l.custom == Some(fixity.precedence))?;
+
+            return Some((index, InfixOp::Custom(symbol), fixity.associativity));
+        }
+
+        let token = match self.peek_keyword() {
+            Some(Keyword::In) if !self.is_in_allowed || !is_expr_start(self.peek_second()) => {
+                return None;
+            }
+            Some(keyword) => InfixToken::Keyword(keyword),
+            None => InfixToken::Type(self.peek()),
+        };
+
+        INFIX_LEVELS
+            .iter()
+            .enumerate()
+            .find_map(|(i, l)| Some((i, l.operator(token)?, Associativity::Left)))
+    }
+
+    /// Returns the contextual [`Keyword`] of the next [`Token`] if it is an
+    /// identifier for a [`Keyword`]. This function returns [`None`] if the
+    /// next [`Token`] is not a [`Keyword`].
+    fn peek_keyword(&self) -> Option<Keyword> {
+        match self.next_token {
+            Token::Ident(symbol) => Keyword::from_name(&symbol.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Returns the [`TokenType`] of the [`Token`] after the next [`Token`]
+    /// without consuming any [`Token`]s. Errors are left to be reported when
+    /// the [`Token`] is consumed.
+    fn peek_second(&self) -> TokenType {
+        self.lexer
+            .clone()
+            .next_token()
+            .map_or(TokenType::Eof, |t| t.token_type())
+    }
+
+    /// Consumes the next [`Token`] if it is an identifier for a contextual
+    /// [`Keyword`]. This function returns [`true`] if a [`Token`] was consumed.
+    fn eat_keyword(&mut self, keyword: Keyword) -> bool {
+        if self.peek_keyword() != Some(keyword) {
+            return false;
+        }
+
+        self.bump(); // Consume the keyword.
+        true
+    }
+
+    /// Consumes the next [`Token`] and reports an error if it is not an
+    /// identifier for an expected contextual [`Keyword`].
+    fn expect_keyword(&mut self, expected: Keyword) {
+        if !self.eat_keyword(expected) {
+            let actual = self.bump();
+            let error = ErrorKind::ExpectedKeyword(expected, actual);
+            self.report_error(error, self.previous_span);
+        }
+    }
+
+    /// Returns [`true`] if there is a line break between the most recently
+    /// consumed [`Token`] and the next [`Token`].
+    fn is_next_on_new_line(&self) -> bool {
+        self.source
+            .get(self.previous_span.end..self.next_span.start)
+            .is_some_and(|g| g.contains('\n'))
+    }
+
     /// Returns [`true`] if the next [`Token`] matches a terminator
     /// [`TokenType`] or is the end of source code.
     fn is_terminated(&self, terminator: TokenType) -> bool {
@@ -271,15 +674,35 @@ impl<'src> Parser<'src> {
         next_token_type == terminator || next_token_type == TokenType::Eof
     }
 
+    /// Returns a [`Span`] from a start [`Span`] to the end of the most
+    /// recently consumed [`Token`].
+    const fn span_from(&self, start: Span) -> Span {
+        start.to(self.previous_span)
+    }
+
     /// Consumes the next [`Token`].
     fn bump(&mut self) -> Token {
-        let following_token = loop {
+        let mut following_token = loop {
             match self.lexer.next_token() {
                 Ok(token) => break token,
-                Err(error) => self.report_error(ErrorKind::Lex(error)),
+                Err(error) => self.report_error(ErrorKind::Lex(error), self.lexer.span()),
             }
         };
 
+        if following_token.token_type() != TokenType::Eof {
+            self.token_count += 1;
+
+            // Source code is cut off at the maximum number of tokens so that
+            // the rest of it is not parsed.
+            if let Some(max_tokens) = self.max_tokens
+                && self.token_count > max_tokens
+            {
+                self.report_error(ErrorKind::TooManyTokens(max_tokens), self.lexer.span());
+                following_token = Token::Eof;
+            }
+        }
+
+        self.previous_span = mem::replace(&mut self.next_span, self.lexer.span());
         mem::replace(&mut self.next_token, following_token)
     }
 
@@ -301,62 +724,106 @@ impl<'src> Parser<'src> {
         let actual = self.bump();
 
         if actual.token_type() != expected {
-            self.report_error(ErrorKind::UnexpectedToken(expected, actual));
+            let error = ErrorKind::UnexpectedToken(expected, actual);
+            self.report_error(error, self.previous_span);
         }
     }
 
-    /// Reports an [`ErrorKind`].
-    #[cold]
-    fn report_error(&mut self, error: ErrorKind) {
-        self.error
-            .get_or_insert_with(|| ParseError(Box::new(error)));
-    }
-}
+    /// Enters a nested parsing function. If the maximum depth would be
+    /// exceeded, then an error is reported, the rest of the source code is
+    /// skipped, and [`false`] is returned. Otherwise, the depth is increased
+    /// and [`true`] is returned.
+    fn enter_nested(&mut self) -> bool {
+        if self.depth >= MAX_DEPTH {
+            self.report_error(ErrorKind::TooDeep, self.next_span);
 
-impl BinOp {
-    /// Returns a comparison `BinOp` from a [`TokenType`]. This function returns
-    /// [`None`] if the [`TokenType`] does not correspond to a comparison
-    /// `BinOp`.
-    const fn comparison_from_token_type(token_type: TokenType) -> Option<Self> {
-        let op = match token_type {
-            TokenType::EqualsEquals => Self::Equal,
-            TokenType::BangEquals => Self::NotEqual,
-            TokenType::Less => Self::Less,
-            TokenType::LessEquals => Self::LessEqual,
-            TokenType::Greater => Self::Greater,
-            TokenType::GreaterEquals => Self::GreaterEqual,
-            _ => return None,
-        };
+            while self.peek() != TokenType::Eof {
+                self.bump();
+            }
+
+            return false;
+        }
 
-        Some(op)
+        self.depth += 1;
+        true
     }
 
-    /// Returns a sum `BinOp` from a [`TokenType`]. This function returns
-    /// [`None`] if the [`TokenType`] does not correspond to a sum `BinOp`.
-    const fn sum_from_token_type(token_type: TokenType) -> Option<Self> {
-        let op = match token_type {
-            TokenType::Plus => Self::Add,
-            TokenType::Minus => Self::Subtract,
-            _ => return None,
-        };
+    /// Extends a chain of operations, calls, or indices by one link. If the
+    /// maximum chain length would be exceeded, then an error is reported, the
+    /// rest of the source code is skipped, and [`false`] is returned.
+    /// Otherwise, the chain length is increased and [`true`] is returned.
+    fn extend_chain(&mut self, chain_length: &mut usize) -> bool {
+        if *chain_length >= MAX_CHAIN_LENGTH {
+            self.report_error(ErrorKind::TooLong(MAX_CHAIN_LENGTH), self.next_span);
 
-        Some(op)
-    }
+            while self.peek() != TokenType::Eof {
+                self.bump();
+            }
 
-    /// Returns a term `BinOp` from a [`TokenType`]. This function returns
-    /// [`None`] if the [`TokenType`] does not correspond to a term `BinOp`.
-    const fn term_from_token_type(token_type: TokenType) -> Option<Self> {
-        let op = match token_type {
-            TokenType::Star => Self::Multiply,
-            TokenType::Slash => Self::Divide,
-            _ => return None,
-        };
+            return false;
+        }
+
+        *chain_length += 1;
+        true
+    }
 
-        Some(op)
+    /// Reports an [`ErrorKind`] at a source code [`Span`].
+    #[cold]
+    fn report_error(&mut self, error: ErrorKind, span: Span) {
+        if self.errors.len() < self.max_errors {
+            self.errors.push(ParseError(Box::new(error), span));
+        }
     }
 }
 
-/// Creates a new synthetic [`Expr`] for error recovery.
-const fn error_expr() -> Expr {
-    Expr::Literal(Literal::Number(0.0))
+/// Creates a new binary [`Expr`] spanning both of its operands.
+fn binary_expr(op: BinOp, lhs: Expr, rhs: Expr) -> Expr {
+    let span = lhs.span.to(rhs.span);
+    Expr::new(ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)), span)
+}
+
+/// Returns the [`BinOp`] of a compound assignment [`TokenType`]. This function
+/// returns [`None`] if the [`TokenType`] is not a compound assignment.
+const fn compound_assign_op(token_type: TokenType) -> Option<BinOp> {
+    let op = match token_type {
+        TokenType::PlusEquals => BinOp::Add,
+        TokenType::MinusEquals => BinOp::Subtract,
+        TokenType::StarEquals => BinOp::Multiply,
+        TokenType::SlashEquals => BinOp::Divide,
+        _ => return None,
+    };
+
+    Some(op)
+}
+
+/// Appends a trailing block argument [`Expr`] to a call's argument list
+/// [`ExprKind`].
+fn trailing_block_list(list: ExprKind, block: Expr) -> ExprKind {
+    let mut args = match list {
+        ExprKind::Paren(arg) => vec![*arg],
+        ExprKind::Tuple(args) => args.into_vec(),
+        _ => unreachable!("argument list should be parenthesized or a tuple"),
+    };
+
+    args.push(block);
+    ExprKind::Tuple(args.into_boxed_slice())
+}
+
+/// Creates a new synthetic [`ExprKind`] for error recovery.
+const fn error_expr_kind() -> ExprKind {
+    ExprKind::Literal(Literal::Number(0.0))
+}
+
+/// Returns [`true`] if a [`TokenType`] can begin an [`Expr`].
+const fn is_expr_start(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::Literal
+            | TokenType::Ident
+            | TokenType::OpenParen
+            | TokenType::OpenBracket
+            | TokenType::OpenBrace
+            | TokenType::Minus
+            | TokenType::Bang
+    )
 }
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use crate::symbols::Symbol;
+
+/// A table of custom operator definitions. Each session has its own
+/// `Operators` so that operators defined on one REPL line can be parsed on
+/// later lines.
+#[derive(Clone, Default)]
+pub struct Operators(HashMap<Symbol, Fixity>);
+
+impl Operators {
+    /// Creates new empty `Operators`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defines a custom operator with a [`Symbol`] and a [`Fixity`], replacing
+    /// any existing definition.
+    pub fn define(&mut self, symbol: Symbol, fixity: Fixity) {
+        self.0.insert(symbol, fixity);
+    }
+
+    /// Returns the [`Fixity`] of a custom operator from its [`Symbol`]. This
+    /// function returns [`None`] if the operator is not defined.
+    pub fn fixity(&self, symbol: Symbol) -> Option<Fixity> {
+        self.0.get(&symbol).copied()
+    }
+}
+
+/// The precedence and associativity of a custom operator.
+#[derive(Clone, Copy)]
+pub struct Fixity {
+    /// The [`Precedence`].
+    pub precedence: Precedence,
+
+    /// The [`Associativity`].
+    pub associativity: Associativity,
+}
+
+impl Default for Fixity {
+    fn default() -> Self {
+        Self {
+            precedence: Precedence::Term,
+            associativity: Associativity::Left,
+        }
+    }
+}
+
+/// A custom operator's precedence level.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Precedence {
+    /// The precedence level of `+` and `-`.
+    Sum,
+
+    /// The precedence level of `*` and `/`.
+    Term,
+}
+
+/// A custom operator's associativity.
+#[derive(Clone, Copy)]
+pub enum Associativity {
+    /// Operations are grouped from the left (e.g. `(a ⊕ b) ⊕ c`).
+    Left,
+
+    /// Operations are grouped from the right (e.g. `a ⊕ (b ⊕ c)`).
+    Right,
+}
@@ -1,9 +1,11 @@
 use super::*;
+use crate::{ast, limits::Limits};
 
 /// Asserts that an expected [`ErrorKind`] is produced from source code.
 macro_rules! assert_error {
     ($src:literal, $err:pat $(if $guard:expr)?) => {
-        let error_kind = *parse_source($src).expect_err("test source should be invalid").0;
+        let mut errors = parse_source($src, &Settings::new(), &mut Operators::new()).expect_err("test source should be invalid");
+        let error_kind = *errors.swap_remove(0).0;
         assert!(matches!(error_kind, $err $(if $guard)?));
     };
 }
@@ -181,6 +183,30 @@ fn call_arguments_allow_trailing_commas() {
     assert_ast("f(1, 2,)", "(a: (f (t: 1 2)))");
 }
 
+/// Tests that lists and indices are parsed.
+#[test]
+fn lists_and_indices_are_parsed() {
+    assert_ast("[]", "(a: (l:))");
+    assert_ast("[1]", "(a: (l: 1))");
+    assert_ast("[1, 2,]", "(a: (l: 1 2))");
+    assert_ast("[[x], []]", "(a: (l: (l: x) (l:)))");
+    assert_ast("xs[0]", "(a: ([] xs 0))");
+    assert_ast("f(x)[i][j]", "(a: ([] ([] (f (p: x)) i) j))");
+    assert_ast("xs[0](y)", "(a: (([] xs 0) (p: y)))");
+    assert_ast("-xs[0]^2", "(a: (- (^ ([] xs 0) 2)))");
+    assert_error!(
+        "[1 2]",
+        ErrorKind::UnexpectedToken(TokenType::CloseBracket, _)
+    );
+}
+
+/// Tests that brackets on a new line start a new statement instead of
+/// indexing the previous statement.
+#[test]
+fn indices_must_be_on_the_same_line() {
+    assert_ast("xs\n[0]", "(a: xs (l: 0))");
+}
+
 /// Tests that mismatched types are not checked by the [`Parser`].
 #[test]
 fn mismatched_types_are_unchecked() {
@@ -193,31 +219,75 @@ fn mismatched_types_are_unchecked() {
 fn comparisons_cannot_be_chained() {
     // Chained comparisons are not supported for forward compatibility with
     // expressions like `min <= value <= max`.
-    assert_error!("1 == x == y", ErrorKind::ChainedComparison);
-    assert_error!("x == y != z", ErrorKind::ChainedComparison);
-    assert_error!("1 != 2 == y", ErrorKind::ChainedComparison);
-    assert_error!("1 != 2 != 3", ErrorKind::ChainedComparison);
-    assert_error!("1 < 2 < 3", ErrorKind::ChainedComparison);
-    assert_error!("1 < 2 <= 3", ErrorKind::ChainedComparison);
-    assert_error!("1 <= 2 < 3", ErrorKind::ChainedComparison);
-    assert_error!("1 <= 2 <= 3", ErrorKind::ChainedComparison);
-    assert_error!("1 > 2 > 3", ErrorKind::ChainedComparison);
-    assert_error!("1 > 2 >= 3", ErrorKind::ChainedComparison);
-    assert_error!("1 >= 2 > 3", ErrorKind::ChainedComparison);
-    assert_error!("1 >= 2 >= 3", ErrorKind::ChainedComparison);
-    assert_error!("x == y < 10", ErrorKind::ChainedComparison);
+    assert_error!("1 == x == y", ErrorKind::ChainedComparison(_));
+    assert_error!("x == y != z", ErrorKind::ChainedComparison(_));
+    assert_error!("1 != 2 == y", ErrorKind::ChainedComparison(_));
+    assert_error!("1 != 2 != 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 < 2 < 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 < 2 <= 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 <= 2 < 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 <= 2 <= 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 > 2 > 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 > 2 >= 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 >= 2 > 3", ErrorKind::ChainedComparison(_));
+    assert_error!("1 >= 2 >= 3", ErrorKind::ChainedComparison(_));
+    assert_error!("x == y < 10", ErrorKind::ChainedComparison(_));
+    assert_error!("x in xs == true", ErrorKind::ChainedComparison(_));
 
     // Comparisons cannot be chained by mixing precedence levels.
-    assert_error!("1 + 2 == 3 - 0 == 4", ErrorKind::ChainedComparison);
-    assert_error!("1 * 2 != 0 / 3 == 4 * 0", ErrorKind::ChainedComparison);
-    assert_error!("1 + 2 >= 3 * 0 < 4", ErrorKind::ChainedComparison);
-    assert_error!("1 + 2 <= 3 / 1 > 0.5", ErrorKind::ChainedComparison);
-    assert_error!("!!true == !false == !!!false", ErrorKind::ChainedComparison);
-    assert_error!("foo() == bar() == baz()", ErrorKind::ChainedComparison);
+    assert_error!("1 + 2 == 3 - 0 == 4", ErrorKind::ChainedComparison(_));
+    assert_error!("1 * 2 != 0 / 3 == 4 * 0", ErrorKind::ChainedComparison(_));
+    assert_error!("1 + 2 >= 3 * 0 < 4", ErrorKind::ChainedComparison(_));
+    assert_error!("1 + 2 <= 3 / 1 > 0.5", ErrorKind::ChainedComparison(_));
+    assert_error!(
+        "!!true == !false == !!!false",
+        ErrorKind::ChainedComparison(_)
+    );
+    assert_error!("foo() == bar() == baz()", ErrorKind::ChainedComparison(_));
 
     // Groupings cannot chain comparisons if they do not contain the comparison
     // operator.
-    assert_error!("(a) == (b,) == {c}", ErrorKind::ChainedComparison);
+    assert_error!("(a) == (b,) == {c}", ErrorKind::ChainedComparison(_));
+}
+
+/// Tests that chained comparisons suggest a logical and of comparisons.
+#[test]
+fn chained_comparisons_suggest_logical_and() {
+    assert_error!(
+        "0 <= x+1 < (n)",
+        ErrorKind::ChainedComparison(s) if s == "0 <= x + 1 && x + 1 < (n)"
+    );
+}
+
+/// Tests that raw strings are parsed as literals.
+#[test]
+fn raw_strings_are_parsed() {
+    assert_ast("`a` in `abc`", "(a: (in `a` `abc`))");
+    assert_ast("f(`x\ny`)", "(a: (f (p: `x\ny`)))");
+    assert_error!("`open", ErrorKind::Lex(_));
+}
+
+/// Tests that statements followed by semicolons are parsed as silent.
+#[test]
+fn silent_statements_are_parsed() {
+    assert_ast("x = 1; x", "(a: (; (= x 1)) x)");
+    assert_ast("1; 2, 3;", "(a: (; 1) 2 (; 3))");
+    assert_ast("{y = 2; y}", "(a: (b: (; (= y 2)) y))");
+    assert_error!("1;;", ErrorKind::ExpectedExpr(_));
+}
+
+/// Tests that membership tests are parsed at the comparison precedence level.
+#[test]
+fn membership_tests_are_parsed() {
+    assert_ast("x in xs", "(a: (in x xs))");
+    assert_ast(
+        "x + 1 in range(a, b * 2)",
+        "(a: (in (+ x 1) (range (t: a (* b 2)))))",
+    );
+    assert_ast(
+        "!(x in xs) && y in ys",
+        "(a: (&& (! (p: (in x xs))) (in y ys)))",
+    );
 }
 
 /// Tests that comparisons can be chained with groupings.
@@ -296,6 +366,48 @@ fn unary_operators_have_expected_precedence_levels() {
     assert_ast("-1 ^ -2 ^ -3", "(a: (- (^ 1 (- (^ 2 (- 3))))))");
 }
 
+/// Tests that superscript exponents are parsed as powers.
+#[test]
+fn superscript_exponents_are_parsed() {
+    assert_ast("x²", "(a: (^ x 2))");
+    assert_ast("x³", "(a: (^ x 3))");
+    assert_ast("-x² * y³", "(a: (* (- (^ x 2)) (^ y 3)))");
+    assert_ast("f(x)²", "(a: (^ (f (p: x)) 2))");
+    assert_ast("(a + b)²", "(a: (^ (p: (+ a b)) 2))");
+    assert_error!("²", ErrorKind::ExpectedExpr(Token::Superscript(_)));
+}
+
+/// Tests that custom operators are parsed with their declared precedence
+/// levels and associativity.
+#[test]
+fn custom_operators_are_parsed() {
+    assert_ast(
+        "operator ⊕ (a, b) = a * b, 1 + 2 ⊕ 3 ⊕ 4",
+        "(a: (operator ⊕ (t: a b) (* a b)) (+ 1 (⊕ (⊕ 2 3) 4)))",
+    );
+
+    assert_ast(
+        "operator ⊖ sum right (a, b) = a - b, 1 * 2 ⊖ 3 ⊖ 4",
+        "(a: (operator ⊖ (t: a b) (- a b)) (⊖ (* 1 2) (⊖ 3 4)))",
+    );
+
+    assert_ast(
+        "operator ⊛ term right (a, b) = a ⊛ b, x ⊛ y ⊛ z",
+        "(a: (operator ⊛ (t: a b) (⊛ a b)) (⊛ x (⊛ y z)))",
+    );
+
+    assert_error!("1 ⊗ 2", ErrorKind::UndefinedOperator(s) if s.to_string() == "⊗");
+    assert_error!(
+        "operator ⊗ up (a, b) = a",
+        ErrorKind::InvalidOperatorModifier(s) if s.to_string() == "up"
+    );
+
+    assert_error!(
+        "operator foo (a, b) = a",
+        ErrorKind::UnexpectedToken(TokenType::CustomOp, Token::Ident(_))
+    );
+}
+
 /// Tests that binary operators have the expected precedence levels.
 #[test]
 fn binary_operators_have_expected_precedence_levels() {
@@ -383,8 +495,294 @@ fn lex_errors_are_caught() {
     );
 }
 
+/// Tests that [`ParseError`]s have [`Span`]s covering the source code which
+/// caused them.
+#[test]
+fn errors_have_spans_covering_source_code() {
+    for (source, expected) in [
+        ("foo + $bar", "$"),
+        ("a = b = c", "="),
+        ("1 < 2 < 3", "1 < 2 < 3"),
+        ("(1 + 2", ""),
+        ("x + ) * 2", ")"),
+    ] {
+        let errors = parse_source(source, &Settings::new(), &mut Operators::new())
+            .expect_err("test source should be invalid");
+        assert_eq!(errors[0].span().slice(source), expected);
+    }
+}
+
+/// Tests that every [`ParseError`] is reported up to the maximum number of
+/// errors.
+#[test]
+fn errors_are_reported_up_to_maximum() {
+    const SOURCE: &str = "$a + $b + $c";
+
+    let errors = parse_source(SOURCE, &Settings::new(), &mut Operators::new())
+        .expect_err("test source should be invalid");
+    let spans: Vec<_> = errors.iter().map(|e| e.span().slice(SOURCE)).collect();
+    assert_eq!(spans, ["$", "$", "$"]);
+
+    let mut settings = Settings::new();
+    settings.limits = Limits {
+        errors: Some(2),
+        ..Limits::default()
+    };
+
+    let capped_errors = parse_source(SOURCE, &settings, &mut Operators::new())
+        .expect_err("test source should be invalid");
+    assert_eq!(capped_errors.len(), 2);
+}
+
 /// Asserts that an expected [`Ast`] is parsed from source code.
 fn assert_ast(source: &str, expected: &str) {
-    let ast = parse_source(source).expect("source code should be valid");
+    let ast = parse_source(source, &Settings::new(), &mut Operators::new())
+        .expect("source code should be valid");
     assert_eq!(ast.to_string(), expected);
 }
+
+/// Tests that [`Expr`]s have [`Span`]s covering their source code.
+#[test]
+fn exprs_have_spans_covering_source_code() {
+    const SOURCE: &str = " f(x, -y) + (1 ? 2 : 3) ";
+
+    let ast = parse_source(SOURCE, &Settings::new(), &mut Operators::new())
+        .expect("source code should be valid");
+    let [expr] = ast.0.as_ref() else {
+        unreachable!("source code should contain one expression");
+    };
+
+    assert_eq!(expr.span.slice(SOURCE), "f(x, -y) + (1 ? 2 : 3)");
+
+    let ExprKind::Binary(_, lhs, rhs) = &expr.kind else {
+        unreachable!("expression should be a binary expression");
+    };
+
+    assert_eq!(lhs.span.slice(SOURCE), "f(x, -y)");
+    assert_eq!(rhs.span.slice(SOURCE), "(1 ? 2 : 3)");
+}
+
+/// Asserts that source code is pretty printed as expected source code.
+fn assert_pretty(source: &str, expected: &str) {
+    let ast = parse_source(source, &Settings::new(), &mut Operators::new())
+        .expect("source code should be valid");
+    assert_eq!(ast::pretty_print(&ast, source), expected);
+}
+
+/// Tests that source code is pretty printed in a consistent format.
+#[test]
+fn source_code_is_pretty_printed() {
+    assert_pretty("sq(x)=x*x sq(2)", "sq(x) = x * x,\nsq(2)\n");
+    assert_pretty("x = 1;\n\n\ny=x²", "x = 1;\n\ny = x²\n");
+    assert_pretty("f = (a,)->{a}", "f = (a,) -> { a }\n");
+    assert_pretty("{a=1,b=2;a+b}", "{\n    a = 1,\n    b = 2;\n    a + b\n}\n");
+    assert_pretty("-(x)^2>1?`a`:!b", "-(x) ^ 2 > 1 ? `a` : !b\n");
+    assert_pretty(
+        "operator ⊕ sum right(a,b)=a+b",
+        "operator ⊕ sum right (a, b) = a + b\n",
+    );
+}
+
+/// Asserts that printing the [`Ast`] parsed from source code produces source
+/// code which parses to the same [`Ast`] and prints the same way again.
+fn assert_round_trip(source: &str) {
+    let print = |ast: &Ast| {
+        let stmts: Vec<String> = ast.0.iter().map(Expr::to_source).collect();
+        stmts.join(", ")
+    };
+
+    let ast = parse_source(source, &Settings::new(), &mut Operators::new())
+        .expect("source code should be valid");
+    let printed = print(&ast);
+    let reparsed = parse_source(&printed, &Settings::new(), &mut Operators::new())
+        .expect("printed source code should be valid");
+    assert_eq!(reparsed.to_string(), ast.to_string());
+    assert_eq!(print(&reparsed), printed);
+
+    let pretty = ast::pretty_print(&ast, source);
+    let pretty_reparsed = parse_source(&pretty, &Settings::new(), &mut Operators::new())
+        .expect("pretty source code should be valid");
+    assert_eq!(pretty_reparsed.to_string(), ast.to_string());
+    assert_eq!(ast::pretty_print(&pretty_reparsed, &pretty), pretty);
+}
+
+/// Tests that printed source code is parsed to the same [`Ast`].
+#[test]
+fn printed_source_code_round_trips() {
+    assert_round_trip("n = 10, f(x) = x * x, f(n)");
+    assert_round_trip("g = (a, b,) -> { c = a - -b; c² + (a ^ b ^ 2) }");
+    assert_round_trip("h = () -> {}, h(), k(x,)");
+    assert_round_trip("x > 1 && !(x in range(1, 2)) || x == 0 ? `yes` : `no`");
+    assert_round_trip("f(x) = x < 0 ? -x : x, f(-0.5)(1)(2)");
+    assert_round_trip("{ a = 1, { b = 2, a + b }; }");
+    assert_round_trip("operator ⊗ term right (a, b) = a * b, 1 ⊗ 2 ⊗ 3");
+}
+
+/// Tests that the grammar documentation matches the generated grammar.
+#[test]
+fn grammar_docs_match_generated_grammar() {
+    let docs = include_str!("../../docs/grammar.md");
+    assert!(docs.contains(&format!("```ebnf\n{}```", grammar_ebnf())));
+}
+
+/// Tests that a block after a call on the same line is parsed as the call's
+/// last argument.
+#[test]
+fn trailing_blocks_are_parsed_as_arguments() {
+    assert_ast("f() { x }", "(a: (f (t: (b: x))))");
+    assert_ast("f(a) { x }", "(a: (f (t: a (b: x))))");
+    assert_ast("f(a, b,) { x }(c)", "(a: ((f (t: a b (b: x))) (p: c)))");
+    assert_ast("f(a)\n{ x }", "(a: (f (p: a)) (b: x))");
+}
+
+/// Tests that guarded function clauses are parsed.
+#[test]
+fn guarded_clauses_are_parsed() {
+    assert_ast(
+        "abs(x) = -x when x < 0, abs(x) = x otherwise",
+        "(a: (= (abs (p: x)) (when (- x) (< x 0))) (= (abs (p: x)) (otherwise x)))",
+    );
+
+    assert_ast("when = otherwise", "(a: (= when otherwise))");
+    assert_error!("f(x) = 1 when x = 2", ErrorKind::ChainedAssignment);
+    assert_round_trip("f(n) = 1 when n <= 1, f(n) = n * f(n - 1) otherwise");
+}
+
+/// Tests that literal parameter patterns are parsed as parameters.
+#[test]
+fn pattern_parameters_are_parsed() {
+    assert_ast(
+        "fib(0) = 0, f(-1, x) = x",
+        "(a: (= (fib (p: 0)) 0) (= (f (t: (- 1) x)) x))",
+    );
+}
+
+/// Tests that `let` expressions are parsed and that `in` ends their values.
+#[test]
+fn let_exprs_are_parsed() {
+    assert_ast("let x = 2 in x * x", "(a: (let x 2 (* x x)))");
+    assert_ast("1 + let x = 2 in x + 3", "(a: (+ 1 (let x 2 (+ x 3))))");
+    assert_ast("let b = (x in y) in b", "(a: (let b (p: (in x y)) b))");
+    assert_ast(
+        "let f(x) = { x in y } in f",
+        "(a: (let (f (p: x)) (b: (in x y)) f))",
+    );
+    assert_error!("let x = 1", ErrorKind::ExpectedKeyword(Keyword::In, _));
+    assert_round_trip("let x = 2 in let y = x in x * y");
+}
+
+/// Tests that `lazy` expressions are parsed.
+#[test]
+fn lazy_exprs_are_parsed() {
+    assert_ast("x = lazy 2 + 3", "(a: (= x (lazy (+ 2 3))))");
+    assert_ast("lazy x -> x", "(a: (lazy (-> x x)))");
+    assert_ast("f(lazy g(1), 2)", "(a: (f (t: (lazy (g (p: 1))) 2)))");
+    assert_round_trip("x = lazy { y = 2, y * y }");
+}
+
+/// Tests that `loop` and `recur` expressions are parsed.
+#[test]
+fn loop_exprs_are_parsed() {
+    assert_ast(
+        "loop(i = 0) { i > 3 ? i : recur(i + 1) }",
+        "(a: (loop (p: (= i 0)) (b: (? (> i 3) i (recur (p: (+ i 1)))))))",
+    );
+    assert_ast(
+        "loop(a = 1, b = 2) { recur(b, a) }",
+        "(a: (loop (t: (= a 1) (= b 2)) (b: (recur (t: b a)))))",
+    );
+    assert_error!(
+        "loop(i = 0) i",
+        ErrorKind::UnexpectedToken(TokenType::OpenBrace, _)
+    );
+    assert_round_trip("loop(acc = 0, i = 1) { i > 9 ? acc : recur(acc + i, i + 1) }");
+}
+
+/// Tests that compound assignments are parsed.
+#[test]
+fn compound_assignments_are_parsed() {
+    assert_ast("x += 1", "(a: (+= x 1))");
+    assert_ast("x /= y - 2", "(a: (/= x (- y 2)))");
+    assert_ast("x *= n -> n", "(a: (*= x (-> n n)))");
+    assert_round_trip("{ x -= 1, y *= x + 2 }");
+}
+
+/// Tests that `while` expressions are parsed and that their bodies are not
+/// passed as call arguments.
+#[test]
+fn while_exprs_are_parsed() {
+    assert_ast(
+        "while i < 3 { i = i + 1 }",
+        "(a: (while (< i 3) (b: (= i (+ i 1)))))",
+    );
+    assert_ast(
+        "while f(i) { i = g(i) { x -> x } }",
+        "(a: (while (f (p: i)) (b: (= i (g (t: i (b: (-> x x))))))))",
+    );
+    assert_error!(
+        "while true",
+        ErrorKind::UnexpectedToken(TokenType::OpenBrace, _)
+    );
+    assert_round_trip("while i <= n { acc = acc + i, i = i + 1 }");
+}
+
+/// Tests that contextual keywords are parsed as variables where the keyword
+/// cannot be used.
+#[test]
+fn contextual_keywords_are_parsed_as_variables() {
+    assert_ast("loop = 1", "(a: (= loop 1))");
+    assert_ast("recur + while", "(a: (+ recur while))");
+    assert_ast("f(lazy, let) = in", "(a: (= (f (t: lazy let)) in))");
+    assert_ast("operator = 2, operator", "(a: (= operator 2) operator)");
+    assert_ast("x in in", "(a: (in x in))");
+    assert_ast("let in = 1 in in", "(a: (let in 1 in))");
+    assert_round_trip("{ loop = [1, 2], lazy = 3, lazy * 2 in loop }");
+}
+
+/// Tests that deeply nested source code is rejected instead of overflowing the
+/// stack.
+#[test]
+fn deep_nesting_is_rejected() {
+    let deep_sources = [
+        format!("{}1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH)),
+        format!("{}1", "-".repeat(MAX_DEPTH)),
+        format!("{}1", "2^".repeat(MAX_DEPTH)),
+    ];
+
+    for source in deep_sources {
+        let mut errors = parse_source(&source, &Settings::new(), &mut Operators::new())
+            .expect_err("test source should be invalid");
+        let error_kind = *errors.swap_remove(0).0;
+
+        assert!(matches!(error_kind, ErrorKind::TooDeep));
+    }
+
+    // Chained operations, calls, and indices are not nested.
+    let flat_sources = [
+        vec!["1"; MAX_DEPTH * 4].join(" + "),
+        vec!["1"; MAX_DEPTH * 4].join(" * 2 - "),
+        format!("f{}", "()".repeat(MAX_DEPTH * 4)),
+        format!("x{}", "[0]".repeat(MAX_DEPTH * 4)),
+    ];
+
+    for source in flat_sources {
+        parse_source(&source, &Settings::new(), &mut Operators::new())
+            .expect("test source should be valid");
+    }
+
+    // Chains which are too long to be lowered and compiled safely are
+    // rejected.
+    let long_sources = [
+        vec!["1"; MAX_CHAIN_LENGTH + 2].join(" + "),
+        format!("f{}", "()".repeat(MAX_CHAIN_LENGTH + 1)),
+        format!("x{}", "[0]".repeat(MAX_CHAIN_LENGTH + 1)),
+    ];
+
+    for source in long_sources {
+        let mut errors = parse_source(&source, &Settings::new(), &mut Operators::new())
+            .expect_err("test source should be invalid");
+        let error_kind = *errors.swap_remove(0).0;
+
+        assert!(matches!(error_kind, ErrorKind::TooLong(MAX_CHAIN_LENGTH)));
+    }
+}
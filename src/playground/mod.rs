@@ -0,0 +1,114 @@
+// The host can only exchange data with WebAssembly through exported functions
+// and linear memory, so the exported functions must not be mangled. They only
+// return pointers to buffers, and never dereference pointers from the host.
+#![expect(unsafe_code, reason = "exported functions must not be mangled")]
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    cell::RefCell,
+    fmt::{self, Display, Write as _},
+};
+
+use crate::embed::{Engine, Observer};
+
+thread_local! {
+    /// The [`Engine`] which keeps global variables between evaluations.
+    static ENGINE: RefCell<Engine> = RefCell::new(new_engine());
+
+    /// The buffer which the host writes source code to.
+    static INPUT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+
+    /// The output which was captured by the most recent evaluation.
+    static OUTPUT: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Resizes the input buffer to a number of bytes and returns a pointer to it,
+/// so that the host can write source code to it before calling
+/// [`clac_eval`].
+#[unsafe(no_mangle)]
+pub extern "C" fn clac_input(len: usize) -> *mut u8 {
+    INPUT.with_borrow_mut(|input| {
+        input.clear();
+        input.resize(len, 0);
+        input.as_mut_ptr()
+    })
+}
+
+/// Evaluates the source code in the input buffer and captures its output.
+/// This function returns [`true`] if the source code ran without errors.
+#[unsafe(no_mangle)]
+pub extern "C" fn clac_eval() -> bool {
+    let source = INPUT.with_borrow(|input| String::from_utf8_lossy(input).into_owned());
+    OUTPUT.with_borrow_mut(String::clear);
+
+    ENGINE.with_borrow_mut(|engine| {
+        let evaluation = engine.eval(&source);
+
+        OUTPUT.with_borrow_mut(|output| {
+            for warning in &evaluation.warnings {
+                push_line(output, &format_args!("Warning: {warning}"));
+            }
+
+            match evaluation.into_result() {
+                Ok(values) => {
+                    for value in values {
+                        push_line(output, &engine.format(&value));
+                    }
+
+                    true
+                }
+                Err(errors) => {
+                    for error in errors {
+                        push_line(output, &format_args!("Error: {error}"));
+                    }
+
+                    false
+                }
+            }
+        })
+    })
+}
+
+/// Returns a pointer to the output which was captured by the most recent call
+/// to [`clac_eval`] as UTF-8.
+#[unsafe(no_mangle)]
+pub extern "C" fn clac_output() -> *const u8 {
+    OUTPUT.with_borrow(|output| output.as_ptr())
+}
+
+/// Returns the length of the output which was captured by the most recent
+/// call to [`clac_eval`] in bytes.
+#[unsafe(no_mangle)]
+pub extern "C" fn clac_output_len() -> usize {
+    OUTPUT.with_borrow(String::len)
+}
+
+/// Resets the [`Engine`], clearing its global variables, custom operators,
+/// and settings.
+#[unsafe(no_mangle)]
+pub extern "C" fn clac_reset() {
+    ENGINE.set(new_engine());
+}
+
+/// An [`Observer`] which captures printed text as output.
+struct Capture;
+
+impl Observer for Capture {
+    fn printed(&mut self, text: &str) {
+        OUTPUT.with_borrow_mut(|output| output.push_str(text));
+    }
+}
+
+/// Creates a new [`Engine`] which captures printed text as output.
+fn new_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_observer(Capture);
+    engine
+}
+
+/// Appends a displayable line to captured output.
+fn push_line<T: Display + ?Sized>(output: &mut String, line: &T) {
+    let _: fmt::Result = writeln!(output, "{line}");
+}
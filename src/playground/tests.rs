@@ -0,0 +1,43 @@
+use super::*;
+
+/// Evaluates source code as if the host had written it to the input buffer,
+/// and returns whether it ran without errors and its captured output.
+fn eval(source: &str) -> (bool, String) {
+    INPUT.set(source.as_bytes().to_vec());
+    let is_ok = clac_eval();
+    (is_ok, OUTPUT.with_borrow(Clone::clone))
+}
+
+/// Tests that results, printed text, warnings, and errors are captured as
+/// output.
+#[test]
+fn output_is_captured() {
+    let (is_ok, output) = eval("x = 2, println(`x`), x * 3");
+    assert!(is_ok);
+    assert_eq!(output, "x\nx\n6\n");
+
+    let (is_redefined, redefined) = eval("x = 3");
+    assert!(is_redefined);
+    assert_eq!(redefined, "Warning: variable 'x' is redefined\n");
+
+    let (is_undefined, undefined) = eval("y");
+    assert!(!is_undefined);
+    assert_eq!(undefined, "Error: variable 'y' is undefined\n");
+}
+
+/// Tests that resetting the engine clears global variables and custom
+/// operators.
+#[test]
+fn reset_clears_globals_and_operators() {
+    let (is_ok, _) = eval("x = 1, operator ⊕ (a, b) = a * 10 + b");
+    assert!(is_ok);
+    assert_eq!(eval("x ⊕ 2"), (true, "12\n".to_owned()));
+
+    clac_reset();
+
+    let (is_defined, _) = eval("x");
+    assert!(!is_defined, "global variables should be cleared");
+
+    let (is_operator, _) = eval("1 ⊕ 2");
+    assert!(!is_operator, "custom operators should be cleared");
+}
@@ -0,0 +1,219 @@
+use std::env;
+
+use crate::{format, settings::Settings};
+
+/// The terminal size in `(columns, lines)` which is used if the terminal size
+/// is not known.
+const DEFAULT_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+/// The smallest number of rows or columns in a plot.
+const MIN_PLOT_SIZE: usize = 8;
+
+/// The number of columns reserved for labels on the vertical axis.
+const LABEL_COLUMNS: usize = 12;
+
+/// The number of lines used by a plot which are not rows of points. This
+/// includes the horizontal axis, its labels, and the next REPL prompt.
+const EXTRA_LINES: usize = 3;
+
+/// The number of significant digits displayed in axis labels.
+const LABEL_DIGITS: i32 = 4;
+
+/// The [`char`] used to draw points.
+const POINT: char = '•';
+
+/// The [`char`] used to draw histogram bars.
+const BAR: char = '█';
+
+/// Returns the number of `(columns, rows)` of points which fit in a plot on the
+/// terminal. The terminal size is read from the `COLUMNS` and `LINES`
+/// environment variables if they are set.
+pub fn plot_size() -> (usize, usize) {
+    let lines = read_size_var("LINES").unwrap_or(DEFAULT_TERMINAL_SIZE.1);
+
+    (
+        terminal_columns()
+            .saturating_sub(LABEL_COLUMNS)
+            .max(MIN_PLOT_SIZE),
+        lines.saturating_sub(EXTRA_LINES).max(MIN_PLOT_SIZE),
+    )
+}
+
+/// Returns the number of columns in the terminal. The terminal size is read
+/// from the `COLUMNS` environment variable if it is set.
+pub fn terminal_columns() -> usize {
+    read_size_var("COLUMNS").unwrap_or(DEFAULT_TERMINAL_SIZE.0)
+}
+
+/// Reads a terminal size from an environment variable. This function returns
+/// [`None`] if the environment variable is not set to a valid size.
+fn read_size_var(name: &str) -> Option<usize> {
+    env::var(name).ok()?.trim().parse().ok()
+}
+
+/// Renders a plot of evenly spaced `y` values between a minimum and maximum
+/// `x` value with a number of rows. Axis labels are formatted with a session's
+/// [`Settings`]. Non-finite `y` values are not drawn. The plot is returned as
+/// lines of text.
+pub fn render(ys: &[f64], x_min: f64, x_max: f64, rows: usize, settings: &Settings) -> Vec<String> {
+    let finite_ys = ys.iter().copied().filter(|y| y.is_finite());
+    let y_min = finite_ys.clone().fold(f64::INFINITY, f64::min);
+    let y_max = finite_ys.fold(f64::NEG_INFINITY, f64::max);
+
+    // A plot with no points is drawn around zero.
+    let (y_min, y_max) = if y_min <= y_max {
+        (y_min, y_max)
+    } else {
+        (0.0_f64, 0.0_f64)
+    };
+
+    let rows = rows.max(1);
+    let mut grid = vec![vec![' '; ys.len()]; rows];
+
+    // Rows are drawn from the top, so the row of the largest value is zero.
+    #[expect(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "rows are small and row positions are clamped to the plot"
+    )]
+    let row_of = |y: f64| {
+        let last_row = (rows - 1) as f64;
+
+        if y_max > y_min {
+            ((y_max - y) / (y_max - y_min) * last_row).round() as usize
+        } else {
+            (last_row / 2.0_f64).round() as usize
+        }
+    };
+
+    if y_min < 0.0_f64 && y_max > 0.0_f64 {
+        grid[row_of(0.0_f64)].fill('─');
+    }
+
+    for (column, y) in ys.iter().enumerate().filter(|(_, y)| y.is_finite()) {
+        grid[row_of(*y)][column] = POINT;
+    }
+
+    let max_label = label(y_max, settings);
+    let min_label = label(y_min, settings);
+    let label_width = max_label.chars().count().max(min_label.chars().count());
+    let mut lines = Vec::with_capacity(rows + 2);
+
+    for (row, cells) in grid.into_iter().enumerate() {
+        let (label, axis) = match row {
+            0 => (max_label.as_str(), '┤'),
+            r if r == rows - 1 => (min_label.as_str(), '┤'),
+            _ => ("", '│'),
+        };
+
+        let cells: String = cells.into_iter().collect();
+        lines.push(format!("{label:>label_width$} {axis}{cells}"));
+    }
+
+    lines.push(format!("{:label_width$} └{}", "", "─".repeat(ys.len())));
+
+    let x_min = label(x_min, settings);
+    let x_max = label(x_max, settings);
+    let gap = (ys.len() + 1).saturating_sub(x_min.chars().count());
+    lines.push(format!("{:label_width$} {x_min}{x_max:>gap$}", ""));
+
+    lines
+}
+
+/// Formats a number as an axis label rounded to [`LABEL_DIGITS`] significant
+/// digits with a session's [`Settings`].
+fn label(value: f64, settings: &Settings) -> String {
+    let magnitude = if value == 0.0_f64 {
+        0_i32
+    } else {
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the exponents of finite numbers are small"
+        )]
+        let magnitude = value.abs().log10().floor() as i32;
+        magnitude
+    };
+
+    let places = (LABEL_DIGITS - 1_i32 - magnitude)
+        .max(0_i32)
+        .cast_unsigned();
+    let rounding = settings.number_format.rounding;
+    format::format_number(format::round(value, places, rounding), settings)
+}
+
+/// Renders a histogram of the counts of values in bins between edges which
+/// fits in a number of columns. Each bin is a row with its range, a bar scaled
+/// to the largest count, and its count. Ranges are formatted with a session's
+/// [`Settings`]. There must be one more edge than there are counts. The
+/// histogram is returned as lines of text.
+pub fn render_histogram(
+    edges: &[f64],
+    counts: &[usize],
+    columns: usize,
+    settings: &Settings,
+) -> Vec<String> {
+    let lowers = edges
+        .iter()
+        .map(|&e| label(e, settings))
+        .collect::<Vec<_>>();
+    let (lowers, uppers) = (&lowers[..counts.len()], &lowers[1..]);
+    let width_of = |labels: &[String]| labels.iter().map(|l| l.chars().count()).max();
+    let lower_width = width_of(lowers).unwrap_or_default();
+    let upper_width = width_of(uppers).unwrap_or_default();
+
+    let max_count = counts.iter().copied().max().unwrap_or_default();
+    let count_width = max_count.to_string().len();
+
+    // The range labels, separators, and counts take up the rest of the row.
+    let bar_width = columns
+        .saturating_sub(lower_width + upper_width + count_width + 6)
+        .max(MIN_PLOT_SIZE);
+
+    counts
+        .iter()
+        .enumerate()
+        .map(|(bin, &count)| {
+            let bar = if max_count == 0 {
+                0
+            } else {
+                // Non-empty bins are always drawn with at least one column.
+                (count * bar_width).div_ceil(max_count)
+            };
+
+            let bar = BAR.to_string().repeat(bar);
+            let (lower, upper) = (&lowers[bin], &uppers[bin]);
+            format!("{lower:>lower_width$} – {upper:>upper_width$} │{bar} {count}")
+        })
+        .collect()
+}
+
+/// Renders an aligned table with a header row and rows of cells. Every row
+/// must have the same number of cells as the header row. The table is
+/// returned as lines of text.
+pub fn render_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> Vec<String> {
+    let mut widths = header.map(|h| h.chars().count());
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: [&str; N]| {
+        let cells = cells.iter().zip(widths).map(|(c, w)| format!("{c:>w$}"));
+        cells.collect::<Vec<_>>().join(" │ ")
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    lines.push(format_row(header));
+
+    let rules = widths.map(|w| "─".repeat(w));
+    lines.push(rules.join("─┼─"));
+
+    for row in rows {
+        lines.push(format_row(row.each_ref().map(String::as_str)));
+    }
+
+    lines
+}
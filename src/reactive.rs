@@ -0,0 +1,175 @@
+use std::{ptr, rc::Rc};
+
+use crate::{
+    cfg::Cfg,
+    compile,
+    hir::{Expr, Hir, Stmt},
+    interpret::{self, Globals, InterpretError},
+    locals::LocalTable,
+    output::outln,
+    symbols::Symbol,
+};
+
+/// A recorded global variable assignment which can be run again to recalculate
+/// the global variable.
+pub struct Formula {
+    /// The [`Symbol`]s of the global variables read by the `Formula`.
+    dependencies: Box<[Symbol]>,
+
+    /// The compiled [`Cfg`] of the global variable assignment.
+    cfg: Cfg,
+
+    /// Whether the `Formula` defines a function. Functions read global
+    /// variables when they are called, so they are not recalculated, but
+    /// their dependents are.
+    is_function: bool,
+}
+
+impl Formula {
+    /// Creates a new `Formula` from a global variable assignment [`Stmt`] with
+    /// its [`LocalTable`] and source code.
+    fn new(stmt: &Stmt, locals: &LocalTable, source: &Rc<str>) -> Self {
+        let mut dependencies = Vec::new();
+        stmt.collect_global_reads(&mut dependencies);
+
+        Self {
+            dependencies: dependencies.into_boxed_slice(),
+            cfg: compile::compile_stmt(stmt, locals, source),
+            is_function: matches!(
+                stmt,
+                Stmt::AssignGlobal(_, value, _) if matches!(**value, Expr::Function(..))
+            ),
+        }
+    }
+
+    /// Returns [`true`] if the `Formula` reads a global variable from its
+    /// [`Symbol`].
+    fn depends_on(&self, symbol: Symbol) -> bool {
+        self.dependencies.contains(&symbol)
+    }
+}
+
+/// Records the [`Formula`]s of global variables which were assigned by [`Hir`]
+/// in [`Globals`], from the [`Hir`]'s [`LocalTable`] and source code. Global
+/// variables which are assigned together by unpacking a tuple share a
+/// [`Formula`]. Global variables which read themselves or which were assigned
+/// after a top-level local variable cannot be run again on their own, so their
+/// [`Formula`]s are removed instead.
+pub fn record_formulas(
+    hir: &Hir,
+    locals: &LocalTable,
+    source: &Rc<str>,
+    globals: &mut Globals,
+    assigned: &[Symbol],
+) {
+    let has_locals = hir.0.iter().any(|s| matches!(s, Stmt::DefineLocal(..)));
+    let mut formulas: Vec<(&Stmt, Rc<Formula>)> = Vec::new();
+
+    for symbol in assigned {
+        let stmt = hir
+            .global_assignment_stmts()
+            .filter_map(|(s, stmt)| (s == *symbol).then_some(stmt))
+            .last();
+
+        let formula = match stmt {
+            Some(stmt) if !has_locals => {
+                let recorded = formulas
+                    .iter()
+                    .find_map(|(s, f)| ptr::eq(*s, stmt).then(|| Rc::clone(f)));
+
+                Some(recorded.unwrap_or_else(|| {
+                    let formula = Rc::new(Formula::new(stmt, locals, source));
+                    formulas.push((stmt, Rc::clone(&formula)));
+                    formula
+                }))
+            }
+            _ => None,
+        };
+
+        match formula {
+            Some(formula) if !formula.depends_on(*symbol) => globals.set_formula(*symbol, formula),
+            _ => globals.remove_formula(*symbol),
+        }
+    }
+}
+
+/// Recalculates and prints the global variables in [`Globals`] which depend on
+/// changed global variables, directly or indirectly. Each global variable is
+/// recalculated after the global variables it depends on, and each shared
+/// [`Formula`] is only run once. Functions are not recalculated or printed.
+///
+/// # Errors
+/// An error is returned if a [`Formula`] could not be run.
+pub fn recalculate(globals: &mut Globals, changed: &[Symbol]) -> Result<(), InterpretError> {
+    let mut order = Vec::new();
+
+    for symbol in changed {
+        visit_dependents(globals, *symbol, changed, &mut Vec::new(), &mut order);
+    }
+
+    // Dependents were visited before the global variables they depend on.
+    order.reverse();
+
+    let mut result = Ok(());
+    let mut run: Vec<Rc<Formula>> = Vec::new();
+
+    for symbol in order {
+        let Some(formula) = globals.formula(symbol).filter(|f| !f.is_function) else {
+            continue;
+        };
+
+        if !run.iter().any(|f| Rc::ptr_eq(f, &formula)) {
+            result = interpret::interpret_cfg(&formula.cfg, globals);
+
+            if result.is_err() {
+                break;
+            }
+
+            run.push(formula);
+        }
+
+        if let Some(value) = globals.read(symbol) {
+            outln!(
+                globals.output(),
+                "{symbol} = {}",
+                value.display(globals.settings())
+            );
+        }
+    }
+
+    // Recalculated global variables keep their original source code.
+    globals.take_assigned();
+    result
+}
+
+/// Appends the global variables in [`Globals`] which depend on a [`Symbol`] to
+/// an order after every global variable which depends on them. Changed global
+/// variables are not recalculated, and global variables which are still being
+/// visited are skipped to stop dependency cycles.
+fn visit_dependents(
+    globals: &Globals,
+    symbol: Symbol,
+    changed: &[Symbol],
+    visiting: &mut Vec<Symbol>,
+    order: &mut Vec<Symbol>,
+) {
+    let dependents = globals
+        .formulas()
+        .filter(|(_, f)| f.depends_on(symbol))
+        .map(|(s, _)| s)
+        .collect::<Vec<_>>();
+
+    for dependent in dependents {
+        if changed.contains(&dependent)
+            || visiting.contains(&dependent)
+            || order.contains(&dependent)
+        {
+            continue;
+        }
+
+        visiting.push(dependent);
+        visit_dependents(globals, dependent, changed, visiting, order);
+        visiting.pop();
+        order.push(dependent);
+    }
+}
@@ -0,0 +1,656 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+    env,
+    fmt::{self, Write as _},
+    fs,
+    io::{self, IsTerminal as _},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process::{self, Command},
+    rc::Rc,
+};
+
+use crate::{
+    Mode,
+    ast::Literal,
+    format::RoundingMode,
+    help,
+    integer::IntegerMode,
+    interpret::{Globals, Snapshot, Value},
+    lex::{self, BracketError},
+    lower::GlobalMode,
+    output::{Output, errln, outln},
+    source::Origin,
+    spans::SourceSpan,
+    symbols::Symbol,
+    terminal::{InputSource, PlainInput},
+};
+
+/// The marker sent by a terminal before pasted text in bracketed paste mode.
+const PASTE_START: &str = "\x1b[200~";
+
+/// The marker sent by a terminal after pasted text in bracketed paste mode.
+const PASTE_END: &str = "\x1b[201~";
+
+/// Runs Clac in REPL mode with [`Globals`].
+pub fn run_repl(globals: &mut Globals) {
+    const EXIT_SHORTCUT: &str = cfg_select! {
+        windows => "Ctrl+Z",
+        _ => "Ctrl+D",
+    };
+
+    println!("Clac - Functional command line calculator\nEnter [{EXIT_SHORTCUT}] to exit.");
+    let mut repl = Repl::new(globals, Box::new(PlainInput));
+    let mut source = String::new();
+
+    // Bracketed paste mode allows multiple pasted lines to be run as one unit.
+    let is_bracketed_paste = io::stdin().is_terminal() && io::stdout().is_terminal();
+
+    if is_bracketed_paste {
+        print!("\x1b[?2004h");
+    }
+
+    loop {
+        println!();
+        source.clear();
+
+        if let Err(error) = repl.input.read_line("clac> ", &mut source) {
+            eprintln!("Could not read line: {error}");
+            continue;
+        }
+
+        if source.is_empty() {
+            break;
+        }
+
+        if source.contains(PASTE_START) {
+            read_lines_until(repl.input.as_mut(), &mut source, |s| s.contains(PASTE_END));
+            source = source.replace(PASTE_START, "").replace(PASTE_END, "");
+        }
+
+        read_continuation_lines(repl.input.as_mut(), &mut source);
+        repl.run_line(&source);
+    }
+
+    if is_bracketed_paste {
+        print!("\x1b[?2004l");
+    }
+
+    println!("\nReceived [{EXIT_SHORTCUT}], exiting...");
+}
+
+/// Appends continuation lines from an [`InputSource`] to a buffer of source
+/// code until it has no unclosed brackets or the end of input is reached. REPL
+/// commands are never continued.
+pub fn read_continuation_lines(input: &mut dyn InputSource, buffer: &mut String) {
+    if buffer.trim_start().starts_with(':') {
+        return;
+    }
+
+    while matches!(lex::check_brackets(buffer), Err(BracketError::Unclosed(..))) {
+        match input.read_line("....> ", buffer) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(error) => {
+                eprintln!("Could not read line: {error}");
+                break;
+            }
+        }
+    }
+}
+
+/// Appends lines from an [`InputSource`] to a buffer until the buffer matches
+/// a predicate function or the end of input is reached.
+fn read_lines_until<F: Fn(&str) -> bool>(
+    input: &mut dyn InputSource,
+    buffer: &mut String,
+    predicate: F,
+) {
+    while !predicate(buffer) {
+        match input.read_line("", buffer) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(error) => {
+                eprintln!("Could not read line: {error}");
+                break;
+            }
+        }
+    }
+}
+
+/// A REPL session.
+struct Repl<'glb> {
+    /// The [`Globals`].
+    globals: &'glb mut Globals,
+
+    /// The [`InputSource`] which lines are read from.
+    input: Box<dyn InputSource>,
+
+    /// The source code from the most recent `:edit` command.
+    edit_buffer: String,
+
+    /// The named [`Snapshot`]s in the order they were first saved.
+    snapshots: Vec<(String, Snapshot)>,
+
+    /// The [`Snapshot`] from before the most recent change to the [`Globals`],
+    /// if it can be undone.
+    undo_snapshot: Option<Snapshot>,
+}
+
+impl<'glb> Repl<'glb> {
+    /// Creates a new `Repl` from [`Globals`] and an [`InputSource`].
+    fn new(globals: &'glb mut Globals, input: Box<dyn InputSource>) -> Self {
+        Self {
+            globals,
+            input,
+            edit_buffer: String::new(),
+            snapshots: Vec::new(),
+            undo_snapshot: None,
+        }
+    }
+
+    /// Runs a line of input as either source code or a REPL command.
+    fn run_line(&mut self, line: &str) {
+        self.globals.output().log_input(line);
+
+        match line.trim_start().strip_prefix(':') {
+            None => {
+                if brackets_are_balanced(self.globals.output(), line) {
+                    self.run_undoable(|r| {
+                        run_code(line, r.globals, Mode::Execute);
+                    });
+                }
+            }
+            Some(command) => self.run_command(command),
+        }
+    }
+
+    /// Runs a REPL command after its leading colon.
+    fn run_command(&mut self, command: &str) {
+        let command = command.trim();
+        let (name, args) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+
+        match name {
+            "check" => {
+                if run_code(args, self.globals, Mode::Check) {
+                    outln!(self.globals.output(), "No errors found.");
+                }
+            }
+            "edit" => self.run_undoable(|r| r.run_edit(args)),
+            "source" => self.run_source(args),
+            "save" => self.run_save(args),
+            "load" => self.run_undoable(|r| r.run_load(args)),
+            "undef" | "forget" => self.run_undoable(|r| r.run_undef(args)),
+            "snapshot" => self.run_snapshot(args),
+            "restore" => self.run_undoable(|r| r.run_restore(args)),
+            "undo" => self.run_undo(),
+            "log" => run_log(self.globals.output(), args),
+            "paste" => self.run_undoable(Self::run_paste),
+            "rounding" => self.run_rounding(args),
+            "mode" => self.run_mode(args),
+            "reactive" => self.run_reactive(args),
+            "help" => help::run_help(self.globals.output(), args),
+            _ => errln!(self.globals.output(), "Error: unknown command ':{name}'"),
+        }
+    }
+
+    /// Runs a function with the `Repl`. If the [`Globals`] were changed, then
+    /// the change can be reverted with the `:undo` command.
+    fn run_undoable<F: FnOnce(&mut Self)>(&mut self, function: F) {
+        let snapshot = self.globals.snapshot();
+        let change_count = self.globals.change_count();
+        function(self);
+
+        if self.globals.change_count() != change_count {
+            self.undo_snapshot = Some(snapshot);
+        }
+    }
+
+    /// Runs the `:rounding` command. The [`RoundingMode`] used by `round` and
+    /// fixed decimal places is changed. If no arguments are given, then the
+    /// current [`RoundingMode`] is printed.
+    fn run_rounding(&mut self, arg: &str) {
+        let output = self.globals.output().clone();
+        let number_format = &mut self.globals.settings_mut().number_format;
+
+        if arg.is_empty() {
+            outln!(
+                output,
+                "Rounding mode is '{}'.",
+                number_format.rounding.name()
+            );
+            return;
+        }
+
+        let Some(rounding) = RoundingMode::from_name(arg) else {
+            errln!(output, "Error: unknown rounding mode '{arg}'");
+            return;
+        };
+
+        number_format.rounding = rounding;
+    }
+
+    /// Runs the `:mode` command. The [`IntegerMode`] used for wrapping integer
+    /// arithmetic is changed. If no arguments are given, then the current mode
+    /// is printed.
+    fn run_mode(&mut self, arg: &str) {
+        let output = self.globals.output().clone();
+        let integer_mode = &mut self.globals.settings_mut().integer_mode;
+
+        if arg.is_empty() {
+            let name = integer_mode.map_or_else(|| "float".to_owned(), IntegerMode::name);
+            outln!(output, "Mode is '{name}'.");
+            return;
+        }
+
+        if arg == "float" {
+            *integer_mode = None;
+        } else if let Some(mode) = IntegerMode::from_name(arg) {
+            *integer_mode = Some(mode);
+        } else {
+            errln!(output, "Error: unknown mode '{arg}'");
+        }
+    }
+
+    /// Runs the `:reactive` command. Reactive mode is enabled or disabled. If
+    /// no arguments are given, then whether reactive mode is enabled is
+    /// printed.
+    fn run_reactive(&mut self, arg: &str) {
+        let output = self.globals.output().clone();
+        let reactive = &mut self.globals.settings_mut().reactive;
+
+        match arg {
+            "" => {
+                if *reactive {
+                    outln!(output, "Reactive mode is on.");
+                } else {
+                    outln!(output, "Reactive mode is off.");
+                }
+            }
+            "on" => *reactive = true,
+            "off" => *reactive = false,
+            _ => errln!(output, "Error: reactive mode must be 'on' or 'off'"),
+        }
+    }
+
+    /// Runs the `:undo` command. The most recent change to the [`Globals`] is
+    /// reverted. Only one change can be undone.
+    fn run_undo(&mut self) {
+        match self.undo_snapshot.take() {
+            Some(snapshot) => {
+                self.globals.restore(&snapshot);
+                outln!(
+                    self.globals.output(),
+                    "Undid the last change to global variables."
+                );
+            }
+            None => errln!(self.globals.output(), "Error: there is nothing to undo"),
+        }
+    }
+
+    /// Runs the `:edit` command. The edit buffer is opened in the user's
+    /// editor, then executed after the editor is closed. If a global variable
+    /// name is given, then the edit buffer is replaced with the source code
+    /// which defined it.
+    fn run_edit(&mut self, name: &str) {
+        if !name.is_empty() {
+            let Some(source) = self.source(name) else {
+                return;
+            };
+
+            self.edit_buffer = source.to_owned();
+        }
+
+        match edit_text(&self.edit_buffer) {
+            Ok(source) => {
+                self.edit_buffer = source;
+                run_code(&self.edit_buffer, self.globals, Mode::Execute);
+            }
+            Err(error) => errln!(self.globals.output(), "Could not edit source code: {error}"),
+        }
+    }
+
+    /// Runs the `:paste` command. Lines are read until a line containing only
+    /// `:end`, then executed as one unit.
+    fn run_paste(&mut self) {
+        const END_COMMAND: &str = ":end";
+
+        outln!(
+            self.globals.output(),
+            "Paste code, then enter '{END_COMMAND}' on its own line to run it."
+        );
+        let mut source = String::new();
+        read_lines_until(self.input.as_mut(), &mut source, |s| {
+            s.lines()
+                .next_back()
+                .is_some_and(|l| l.trim() == END_COMMAND)
+        });
+
+        if let Some(end) = source.trim_end().strip_suffix(END_COMMAND) {
+            let end_len = end.len();
+            source.truncate(end_len);
+        }
+
+        self.globals.output().log_input(&source);
+
+        if brackets_are_balanced(self.globals.output(), &source) {
+            run_code(&source, self.globals, Mode::Execute);
+        }
+    }
+
+    /// Runs the `:source` command. The source code which defined a global
+    /// variable is printed.
+    fn run_source(&self, name: &str) {
+        if let Some(source) = self.source(name) {
+            outln!(self.globals.output(), "{source}");
+        }
+    }
+
+    /// Runs the `:save` command. The global variables are written to a file
+    /// which can be loaded with the `:load` command.
+    fn run_save(&self, path: &str) {
+        if path.is_empty() {
+            errln!(self.globals.output(), "Error: a file path is required");
+            return;
+        }
+
+        if let Err(error) = fs::write(path, saved_globals(self.globals)) {
+            errln!(self.globals.output(), "Could not save to '{path}': {error}");
+        }
+    }
+
+    /// Runs the `:load` command. A file written by the `:save` command, or any
+    /// other Clac source code, is read and executed.
+    fn run_load(&mut self, path: &str) {
+        if path.is_empty() {
+            errln!(self.globals.output(), "Error: a file path is required");
+            return;
+        }
+
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(error) => {
+                errln!(self.globals.output(), "Could not load '{path}': {error}");
+                return;
+            }
+        };
+
+        if brackets_are_balanced(self.globals.output(), &source) {
+            run_code(&source, self.globals, Mode::Execute);
+        }
+    }
+
+    /// Runs the `:undef` command. Global variables are removed by name so that
+    /// they can be defined again. Native functions are protected in the same
+    /// way as they are from redefinition.
+    fn run_undef(&mut self, names: &str) {
+        if names.is_empty() {
+            errln!(self.globals.output(), "Error: a variable name is required");
+        }
+
+        let native_overrides = self.globals.settings().native_overrides;
+
+        for name in names.split_whitespace() {
+            let symbol = Symbol::intern(name);
+
+            if !native_overrides && self.globals.is_native(symbol) {
+                errln!(
+                    self.globals.output(),
+                    "Error: native function '{name}' cannot be redefined without '--allow-native-overrides'"
+                );
+            } else if !self.globals.remove(symbol) {
+                errln!(
+                    self.globals.output(),
+                    "Error: variable '{name}' is undefined"
+                );
+            }
+        }
+    }
+
+    /// Runs the `:snapshot` command. The global variables are saved in a named
+    /// [`Snapshot`], replacing any [`Snapshot`] with the same name. If no name
+    /// is given, then the saved [`Snapshot`]s are listed.
+    fn run_snapshot(&mut self, name: &str) {
+        if name.is_empty() {
+            if self.snapshots.is_empty() {
+                outln!(self.globals.output(), "No snapshots have been saved.");
+            }
+
+            for (saved_name, snapshot) in &self.snapshots {
+                outln!(
+                    self.globals.output(),
+                    "{saved_name} ({} global variables)",
+                    snapshot.variable_count()
+                );
+            }
+
+            return;
+        }
+
+        let snapshot = self.globals.snapshot();
+
+        match self.snapshots.iter_mut().find(|(n, _)| n == name) {
+            Some((_, old_snapshot)) => *old_snapshot = snapshot,
+            None => self.snapshots.push((name.to_owned(), snapshot)),
+        }
+
+        outln!(self.globals.output(), "Saved snapshot '{name}'.");
+    }
+
+    /// Runs the `:restore` command. The global variables are replaced with
+    /// those in a named [`Snapshot`]. The [`Snapshot`] is kept, so it can be
+    /// restored again to try something else.
+    fn run_restore(&mut self, name: &str) {
+        if name.is_empty() {
+            errln!(self.globals.output(), "Error: a snapshot name is required");
+            return;
+        }
+
+        match self.snapshots.iter().find(|(n, _)| n == name) {
+            Some((_, snapshot)) => {
+                self.globals.restore(snapshot);
+                outln!(self.globals.output(), "Restored snapshot '{name}'.");
+            }
+            None => errln!(
+                self.globals.output(),
+                "Error: snapshot '{name}' is undefined"
+            ),
+        }
+    }
+
+    /// Returns the source code which defined a global variable from its name.
+    /// This function prints an error and returns [`None`] if the global
+    /// variable has no source code.
+    fn source(&self, name: &str) -> Option<&str> {
+        if name.is_empty() {
+            errln!(self.globals.output(), "Error: a variable name is required");
+            return None;
+        }
+
+        let symbol = Symbol::intern(name);
+        let source = self.globals.source(symbol);
+
+        if source.is_none() {
+            if self.globals.contains(symbol) {
+                errln!(
+                    self.globals.output(),
+                    "Error: variable '{name}' is built-in and has no source code"
+                );
+            } else {
+                errln!(
+                    self.globals.output(),
+                    "Error: variable '{name}' is undefined"
+                );
+            }
+        }
+
+        source
+    }
+}
+
+/// Runs source code with [`Globals`] in a [`Mode`]. A panic while running the
+/// source code is reported as an error instead of ending the session, so that
+/// global variables are not lost to a bug in Clac. This function returns
+/// [`true`] if no errors occurred.
+fn run_code(source: &str, globals: &mut Globals, mode: Mode) -> bool {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        crate::run_source(source, Origin::Repl, globals, mode, GlobalMode::Redefinable)
+    }));
+
+    result.unwrap_or_else(|_| {
+        errln!(
+            globals.output(),
+            "Error: internal error, please report this as a bug"
+        );
+        false
+    })
+}
+
+/// Returns the contents of a file written by the `:save` command. Global
+/// variables are written in definition order. Data values are written as
+/// source code for their current values, so they do not depend on the global
+/// variables they were calculated from. Functions, other values, and values
+/// which are recalculated by a formula are written as the source code which
+/// defined them.
+pub fn saved_globals(globals: &Globals) -> String {
+    let mut contents = String::new();
+
+    for symbol in globals.symbols() {
+        if globals.is_native(symbol) {
+            continue;
+        }
+
+        let value = globals
+            .read(symbol)
+            .filter(|_| globals.formula(symbol).is_none())
+            .and_then(value_source);
+
+        match value {
+            Some(value) => {
+                let _: fmt::Result = writeln!(contents, "{symbol} = {value}");
+            }
+            None => {
+                if let Some(source) = globals.source(symbol) {
+                    contents.push_str(source);
+                    contents.push('\n');
+                }
+            }
+        }
+    }
+
+    contents
+}
+
+/// Returns source code which evaluates to a data [`Value`]. This function
+/// returns [`None`] if the [`Value`] is or contains a value which cannot be
+/// written as source code, such as a function or a non-finite number.
+fn value_source(value: &Value) -> Option<String> {
+    let elems_source = |elems: &[Value]| elems.iter().map(value_source).collect::<Option<Vec<_>>>();
+
+    let source = match value {
+        // Numbers are displayed without exponents, so they are read back
+        // exactly.
+        Value::Number(value) if value.is_finite() => value.to_string(),
+        Value::Integer(value) => value.to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Str(value) => Literal::Str(Rc::clone(value)).to_string(),
+        Value::List(elems) => format!("[{}]", elems_source(elems)?.join(", ")),
+        Value::Tuple(elems) => match elems_source(elems)?.as_slice() {
+            [elem] => format!("({elem},)"),
+            elems => format!("({})", elems.join(", ")),
+        },
+        Value::Date(date) => format!("date(1970, 1, 1) + seconds({date})"),
+        Value::Duration(duration) => format!("seconds({duration})"),
+        _ => return None,
+    };
+
+    Some(source)
+}
+
+/// Returns [`true`] if the parentheses and braces in source code are balanced.
+/// Otherwise, an error is printed to an [`Output`] with a caret under the
+/// unbalanced bracket.
+fn brackets_are_balanced(output: &Output, source: &str) -> bool {
+    let Err(error) = lex::check_brackets(source) else {
+        return true;
+    };
+
+    let span = SourceSpan {
+        source: source.into(),
+        span: error.span(),
+    };
+
+    errln!(output, "Error: {error}{}", Origin::Repl.locate(Some(&span)));
+    false
+}
+
+/// Runs the `:log` command. Logging to a transcript file is turned on or off,
+/// or started with a new transcript file. If no arguments are given, then the
+/// current logging status is printed to an [`Output`].
+fn run_log(output: &Output, arg: &str) {
+    let result = match arg {
+        "" => {
+            match output.logging_path() {
+                None => outln!(output, "Logging is off."),
+                Some(path) => outln!(output, "Logging to '{}'.", path.display()),
+            }
+
+            return;
+        }
+        "on" => output.start_logging(None),
+        "off" => {
+            output.stop_logging();
+            Ok(())
+        }
+        path => output.start_logging(Some(Path::new(path))),
+    };
+
+    if let Err(error) = result {
+        errln!(output, "Could not start logging: {error}");
+    }
+}
+
+/// The editor which is used if neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = cfg_select! {
+    windows => "notepad",
+    _ => "vi",
+};
+
+/// Opens text in the user's editor and returns the edited text. This function
+/// returns an [`io::Error`] if the editor could not be run.
+fn edit_text(text: &str) -> io::Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_owned());
+
+    run_editor(&editor, text)
+}
+
+/// Opens text in an editor command and returns the edited text. This function
+/// returns an [`io::Error`] if the editor could not be run.
+fn run_editor(editor: &str, text: &str) -> io::Result<String> {
+    // The editor may be given with arguments (e.g. `code --wait`).
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args.next().unwrap_or(DEFAULT_EDITOR);
+
+    let path = env::temp_dir().join(format!("clac-edit-{}.clac", process::id()));
+    fs::write(&path, text)?;
+
+    let status = Command::new(program).args(editor_args).arg(&path).status();
+    let edited_text = status.and_then(|s| {
+        if s.success() {
+            fs::read_to_string(&path)
+        } else {
+            Err(io::Error::other(format!("editor exited with {s}")))
+        }
+    });
+
+    // The temporary file is only a scratch buffer, so failing to remove it is
+    // not an error.
+    let _: io::Result<()> = fs::remove_file(&path);
+    edited_text
+}
@@ -0,0 +1,423 @@
+use super::*;
+use crate::settings::Settings;
+
+/// An [`InputSource`] which reads lines from a list and records its prompts.
+#[derive(Default)]
+struct ScriptedInput {
+    /// The lines which have not been read yet.
+    lines: Vec<&'static str>,
+
+    /// The prompts which were printed.
+    prompts: Vec<String>,
+}
+
+impl InputSource for ScriptedInput {
+    fn read_line(&mut self, prompt: &str, buffer: &mut String) -> io::Result<usize> {
+        self.prompts.push(prompt.to_owned());
+
+        if self.lines.is_empty() {
+            return Ok(0);
+        }
+
+        let line = self.lines.remove(0);
+        buffer.push_str(line);
+        Ok(line.len())
+    }
+}
+
+/// Tests that the REPL reads continuation lines from an [`InputSource`] until
+/// brackets are closed, and never continues REPL commands.
+#[test]
+fn repl_reads_continuation_lines() {
+    let mut input = ScriptedInput {
+        lines: vec!["x +\n", "1 }\n", "unread\n"],
+        prompts: Vec::new(),
+    };
+
+    let mut source = "f(x) = {\n".to_owned();
+    read_continuation_lines(&mut input, &mut source);
+    assert_eq!(source, "f(x) = {\nx +\n1 }\n");
+    assert_eq!(input.prompts, ["....> ", "....> "]);
+    assert_eq!(input.lines, ["unread\n"]);
+
+    let mut command = ":check (\n".to_owned();
+    read_continuation_lines(&mut input, &mut command);
+    assert_eq!(command, ":check (\n");
+    assert_eq!(input.lines, ["unread\n"]);
+
+    let mut unfinished = "(\n".to_owned();
+    input.lines.clear();
+    read_continuation_lines(&mut input, &mut unfinished);
+    assert_eq!(unfinished, "(\n", "the end of input should stop reading");
+}
+
+/// Tests that only unclosed brackets continue REPL input, and that continued
+/// source code is run as one line.
+#[test]
+fn continued_lines_are_run_as_one_line() {
+    let mut input = ScriptedInput {
+        lines: vec!["  y = x * 2,\n", "  [y + 1]\n", "}\n"],
+        prompts: Vec::new(),
+    };
+
+    for line in ["`(`\n", "(]\n", "x)\n"] {
+        let mut source = line.to_owned();
+        read_continuation_lines(&mut input, &mut source);
+        assert_eq!(source, line, "'{line}' should not be continued");
+    }
+
+    let mut source = "f(x) = {\n".to_owned();
+    read_continuation_lines(&mut input, &mut source);
+    assert_eq!(input.prompts, ["....> ", "....> ", "....> "]);
+
+    let mut globals = crate::new_globals(Settings::new());
+    let lines = [source.as_str(), "f(20)"];
+    let (output, errors) = run_lines(&mut globals, &lines);
+    assert_eq!(output, "[41]\n");
+    assert_eq!(errors, "");
+}
+
+/// Runs lines of input in a REPL session with [`Globals`] and returns the
+/// captured output and errors.
+fn run_lines(globals: &mut Globals, lines: &[&str]) -> (String, String) {
+    globals.output().start_capture();
+    let mut repl = Repl::new(globals, Box::new(ScriptedInput::default()));
+
+    for line in lines {
+        repl.run_line(line);
+    }
+
+    let capture = globals.output().take_capture();
+    (capture.output(), capture.errors())
+}
+
+/// Tests that the `:check` command reports errors without running source
+/// code.
+#[test]
+fn check_command_does_not_run_code() {
+    let mut globals = crate::new_globals(Settings::new());
+    let (output, errors) = run_lines(&mut globals, &[":check x = println(1)", ":check 1 +"]);
+    assert_eq!(output, "No errors found.\n");
+    assert!(errors.starts_with("Error: expected an expression, got end of file"));
+    assert!(!globals.contains(Symbol::intern("x")));
+}
+
+/// Tests that text is edited by running an editor command with arguments, and
+/// that failing editors are reported as errors.
+#[cfg(unix)]
+#[test]
+fn editor_commands_edit_text() {
+    let text = run_editor("sed -i s/1/2/", "x = 1\n").expect("the editor should run");
+    assert_eq!(text, "x = 2\n");
+    run_editor("false", "x = 1\n").expect_err("a failing editor should be an error");
+}
+
+/// Tests that the `:source` command prints the source code which defined a
+/// global variable, and that saved global variables can be loaded.
+#[test]
+fn defining_source_is_printed_and_saved() {
+    let path = env::temp_dir().join(format!("clac-save-test-{}.clac", process::id()));
+    let save_command = format!(":save {}", path.display());
+    let load_command = format!(":load {}", path.display());
+
+    let mut saved_globals = crate::new_globals(Settings::new());
+    let lines = ["f(x) = x + 1", ":source f", ":source g", &save_command];
+    let (output, errors) = run_lines(&mut saved_globals, &lines);
+    assert_eq!(output, "f(x) = x + 1\n");
+    assert_eq!(errors, "Error: variable 'g' is undefined\n");
+
+    let mut loaded_globals = crate::new_globals(Settings::new());
+    let (loaded_output, loaded_errors) = run_lines(&mut loaded_globals, &[&load_command, "f(2)"]);
+    fs::remove_file(&path).expect("saved file should be removed");
+    assert_eq!(loaded_output, "3\n");
+    assert_eq!(loaded_errors, "");
+}
+
+/// Tests that the `:undef` and `:forget` commands remove global variables so
+/// that they can be defined again.
+#[test]
+fn undef_command_removes_globals() {
+    let mut globals = crate::new_globals(Settings::new());
+    let lines = [
+        "x = 1, y = 2",
+        ":undef x y",
+        ":forget z",
+        ":undef",
+        ":undef sqrt",
+        "x = 3",
+        "x, sqrt(9)",
+    ];
+    let (output, errors) = run_lines(&mut globals, &lines);
+    assert_eq!(output, "3\n3\n");
+
+    assert_eq!(
+        errors,
+        "Error: variable 'z' is undefined\nError: a variable name is required\n\
+         Error: native function 'sqrt' cannot be redefined without '--allow-native-overrides'\n"
+    );
+
+    assert!(!globals.contains(Symbol::intern("y")));
+}
+
+/// Tests that the `:undo` command reverts only the most recent change to
+/// global variables, and skips lines which do not change them.
+#[test]
+fn undo_command_reverts_last_change() {
+    let mut globals = crate::new_globals(Settings::new());
+    let lines = [
+        ":undo", "x = 1", "x = 2", "x + 1", ":undo", "x", ":undo", ":undef x", ":undo", "x",
+    ];
+    let (output, errors) = run_lines(&mut globals, &lines);
+
+    assert_eq!(
+        output,
+        "3\nUndid the last change to global variables.\n1\n\
+         Undid the last change to global variables.\n1\n"
+    );
+
+    assert!(errors.starts_with("Error: there is nothing to undo\n"));
+    assert!(errors.ends_with("Error: there is nothing to undo\n"));
+}
+
+/// Tests that global variables can be redefined in the REPL with a warning.
+#[test]
+fn globals_are_redefined_with_warnings() {
+    let mut globals = crate::new_globals(Settings::new());
+    let (output, errors) = run_lines(&mut globals, &["count = 1", "count = 2", "count"]);
+    assert_eq!(output, "2\n");
+    assert!(errors.starts_with("Warning: variable 'count' is redefined"));
+}
+
+/// Tests that the `:log` command logs input and output to a transcript file
+/// until logging is stopped.
+#[test]
+fn log_command_writes_transcripts() {
+    let path = env::temp_dir().join(format!("clac-log-test-{}.txt", process::id()));
+    let log_command = format!(":log {}", path.display());
+    let mut globals = crate::new_globals(Settings::new());
+
+    let lines = ["1 + 2", &log_command, "3 + 4", ":log off", "5 + 6", ":log"];
+    let (output, errors) = run_lines(&mut globals, &lines);
+    let transcript = fs::read_to_string(&path).expect("transcript should be written");
+    fs::remove_file(&path).expect("transcript should be removed");
+    assert_eq!(output, "3\n7\n11\nLogging is off.\n");
+    assert_eq!(errors, "");
+
+    let logged_lines = transcript.lines().collect::<Vec<_>>();
+    assert_eq!(logged_lines.len(), 3);
+    assert!(logged_lines[0].ends_with("] clac> 3 + 4"));
+    assert_eq!(logged_lines[1], "7");
+    assert!(logged_lines[2].ends_with("] clac> :log off"));
+}
+
+/// Tests that the `:paste` command runs lines as one unit until a line
+/// containing only `:end`.
+#[test]
+fn paste_command_runs_lines_as_one_unit() {
+    let mut globals = crate::new_globals(Settings::new());
+    globals.output().start_capture();
+
+    let input = ScriptedInput {
+        lines: vec![
+            "f(x) = {\n",
+            "x * 2\n",
+            "}\n",
+            "a = 3\n",
+            "f(a)\n",
+            " :end\n",
+            "unread\n",
+        ],
+        prompts: Vec::new(),
+    };
+
+    let mut repl = Repl::new(&mut globals, Box::new(input));
+    repl.run_line(":paste");
+    repl.run_line("f(4)");
+
+    let capture = globals.output().take_capture();
+    let output = capture.output();
+    assert!(output.starts_with("Paste code, then enter ':end' on its own line to run it.\n"));
+    assert!(output.ends_with("6\n8\n"));
+    assert_eq!(capture.errors(), "");
+}
+
+/// Tests that the `:rounding` command prints and changes the rounding mode.
+#[test]
+fn rounding_command_changes_rounding_mode() {
+    let mut globals = crate::new_globals(Settings::new());
+    let lines = [
+        ":rounding",
+        ":rounding half-even",
+        "round(2.5)",
+        ":rounding",
+        ":rounding up",
+    ];
+    let (output, errors) = run_lines(&mut globals, &lines);
+    assert_eq!(
+        output,
+        "Rounding mode is 'half-up'.\n2\nRounding mode is 'half-even'.\n"
+    );
+    assert_eq!(errors, "Error: unknown rounding mode 'up'\n");
+}
+
+/// Tests that the `:help` command shows help for natives, commands, and
+/// topics, and searches natives by name and description.
+#[test]
+fn help_command_shows_help() {
+    let mut globals = crate::new_globals(Settings::new());
+
+    let (output, errors) = run_lines(&mut globals, &[":help sqrt", ":help exchange"]);
+    assert_eq!(
+        output,
+        "sqrt(n: number) -> number\n\
+         Returns the square root of `n`.\n\
+         Category: math\n\
+         set_rate(unit: money, rate: number) -> money\n"
+    );
+    assert_eq!(errors, "");
+
+    let (topic_output, topic_errors) = run_lines(&mut globals, &[":help", ":help topic operators"]);
+    assert!(topic_output.starts_with("Commands:\n"));
+    assert!(topic_output.contains("  :help [NAME]"));
+    assert!(topic_output.contains("Operators from lowest to highest precedence:\n"));
+    assert_eq!(topic_errors, "");
+
+    let lines = [":help zzz", ":help topic", ":help topic nope"];
+    let (missing_output, missing_errors) = run_lines(&mut globals, &lines);
+    assert_eq!(missing_output, "");
+    assert_eq!(
+        missing_errors,
+        "Error: no help found for 'zzz'\n\
+         Error: a topic name is required\n\
+         Error: unknown topic 'nope'\n"
+    );
+}
+
+/// Tests that a panic while running code is reported as an internal error,
+/// and that the session keeps its global variables.
+#[test]
+fn panics_are_reported_as_internal_errors() {
+    let mut globals = crate::new_globals(Settings::new());
+    let boom = Symbol::intern("boom");
+
+    globals.set_provider(Some(Rc::new(move |symbol| {
+        assert!(symbol != boom, "provider should panic");
+        None
+    })));
+
+    let (output, errors) = run_lines(&mut globals, &["x = 1", "boom + 1", "x"]);
+    assert_eq!(output, "1\n");
+    assert_eq!(
+        errors,
+        "Error: internal error, please report this as a bug\n"
+    );
+}
+
+/// Tests that calls to global functions are checked against the arity of
+/// their latest definition, except in function bodies which may run after the
+/// function is redefined again.
+#[test]
+fn calls_are_checked_against_redefined_arities() {
+    let mut globals = crate::new_globals(Settings::new());
+    let lines = [
+        "f(x) = x",
+        "f(x, y) = x + y",
+        "f(1, 2)",
+        "f(1)",
+        "g(x) = f(x, x, x)",
+        "f(x, y, z) = x + y + z",
+        "g(2)",
+        "f = 3",
+        "f(1)",
+    ];
+    let (output, errors) = run_lines(&mut globals, &lines);
+    assert_eq!(output, "3\n6\n");
+
+    let errors = errors.lines().filter(|l| l.starts_with("Error: "));
+    assert_eq!(
+        errors.collect::<Vec<_>>(),
+        [
+            "Error: function 'f' expects 2 argument(s), but 1 were given",
+            "Error: only functions can be called",
+        ]
+    );
+}
+
+/// Tests that global variables are saved in the order they were first
+/// defined, and that removed global variables are defined again at the end.
+#[test]
+fn globals_are_saved_in_definition_order() {
+    let mut globals = crate::new_globals(Settings::new());
+    let lines = [
+        "c = 1", "a = 2", "f(x) = x", "b = 3", "a = 4", ":undef c", "c = 5",
+    ];
+    run_lines(&mut globals, &lines);
+
+    let names = globals.symbols().filter(|&s| !globals.is_native(s));
+    let names = names.map(|s| s.to_string()).collect::<Vec<_>>();
+    assert_eq!(names, ["a", "f", "b", "c"]);
+    assert_eq!(saved_globals(&globals), "a = 4\nf(x) = x\nb = 3\nc = 5\n");
+}
+
+/// Tests that saved global variables are loaded with their current values,
+/// even if the global variables they were calculated from have changed.
+#[test]
+fn saved_globals_round_trip() {
+    let mut globals = crate::new_globals(Settings::new());
+    let lines = [
+        "x = 1",
+        "y = [x]",
+        "x = 2",
+        "q = 1",
+        "q = [q]",
+        "f(n) = n * x",
+        "s = (\"a`b\", 0.1, -3, (true,), days(2), date(2024, 2, 29) + hours(1))",
+    ];
+    run_lines(&mut globals, &lines);
+
+    let saved = saved_globals(&globals);
+    assert_eq!(
+        saved,
+        "x = 2\ny = [1]\nq = [1]\nf(n) = n * x\n\
+         s = (\"a`b\", 0.1, -3, (true,), seconds(172800), \
+         date(1970, 1, 1) + seconds(1709168400))\n"
+    );
+
+    let mut loaded = crate::new_globals(Settings::new());
+    let (_, errors) = run_lines(&mut loaded, &[&saved]);
+    assert_eq!(errors, "");
+    assert_eq!(saved_globals(&loaded), saved);
+
+    let (output, _) = run_lines(&mut loaded, &["y, q, f(3), s[4] == days(2)"]);
+    assert_eq!(output, "[1]\n[1]\n6\ntrue\n");
+}
+
+/// Tests that call sites call their current callee, including global
+/// functions which were redefined after the call site was compiled.
+#[test]
+fn call_sites_call_current_callees() {
+    let mut globals = crate::new_globals(Settings::new());
+
+    let lines = [
+        "f(x) = x + 1",
+        "g(y) = f(y)",
+        "g(1)",
+        "f(x) = x * 10",
+        "g(1)",
+        "apply(k, x) = k(x)",
+        "apply(sqrt, 16), apply(f, 2), apply(x -> -x, 3), apply(f, 4)",
+        "apply((a, b) -> a, 5)",
+    ];
+
+    let (output, errors) = run_lines(&mut globals, &lines);
+    assert_eq!(output, "2\n10\n4\n20\n-3\n40\n");
+    let error_lines = errors.lines().collect::<Vec<_>>();
+    assert_eq!(
+        error_lines[error_lines.len() - 3..],
+        [
+            "Error: incorrect number of arguments for function call",
+            "  apply(k, x) = k(x)",
+            "                ^^^^",
+        ]
+    );
+}
@@ -0,0 +1,52 @@
+use std::io;
+
+use thiserror::Error;
+
+/// An error caught while handling an HTTP request.
+#[derive(Debug, Error)]
+pub enum RequestError {
+    /// The request could not be read.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// The request is not valid HTTP.
+    #[error("malformed HTTP request")]
+    Malformed,
+
+    /// The request's headers or body are too large.
+    #[error("request is too large")]
+    TooLarge,
+
+    /// The request's path does not exist.
+    #[error("path '{0}' does not exist")]
+    NotFound(String),
+
+    /// The request's method is not allowed for its path.
+    #[error("method '{0}' is not allowed")]
+    MethodNotAllowed(String),
+
+    /// The request's body is not a JSON object with a source code string.
+    #[error("request body must be a JSON object with a 'source' string")]
+    InvalidBody,
+
+    /// The request names a session which does not exist or has expired.
+    #[error("session '{0}' does not exist")]
+    UnknownSession(String),
+
+    /// Evaluating the request's source code panicked.
+    #[error("internal error, please report this as a bug")]
+    Internal,
+}
+
+impl RequestError {
+    /// Returns the HTTP status code for the `RequestError`.
+    pub const fn status(&self) -> u16 {
+        match self {
+            Self::Io(_) | Self::Malformed | Self::InvalidBody => 400,
+            Self::TooLarge => 413,
+            Self::NotFound(_) | Self::UnknownSession(_) => 404,
+            Self::MethodNotAllowed(_) => 405,
+            Self::Internal => 500,
+        }
+    }
+}
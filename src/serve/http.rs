@@ -0,0 +1,123 @@
+use std::io::{self, BufRead, Read, Take, Write};
+
+use crate::json::Json;
+
+use super::errors::RequestError;
+
+/// The largest size of a request's headers which will be read, in bytes.
+const MAX_HEADERS_SIZE: u64 = 8 * 1024;
+
+/// The largest size of a request's body which will be read, in bytes.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// An HTTP request.
+#[derive(Debug)]
+pub struct Request {
+    /// The method.
+    pub method: String,
+
+    /// The path, without a query string.
+    pub path: String,
+
+    /// The body.
+    pub body: Vec<u8>,
+}
+
+/// Reads a [`Request`] from a reader. This function returns a
+/// [`RequestError`] if the [`Request`] could not be read or is invalid.
+pub fn read_request<R: BufRead>(reader: &mut R) -> Result<Request, RequestError> {
+    let mut headers = Read::take(reader, MAX_HEADERS_SIZE);
+    let request_line = read_line(&mut headers)?;
+    let mut parts = request_line.split(' ');
+
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(RequestError::Malformed);
+    };
+
+    if !version.starts_with("HTTP/1.") {
+        return Err(RequestError::Malformed);
+    }
+
+    let path = target.split_once('?').map_or(target, |(p, _)| p);
+    let mut content_length = 0;
+
+    loop {
+        let line = read_line(&mut headers)?;
+
+        if line.is_empty() {
+            break;
+        }
+
+        let (name, value) = line.split_once(':').ok_or(RequestError::Malformed)?;
+
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let Ok(length) = value.trim().parse() else {
+                return Err(RequestError::Malformed);
+            };
+
+            content_length = length;
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(RequestError::TooLarge);
+    }
+
+    let mut body = vec![0; content_length];
+    headers.into_inner().read_exact(&mut body)?;
+
+    Ok(Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        body,
+    })
+}
+
+/// Writes an HTTP response with a status code and a JSON body to a writer.
+/// This function returns an [`io::Error`] if the response could not be
+/// written.
+pub fn write_response<W: Write>(writer: &mut W, status: u16, body: &Json) -> io::Result<()> {
+    let body = body.to_string();
+
+    write!(
+        writer,
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason_phrase(status),
+        body.len(),
+    )?;
+
+    writer.flush()
+}
+
+/// Reads a header line without its line ending. This function returns a
+/// [`RequestError`] if the line could not be read, is not UTF-8, or is cut
+/// off by the end of the request or the limit on the size of headers.
+fn read_line<R: BufRead>(reader: &mut Take<R>) -> Result<String, RequestError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    if !line.ends_with('\n') {
+        return Err(if reader.limit() == 0 {
+            RequestError::TooLarge
+        } else {
+            RequestError::Malformed
+        });
+    }
+
+    line.truncate(line.trim_end_matches(['\r', '\n']).len());
+    Ok(line)
+}
+
+/// Returns the reason phrase for an HTTP status code.
+const fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Content Too Large",
+        _ => "",
+    }
+}
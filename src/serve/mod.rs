@@ -0,0 +1,267 @@
+#[cfg(test)]
+mod tests;
+
+mod errors;
+mod http;
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, hash_map::RandomState},
+    hash::BuildHasher as _,
+    io::BufReader,
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    process::ExitCode,
+    rc::Rc,
+    time::Duration,
+};
+
+use crate::{
+    embed::{Engine, Observer},
+    json::Json,
+    settings::Settings,
+};
+
+use self::{
+    errors::RequestError,
+    http::{Request, read_request, write_response},
+};
+
+/// The port which is listened on if no port is given.
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// The largest number of sessions which are kept at once. The least recently
+/// used session is removed when a new session would exceed this number.
+const MAX_SESSIONS: usize = 64;
+
+/// The time to wait for a client to send a request before closing its
+/// connection.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum number of steps run by one request if no maximum is given.
+/// Requests are handled one at a time, so code which never finishes must be
+/// stopped before it blocks every other session. Code which builds huge lists
+/// or strings in a few steps is stopped by the length limit instead.
+const DEFAULT_MAX_STEPS: usize = 10_000_000;
+
+/// Runs an HTTP server on a local port which evaluates source code with
+/// [`Settings`] for each session, and returns an [`ExitCode`] if the port
+/// could not be listened on.
+pub fn run_server(port: u16, mut settings: Settings) -> ExitCode {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Could not listen on port {port}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    eprintln!("Listening on http://{}:{port}", Ipv4Addr::LOCALHOST);
+    settings.limits.steps.get_or_insert(DEFAULT_MAX_STEPS);
+    let mut server = Server::new(settings);
+
+    // Requests are handled one at a time because each session's engine can
+    // only be used on the thread which created it.
+    for stream in listener.incoming().flatten() {
+        server.handle_connection(&stream);
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// A server which evaluates source code in sessions.
+struct Server {
+    /// The [`Settings`] for new [`Session`]s.
+    settings: Settings,
+
+    /// The [`Session`]s by identifier.
+    sessions: HashMap<String, Session>,
+
+    /// The number of requests which have been handled.
+    request_count: u64,
+}
+
+impl Server {
+    /// Creates a new `Server` with [`Settings`] for its [`Session`]s.
+    fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            sessions: HashMap::new(),
+            request_count: 0,
+        }
+    }
+
+    /// Reads a [`Request`] from a [`TcpStream`] and writes its response.
+    fn handle_connection(&mut self, stream: &TcpStream) {
+        // A connection which cannot be configured, read from, or written to
+        // is dropped because the client has disconnected.
+        if stream.set_read_timeout(Some(READ_TIMEOUT)).is_err() {
+            return;
+        }
+
+        let mut reader = BufReader::new(stream);
+
+        let (status, body) = match read_request(&mut reader).and_then(|r| self.handle(&r)) {
+            Ok(body) => (200, body),
+            Err(error) => (
+                error.status(),
+                Json::object([("error", error.to_string().into())]),
+            ),
+        };
+
+        let _: Result<_, _> = write_response(&mut &*stream, status, &body);
+    }
+
+    /// Handles a [`Request`] and returns the body of its response. This
+    /// function returns a [`RequestError`] if the [`Request`] is invalid.
+    fn handle(&mut self, request: &Request) -> Result<Json, RequestError> {
+        if request.path != "/eval" {
+            return Err(RequestError::NotFound(request.path.clone()));
+        }
+
+        if request.method != "POST" {
+            return Err(RequestError::MethodNotAllowed(request.method.clone()));
+        }
+
+        let body = str::from_utf8(&request.body)
+            .ok()
+            .and_then(Json::parse)
+            .ok_or(RequestError::InvalidBody)?;
+
+        let source = body
+            .get("source")
+            .and_then(Json::as_str)
+            .ok_or(RequestError::InvalidBody)?;
+
+        let id = match body.get("session") {
+            None | Some(Json::Null) => self.new_session(),
+            Some(Json::Str(id)) if self.sessions.contains_key(id) => id.clone(),
+            Some(Json::Str(id)) => return Err(RequestError::UnknownSession(id.clone())),
+            Some(_) => return Err(RequestError::InvalidBody),
+        };
+
+        self.request_count += 1;
+        let session = self
+            .sessions
+            .get_mut(&id)
+            .ok_or_else(|| RequestError::UnknownSession(id.clone()))?;
+
+        session.last_used = self.request_count;
+
+        // A panic is caught so that a bug in Clac does not stop every other
+        // session. The session may have been left in an invalid state, so it
+        // is removed.
+        let Ok(response) =
+            panic::catch_unwind(AssertUnwindSafe(|| session.eval(id.clone(), source)))
+        else {
+            self.sessions.remove(&id);
+            return Err(RequestError::Internal);
+        };
+
+        Ok(response)
+    }
+
+    /// Creates a new [`Session`] and returns its identifier. The least
+    /// recently used [`Session`] is removed if there are too many.
+    fn new_session(&mut self) -> String {
+        if self.sessions.len() >= MAX_SESSIONS
+            && let Some(oldest) = self
+                .sessions
+                .iter()
+                .min_by_key(|(_, s)| s.last_used)
+                .map(|(id, _)| id.clone())
+        {
+            self.sessions.remove(&oldest);
+        }
+
+        let id = loop {
+            let id = format!("{:016x}", RandomState::new().hash_one(self.request_count));
+
+            if !self.sessions.contains_key(&id) {
+                break id;
+            }
+        };
+
+        self.sessions
+            .insert(id.clone(), Session::new(self.settings));
+        id
+    }
+}
+
+/// An [`Engine`] whose global variables persist between requests.
+struct Session {
+    /// The [`Engine`] which evaluates source code.
+    engine: Engine,
+
+    /// The text which was printed by the request being handled.
+    output: Rc<RefCell<String>>,
+
+    /// The number of requests which had been handled when the `Session` was
+    /// last used.
+    last_used: u64,
+}
+
+impl Session {
+    /// Creates a new `Session` with [`Settings`].
+    fn new(settings: Settings) -> Self {
+        let output = Rc::default();
+        let mut engine = Engine::with_settings(settings);
+        engine.set_observer(Capture(Rc::clone(&output)));
+
+        Self {
+            engine,
+            output,
+            last_used: 0,
+        }
+    }
+
+    /// Evaluates source code and returns the body of its response with the
+    /// `Session`'s identifier.
+    fn eval(&mut self, id: String, source: &str) -> Json {
+        self.output.borrow_mut().clear();
+
+        let evaluation = self.engine.eval(source);
+
+        let results = if evaluation.is_ok() {
+            let values = evaluation.values.iter();
+            values.map(|v| self.engine.format(v).into()).collect()
+        } else {
+            Vec::new()
+        };
+
+        let warnings = evaluation
+            .warnings
+            .iter()
+            .map(|w| w.to_string().into())
+            .collect();
+
+        let diagnostics: Vec<Json> = evaluation
+            .errors
+            .iter()
+            .map(|e| {
+                Json::object([
+                    ("stage", e.stage().into()),
+                    ("message", e.to_string().into()),
+                ])
+            })
+            .collect();
+
+        Json::object([
+            ("session", id.into()),
+            ("ok", diagnostics.is_empty().into()),
+            ("results", Json::Array(results)),
+            ("output", self.output.take().into()),
+            ("diagnostics", Json::Array(diagnostics)),
+            ("warnings", Json::Array(warnings)),
+        ])
+    }
+}
+
+/// An [`Observer`] which captures printed text.
+struct Capture(Rc<RefCell<String>>);
+
+impl Observer for Capture {
+    fn printed(&mut self, text: &str) {
+        self.0.borrow_mut().push_str(text);
+    }
+}
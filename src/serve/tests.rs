@@ -0,0 +1,223 @@
+use crate::{json::Json, settings::Settings};
+
+use super::{RequestError, Server, http};
+
+/// Reads an HTTP request from text.
+fn request(text: &str) -> Result<http::Request, RequestError> {
+    http::read_request(&mut text.as_bytes())
+}
+
+/// Handles a request to evaluate source code in a session with a [`Server`]
+/// and returns the body of its response, or a [`RequestError`] if it failed.
+fn post(server: &mut Server, source: &str, session: Option<&str>) -> Result<Json, RequestError> {
+    let mut body = vec![("source".to_owned(), source.into())];
+
+    if let Some(session) = session {
+        body.push(("session".to_owned(), session.into()));
+    }
+
+    let body = Json::Object(body).to_string();
+    let text = format!(
+        "POST /eval HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+
+    let request = request(&text).expect("request should be valid");
+    server.handle(&request)
+}
+
+/// Handles a request to evaluate source code in a session with a [`Server`]
+/// and returns the body of its response.
+fn eval(server: &mut Server, source: &str, session: Option<&str>) -> Json {
+    post(server, source, session).expect("request should be handled")
+}
+
+/// Tests that HTTP requests are parsed and invalid requests are rejected.
+#[test]
+fn http_requests_are_parsed() {
+    let parsed = request("POST /eval?x=1 HTTP/1.1\r\nHost: a\r\ncontent-length: 3\r\n\r\nabc")
+        .expect("request should be valid");
+
+    assert_eq!(parsed.method, "POST");
+    assert_eq!(parsed.path, "/eval");
+    assert_eq!(parsed.body, b"abc");
+
+    for invalid in [
+        "",
+        "GET\r\n\r\n",
+        "GET / FTP\r\n\r\n",
+        "GET / HTTP/1.1\r\nHost\r\n\r\n",
+    ] {
+        assert!(
+            matches!(request(invalid), Err(RequestError::Malformed)),
+            "{invalid:?} should be malformed",
+        );
+    }
+
+    let long = format!("GET / HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(10_000));
+    assert!(
+        matches!(request(&long), Err(RequestError::TooLarge)),
+        "long headers should be too large",
+    );
+}
+
+/// Tests that sessions keep their global variables between requests.
+#[test]
+fn sessions_keep_global_variables() {
+    let mut server = Server::new(Settings::new());
+    let response = eval(&mut server, "x = 2, println(x), x * 3", None);
+    let session = response
+        .get("session")
+        .and_then(Json::as_str)
+        .expect("response should have a session")
+        .to_owned();
+
+    assert_eq!(response.get("ok"), Some(&Json::Bool(true)));
+    assert_eq!(
+        response.get("results"),
+        Some(&Json::Array(vec!["2".into(), "6".into()]))
+    );
+    assert_eq!(response.get("output").and_then(Json::as_str), Some("2\n"));
+
+    let same_session = eval(&mut server, "x = x + 1, x", Some(&session));
+    assert_eq!(
+        same_session.get("results"),
+        Some(&Json::Array(vec!["3".into()]))
+    );
+    assert_eq!(
+        same_session.get("warnings"),
+        Some(&Json::Array(vec!["variable 'x' is redefined".into()]))
+    );
+
+    let new_session = eval(&mut server, "x + 1", None);
+    assert_eq!(new_session.get("ok"), Some(&Json::Bool(false)));
+    assert_eq!(
+        new_session.get("diagnostics"),
+        Some(&Json::Array(vec![Json::object([
+            ("stage", "lower".into()),
+            ("message", "variable 'x' is undefined".into()),
+        ])])),
+    );
+
+    let body = r#"{"source":"1","session":"missing"}"#;
+    let request = request(&format!(
+        "POST /eval HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    ))
+    .expect("request should be valid");
+
+    assert!(
+        matches!(
+            server.handle(&request),
+            Err(RequestError::UnknownSession(_))
+        ),
+        "missing sessions should be unknown",
+    );
+}
+
+/// Returns the `session` identifier from the body of a response.
+fn session(response: &Json) -> &str {
+    response
+        .get("session")
+        .and_then(Json::as_str)
+        .expect("response should have a session")
+}
+
+/// Returns the first diagnostic message from the body of a response.
+fn first_diagnostic(response: &Json) -> Option<&str> {
+    match response.get("diagnostics") {
+        Some(Json::Array(diagnostics)) => diagnostics.first()?.get("message")?.as_str(),
+        _ => None,
+    }
+}
+
+/// Tests that code which runs for too many steps is stopped without stopping
+/// the server.
+#[test]
+fn long_running_code_is_stopped() {
+    let mut settings = Settings::new();
+    settings.limits.steps = Some(1000);
+    let mut server = Server::new(settings);
+
+    let looping = eval(&mut server, "x = 1, while true { 1 }", None);
+    assert_eq!(looping.get("ok"), Some(&Json::Bool(false)));
+    assert_eq!(
+        first_diagnostic(&looping),
+        Some("code ran for more than 1000 steps")
+    );
+
+    let id = session(&looping).to_owned();
+    let same_session = eval(&mut server, "x + 1", Some(&id));
+    assert_eq!(
+        same_session.get("results"),
+        Some(&Json::Array(vec!["2".into()]))
+    );
+}
+
+/// Tests that code which builds a huge list in a few steps is stopped by the
+/// length limit without stopping the server.
+#[test]
+fn long_values_are_stopped() {
+    let mut settings = Settings::new();
+    settings.limits.length = Some(1000);
+    let mut server = Server::new(settings);
+
+    let doubling = eval(&mut server, "xs = [1], while true { xs = xs + xs }", None);
+    assert_eq!(
+        first_diagnostic(&doubling),
+        Some("list or string is longer than the maximum length of 1000")
+    );
+
+    let id = session(&doubling).to_owned();
+    let same_session = eval(&mut server, "len(xs)", Some(&id));
+    assert_eq!(
+        same_session.get("results"),
+        Some(&Json::Array(vec!["1".into()]))
+    );
+}
+
+/// Tests that a panic while evaluating source code is reported as an internal
+/// error, and that its session is removed without stopping the server.
+#[test]
+fn panics_are_internal_errors() {
+    let mut server = Server::new(Settings::new());
+    let id = session(&eval(&mut server, "x = 1", None)).to_owned();
+
+    let session = server.sessions.get_mut(&id).expect("session should exist");
+    session.engine.set_provider(|name: &str| {
+        assert!(name != "boom", "provider should panic");
+        None
+    });
+
+    let error = post(&mut server, "boom", Some(&id)).expect_err("request should fail");
+    assert!(matches!(error, RequestError::Internal));
+    assert_eq!(error.status(), 500);
+    assert!(!server.sessions.contains_key(&id));
+
+    let other = eval(&mut server, "1 + 1", None);
+    assert_eq!(other.get("results"), Some(&Json::Array(vec!["2".into()])));
+}
+
+/// Tests that custom operators and settings do not leak between sessions.
+#[test]
+fn sessions_are_isolated() {
+    let mut settings = Settings::new();
+    settings.limits.instructions = Some(50);
+    let mut server = Server::new(settings);
+
+    let defining = eval(&mut server, "operator ⊕ (a, b) = a * 10 + b, 1 ⊕ 2", None);
+    assert_eq!(
+        defining.get("results"),
+        Some(&Json::Array(vec!["12".into()]))
+    );
+
+    let other = eval(&mut server, "1 ⊕ 2", None);
+    assert_eq!(other.get("ok"), Some(&Json::Bool(false)));
+
+    let long_source = vec!["1"; 50].join(" + ");
+    let limited = eval(&mut server, &long_source, None);
+    assert_eq!(
+        first_diagnostic(&limited),
+        Some("code compiles to more than 50 instructions")
+    );
+}
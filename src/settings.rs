@@ -0,0 +1,57 @@
+use crate::{
+    format::NumberFormat,
+    integer::IntegerMode,
+    limits::Limits,
+    output::{Echo, ResultFormat},
+};
+
+/// The settings of one session, which are stored with its [`Globals`]. Each
+/// session's `Settings` are independent, so changing them (e.g. with a REPL
+/// command) does not affect any other session. `Settings` can be sent to other
+/// threads so that their sessions behave like the current one.
+///
+/// [`Globals`]: crate::interpret::Globals
+#[derive(Clone, Copy, Default)]
+pub struct Settings {
+    /// The [`NumberFormat`].
+    pub number_format: NumberFormat,
+
+    /// The [`IntegerMode`], if any.
+    pub integer_mode: Option<IntegerMode>,
+
+    /// Whether SI prefix suffixes are recognized on number literals.
+    pub si_suffixes: bool,
+
+    /// Whether global variables bound to native functions can be redefined.
+    pub native_overrides: bool,
+
+    /// The [`Limits`].
+    pub limits: Limits,
+
+    /// Whether reactive mode is enabled. In reactive mode, global variable
+    /// assignments are recorded as formulas, and reassigning a global variable
+    /// recalculates and prints the global variables which depend on it.
+    pub reactive: bool,
+
+    /// The [`Echo`] mode.
+    pub echo: Echo,
+
+    /// The [`ResultFormat`].
+    pub result_format: ResultFormat,
+}
+
+impl Settings {
+    /// Creates new default `Settings`.
+    pub const fn new() -> Self {
+        Self {
+            number_format: NumberFormat::new(),
+            integer_mode: None,
+            si_suffixes: false,
+            native_overrides: false,
+            limits: Limits::UNLIMITED,
+            reactive: false,
+            echo: Echo::On,
+            result_format: ResultFormat::Pretty,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+
+use crate::spans::{SourceSpan, Span};
+
+/// Where source code came from.
+#[derive(Clone, Copy)]
+pub enum Origin<'pth> {
+    /// Code arguments or standard input.
+    Args,
+
+    /// A line or edit buffer entered in the REPL.
+    Repl,
+
+    /// A script file from its path.
+    File(&'pth Path),
+}
+
+impl<'pth> Origin<'pth> {
+    /// Returns a [`Location`] in the `Origin` from a [`SourceSpan`], if any.
+    pub const fn locate<'spn>(self, span: Option<&'spn SourceSpan>) -> Location<'pth, 'spn> {
+        Location { origin: self, span }
+    }
+}
+
+/// A location in an [`Origin`] which is displayed after an error.
+pub struct Location<'pth, 'spn> {
+    /// The [`Origin`].
+    origin: Origin<'pth>,
+
+    /// The [`SourceSpan`], if any.
+    span: Option<&'spn SourceSpan>,
+}
+
+impl Display for Location<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.origin, self.span) {
+            (Origin::Args | Origin::Repl, None) => Ok(()),
+            (Origin::Args | Origin::Repl, Some(span)) => fmt_snippet(f, span),
+            (Origin::File(path), None) => write!(f, "\n  in '{}'", path.display()),
+            (Origin::File(path), Some(span)) => {
+                let (line, column) = span.line_column();
+                write!(f, "\n  at {}:{line}:{column}", path.display())?;
+                fmt_snippet(f, span)
+            }
+        }
+    }
+}
+
+/// Writes the line of source code where a [`SourceSpan`] starts, followed by
+/// carets under the part of the line covered by the [`SourceSpan`].
+fn fmt_snippet(f: &mut Formatter<'_>, span: &SourceSpan) -> fmt::Result {
+    let source = &span.source;
+    let Span { start, end } = span.span;
+    let line_start = Span::new(0, start)
+        .slice(source)
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let line_end = Span::new(start, source.len())
+        .slice(source)
+        .find('\n')
+        .map_or(source.len(), |i| start + i);
+
+    let line = Span::new(line_start, line_end).slice(source).trim_end();
+
+    // Tabs are kept in the padding so that the carets line up with the line.
+    let padding: String = Span::new(line_start, start)
+        .slice(source)
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    let width = Span::new(start, end.min(line_end))
+        .slice(source)
+        .chars()
+        .count();
+    write!(f, "\n  {line}\n  {padding}{}", "^".repeat(width.max(1)))
+}
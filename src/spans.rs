@@ -0,0 +1,58 @@
+use std::rc::Rc;
+
+/// A range of byte offsets into source code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    /// The byte offset to the start of the `Span`.
+    pub start: usize,
+
+    /// The byte offset to the end of the `Span`.
+    pub end: usize,
+}
+
+impl Span {
+    /// Creates a new `Span` from start and end byte offsets.
+    pub const fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns a new `Span` from the start of the `Span` to the end of another
+    /// `Span`.
+    pub const fn to(self, other: Self) -> Self {
+        Self::new(self.start, other.end)
+    }
+
+    /// Returns the string slice of source code covered by the `Span`. This
+    /// function returns an empty string slice if the `Span` does not lie on
+    /// code point boundaries of the source code.
+    pub fn slice(self, source: &str) -> &str {
+        source.get(self.start..self.end).unwrap_or_default()
+    }
+}
+
+/// A [`Span`] with the source code that it belongs to.
+#[derive(Clone, Debug)]
+pub struct SourceSpan {
+    /// The source code.
+    pub source: Rc<str>,
+
+    /// The [`Span`].
+    pub span: Span,
+}
+
+impl SourceSpan {
+    /// Returns the one-based line and column numbers of the start of the
+    /// `SourceSpan`. Columns are counted in characters.
+    pub fn line_column(&self) -> (usize, usize) {
+        let before = Span::new(0, self.span.start).slice(&self.source);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + 1;
+        let column = Span::new(line_start, self.span.start)
+            .slice(&self.source)
+            .chars()
+            .count()
+            + 1;
+
+        (line, column)
+    }
+}
@@ -32,6 +32,12 @@ impl Symbol {
 
         Self(index)
     }
+
+    /// Returns the `Symbol`'s index. Indices are small, dense, and unique to
+    /// each name, so they can be used as slot indices.
+    pub const fn index(self) -> usize {
+        self.0
+    }
 }
 
 impl Display for Symbol {
@@ -0,0 +1,24 @@
+use std::io::{self, Write as _};
+
+/// A source of lines of input for the REPL. Lines are read from standard input
+/// by [`PlainInput`], but other sources can be used to drive the REPL (e.g. from
+/// a script in tests).
+pub trait InputSource {
+    /// Prints a prompt, then reads a line of input and appends it to a buffer
+    /// with its line ending. This function returns the number of bytes which
+    /// were read, which is zero at the end of input, or an [`io::Error`] if
+    /// the line could not be read.
+    fn read_line(&mut self, prompt: &str, buffer: &mut String) -> io::Result<usize>;
+}
+
+/// An [`InputSource`] which reads lines from standard input. Any line editing
+/// is provided by the terminal, not by Clac.
+pub struct PlainInput;
+
+impl InputSource for PlainInput {
+    fn read_line(&mut self, prompt: &str, buffer: &mut String) -> io::Result<usize> {
+        print!("{prompt}");
+        io::stdout().flush()?;
+        io::stdin().read_line(buffer)
+    }
+}
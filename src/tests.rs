@@ -0,0 +1,1773 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    process,
+};
+
+use super::*;
+use crate::{
+    completions::Shell, integer::IntegerMode, limits::Limits, output::ResultFormat, symbols::Symbol,
+};
+
+/// The names of variables in generated programs. There are only a few names so
+/// that generated programs often shadow variables which are also captured.
+const GENERATED_NAMES: [&str; 3] = ["a", "b", "c"];
+
+/// The number of programs with nested closures to generate.
+const GENERATED_PROGRAM_COUNT: usize = 200;
+
+/// The expected results of running a spec file's source code.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Expected {
+    /// The expected output.
+    stdout: String,
+
+    /// The expected errors, each written as the name of the stage which caught
+    /// the error and the error's message.
+    errors: Vec<String>,
+}
+
+/// Splits a spec file into its source code and [`Expected`] results. Spec files
+/// are divided into `--- source`, `--- stdout`, and `--- errors` sections.
+/// Sections after the source code may be omitted if they are expected to be
+/// empty.
+fn parse_spec(spec: &str) -> (String, Expected) {
+    let mut source = String::new();
+    let mut expected = Expected::default();
+    let mut section = None;
+
+    for line in spec.lines() {
+        if let Some(name) = line.strip_prefix("--- ") {
+            section = Some(name.trim());
+            continue;
+        }
+
+        match section {
+            Some("source") => {
+                source.push_str(line);
+                source.push('\n');
+            }
+            Some("stdout") => {
+                expected.stdout.push_str(line);
+                expected.stdout.push('\n');
+            }
+            Some("errors") if !line.is_empty() => expected.errors.push(line.to_owned()),
+            Some("errors") => {}
+            _ => unreachable!("spec line '{line}' should be in a known section"),
+        }
+    }
+
+    (source, expected)
+}
+
+/// Runs source code through every stage of Clac with new [`Globals`] and
+/// returns its results.
+fn run_program(source: &str, path: &Path) -> Expected {
+    let mut globals = new_globals(Settings::new());
+
+    globals.output().start_capture();
+
+    let result = try_run_source(
+        source,
+        Origin::File(path),
+        &mut globals,
+        Mode::Execute,
+        GlobalMode::Sealed,
+        None,
+    );
+
+    let capture = globals.output().take_capture();
+
+    let errors = result
+        .err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| format!("{}: {}", e.stage(), e.message()));
+
+    Expected {
+        stdout: capture.output(),
+        errors: errors.collect(),
+    }
+}
+
+/// Returns the paths to the spec files in the `spec` directory, sorted by
+/// name.
+fn spec_paths() -> Vec<PathBuf> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("spec");
+    let entries = fs::read_dir(&directory).expect("spec directory should be readable");
+
+    let mut paths: Vec<PathBuf> = entries
+        .map(|e| e.expect("spec directory entry should be readable").path())
+        .filter(|p| p.extension().is_some_and(|e| e == "spec"))
+        .collect();
+
+    paths.sort();
+    paths
+}
+
+/// Tests that the source code in each spec file in the `spec` directory
+/// produces its expected output and errors.
+#[test]
+fn spec_files_produce_expected_results() {
+    let paths = spec_paths();
+    let mut failures = Vec::new();
+    assert!(!paths.is_empty(), "there should be spec files");
+
+    for path in paths {
+        let spec = fs::read_to_string(&path).expect("spec file should be readable");
+        let (source, expected) = parse_spec(&spec.replace("\r\n", "\n"));
+        let actual = run_program(&source, &path);
+
+        if actual != expected {
+            failures.push(format!(
+                "{}:\nexpected {expected:#?}\nactual {actual:#?}",
+                path.file_name().unwrap_or_default().display()
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "spec files did not produce their expected results:\n\n{}",
+        failures.join("\n\n")
+    );
+}
+
+/// A deterministic pseudo-random number generator for generating programs.
+struct Rng(u64);
+
+impl Rng {
+    /// Returns the next pseudo-random number below a bound.
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13_u32;
+        self.0 ^= self.0 >> 7_u32;
+        self.0 ^= self.0 << 17_u32;
+
+        let bound = u64::try_from(bound).expect("bound should fit in 64 bits");
+        usize::try_from(self.0 % bound).expect("number should be below the bound")
+    }
+
+    /// Returns a random variable name from [`GENERATED_NAMES`].
+    fn name(&mut self) -> &'static str {
+        GENERATED_NAMES[self.below(GENERATED_NAMES.len())]
+    }
+}
+
+/// A generator of programs with closures nested several functions deep, which
+/// tracks the value that each program should produce.
+struct ClosureGenerator {
+    /// The [`Rng`].
+    rng: Rng,
+
+    /// The names and values of the variables in scope, innermost last.
+    scope: Vec<(&'static str, i64)>,
+
+    /// The number of functions generated, which is used to name functions.
+    function_count: usize,
+}
+
+impl ClosureGenerator {
+    /// Creates a new `ClosureGenerator` from a seed.
+    const fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng(seed),
+            scope: Vec::new(),
+            function_count: 0,
+        }
+    }
+
+    /// Generates a program with functions nested to a depth and returns its
+    /// source code and the value it should print.
+    fn program(&mut self, depth: usize) -> (String, i64) {
+        self.scope.clear();
+        self.function_count = 0;
+        self.body(depth)
+    }
+
+    /// Generates an expression whose value is nested inside functions to a
+    /// depth and returns its source code and value.
+    fn body(&mut self, depth: usize) -> (String, i64) {
+        if depth == 0 {
+            let (sum, value) = self.sum();
+
+            // Closures created inside loops capture the current scope.
+            return if self.rng.below(2) == 0 {
+                (sum, value)
+            } else {
+                let source = format!(
+                    "loop(i = 0, acc = 0) {{ i < 2 ? recur(i + 1, acc + (() -> {sum})()) : acc }}"
+                );
+
+                (source, value * 2)
+            };
+        }
+
+        let outer_len = self.scope.len();
+        let function = format!("f{}", self.function_count);
+        self.function_count += 1;
+
+        let (arg, arg_value) = self.sum();
+        let param = self.rng.name();
+        self.scope.push((param, arg_value));
+
+        let mut locals = Vec::new();
+        let mut local_names = Vec::new();
+
+        for _ in 0..self.rng.below(3) {
+            let name = self.rng.name();
+
+            if local_names.contains(&name) {
+                continue;
+            }
+
+            let (source, value) = self.sum();
+            locals.push(format!("{name} = {source}, "));
+            self.scope.push((name, value));
+            local_names.push(name);
+        }
+
+        let locals = locals.concat();
+        let (inner, value) = self.body(depth - 1);
+        self.scope.truncate(outer_len);
+
+        // The inner expression is either evaluated inside the function, or in
+        // a closure which is returned and called after the function's scope
+        // has ended.
+        let source = if self.rng.below(2) == 0 {
+            format!("{{ {function}({param}) = {{ {locals}{inner} }}, {function}({arg}) }}")
+        } else {
+            format!(
+                "{{ {function}({param}) = {{ {locals}() -> {inner} }}, k = {function}({arg}), k() }}"
+            )
+        };
+
+        (source, value)
+    }
+
+    /// Generates a sum of two variables or literals and returns its source code
+    /// and value.
+    fn sum(&mut self) -> (String, i64) {
+        let (lhs, lhs_value) = self.operand();
+        let (rhs, rhs_value) = self.operand();
+        (format!("{lhs} + {rhs}"), lhs_value + rhs_value)
+    }
+
+    /// Generates a variable in scope, or a literal if the variable is not in
+    /// scope, and returns its source code and value.
+    fn operand(&mut self) -> (String, i64) {
+        let name = self.rng.name();
+
+        if let Some((_, value)) = self.scope.iter().rev().find(|(n, _)| *n == name) {
+            return (name.to_owned(), *value);
+        }
+
+        let value = self.rng.below(9) + 1;
+        let value = i64::try_from(value).expect("literal should fit in 64 bits");
+        (value.to_string(), value)
+    }
+}
+
+/// Tests that generated programs with closures nested several functions deep
+/// produce their expected results. The programs capture parameters and
+/// locals across three or more function depths, and often shadow captured
+/// variables. Debug builds also check that upvars are balanced whenever a
+/// function returns.
+#[test]
+fn nested_closure_programs_produce_expected_results() {
+    let mut generator = ClosureGenerator::new(0x5eed_c105);
+    let path = Path::new("generated.clac");
+
+    for _ in 0..GENERATED_PROGRAM_COUNT {
+        let depth = 3 + generator.rng.below(3);
+        let (source, value) = generator.program(depth);
+
+        let expected = Expected {
+            stdout: format!("{value}\n"),
+            errors: Vec::new(),
+        };
+
+        assert_eq!(
+            run_program(&source, path),
+            expected,
+            "generated program should print {value}:\n{source}"
+        );
+    }
+}
+
+/// Tests that an [`Engine`] returns the results of top-level expressions as
+/// [`Value`]s which can be inspected, and keeps global variables between
+/// evaluations.
+#[test]
+fn engine_results_can_be_inspected() {
+    let mut engine = Engine::new();
+    let results = engine
+        .eval("x = 2, x + 1, x > 1, \"a\" + \"b\", square(n) = n * n, square, sqrt")
+        .into_result()
+        .expect("source code should evaluate");
+
+    let [three, is_greater, text, square, sqrt] = results.as_slice() else {
+        unreachable!("there should be five results, got {results:?}");
+    };
+
+    assert_eq!(*three, Value::Number(3.0));
+    assert_eq!(*is_greater, Value::Bool(true));
+    assert_eq!(*text, Value::from("ab"));
+    assert!(matches!(square, Value::Function(f) if f.name().as_deref() == Some("square")));
+    assert!(matches!(sqrt, Value::Function(f) if f.name().as_deref() == Some("sqrt")));
+
+    let later_results = engine
+        .eval("x * 10")
+        .into_result()
+        .expect("globals should be kept");
+    assert_eq!(later_results, [Value::Number(20.0)]);
+
+    let single = Value::Tuple(vec![Value::Number(1.0)]);
+    assert_eq!(single.to_string(), "(1,)");
+
+    let errors = engine
+        .eval("y + 1")
+        .into_result()
+        .expect_err("y should be undefined");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].stage(), "lower");
+    assert_eq!(errors[0].to_string(), "variable 'y' is undefined");
+}
+
+#[test]
+fn function_handles_can_be_called() {
+    let mut engine = Engine::new();
+    let results = engine
+        .eval("scale = 2, adder(n) = x -> x + n * scale, adder(10), sqrt, 1 + 1")
+        .into_result()
+        .expect("source code should evaluate");
+
+    let [Value::Function(add_ten), Value::Function(sqrt), two] = results.as_slice() else {
+        unreachable!("there should be two functions and a number, got {results:?}");
+    };
+
+    let sum = add_ten.call(&engine, &[1.0_f64.into()]);
+    assert_eq!(sum.expect("call should succeed"), Value::Number(21.0));
+
+    let root = sqrt.call(&engine, &[16.0_f64.into()]);
+    assert_eq!(root.expect("call should succeed"), Value::Number(4.0));
+
+    engine
+        .eval("scale = 3")
+        .into_result()
+        .expect("scale should be redefined");
+    let rescaled_sum = add_ten.call(&engine, &[1.0_f64.into()]);
+    assert_eq!(
+        rescaled_sum.expect("call should succeed"),
+        Value::Number(31.0)
+    );
+
+    let arity_error = add_ten
+        .call(&engine, &[])
+        .expect_err("arity should be checked");
+    assert_eq!(arity_error.stage(), "interpret");
+
+    let extra_args = [two.clone(), two.clone()];
+    let extra_error = add_ten
+        .call(&engine, &extra_args)
+        .expect_err("arity should be checked");
+    assert_eq!(extra_error.stage(), "interpret");
+
+    let other = Value::Other("12.00 USD".to_owned());
+    let other_error = add_ten
+        .call(&engine, &[other])
+        .expect_err("other values are unsupported");
+    assert_eq!(other_error.stage(), "interpret");
+}
+
+#[test]
+fn undefined_globals_are_provided_by_the_host() {
+    let requests = Rc::new(Cell::new(0_u32));
+    let mut engine = Engine::new();
+
+    engine.set_provider({
+        let requests = Rc::clone(&requests);
+
+        move |name: &str| {
+            requests.set(requests.get() + 1);
+            (name == "price").then_some(Value::Number(10.0))
+        }
+    });
+
+    let results = engine
+        .eval("price * 2, f(price) = price, f(3)")
+        .into_result();
+    assert_eq!(
+        results.expect("price should be provided"),
+        [Value::Number(20.0), Value::Number(3.0)]
+    );
+
+    let later_results = engine
+        .eval("price + 1")
+        .into_result()
+        .expect("price should be kept");
+    assert_eq!(later_results, [Value::Number(11.0)]);
+    assert_eq!(requests.get(), 1, "price should only be provided once");
+
+    let errors = engine
+        .eval("cost")
+        .into_result()
+        .expect_err("cost should be undefined");
+    assert_eq!(errors[0].to_string(), "variable 'cost' is undefined");
+    assert_eq!(requests.get(), 2);
+}
+
+#[test]
+fn host_objects_overload_operators() {
+    /// A length in meters.
+    #[derive(Debug, PartialEq)]
+    struct Meters(f64);
+
+    impl Display for Meters {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "{} m", self.0)
+        }
+    }
+
+    impl HostObject for Meters {
+        fn type_name(&self) -> &'static str {
+            "meters"
+        }
+
+        fn arith(&self, op: ArithOp, other: &Value, _side: Side) -> Option<Result<Value, String>> {
+            let result = match (op, other) {
+                (ArithOp::Add, Value::Object(other)) => {
+                    Self(self.0 + other.downcast_ref::<Self>()?.0)
+                }
+                (ArithOp::Multiply, Value::Number(scale)) => Self(self.0 * scale),
+                (ArithOp::Divide, _) => return Some(Err("cannot divide lengths".to_owned())),
+                _ => return None,
+            };
+
+            Some(Ok(Value::Object(ObjectHandle::new(result))))
+        }
+
+        #[expect(clippy::float_cmp, reason = "lengths are only equal if they are exact")]
+        fn equals(&self, other: &dyn HostObject) -> bool {
+            let other: &dyn Any = other;
+            other.downcast_ref::<Self>().is_some_and(|o| o.0 == self.0)
+        }
+
+        fn compare(&self, other: &dyn HostObject) -> Option<Ordering> {
+            let other: &dyn Any = other;
+            self.0.partial_cmp(&other.downcast_ref::<Self>()?.0)
+        }
+    }
+
+    let mut engine = Engine::new();
+    engine.set_provider(|name: &str| {
+        (name == "m").then(|| Value::Object(ObjectHandle::new(Meters(1.0))))
+    });
+
+    let results = engine
+        .eval("x = 2 * m + m, x, x == 3 * m, m < x, [m]")
+        .into_result()
+        .expect("source code should evaluate");
+
+    let [Value::Object(length), is_equal, is_less, list] = results.as_slice() else {
+        unreachable!("there should be an object and three values, got {results:?}");
+    };
+
+    assert_eq!(length.downcast_ref::<Meters>(), Some(&Meters(3.0)));
+    assert_eq!(length.to_string(), "3 m");
+    assert_eq!(*is_equal, Value::Bool(true));
+    assert_eq!(*is_less, Value::Bool(true));
+    assert_eq!(list.to_string(), "[1 m]");
+
+    let errors = engine
+        .eval("m / m")
+        .into_result()
+        .expect_err("division should be rejected");
+    assert_eq!(errors[0].to_string(), "cannot divide lengths");
+
+    let type_errors = engine
+        .eval("-m")
+        .into_result()
+        .expect_err("negation should be unsupported");
+    assert_eq!(type_errors[0].stage(), "interpret");
+}
+
+#[test]
+fn observers_are_notified_of_events() {
+    #[derive(Default)]
+    struct Log(Rc<RefCell<Vec<String>>>);
+
+    impl Observer for Log {
+        fn statement_executed(&mut self, result: Option<&Value>) {
+            let result = result.map_or_else(|| "none".to_owned(), ToString::to_string);
+            self.0.borrow_mut().push(format!("statement {result}"));
+        }
+
+        fn printed(&mut self, text: &str) {
+            self.0.borrow_mut().push(format!("printed {text:?}"));
+        }
+
+        fn error(&mut self, error: &EvalError) {
+            self.0.borrow_mut().push(format!("error {error}"));
+        }
+
+        fn function_called(&mut self, function: &FunctionHandle, args: &[Value]) {
+            let name = function.name().unwrap_or_default();
+            self.0.borrow_mut().push(format!("call {name} {args:?}"));
+        }
+    }
+
+    let log = Log::default();
+    let events = Rc::clone(&log.0);
+    let mut engine = Engine::new();
+    engine.set_observer(log);
+
+    let results = engine
+        .eval("f(x) = x + 1, print(f(1)), f(2)")
+        .into_result()
+        .expect("code should run");
+
+    assert_eq!(results, [Value::Number(2.0), Value::Number(3.0)]);
+    assert_eq!(
+        *events.borrow(),
+        [
+            "statement none",
+            "call f [Number(1.0)]",
+            "call print [Number(2.0)]",
+            "printed \"2\"",
+            "statement 2",
+            "call f [Number(2.0)]",
+            "statement 3",
+        ]
+    );
+
+    events.borrow_mut().clear();
+    engine
+        .eval("1 / 0")
+        .into_result()
+        .expect_err("division should fail");
+    assert_eq!(*events.borrow(), ["error cannot divide by zero"]);
+}
+
+/// Tests that an [`Engine`] returns warnings with its results instead of
+/// printing them.
+#[test]
+fn engines_return_warnings() {
+    let mut engine = Engine::new();
+    let evaluation = engine.eval("x = 1, x = 2, x");
+    assert!(evaluation.is_ok());
+    assert_eq!(evaluation.values, [Value::Number(2.0)]);
+
+    let warnings: Vec<String> = evaluation
+        .warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(warnings, ["variable 'x' is redefined"]);
+}
+
+/// Evaluates source code with a new [`Engine`] and returns the messages of
+/// its warnings.
+fn warning_messages(source: &str) -> Vec<String> {
+    let evaluation = Engine::new().eval(source);
+    assert!(evaluation.is_ok(), "source code should be valid");
+
+    evaluation
+        .warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Tests that conditional expressions with branches that can never be run or
+/// that are identical report warnings.
+#[test]
+fn conditional_branches_report_warnings() {
+    assert_eq!(
+        warning_messages("true ? 1 : 2"),
+        ["this branch can never be executed"]
+    );
+    assert_eq!(
+        warning_messages("false ? 1 : 2"),
+        ["this branch can never be executed"]
+    );
+    assert_eq!(
+        warning_messages("x = 2, x > 1 ? 5 : 5"),
+        ["both branches are identical, so the condition has no effect"]
+    );
+    assert!(warning_messages("x = 2, x > 1 ? 5 : 6").is_empty());
+}
+
+/// Tests that constant conditions and comparisons between a value and itself
+/// report warnings.
+#[test]
+fn constant_conditions_report_warnings() {
+    let always_true = "this condition is always true";
+    let always_false = "this condition is always false";
+    assert_eq!(warning_messages("x = 3, x > 1 && true"), [always_true]);
+    assert_eq!(warning_messages("x = 3, false || x > 1"), [always_false]);
+    assert_eq!(
+        warning_messages("f(x) = 1 when false, f(x) = 2 otherwise"),
+        [always_false]
+    );
+
+    assert_eq!(
+        warning_messages("x = 3, x == x, x >= x, 2 <= 2"),
+        ["this compares a value with itself, so it is always true"; 3]
+    );
+    assert_eq!(
+        warning_messages("x = 3, x != x, x < x, `a` > `a`"),
+        ["this compares a value with itself, so it is always false"; 3]
+    );
+
+    assert!(warning_messages("x = 3, y = 4, x == y, x + x, 1 == 2, x > 1 && y > 1").is_empty());
+}
+
+/// Tests that local variables which shadow other variables report warnings.
+#[test]
+fn shadowing_locals_report_warnings() {
+    assert_eq!(
+        warning_messages("f(x) = { x = x * 2; x }"),
+        ["variable 'x' shadows a parameter"]
+    );
+    assert_eq!(
+        warning_messages("f(a) = { y = 1; { y = 2; y } }"),
+        ["variable 'y' shadows an outer local variable"]
+    );
+    assert_eq!(
+        warning_messages("z = 1, f(a) = { z = a; z }"),
+        ["variable 'z' shadows a global variable"]
+    );
+    assert_eq!(
+        warning_messages("f(a) = { sqrt = a; sqrt }"),
+        ["variable 'sqrt' shadows a native function"]
+    );
+    assert!(warning_messages("f(a) = { b = a; b }, g(a) = { b = a; b }").is_empty());
+}
+
+/// Tests that function parameters which shadow native functions or global
+/// variables report warnings.
+#[test]
+fn shadowing_params_report_warnings() {
+    let mut engine = Engine::new();
+    let evaluation = engine.eval("f(sqrt) = sqrt, x = 1, g(x) = x, h((x, y)) = y, k(z) = z");
+    assert!(evaluation.is_ok());
+
+    let warnings: Vec<String> = evaluation
+        .warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            "variable 'sqrt' shadows a native function",
+            "variable 'x' shadows a global variable",
+            "variable 'x' shadows a global variable",
+        ]
+    );
+}
+
+/// Tests that each [`Engine`] has its own settings.
+#[test]
+fn engine_settings_are_per_engine() {
+    let mut configured = Engine::new();
+    configured.set_mode("u8").expect("mode should exist");
+    configured
+        .set_decimals(Some(2))
+        .expect("decimals should be valid");
+    configured.set_max_instructions(Some(20));
+    let default = Engine::new();
+
+    let wrapped = configured.eval("255 + 1").into_result();
+    assert_eq!(wrapped.expect("code should run"), [Value::Number(0.0)]);
+
+    let third = Value::Number(1.0 / 3.0);
+    assert_eq!(configured.format(&third), "0.33");
+    assert_eq!(default.format(&third), "0.3333333333333333");
+
+    let long_source = vec!["1"; 20].join(" + ");
+    let errors = configured.eval(&long_source).errors;
+    assert_eq!(errors.first().map(EvalError::stage), Some("limit"));
+
+    let error = configured
+        .set_rounding("nearest")
+        .expect_err("mode should not exist");
+    assert_eq!(error.to_string(), "invalid rounding mode 'nearest'");
+}
+
+/// Tests that global variables saved by the REPL's `:save` command are restored
+/// with the same values when the file is run again.
+#[test]
+fn saved_globals_can_be_loaded() {
+    let run = |source: &str, globals: &mut Globals| {
+        try_run_source(
+            source,
+            Origin::Repl,
+            globals,
+            Mode::Execute,
+            GlobalMode::Redefinable,
+            None,
+        )
+        .expect("source code should run");
+    };
+
+    let mut globals = new_globals(Settings::new());
+    run(
+        "x = 1 / 3, f(n) = n * x, x = x * 2, (p, q) = (10 ^ -9, 10 ^ 300), b = x > 0",
+        &mut globals,
+    );
+
+    let saved = repl::saved_globals(&globals);
+    let mut loaded = new_globals(Settings::new());
+    run(&saved, &mut loaded);
+
+    let mut collected = Collected::default();
+    try_run_source(
+        "f(3) == 2, x == 2 / 3, p == 10 ^ -9, q == 10 ^ 300, b",
+        Origin::Repl,
+        &mut loaded,
+        Mode::Execute,
+        GlobalMode::Redefinable,
+        Some(&mut collected),
+    )
+    .expect("loaded globals should be defined");
+
+    assert_eq!(collected.results.len(), 5);
+    assert!(
+        collected
+            .results
+            .iter()
+            .all(|r| matches!(r, interpret::Value::Bool(true))),
+        "loaded globals should have their saved values:\n{saved}"
+    );
+}
+
+/// Runs source code with [`Globals`] as REPL input and returns its results
+/// displayed with the [`Globals`]' [`Settings`], or its error messages.
+fn run_results(source: &str, globals: &mut Globals) -> Result<Vec<String>, Vec<String>> {
+    let mut collected = Collected::default();
+
+    try_run_source(
+        source,
+        Origin::Repl,
+        globals,
+        Mode::Execute,
+        GlobalMode::Redefinable,
+        Some(&mut collected),
+    )
+    .map_err(|errors| errors.iter().map(ClacError::message).collect::<Vec<_>>())?;
+
+    let settings = globals.settings();
+    Ok(collected
+        .results
+        .iter()
+        .map(|r| r.display(settings).to_string())
+        .collect())
+}
+
+/// Tests that SI prefix suffixes are only recognized in sessions whose
+/// [`Settings`] enable them.
+#[test]
+fn si_suffixes_are_set_per_session() {
+    let mut settings = Settings::new();
+    settings.si_suffixes = true;
+    let mut with_suffixes = new_globals(settings);
+    let mut without_suffixes = new_globals(Settings::new());
+
+    let source = "k = 1000, 4.7k";
+    let suffixed = run_results(source, &mut with_suffixes);
+    assert_eq!(suffixed.expect("source should run"), ["4700"]);
+
+    let unsuffixed = run_results(source, &mut without_suffixes);
+    assert_eq!(unsuffixed.expect("source should run"), ["4.7", "1000"]);
+}
+
+/// Tests that native functions can only be redefined in sessions whose
+/// [`Settings`] allow it.
+#[test]
+fn native_overrides_are_set_per_session() {
+    let mut settings = Settings::new();
+    settings.native_overrides = true;
+    let mut overriding = new_globals(settings);
+    let mut protected = new_globals(Settings::new());
+
+    let source = "sqrt(x) = x, sqrt(4)";
+    let overridden = run_results(source, &mut overriding);
+    assert_eq!(overridden.expect("source should run"), ["4"]);
+
+    let errors = run_results(source, &mut protected).expect_err("source should not run");
+    assert_eq!(
+        errors,
+        ["native function 'sqrt' cannot be redefined without '--allow-native-overrides'"]
+    );
+}
+
+/// Tests that reactive mode is only enabled in sessions whose [`Settings`]
+/// enable it.
+#[test]
+fn reactive_mode_is_set_per_session() {
+    let mut settings = Settings::new();
+    settings.reactive = true;
+    let mut reactive = new_globals(settings);
+    let mut other = new_globals(Settings::new());
+
+    for globals in [&mut reactive, &mut other] {
+        run_results("a = 1, b = a * 2", globals).expect("source should run");
+        globals.output().start_capture();
+        run_results("a = 5", globals).expect("source should run");
+    }
+
+    assert_eq!(reactive.output().take_capture().output(), "b = 10\n");
+    assert_eq!(other.output().take_capture().output(), "");
+    assert_eq!(
+        run_results("b", &mut other).expect("source should run"),
+        ["2"]
+    );
+}
+
+/// Runs source code in a new reactive session after setup source code, and
+/// returns the source code's captured output and its [`Warning`]s.
+fn run_reactive(setup: &str, source: &str) -> (String, Vec<Warning>) {
+    let mut settings = Settings::new();
+    settings.reactive = true;
+    let mut globals = new_globals(settings);
+    run_results(setup, &mut globals).expect("setup should run");
+
+    globals.output().start_capture();
+    let mut collected = Collected::default();
+
+    try_run_source(
+        source,
+        Origin::Repl,
+        &mut globals,
+        Mode::Execute,
+        GlobalMode::Redefinable,
+        Some(&mut collected),
+    )
+    .expect("source should run");
+
+    (globals.output().take_capture().output(), collected.warnings)
+}
+
+/// Tests that global variables assigned by unpacking a tuple are recalculated
+/// together in reactive mode.
+#[test]
+fn reactive_mode_recalculates_unpacked_globals() {
+    let (output, warnings) = run_reactive("a = 1, (p, q) = (a, a + 1)", "a = 5");
+    assert_eq!(output, "q = 6\np = 5\n");
+    assert!(warnings.is_empty());
+}
+
+/// Tests that reassigning global variables in reactive mode does not report
+/// redefinition warnings.
+#[test]
+fn reactive_mode_does_not_warn_on_reassignment() {
+    let (output, warnings) = run_reactive("a = 1, b = a * 2", "a = 2, a = 3");
+    assert_eq!(output, "b = 6\n");
+    assert!(warnings.is_empty());
+}
+
+/// Tests that functions are not recalculated in reactive mode because they
+/// read global variables when they are called.
+#[test]
+fn reactive_mode_does_not_recalculate_functions() {
+    let (output, _) = run_reactive("a = 1, f(x) = x + a, b = f(1)", "a = 5");
+    assert_eq!(output, "b = 6\n");
+}
+
+/// Tests that long chains of operations are run without being rejected as
+/// deeply nested or overflowing the stack, and that longer chains are rejected
+/// with an error.
+#[test]
+fn long_operation_chains_are_run() {
+    for term_count in [600_usize, 2001] {
+        let source = vec!["1"; term_count].join(" + ");
+        let mut globals = new_globals(Settings::new());
+        let results = run_results(&source, &mut globals).expect("source should run");
+        assert_eq!(results, [term_count.to_string()]);
+    }
+
+    for source in [
+        vec!["1"; 2002].join(" + "),
+        format!("f(x) = f, f{}", "(1)".repeat(2001)),
+    ] {
+        let mut globals = new_globals(Settings::new());
+        let errors = run_results(&source, &mut globals).expect_err("source should be too long");
+        assert_eq!(
+            errors,
+            ["expression chains more than 2000 operations, calls, or indices"]
+        );
+    }
+}
+
+/// Runs source code in a new session with [`Limits`] and returns its displayed
+/// results.
+fn run_limited_results(source: &str, limits: Limits) -> Result<Vec<String>, Vec<String>> {
+    let mut settings = Settings::new();
+    settings.limits = limits;
+    run_results(source, &mut new_globals(settings))
+}
+
+/// Tests that the nesting depth limit counts brackets instead of operations.
+#[test]
+fn depth_limit_counts_brackets() {
+    let limits = Limits {
+        depth: Some(10),
+        ..Limits::default()
+    };
+
+    for source in [
+        "((((1))))",
+        &["1"; 11].join(" + "),
+        "f(x) = x, f(f(f(f(1))))",
+        "[[[1]]][0][0][0]",
+        "{ { { 1 } } }",
+    ] {
+        run_limited_results(source, limits).expect("source should not be too deep");
+    }
+
+    let deep = format!("{}1{}", "(".repeat(11), ")".repeat(11));
+    let errors = run_limited_results(&deep, limits).expect_err("source should be too deep");
+    assert_eq!(errors, ["code is nested more than 10 levels deep"]);
+
+    run_results(&deep, &mut new_globals(Settings::new())).expect("source should be unlimited");
+}
+
+/// Tests that the limits on tokens, instructions, recursion, and length are
+/// set per session.
+#[test]
+fn limits_are_set_per_session() {
+    let token_limits = Limits {
+        tokens: Some(3),
+        ..Limits::default()
+    };
+
+    run_limited_results("1 + 2", token_limits).expect("source should have few tokens");
+    run_limited_results("1 + 2 + 3", token_limits).expect_err("source should have many tokens");
+
+    let instruction_limits = Limits {
+        instructions: Some(5),
+        ..Limits::default()
+    };
+
+    run_limited_results("1", instruction_limits).expect("source should be short");
+    run_limited_results(&["1"; 10].join(", "), instruction_limits)
+        .expect_err("source should be long");
+
+    let recursion_limits = Limits {
+        recursion: Some(10),
+        ..Limits::default()
+    };
+
+    let source = "f(n) = n == 0 ? 0 : 1 + f(n - 1), f(50)";
+    run_limited_results(source, recursion_limits).expect_err("recursion should be too deep");
+    let unlimited = run_results(source, &mut new_globals(Settings::new()));
+    assert_eq!(unlimited.expect("recursion should be shallow"), ["50"]);
+
+    let length_limits = Limits {
+        length: Some(4),
+        ..Limits::default()
+    };
+
+    run_limited_results("[1, 2] + [3, 4], `ab` + `cd`", length_limits)
+        .expect("values should be short");
+    let long_list = run_limited_results("[1, 2, 3] + [4, 5]", length_limits);
+    let long_string = run_limited_results("`abc` + `de`", length_limits);
+
+    for errors in [long_list, long_string] {
+        assert_eq!(
+            errors.expect_err("value should be long"),
+            ["list or string is longer than the maximum length of 4"]
+        );
+    }
+}
+
+/// Tests that custom operators are defined per session and are kept between
+/// runs of source code in the same session.
+#[test]
+fn custom_operators_are_defined_per_session() {
+    let mut defining = new_globals(Settings::new());
+    let mut other = new_globals(Settings::new());
+
+    run_results("operator ⊕ (a, b) = a * 10 + b", &mut defining).expect("source should run");
+    let defined = run_results("1 ⊕ 2 ⊕ 3", &mut defining).expect("source should run");
+    assert_eq!(defined, ["123"]);
+
+    let undefined = run_results("1 ⊕ 2", &mut other).expect_err("source should not run");
+    assert_eq!(undefined.len(), 1);
+}
+
+/// Tests that output is captured per session.
+#[test]
+fn output_is_captured_per_session() {
+    let mut capturing = new_globals(Settings::new());
+    let mut other = new_globals(Settings::new());
+    capturing.output().start_capture();
+    other.output().start_capture();
+
+    run_results("println(1)", &mut capturing).expect("source should run");
+    run_results("println(2), println(3)", &mut other).expect("source should run");
+
+    assert_eq!(capturing.output().take_capture().output(), "1\n");
+    assert_eq!(other.output().take_capture().output(), "2\n3\n");
+}
+
+/// Returns the output of running source code in a new session with a
+/// [`ResultFormat`].
+fn run_formatted(source: &str, result_format: ResultFormat) -> String {
+    let mut globals = new_globals(Settings {
+        result_format,
+        ..Settings::new()
+    });
+
+    globals.output().start_capture();
+    assert!(run_source(
+        source,
+        Origin::Args,
+        &mut globals,
+        Mode::Execute,
+        GlobalMode::Sealed
+    ));
+
+    globals.output().take_capture().output()
+}
+
+/// Tests that tabular results are printed as rows in the CSV and TSV
+/// [`ResultFormat`]s.
+#[test]
+fn tabular_results_are_printed_as_rows() {
+    let source = "[(1, `a, b`), (2, `say \"hi\"`)], [[1, 2], [3, 4]], [1, 2]";
+
+    assert_eq!(
+        run_formatted(source, ResultFormat::Csv),
+        "1,\"a, b\"\n2,\"say \"\"hi\"\"\"\n1,2\n3,4\n[1, 2]\n"
+    );
+
+    assert_eq!(
+        run_formatted(source, ResultFormat::Tsv),
+        "1\ta, b\n2\t\"say \"\"hi\"\"\"\n1\t2\n3\t4\n[1, 2]\n"
+    );
+
+    assert_eq!(
+        run_formatted(source, ResultFormat::Pretty),
+        "[(1, `a, b`), (2, `say \"hi\"`)]\n[[1, 2],\n [3, 4]]\n[1, 2]\n"
+    );
+}
+
+/// Tests that histograms count values in bins of equal width.
+#[test]
+fn histograms_count_values_in_bins() {
+    let mut globals = new_globals(Settings::new());
+    globals.output().start_capture();
+
+    let results = run_results(
+        "hist([1, 2, 2, 3, 3, 3, 4, 4, 5, 10], 3), hist([7, 7], 2)",
+        &mut globals,
+    );
+
+    assert_eq!(
+        results.as_deref(),
+        Ok(["[6, 3, 1]", "[0, 2]"].map(String::from).as_slice())
+    );
+
+    let errors = run_results("hist([1], 0), hist([1], 1.5), hist([`a`], 1)", &mut globals);
+    assert_eq!(errors.map_err(|e| e.len()), Err(1));
+
+    let empty_errors = run_results("hist([], 3)", &mut globals);
+    let infinite_errors = run_results("hist([10 ^ 400], 3)", &mut globals);
+    assert_eq!(empty_errors, infinite_errors);
+    assert_eq!(
+        empty_errors,
+        Err(vec![
+            "histograms need at least one finite number".to_owned()
+        ])
+    );
+
+    let lines = plot::render_histogram(
+        &[1.0_f64, 4.0_f64, 7.0_f64, 10.0_f64],
+        &[6, 3, 0],
+        30,
+        &Settings::new(),
+    );
+    assert_eq!(
+        lines,
+        [
+            "1 –  4 │████████████████████ 6",
+            "4 –  7 │██████████ 3",
+            "7 – 10 │ 0",
+        ]
+    );
+}
+
+/// Asserts that source code run in a new session with an [`IntegerMode`]
+/// displays its expected results.
+fn assert_integer_results<const N: usize>(source: &str, mode: &str, expected: [&str; N]) {
+    let mut settings = Settings::new();
+    settings.integer_mode = Some(IntegerMode::from_name(mode).expect("mode should exist"));
+    let results = run_results(source, &mut new_globals(settings));
+    assert_eq!(
+        results.expect("source should run"),
+        expected,
+        "{source} in {mode}"
+    );
+}
+
+/// Tests that integer modes use exact wrapping integer arithmetic.
+#[test]
+fn integer_modes_use_wrapping_integer_arithmetic() {
+    let max = format!("18446744073709551615 (0x{:X}, 0b{:b})", u64::MAX, u64::MAX);
+    assert_integer_results("2^64 - 1", "u64", [max.as_str()]);
+
+    let pattern = 1_u64 << 63_u32;
+    let min = format!("-9223372036854775808 (0x{pattern:X}, 0b{pattern:b})");
+    assert_integer_results("-(2^63), 2^63", "i64", [min.as_str(), min.as_str()]);
+
+    assert_integer_results(
+        "200 + 100, 2 - 3, 16 * 16",
+        "u8",
+        [
+            "44 (0x2C, 0b101100)",
+            "255 (0xFF, 0b11111111)",
+            "0 (0x0, 0b0)",
+        ],
+    );
+
+    assert_integer_results(
+        "127 + 1, -128 - 1",
+        "i8",
+        ["-128 (0x80, 0b10000000)", "127 (0x7F, 0b1111111)"],
+    );
+}
+
+/// Tests that integer literals are exact at the boundaries of 64-bit integer
+/// modes.
+#[test]
+fn integer_literals_are_exact_in_integer_modes() {
+    let signed_max = format!("9223372036854775807 (0x{:X}, 0b{:b})", i64::MAX, i64::MAX);
+    let pattern = 1_u64 << 63_u32;
+    let signed_min = format!("-9223372036854775808 (0x{pattern:X}, 0b{pattern:b})");
+    assert_integer_results(
+        "9223372036854775807, 9223372036854775807 + 1, -9223372036854775808",
+        "i64",
+        [
+            signed_max.as_str(),
+            signed_min.as_str(),
+            signed_min.as_str(),
+        ],
+    );
+
+    let unsigned_max = format!("18446744073709551615 (0x{:X}, 0b{:b})", u64::MAX, u64::MAX);
+    let odd = format!("9007199254740993 (0x{0:X}, 0b{0:b})", (1_u64 << 53_u32) + 1);
+    assert_integer_results(
+        "18446744073709551615, 18446744073709551615 + 1, 9007199254740993",
+        "u64",
+        [unsigned_max.as_str(), "0 (0x0, 0b0)", odd.as_str()],
+    );
+
+    let results = run_results("9007199254740993", &mut new_globals(Settings::new()));
+    assert_eq!(results.expect("source should run"), ["9007199254740992"]);
+}
+
+/// Tests that non-integer results are truncated toward zero in integer modes.
+#[test]
+fn integer_modes_truncate_non_integer_results() {
+    assert_integer_results(
+        "sqrt(2), 7 / 2, 2^-1, 2.9, [sqrt(3)]",
+        "u32",
+        [
+            "1 (0x1, 0b1)",
+            "3 (0x3, 0b11)",
+            "0 (0x0, 0b0)",
+            "2 (0x2, 0b10)",
+            "[1 (0x1, 0b1)]",
+        ],
+    );
+
+    assert_integer_results("-7 / 2", "i8", ["-3 (0xFD, 0b11111101)"]);
+
+    let mut settings = Settings::new();
+    settings.integer_mode = IntegerMode::from_name("i32");
+    let errors = run_results("1 / 0", &mut new_globals(settings));
+    assert_eq!(
+        errors.expect_err("source should not run"),
+        ["cannot divide by zero"]
+    );
+}
+
+/// Tests that fused arithmetic on local variables and numbers wraps in integer
+/// modes and rejects values other than numbers.
+#[test]
+fn fused_local_arithmetic_is_checked() {
+    assert_integer_results(
+        "f(x) = x + 1, g(x) = x - 1, f(255), g(0), f(1)",
+        "u8",
+        ["0 (0x0, 0b0)", "255 (0xFF, 0b11111111)", "2 (0x2, 0b10)"],
+    );
+
+    let mut globals = new_globals(Settings::new());
+    let results = run_results("f(x) = x + 0.5, g(x) = x - 0.5, f(1), g(1)", &mut globals);
+    assert_eq!(results.expect("source should run"), ["1.5", "0.5"]);
+
+    for source in ["f(days(1))", "g(`a`)"] {
+        let errors = run_results(source, &mut globals);
+        assert_eq!(errors.expect_err("source should not run"), ["type error"]);
+    }
+}
+
+/// Tests that integer modes are set per session.
+#[test]
+fn integer_modes_are_set_per_session() {
+    let mut integers = new_globals(Settings::new());
+    integers.settings_mut().integer_mode = IntegerMode::from_name("u8");
+    let mut floats = new_globals(Settings::new());
+
+    let wrapped = run_results("255 + 1.5", &mut integers).expect("source should run");
+    assert_eq!(wrapped, ["0 (0x0, 0b0)"]);
+
+    let unwrapped = run_results("255 + 1.5", &mut floats).expect("source should run");
+    assert_eq!(unwrapped, ["256.5"]);
+}
+
+/// Parses [`Options`] from command line arguments.
+fn parse_args(arguments: &[&str]) -> Result<Options, args::ArgsError> {
+    args::parse_args(arguments.iter().map(|&a| a.to_owned()))
+}
+
+/// Tests that the `--check` option checks code without running it, and cannot
+/// be used with the REPL.
+#[test]
+fn check_option_sets_check_mode() {
+    let options = parse_args(&["--check", "1 +"]).expect("arguments should be valid");
+    assert!(options.mode == Mode::Check);
+    assert_eq!(options.code, ["1 +"]);
+
+    assert!(matches!(
+        parse_args(&["repl", "--check"]),
+        Err(args::ArgsError::Conflict("repl", "--check"))
+    ));
+}
+
+/// Tests that the dump modes print source code as an AST, HIR, or CFG without
+/// running it.
+#[test]
+fn dump_modes_print_stages() {
+    let source = "x = 2, f(y) = { z = y + x, z * 2 }, f(x)";
+    let mut globals = new_globals(Settings::new());
+    globals.output().start_capture();
+
+    for mode in [Mode::DumpAst, Mode::DumpHir, Mode::DumpCfg] {
+        run_source(source, Origin::Args, &mut globals, mode, GlobalMode::Sealed);
+    }
+
+    let capture = globals.output().take_capture();
+    let output = capture.output();
+    let mut dumps = output.splitn(3, '\n');
+
+    assert_eq!(
+        dumps.next(),
+        Some("(a: (= x 2) (= (f (p: y)) (b: (= z (+ y x)) (* z 2))) (f (p: x)))")
+    );
+    assert_eq!(
+        dumps.next(),
+        Some(
+            "(h: (global x 2) (global f (fn f#0 (y#1) (b: (local z#2 (+ y#1 x)) (* z#2 2)))) \
+             (print (call f x)))"
+        )
+    );
+
+    let cfg = dumps.next().expect("CFG should be dumped");
+    assert!(cfg.starts_with("main:\n"));
+    assert!(cfg.contains("push_function   f(y):"));
+    assert!(cfg.ends_with("print\n        halt\n"));
+    assert_eq!(capture.errors(), "");
+    assert!(!globals.contains(Symbol::intern("x")));
+}
+
+/// Tests that watched script files are run with new global variables unless
+/// they should be kept.
+#[test]
+fn watched_files_reset_globals() {
+    let path = env::temp_dir().join(format!("clac-watch-test-{}.clac", process::id()));
+    let mut globals = new_globals(Settings::new());
+    globals.output().start_capture();
+
+    fs::write(&path, "x = 1, x").expect("script file should be written");
+    run_watched_file(&path, &mut globals, Mode::Execute, false);
+    fs::write(&path, "y = x + 1, y").expect("script file should be written");
+    run_watched_file(&path, &mut globals, Mode::Execute, true);
+    run_watched_file(&path, &mut globals, Mode::Execute, false);
+    fs::remove_file(&path).expect("script file should be removed");
+
+    let capture = globals.output().take_capture();
+    assert_eq!(capture.output(), "1\n2\n");
+    assert!(capture.errors().contains("variable 'x' is undefined"));
+}
+
+/// Tests that watched script files can redefine kept global variables.
+#[test]
+fn watched_files_redefine_kept_globals() {
+    let path = env::temp_dir().join(format!("clac-watch-keep-test-{}.clac", process::id()));
+    let mut globals = new_globals(Settings::new());
+    globals.output().start_capture();
+
+    fs::write(&path, "x = 1, x").expect("script file should be written");
+    run_watched_file(&path, &mut globals, Mode::Execute, true);
+    fs::write(&path, "x = 2, x").expect("script file should be written");
+    run_watched_file(&path, &mut globals, Mode::Execute, true);
+    fs::remove_file(&path).expect("script file should be removed");
+
+    let capture = globals.output().take_capture();
+    assert_eq!(capture.output(), "1\n2\n");
+    assert!(!capture.errors().contains("already defined"));
+}
+
+/// Tests that the `--watch` option cannot be used with code, script files, or
+/// preloaded files.
+#[test]
+fn watch_option_is_exclusive() {
+    let options = parse_args(&["--watch", "a.clac", "--keep-globals"]);
+    let options = options.expect("arguments should be valid");
+    assert_eq!(options.watch.as_deref(), Some(Path::new("a.clac")));
+    assert!(options.keep_globals);
+
+    assert!(matches!(
+        parse_args(&["--watch", "a.clac", "1 + 2"]),
+        Err(args::ArgsError::UnexpectedCode("--watch"))
+    ));
+    assert!(matches!(
+        parse_args(&["--watch", "a.clac", "--file", "b.clac"]),
+        Err(args::ArgsError::Conflict("--file", "--watch"))
+    ));
+    assert!(matches!(
+        parse_args(&["--watch", "a.clac", "--preload", "b.clac"]),
+        Err(args::ArgsError::Conflict("--preload", "--watch"))
+    ));
+}
+
+/// Tests that script files are given with the `--file` option or as code
+/// arguments which all end in `.clac`, and cannot be mixed with code.
+#[test]
+fn script_files_are_given_as_arguments() {
+    let flagged = parse_args(&["-f", "a.clac", "--file", "b.txt"]);
+    let flagged = flagged.expect("arguments should be valid");
+    assert_eq!(flagged.files, [Path::new("a.clac"), Path::new("b.txt")]);
+
+    let named = parse_args(&["a.clac", "B.CLAC"]).expect("arguments should be valid");
+    assert_eq!(named.files, [Path::new("a.clac"), Path::new("B.CLAC")]);
+    assert!(named.code.is_empty());
+
+    let code = parse_args(&["a.clac", "+", "1"]).expect("arguments should be valid");
+    assert!(code.files.is_empty());
+    assert_eq!(code.code, ["a.clac", "+", "1"]);
+
+    assert!(matches!(
+        parse_args(&["--file", "a.clac", "1 + 2"]),
+        Err(args::ArgsError::UnexpectedCode("--file"))
+    ));
+}
+
+/// Tests that errors in script files show the file name, and the line and
+/// column where they happened.
+#[test]
+fn script_file_errors_show_locations() {
+    let mut globals = new_globals(Settings::new());
+    globals.output().start_capture();
+    run_source(
+        "x = 1\ny = 0, z = \t x / y",
+        Origin::File(Path::new("script.clac")),
+        &mut globals,
+        Mode::Execute,
+        GlobalMode::Sealed,
+    );
+
+    assert_eq!(
+        globals.output().take_capture().errors(),
+        "Error: cannot divide by zero\n  at script.clac:2:14\n  y = 0, z = \t x / y\n             \t ^^^^^\n"
+    );
+}
+
+/// Tests that script files run on an [`EnginePool`] after the preloaded files
+/// with separate global variables, and their output is printed in order.
+#[test]
+fn script_files_run_in_separate_engines() {
+    let directory = env::temp_dir().join(format!("clac-pool-test-{}", process::id()));
+    fs::create_dir_all(&directory).expect("directory should be created");
+
+    let files = [
+        ("preload.clac", "offset = 10"),
+        ("a.clac", "x = offset + 1, x"),
+        ("b.clac", "x"),
+        ("c.clac", "offset * 2"),
+    ];
+
+    let paths: Vec<_> = files
+        .iter()
+        .map(|(name, source)| {
+            let path = directory.join(name);
+            fs::write(&path, source).expect("script file should be written");
+            path
+        })
+        .collect();
+
+    let output = Output::default();
+    output.start_capture();
+    let two = NonZeroUsize::new(2);
+    let exit_code = run_files(
+        &paths[1..],
+        &paths[..1],
+        Mode::Execute,
+        two,
+        &output,
+        Settings::new(),
+    );
+    fs::remove_dir_all(&directory).expect("directory should be removed");
+
+    let capture = output.take_capture();
+    assert_eq!(capture.output(), "11\n20\n");
+    assert!(capture.errors().contains("variable 'x' is undefined"));
+    assert_eq!(exit_code, ExitCode::FAILURE);
+}
+
+/// Tests that code quoted by `pmap` and `solve` is rejected if it uses
+/// variables which it cannot access when it is run.
+#[test]
+fn quoted_code_uses_accessible_variables() {
+    let rejected = [
+        (
+            "f(x) = x * 2, pmap(f, [1, 2])",
+            "functions passed to 'pmap' run on new threads and cannot use global variable 'f'",
+        ),
+        (
+            "k = 3, pmap(x -> x * k, [1, 2])",
+            "functions passed to 'pmap' run on new threads and cannot use global variable 'k'",
+        ),
+        (
+            "g(k) = pmap(x -> x * k, [1, 2])",
+            "code quoted by 'pmap' cannot use local variable 'k'",
+        ),
+        (
+            "f(a) = solve(x ^ 2 - a == 0, x)",
+            "code quoted by 'solve' cannot use local variable 'a'",
+        ),
+    ];
+
+    for (source, error) in rejected {
+        let errors = run_results(source, &mut new_globals(Settings::new()));
+        assert_eq!(
+            errors.expect_err("source should not run"),
+            [error],
+            "{source}"
+        );
+    }
+
+    let accepted = [
+        ("h(x) = pmap(x -> sqrt(x) + 1, [x]), h(4)", "[3]"),
+        ("pmap(x -> let y = x * 2 in y + 1, [1, 2])", "[3, 5]"),
+        ("a = 4, solve(x ^ 2 - a == 0, x)", "{-2, 2}"),
+        ("f(x) = solve(x ^ 2 == 4, x), f(1)", "{-2, 2}"),
+    ];
+
+    for (source, result) in accepted {
+        let results = run_results(source, &mut new_globals(Settings::new()));
+        assert_eq!(results.expect("source should run"), [result], "{source}");
+    }
+}
+
+/// Runs source code in a new session with [`Settings`] from command line
+/// arguments and returns its displayed results.
+fn run_with_args(arguments: &[&str], source: &str) -> Vec<String> {
+    let options = parse_args(arguments).expect("arguments should be valid");
+    let mut globals = new_globals(session_settings(&options));
+    run_results(source, &mut globals).expect("source should run")
+}
+
+/// Tests that numbers are displayed with decimal commas and grouped digits.
+#[test]
+fn numbers_are_displayed_with_separators() {
+    let source = "1234567.5, -1234, 123, [0.25, 1000]";
+    assert_eq!(
+        run_with_args(&["--group-digits"], source),
+        ["1,234,567.5", "-1,234", "123", "[0.25, 1,000]"]
+    );
+    assert_eq!(
+        run_with_args(&["--decimal-comma"], source),
+        ["1234567,5", "-1234", "123", "[0,25; 1000]"]
+    );
+    assert_eq!(
+        run_with_args(&["--decimal-comma", "--group-digits"], source),
+        ["1.234.567,5", "-1.234", "123", "[0,25; 1.000]"]
+    );
+    assert_eq!(
+        run_args_output(&["--decimal-comma"], "(1.5, [2, 0.5]), [[1.5, 2], [3, 4]]"),
+        "(1,5; [2; 0,5])\n[[1,5; 2];\n [  3; 4]]\n"
+    );
+    assert_eq!(
+        run_with_args(&["--group-digits", "--mode", "u32"], "1000000"),
+        ["1,000,000 (0xF4240, 0b11110100001001000000)"]
+    );
+}
+
+/// Tests that numbers are displayed in the notation chosen by the
+/// `--notation` option.
+#[test]
+fn numbers_are_displayed_in_notations() {
+    let source = "12300, 0.0047, -1, 1 / 3, [0.125], -0.0000047";
+    assert_eq!(
+        run_with_args(&["--notation", "engineering"], source),
+        [
+            "12.3e3",
+            "4.7e-3",
+            "-1",
+            "333.3333333333333e-3",
+            "[125e-3]",
+            "-4.7e-6"
+        ]
+    );
+    assert_eq!(
+        run_with_args(&["--notation", "si"], source),
+        [
+            "12.3k",
+            "4.7m",
+            "-1",
+            "333.3333333333333m",
+            "[125m]",
+            "-4.7µ"
+        ]
+    );
+    assert_eq!(
+        run_with_args(&["--notation", "fraction"], source),
+        ["12300", "4/851 (approx)", "-1", "1/3", "[1/8]", "-4.7e-6"]
+    );
+    assert!(matches!(
+        parse_args(&["--notation", "roman"]),
+        Err(args::ArgsError::InvalidValue("--notation", value)) if value == "roman"
+    ));
+}
+
+/// Tests that `round` and fixed decimal places use the rounding mode chosen by
+/// the `--rounding` option.
+#[test]
+fn numbers_are_rounded_with_rounding_modes() {
+    let source = "round(2.5), round(3.5), round(-2.5), round(2.675, 2)";
+    assert_eq!(run_with_args(&[], source), ["3", "4", "-3", "2.68"]);
+    assert_eq!(
+        run_with_args(&["--rounding", "half-even"], source),
+        ["2", "4", "-2", "2.68"]
+    );
+    assert_eq!(
+        run_with_args(&["--rounding", "toward-zero"], source),
+        ["2", "3", "-2", "2.67"]
+    );
+    assert_eq!(
+        run_with_args(
+            &["--rounding", "half-even", "--decimals", "1"],
+            "0.25, 0.35, 9.96"
+        ),
+        ["0.2", "0.4", "10.0"]
+    );
+}
+
+/// Runs source code as a code argument in a new session with [`Settings`] from
+/// command line arguments and returns its captured output.
+fn run_args_output(arguments: &[&str], source: &str) -> String {
+    let options = parse_args(arguments).expect("arguments should be valid");
+    let mut globals = new_globals(session_settings(&options));
+    globals.output().start_capture();
+    run_source(
+        source,
+        Origin::Args,
+        &mut globals,
+        Mode::Execute,
+        GlobalMode::Sealed,
+    );
+    globals.output().take_capture().output()
+}
+
+/// Tests that the `--echo` option controls whether top-level results are
+/// printed, and never hides explicitly printed values.
+#[test]
+fn echo_option_hides_results() {
+    let source = "1 + 2, println(`hello`), 4";
+    assert_eq!(run_args_output(&[], source), "3\nhello\nhello\n4\n");
+    assert_eq!(
+        run_args_output(&["--echo", "on"], source),
+        "3\nhello\nhello\n4\n"
+    );
+    assert_eq!(run_args_output(&["--echo", "off"], source), "hello\n");
+
+    assert!(matches!(
+        parse_args(&["--echo", "loud"]),
+        Err(args::ArgsError::InvalidValue("--echo", value)) if value == "loud"
+    ));
+}
+
+/// Tests that output is written to a file by the `--output` option.
+#[test]
+fn output_option_redirects_output() {
+    let path = env::temp_dir().join(format!("clac-output-test-{}.txt", process::id()));
+    let path_arg = path.to_str().expect("temporary path should be valid UTF-8");
+    let options = parse_args(&["--output", path_arg, "1 + 2, println(`hi`)"]);
+    let options = options.expect("arguments should be valid");
+    assert_eq!(options.output.as_deref(), Some(path.as_path()));
+
+    let mut globals = new_globals(session_settings(&options));
+    globals
+        .output()
+        .redirect(&path)
+        .expect("output file should be created");
+    run_source(
+        &options.code.join(" "),
+        Origin::Args,
+        &mut globals,
+        Mode::Execute,
+        GlobalMode::Sealed,
+    );
+    drop(globals);
+
+    let output = fs::read_to_string(&path).expect("output file should be read");
+    fs::remove_file(&path).expect("output file should be removed");
+    assert_eq!(output, "3\nhi\nhi\n");
+}
+
+/// Tests that plots draw points, a zero line, and axis labels, and skip
+/// points which are not finite.
+#[test]
+fn plots_draw_finite_points() {
+    let ys = [2.0_f64, 0.5_f64, -1.0_f64, f64::NAN, 0.5_f64, 2.0_f64];
+    assert_eq!(
+        plot::render(&ys, -1.0_f64, 1.0_f64, 4, &Settings::new()),
+        [
+            " 2 ┤•    •",
+            "   │      ",
+            "   │─•──•─",
+            "-1 ┤  •   ",
+            "   └──────",
+            "   -1    1",
+        ]
+    );
+
+    assert_eq!(
+        plot::render(&[1.0_f64; 3], 0.0_f64, 2.0_f64, 3, &Settings::new()),
+        ["1 ┤   ", "  │•••", "1 ┤   ", "  └───", "  0  2"]
+    );
+
+    let mut globals = new_globals(Settings::new());
+    globals.output().start_capture();
+    let errors = run_results("plot(x -> x, 1, 0)", &mut globals);
+    assert_eq!(
+        errors.expect_err("range should be invalid"),
+        ["plot range must be finite with a minimum less than its maximum"]
+    );
+}
+
+/// Tests that tables print a function's values over a range in steps, and
+/// that invalid steps are rejected.
+#[test]
+fn tables_print_function_values() {
+    let mut globals = new_globals(Settings::new());
+    globals.output().start_capture();
+
+    run_results(
+        "table(x -> x ^ 3, 0, 2, 0.5), table(x -> 2 * x, 0, 1, 0.3)",
+        &mut globals,
+    )
+    .expect("source should run");
+
+    let output = globals.output().take_capture().output();
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        [
+            "  x │  f(x)",
+            "────┼──────",
+            "  0 │     0",
+            "0.5 │ 0.125",
+            "  1 │     1",
+            "1.5 │ 3.375",
+            "  2 │     8",
+            "  x │ f(x)",
+            "────┼─────",
+            "  0 │    0",
+            "0.3 │  0.6",
+            "0.6 │  1.2",
+            "0.9 │  1.8",
+        ]
+    );
+
+    assert_eq!(
+        plot::render_table(["a", "long"], &[["100".to_owned(), "1".to_owned()]]),
+        ["  a │ long", "────┼─────", "100 │    1"]
+    );
+
+    let errors = run_results("table(x -> x, 0, 1, 0)", &mut globals);
+    assert_eq!(
+        errors.expect_err("step should be invalid"),
+        ["table step must be positive and give from 1 to 10000 rows"]
+    );
+}
+
+/// Tests that completion scripts are generated for every command line option
+/// and command.
+#[test]
+fn completion_scripts_complete_options() {
+    let bash = completions::generate(Shell::Bash);
+    let zsh = completions::generate(Shell::Zsh);
+    let fish = completions::generate(Shell::Fish);
+
+    for option in args::OPTIONS {
+        let long = option.name.trim_start_matches('-');
+        assert!(bash.contains(option.name), "{} in bash", option.name);
+        assert!(zsh.contains(option.name), "{} in zsh", option.name);
+        assert!(
+            fish.contains(&format!(" -l {long}")),
+            "{} in fish",
+            option.name
+        );
+    }
+
+    for command in args::COMMANDS {
+        assert!(bash.contains(command.name), "{} in bash", command.name);
+        assert!(zsh.contains(command.name), "{} in zsh", command.name);
+        assert!(fish.contains(command.name), "{} in fish", command.name);
+    }
+
+    assert!(bash.contains(
+        "--rounding) COMPREPLY=($(compgen -W \"half-up half-even toward-zero\" -- \"$cur\"))"
+    ));
+    assert!(bash.ends_with("complete -F _clac clac\n"));
+    assert!(zsh.starts_with("#compdef clac\n"));
+    assert!(fish.contains("complete -c clac -s f -l file -r -F"));
+
+    assert!(matches!(
+        parse_args(&["completions", "fish"]).map(|o| o.command),
+        Ok(Some(Command::Completions(Shell::Fish)))
+    ));
+    assert!(matches!(
+        parse_args(&["completions", "powershell"]),
+        Err(args::ArgsError::InvalidValue("completions", shell)) if shell == "powershell"
+    ));
+}
+
+/// Tests that the `repl` command accepts preloaded files, and cannot be used
+/// with code or script files.
+#[test]
+fn repl_command_accepts_preloaded_files() {
+    let options = parse_args(&["repl", "--preload", "a.clac", "--preload", "b.clac"]);
+    let options = options.expect("arguments should be valid");
+    assert!(matches!(options.command, Some(Command::Repl)));
+    assert_eq!(options.preload, [Path::new("a.clac"), Path::new("b.clac")]);
+
+    assert!(matches!(
+        parse_args(&["repl", "1", "+", "2"]),
+        Err(args::ArgsError::UnexpectedCode("repl"))
+    ));
+    assert!(matches!(
+        parse_args(&["repl", "--file", "a.clac"]),
+        Err(args::ArgsError::Conflict("repl", "--file"))
+    ));
+    assert!(matches!(
+        parse_args(&["serve", "--preload", "a.clac"]),
+        Err(args::ArgsError::Conflict("serve", "--preload"))
+    ));
+}
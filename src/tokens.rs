@@ -43,17 +43,26 @@ define_tokens! {
     (Eof, "An end of source code marker.", "end of file"),
     (Literal(Literal), "A [`Literal`].", "a literal"),
     (Ident(Symbol), "An identifier.", "an identifier"),
+    (CustomOp(Symbol), "A custom operator symbol (e.g. `⊕`).", "a custom operator"),
     (OpenParen, "An opening parenthesis (`(`).", "an opening '('"),
     (CloseParen, "A closing parenthesis (`)`).", "a closing ')'"),
     (OpenBrace, "An opening brace (`{`).", "an opening '{'"),
     (CloseBrace, "A closing brace (`}`).", "a closing '}'"),
+    (OpenBracket, "An opening square bracket (`[`).", "an opening '['"),
+    (CloseBracket, "A closing square bracket (`]`).", "a closing ']'"),
     (Comma, "A comma (`,`).", "','"),
+    (Semicolon, "A semicolon (`;`).", "';'"),
     (Plus, "A plus sign (`+`).", "'+'"),
+    (PlusEquals, "A plus sign and equals sign (`+=`).", "'+='"),
     (Minus, "A minus sign (`-`).", "'-'"),
+    (MinusEquals, "A minus sign and equals sign (`-=`).", "'-='"),
     (MinusGreater, "A minus sign and greater than symbol (`->`).", "'->'"),
     (Star, "An asterisk (`*`).", "'*'"),
+    (StarEquals, "An asterisk and equals sign (`*=`).", "'*='"),
     (Slash, "A forward slash (`/`).", "'/'"),
+    (SlashEquals, "A forward slash and equals sign (`/=`).", "'/='"),
     (Caret, "A caret (`^`).", "'^'"),
+    (Superscript(f64), "A superscript exponent (`²` or `³`).", "a superscript exponent"),
     (Equals, "An equals sign (`=`).", "'='"),
     (EqualsEquals, "A double equals sign (`==`).", "'=='"),
     (Bang, "An exclamation mark (`!`).", "'!'"),
@@ -72,8 +81,9 @@ impl Literal {
     /// Returns the name of the `Literal`'s type.
     const fn type_name(&self) -> &'static str {
         match self {
-            Self::Number(_) => "number",
+            Self::Number(_) | Self::Integer(_) => "number",
             Self::Bool(_) => "bool",
+            Self::Str(_) => "string",
         }
     }
 }
@@ -81,11 +91,13 @@ impl Literal {
 impl Display for Token {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
+            Self::Literal(Literal::Str(_)) => f.write_str("a string"),
             Self::Literal(literal) => {
                 let type_name = literal.type_name();
                 write!(f, "{type_name} '{literal}'")
             }
             Self::Ident(symbol) => write!(f, "identifier '{symbol}'"),
+            Self::CustomOp(symbol) => write!(f, "operator '{symbol}'"),
             _ => Display::fmt(&self.token_type(), f),
         }
     }
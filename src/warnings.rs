@@ -0,0 +1,117 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{spans::Span, symbols::Symbol};
+
+/// A non-fatal diagnostic about suspicious source code.
+#[derive(Debug)]
+pub struct Warning {
+    /// The [`WarningKind`].
+    kind: WarningKind,
+
+    /// The source code [`Span`] where the `Warning` occurred.
+    span: Span,
+}
+
+impl Warning {
+    /// Creates a new `Warning` from a [`WarningKind`] and a source code
+    /// [`Span`].
+    pub const fn new(kind: WarningKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    /// Returns the [`WarningKind`].
+    pub const fn kind(&self) -> WarningKind {
+        self.kind
+    }
+
+    /// Returns the source code [`Span`] where the `Warning` occurred.
+    pub const fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Warning: {}", self.kind)
+    }
+}
+
+/// A [`Warning`]'s kind.
+#[derive(Clone, Copy, Debug)]
+pub enum WarningKind {
+    /// A ternary conditional branch can never be executed.
+    DeadBranch,
+
+    /// A ternary conditional has identical branches.
+    IdenticalBranches,
+
+    /// A condition is always a constant Boolean value.
+    ConstantCondition(bool),
+
+    /// A value was compared with itself, so the comparison always has the same
+    /// result.
+    SelfComparison(bool),
+
+    /// A global variable was redefined.
+    RedefinedVariable(Symbol),
+
+    /// A local variable shadowed a [`Shadowed`] variable.
+    ShadowedVariable(Symbol, Shadowed),
+}
+
+impl Display for WarningKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::DeadBranch => "this branch can never be executed",
+            Self::IdenticalBranches => {
+                "both branches are identical, so the condition has no effect"
+            }
+            Self::ConstantCondition(value) => {
+                return write!(f, "this condition is always {value}");
+            }
+            Self::SelfComparison(result) => {
+                return write!(
+                    f,
+                    "this compares a value with itself, so it is always {result}"
+                );
+            }
+            Self::RedefinedVariable(symbol) => {
+                return write!(f, "variable '{symbol}' is redefined");
+            }
+            Self::ShadowedVariable(symbol, shadowed) => {
+                return write!(f, "variable '{symbol}' shadows {shadowed}");
+            }
+        };
+
+        f.write_str(message)
+    }
+}
+
+/// A kind of variable which can be shadowed by a local variable.
+#[derive(Clone, Copy, Debug)]
+pub enum Shadowed {
+    /// A function parameter.
+    Param,
+
+    /// A local variable from an outer scope.
+    Local,
+
+    /// A global variable.
+    Global,
+
+    /// A global variable which is named after a native function.
+    Native,
+}
+
+impl Display for Shadowed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::Param => "a parameter",
+            Self::Local => "an outer local variable",
+            Self::Global => "a global variable",
+            Self::Native => "a native function",
+        };
+
+        f.write_str(description)
+    }
+}